@@ -0,0 +1,61 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::collections::HashSet;
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use modsync::sync::cleaner::{find_extra_files, find_missing_files};
+
+/// Files in the generated tree, spread over folders like a mod pack's.
+const FILES: usize = 100_000;
+const FILES_PER_FOLDER: usize = 50;
+const FOLDERS_PER_PARENT: usize = 20;
+
+/// Files that differ between the tree and the expected set. Few enough that
+/// the scans' per-file logging doesn't swamp the timing.
+const CHANGED: usize = 10;
+
+/// Relative path of the `i`th file, e.g. `@mod3/addons/folder17/file_42.pbo`.
+fn file_path(i: usize) -> PathBuf {
+    let folder = i / FILES_PER_FOLDER;
+    PathBuf::from(format!("@mod{}", folder / FOLDERS_PER_PARENT))
+        .join("addons")
+        .join(format!("folder{}", folder % FOLDERS_PER_PARENT))
+        .join(format!("file_{}.pbo", i % FILES_PER_FOLDER))
+}
+
+/// A tree of `FILES` empty files, and the files expected in it: all of them
+/// but the first `CHANGED`, plus `CHANGED` that aren't there.
+fn generate_tree() -> (TempDir, HashSet<PathBuf>) {
+    let dir = tempfile::tempdir().expect("Failed to create a temporary directory");
+    for i in 0..FILES {
+        let path = dir.path().join(file_path(i));
+        if i % FILES_PER_FOLDER == 0 {
+            fs::create_dir_all(path.parent().unwrap()).expect("Failed to create a folder");
+        }
+        fs::write(&path, b"").expect("Failed to create a file");
+    }
+    let expected = (CHANGED..FILES + CHANGED).map(file_path).collect();
+    (dir, expected)
+}
+
+fn cleaner(c: &mut Criterion) {
+    let (dir, expected) = generate_tree();
+    let root = dir.path();
+    // Checked against every file, but matching none of them
+    let ignored = vec![PathBuf::from("@mod0/userconfig")];
+    let mut group = c.benchmark_group("cleaner");
+    // Every iteration walks the whole tree
+    group.sample_size(10);
+    group.bench_function("find_extra_files", |b| {
+        b.iter(|| find_extra_files(black_box(root), black_box(&expected), &ignored).unwrap())
+    });
+    group.bench_function("find_missing_files", |b| {
+        b.iter(|| find_missing_files(black_box(root), black_box(&expected)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, cleaner);
+criterion_main!(benches);
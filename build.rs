@@ -0,0 +1,11 @@
+fn main() {
+    // Generate the gRPC service from proto/modsync.proto using the vendored
+    // protoc so no system install is needed.
+    #[cfg(feature = "grpc")]
+    {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc not available");
+        // SAFETY: build scripts are single-threaded at this point.
+        unsafe { std::env::set_var("PROTOC", protoc) };
+        tonic_prost_build::compile_protos("proto/modsync.proto").expect("failed to compile proto/modsync.proto");
+    }
+}
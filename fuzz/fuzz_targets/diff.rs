@@ -0,0 +1,12 @@
+//! The cached and the new torrent compared to show what an update changes.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use modsync::sync::diff::diff_torrents;
+
+fuzz_target!(|torrents: (&[u8], &[u8])| {
+    if let Ok(diff) = diff_torrents(torrents.0, torrents.1) {
+        let _ = diff.size_change();
+    }
+});
@@ -0,0 +1,19 @@
+//! A downloaded or cached torrent file, through everything that reads it
+//! before librqbit does.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use modsync::sync::snapshot::TorrentSummary;
+use modsync::sync::torrent::validate_torrent;
+use modsync::sync::trackers::{announce_urls, strip_announce};
+
+fuzz_target!(|torrent: &[u8]| {
+    let _ = validate_torrent(torrent);
+    let _ = TorrentSummary::from_torrent(torrent);
+    let _ = announce_urls(torrent);
+    if let Ok(stripped) = strip_announce(torrent) {
+        // Stripping leaves a dictionary without trackers to strip
+        assert_eq!(strip_announce(&stripped).unwrap(), stripped);
+    }
+});
@@ -0,0 +1,115 @@
+#ifndef MODSYNC_H
+#define MODSYNC_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define MODSYNC_OK 0
+
+#define MODSYNC_ERR_INVALID_ARGUMENT -1
+
+#define MODSYNC_ERR_ENGINE -2
+
+#define MODSYNC_ERR_NO_UPDATE -3
+
+#define MODSYNC_ERR_PANIC -4
+
+typedef enum ModSyncState {
+  ModSyncState_Idle = 0,
+  ModSyncState_CheckingRemote = 1,
+  ModSyncState_UpdatingTorrent = 2,
+  ModSyncState_CheckingLocal = 3,
+  ModSyncState_LocalActive = 4,
+  ModSyncState_RemoteChanged = 5,
+  ModSyncState_Error = 6,
+  ModSyncState_RestoringSession = 7,
+} ModSyncState;
+
+/**
+ * Opaque handle owned by the caller; release with `modsync_client_free`.
+ */
+typedef struct ModSyncHandle ModSyncHandle;
+
+/**
+ * Snapshot returned by `modsync_poll_status`.
+ */
+typedef struct ModSyncStatus {
+  enum ModSyncState state;
+  uint64_t progress_bytes;
+  uint64_t total_bytes;
+  /**
+   * Non-zero when a remote update is waiting to be applied.
+   */
+  uint8_t update_available;
+  /**
+   * Number of missing files reported by the last verification.
+   */
+  uint64_t missing_files;
+  /**
+   * Number of extra files reported by the last verification.
+   */
+  uint64_t extra_files;
+} ModSyncStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a client and start its session.
+ *
+ * `cached_torrent_path` may be null. Returns null on failure.
+ */
+struct ModSyncHandle *modsync_client_new(const char *torrent_url,
+                                         const char *download_path,
+                                         const char *cached_torrent_path);
+
+/**
+ * Check the remote and apply any update automatically.
+ */
+int modsync_start_sync(struct ModSyncHandle *handle);
+
+/**
+ * Check the remote without applying; poll `update_available` afterwards.
+ */
+int modsync_check_remote(struct ModSyncHandle *handle);
+
+/**
+ * Apply the update found by the last remote check.
+ */
+int modsync_apply_update(struct ModSyncHandle *handle);
+
+/**
+ * Verify the download folder against the active torrent.
+ */
+int modsync_verify(struct ModSyncHandle *handle);
+
+/**
+ * Fill `out` with the latest status snapshot.
+ */
+int modsync_poll_status(struct ModSyncHandle *handle, struct ModSyncStatus *out);
+
+/**
+ * Most recent error message, or null if none. Free with `modsync_string_free`.
+ */
+char *modsync_last_error(struct ModSyncHandle *handle);
+
+/**
+ * Release a string returned by this library.
+ */
+void modsync_string_free(char *s);
+
+/**
+ * Stop the session and release the handle.
+ */
+void modsync_client_free(struct ModSyncHandle *handle);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MODSYNC_H */
@@ -0,0 +1,541 @@
+# German. Messages missing here fall back to English.
+
+## Main window
+
+app-settings = Einstellungen
+app-shortcuts = Tastenkürzel
+app-fleet = Flotte
+app-fleet-hover = Status der entfernten Seedboxen
+app-url-hint = Torrent-URL
+app-load = Laden
+app-load-hover = Einstellungen speichern und die URL auf Updates prüfen
+app-folder-hint = Download-Ordner
+app-browse = Durchsuchen
+app-browse-hover = Download-Ordner auswählen
+app-not-available = Noch nicht verfügbar
+app-check-updates = Nach Updates suchen
+app-verify = Prüfen
+app-force-recheck = Vollständige Neuprüfung erzwingen
+app-force-recheck-hover = Alle Dateien erneut prüfen, wenn sie beschädigt sein könnten. Dauert bei großen Mod-Sets eine Weile.
+app-forget-torrent = Torrent vergessen (Dateien behalten)
+app-forget-torrent-hover = Diesen Torrent nicht mehr synchronisieren. Die heruntergeladenen Dateien bleiben im Ordner.
+app-remove-torrent = Torrent und Dateien entfernen…
+app-remove-torrent-title = Torrent und Dateien entfernen
+app-remove-torrent-warning = Dies löscht alle Dateien des Torrents in { $folder } und alle zwischengespeicherten Torrent-Versionen. Das lässt sich nicht rückgängig machen.
+app-remove-torrent-confirm = Dateien löschen
+app-cancel = Abbrechen
+app-copy-diagnostics = Diagnose kopieren
+app-copy-diagnostics-hover = Version, Einstellungen ohne Passwörter, Status und letzte Aktivität kopieren, um sie in eine Supportanfrage einzufügen.
+app-diagnostics-copied = Diagnoseinformationen in die Zwischenablage kopiert
+app-help = Hilfe
+app-crash-title = ModSync ist abgestürzt
+app-crash-found = { $count ->
+    [one] ModSync wurde beim letzten Mal unerwartet beendet. Ein Absturzbericht wurde gespeichert; bitte füge ihn einer Supportanfrage bei.
+   *[other] ModSync wurde { $count }-mal unerwartet beendet. Absturzberichte wurden gespeichert; bitte füge den neuesten einer Supportanfrage bei.
+}
+app-crash-open = Bericht öffnen
+app-crash-open-folder = Ordner öffnen
+app-crash-dismiss = Schließen
+app-save-diagnostics = Diagnosepaket speichern…
+app-save-diagnostics-hover = Eine ZIP-Datei mit den Diagnoseinformationen, dem Aktivitätsjournal, dem gespeicherten Zustand und der Torrent-Datei speichern, um sie einer Supportanfrage beizufügen.
+app-diagnostics-saved = Diagnosepaket gespeichert unter { $path }
+app-diagnostics-save-failed = Diagnosepaket konnte nicht gespeichert werden: { $error }
+app-launch = Starten
+app-join = Beitreten
+app-enable-seeding = Seeding aktivieren
+app-max-upload = Max. Upload (KB/s):
+app-max-download = Max. Download (KB/s):
+app-preset-custom = Eigene Limits
+app-preset-hover = Zwischen den Bandbreiten-Profilen aus den Einstellungen wechseln
+app-gaming-mode = Spielmodus
+app-gaming-mode-hover = Downloads anhalten, Uploads drosseln und Hinweise ausblenden, während du spielst. { $shortcut }
+app-language = Sprache:
+app-status = Status: { $status }
+error-hint-network = Prüfe deine Internetverbindung und die Torrent-URL und suche dann erneut nach Updates.
+error-hint-disk-full = Das Laufwerk ist voll. Gib Speicherplatz frei oder wähle einen Download-Ordner auf einem anderen Laufwerk.
+error-hint-permission = ModSync darf diese Dateien nicht ändern. Wähle einen anderen Download-Ordner oder passe seine Berechtigungen an.
+error-hint-torrent = Die Torrent-Datei ist beschädigt oder kein Torrent. Bitte den Betreuer des Repos, sie zu prüfen.
+error-hint-engine = Versuche eine vollständige Neuprüfung oder starte ModSync neu.
+error-hint-config = Prüfe die Einstellungen.
+disk-full-title = Laufwerk voll
+disk-full-message = Das Laufwerk von { $folder } ist voll, deshalb wurde der Download angehalten.
+disk-full-free = Freier Speicher: { $free }
+disk-full-free-unknown = unbekannt
+disk-full-advice = Gib Speicherplatz auf dem Laufwerk frei und setze fort, oder wähle einen Download-Ordner auf einem anderen Laufwerk.
+disk-full-open-folder = Ordner öffnen
+disk-full-change-folder = Anderen Ordner wählen…
+disk-full-resume = Fortsetzen
+disk-full-dismiss = Schließen
+app-inconsistent-torrent = Der geladene Torrent ({ $loaded }) ist nicht der zwischengespeicherte ({ $cached }). Er wurde pausiert.
+app-inconsistent-folder = Der geladene Torrent schreibt nach { $loaded }, nicht in den Download-Ordner { $configured }. Er wurde pausiert.
+app-swarm-empty = 0 Seeder – der Download kann hängen bleiben
+app-swarm-empty-hint = Die Tracker kennen niemanden mit dem ganzen Modpaket. Das liegt am Schwarm, nicht an deinem Setup: Der Download geht weiter, sobald ein Seeder online kommt.
+stall-title = Download hängt: seit { $minutes } Minuten kein Fortschritt
+stall-dismiss = Ausblenden
+stall-peers = Verbundene Peers: { $count }
+stall-trackers-answered = Tracker antworten: { $seeders } Seeder, { $leechers } Leecher
+stall-trackers-silent = Tracker: keine Antwort
+stall-trackers-none = Tracker: der Torrent hat keine, Peers kommen nur über DHT
+stall-port-open = Eingehende Verbindungen: angenommen auf Port { $port }
+stall-port-closed = Eingehende Verbindungen: aus, ModSync verbindet sich nur selbst zu Peers
+stall-suggest-wait = Niemand online hat das ganze Modpaket. Der Download geht weiter, sobald ein Seeder online kommt.
+stall-suggest-trackers = Prüfe deine Internetverbindung. Wenn sie funktioniert, sind vielleicht die Tracker ausgefallen; in den Einstellungen kannst du einen zusätzlichen Tracker eintragen.
+stall-suggest-firewall = Kein Peer ist verbunden. Prüfe, ob eine Firewall oder ein Virenscanner ModSync blockiert.
+stall-suggest-rsync = Ein rsync-Server ist eingerichtet. Wenn du in den Einstellungen „Dateien übertragen mit: rsync“ wählst, wird von ihm heruntergeladen.
+app-repair-torrent = Reparieren
+app-repair-torrent-hover = Den zwischengespeicherten Torrent mit den aktuellen Einstellungen neu hinzufügen
+app-file-summary = { $missing } fehlende Dateien, { $extra } zusätzliche Dateien
+app-read-only-extras = Nur-Lesen-Modus: zusätzliche Dateien werden aufgelistet, aber nie gelöscht
+app-read-only-hover = Der Nur-Lesen-Modus ist in den Einstellungen aktiviert
+app-update-available = Eine neue Version des Mod-Pakets ist verfügbar.
+app-pinned = Auf die aktuelle Version festgelegt. Updates werden ignoriert, bis du die Festlegung in den Einstellungen aufhebst.
+app-power-paused-metered = Pausiert, solange die Verbindung getaktet ist.
+app-power-paused-battery = Pausiert, solange der Computer im Akkubetrieb läuft.
+app-power-limited-metered = Gedrosselt, solange die Verbindung getaktet ist.
+app-power-limited-battery = Gedrosselt, solange der Computer im Akkubetrieb läuft.
+app-seed-window-closed = Das Seeden geht um { $start } weiter.
+app-update-summary = { $name } — { $size }, { $files } Dateien
+app-update-unnamed = Unbenannter Torrent
+app-update-info-hash = Infohash { $hash }
+app-apply-update = Update anwenden
+toast-later = Später
+header-tagline = Synchronisierung im Hintergrund
+app-open-folder-failed = Ordner konnte nicht geöffnet werden: { $error }
+
+## Daemon connection
+
+daemon-connected = Mit Daemon verbunden
+daemon-connecting = Verbinde mit Daemon...
+daemon-none = Nicht mit einem Daemon verbunden
+daemon-lost = Verbindung zum Daemon verloren, verbinde neu
+daemon-stopped = Daemon-Verbindung wurde beendet
+
+## Sync status
+
+status-idle = Bereit
+status-restoring-session = Sitzung wird wiederhergestellt...
+status-checking-remote = Suche nach Updates...
+status-updating-torrent = Aktualisiere Torrent...
+status-checking-local = Prüfe lokale Dateien...
+status-local-active = Aktuell
+status-remote-changed = Update verfügbar
+status-error = Fehler: { $error }
+check-progress = { $checked } von { $total } geprüft
+check-speed = { $speed }/s, noch etwa { $remaining }
+check-slow = Die Dateiprüfung ist ungewöhnlich langsam. Oft scannt ein Virenscanner jede gelesene Datei; den Download-Ordner vom Scan auszunehmen hilft.
+restore-title = Vorherige Sitzung wird wiederhergestellt…
+restore-title-checking = Vorherige Sitzung wird wiederhergestellt — { $size } werden geprüft
+restore-explanation = Die bereits heruntergeladenen Dateien werden mit dem Torrent abgeglichen, bevor die Synchronisierung weitergeht. Bei großen Mod-Sets kann das eine Weile dauern.
+
+## Settings
+
+settings-title = Einstellungen
+settings-torrent-url = Torrent-URL:
+settings-mirrors = Spiegel-URLs:
+settings-mirrors-hint = Eine URL pro Zeile
+settings-mirrors-hover = Werden der Reihe nach versucht, wenn die Torrent-URL nicht erreichbar ist. Der zuletzt funktionierende Spiegel wird beim nächsten Mal zuerst versucht.
+settings-trackers = Zusätzliche Tracker:
+settings-trackers-hover = Announce-URLs, die beim Hinzufügen des Torrents zu seinen eigenen Trackern kommen.
+settings-replace-trackers = Tracker des Torrents ersetzen
+settings-replace-trackers-hover = Nur die zusätzlichen Tracker nutzen, für Communities, die ihren Tracker gewechselt haben, ohne den Torrent neu herauszugeben. Der Info-Hash ändert sich nicht. Private Torrents nutzen nur den ersten Tracker.
+settings-stall-minutes = Hängt nach (Minuten):
+settings-stall-minutes-hover = Warnen, wenn ein Download so lange keinen Fortschritt macht. 0 schaltet die Warnung aus.
+settings-check-jitter = Prüf-Streuung (Sekunden):
+settings-check-jitter-hover = Jede Update-Prüfung wartet zufällig bis zu so viel länger, damit nicht alle Mitglieder gleichzeitig beim Webserver anfragen. 0 prüft genau alle 10 Minuten.
+settings-check-splay = Start-Verteilung (Sekunden):
+settings-check-splay-hover = Verteilt die erste Update-Prüfung nach dem Start über so viele Sekunden. Nützlich, wenn viele Mitglieder modsync gleichzeitig starten.
+settings-version-url = Versions-URL:
+settings-version-url-hint = Nur für URLs mit {"{"}latest{"}"}
+settings-version-url-hover = Liefert die aktuelle Version, als Text oder als JSON mit einem Feld „latest“. Sie ersetzt {"{"}latest{"}"} in der Torrent-URL und den Spiegel-URLs, für Repositories, die jede Version unter einem eigenen Pfad ablegen.
+settings-pinned = Auf die aktuelle Version festlegen
+settings-pinned-hover = Updates ignorieren, bis die Festlegung aufgehoben wird, etwa während eine Kampagne bei einem älteren Mod-Paket bleibt. Das Repo wird weiter geprüft, und ein wartendes Update wird nach dem Aufheben angezeigt.
+settings-max-redirects = Max. Weiterleitungen:
+settings-max-redirects-hover = Wie vielen Weiterleitungen beim Abrufen des Torrents gefolgt wird, z. B. bei Kurzlinks
+settings-cross-host-redirects = Weiterleitungen zu anderen Hosts erlauben
+settings-cross-host-redirects-hover = Kurzlinks leiten meist auf einen anderen Host weiter
+settings-s3 = S3-Speicher
+settings-s3-hint = Wird für Torrent-URLs wie s3://bucket/key verwendet
+settings-s3-endpoint = Endpunkt:
+settings-s3-region = Region:
+settings-s3-access-key = Zugriffsschlüssel-ID:
+settings-s3-secret-key = Geheimer Zugriffsschlüssel:
+settings-s3-signing-endpoint = Signierdienst (optional):
+settings-s3-path-style = Pfad-Adressierung
+settings-s3-path-style-hover = Den Bucket in den URL-Pfad setzen, wie es MinIO und die meisten selbst gehosteten Dienste erwarten
+settings-ipfs-gateway = IPFS-Gateway:
+settings-ipfs-gateway-hover = Gateway für ipfs://-Torrent-URLs (experimentell). Jeder Block wird gegen seine CID geprüft, daher genügt jedes öffentliche Gateway.
+settings-engine = Dateien übertragen mit:
+settings-engine-torrent = Torrent
+settings-engine-rsync = rsync
+settings-engine-rsync-hover = Kopiert die Dateien vor dem Hinzufügen des Torrents per SSH von einem rsync-Server. Der Torrent prüft sie weiterhin und ergänzt, was rsync nicht geliefert hat.
+settings-rsync-source = rsync-Quelle:
+settings-mappings = Unterordner
+settings-mappings-hint = Einen Ordner der obersten Ebene des Torrents oder den ganzen Torrent in einem Unterordner des Download-Ordners ablegen. Gilt, sobald der Torrent das nächste Mal hinzugefügt wird.
+settings-mappings-folder = Torrent-Ordner
+settings-mappings-target = Ablegen in
+settings-mappings-whole-torrent = (ganzer Torrent)
+settings-mappings-add = Unterordner hinzufügen
+settings-filters = Übersprungene Dateien
+settings-filters-hint = Torrent-Dateien, die nie heruntergeladen werden, z. B. *.wav oder /optional/**. Ein Muster ohne / passt auf Dateinamen in jedem Ordner, ** auf beliebig viele Ordner. Mit einer Größe werden nur größere Dateien übersprungen. Lokale Kopien übersprungener Dateien gelten nicht als überzählig.
+settings-filters-pattern = Muster
+settings-filters-min-size = Über (MiB)
+settings-filters-any-size = jede Größe
+settings-filters-add = Filter hinzufügen
+settings-extract = Archive
+settings-extract-hint = Zip-Dateien im Repo, die nach Abschluss des Downloads entpackt werden; Pfade relativ zum Download-Ordner. Entpackte Dateien gelten nicht als überzählig.
+settings-extract-archive = Archiv
+settings-extract-target = Entpacken nach
+settings-extract-add = Archiv hinzufügen
+settings-keys-dir = Schlüsselordner:
+settings-keys-dir-hover = Ordner, in den die .bikey-Dateien der Mods nach jedem Download kopiert werden, z. B. der keys-Ordner des Servers. Schlüssel entfernter Mods werden wieder gelöscht; andere Schlüssel dort bleiben erhalten.
+settings-hooks = Hooks
+settings-hooks-hint = Shell-Befehle, die im Download-Ordner ausgeführt werden. MODSYNC_HOOK, MODSYNC_DOWNLOAD_PATH und die folgenden Variablen beschreiben das Ereignis.
+settings-hooks-pre-update = Vor dem Update:
+settings-hooks-pre-update-hover = Läuft vor dem Anwenden eines Updates, mit gesetztem MODSYNC_TORRENT_BYTES. Ein Exit-Code ungleich null überspringt das Update.
+settings-hooks-post-update = Nach dem Update:
+settings-hooks-post-update-hover = Läuft nach einem Update, mit gesetzten MODSYNC_RESULT, MODSYNC_ERROR, MODSYNC_DOWNLOADED_BYTES, MODSYNC_UPLOADED_BYTES und MODSYNC_CHANGED_FILES.
+settings-hooks-post-verify = Nach der Prüfung:
+settings-hooks-post-verify-hover = Läuft nach einer Prüfung oder Reparatur, mit denselben Variablen wie nach einem Update.
+settings-script = Skript:
+settings-script-hover = Rhai-Skript, das auf Sync-Ereignisse reagiert; wird beim Start des Daemons geladen. Nach Änderungen den Daemon neu starten.
+settings-presets = Bandbreiten-Profile
+settings-presets-hint = Benannte Geschwindigkeitslimits, zwischen denen im Kopfbereich gewechselt werden kann, z. B. Spielen oder Nachts. Ein leeres Limit bedeutet unbegrenzt.
+settings-presets-name = Name
+settings-presets-name-hint = Spielen
+settings-presets-unlimited = unbegrenzt
+settings-presets-add = Profil hinzufügen
+settings-gaming = Spielmodus
+settings-gaming-hint = Der Spielmodus hält Downloads an, drosselt Uploads auf ein Minimum und blendet Hinweise aus, bis er wieder ausgeschaltet wird. Das Tastenkürzel funktioniert, solange das ModSync-Fenster den Fokus hat.
+settings-gaming-upload = Upload-Limit (KB/s):
+settings-gaming-shortcut = Tastenkürzel:
+settings-gaming-shortcut-invalid = Kein gültiges Tastenkürzel, Schreibweise z. B. Ctrl+Shift+G
+settings-power = Getaktete Verbindungen und Akku
+settings-power-hint = ModSync prüft jede Minute, ob die Verbindung getaktet ist oder der Computer im Akkubetrieb läuft. Stromsparen hält Übertragungen bei den Grenzen unten. macOS meldet getaktete Verbindungen nicht.
+settings-power-metered = Bei getakteter Verbindung:
+settings-power-battery = Im Akkubetrieb:
+settings-power-ignore = Weiter synchronisieren
+settings-power-low-power = Stromsparen
+settings-power-pause = Pausieren
+settings-power-upload = Upload-Grenze beim Stromsparen (KB/s):
+settings-power-download = Download-Grenze beim Stromsparen (KB/s):
+settings-check = Hintergrundprüfungen
+settings-check-hint = Das Prüfen der Torrent-Dateien liest sie alle, was Spiele ruckeln lassen kann. Hier lässt es sich verlangsamen; Herunterladen und Seeden sind nicht betroffen. Gilt, sobald der Torrent das nächste Mal hinzugefügt wird.
+settings-check-max = Höchstens lesen (MB/s):
+settings-check-low-priority = Niedrige Festplattenpriorität
+settings-check-low-priority-hover = Andere Programme beim Prüfen zuerst auf die Festplatte zugreifen lassen. Nur unter Linux unterstützt.
+settings-check-fastresume = Prüfung beim Neustart überspringen
+settings-check-fastresume-hover = Merken, welche Stücke geprüft wurden, damit ein Neustart nicht alle Dateien erneut prüft. Gilt nach einem Neustart.
+settings-memory = Arbeitsspeicher
+settings-memory-hint = Leer lassen, um am wenigsten Speicher zu verwenden, etwa auf einem NAS mit 1-2 GB RAM. Schreibpuffer, gleichzeitige Prüfungen und Hash-Threads gelten nach einem Neustart.
+settings-memory-write-buffer = Schreibpuffer (MiB):
+settings-memory-write-buffer-hover = Heruntergeladene Daten vor dem Schreiben im Speicher puffern, was langsamen Festplatten hilft. Leer schreibt direkt auf die Festplatte.
+settings-memory-piece-cache = Stück-Cache (MiB):
+settings-memory-piece-cache-hover = Zuletzt heruntergeladene Stücke im Speicher halten, damit ihre Prüfung sie nicht erneut von der Festplatte liest. Leer schaltet den Cache aus.
+settings-memory-concurrent-checks = Gleichzeitige Prüfungen:
+settings-memory-concurrent-checks-hover = Wie viele Torrents gleichzeitig geprüft werden. Leer verwendet 1.
+settings-memory-hashing-threads = Hash-Threads:
+settings-memory-hashing-threads-hover = Wie viele CPU-Kerne heruntergeladene Daten prüfen. Leer verwendet die Hälfte und lässt den Rest für Spiele frei.
+settings-cache = Torrent-Cache
+settings-cache-max = Größenlimit (MiB):
+settings-cache-max-hover = Jede gesehene Torrent-Version wird im Cache-Ordner behalten; über dieser Größe werden die am längsten nicht gesehenen entfernt. Leer lassen für kein Limit.
+settings-cache-versions = Behaltene Versionen:
+settings-cache-versions-hover = Wie viele Torrent-Versionen für ein Zurücksetzen behalten werden; ältere werden entfernt. Leer lassen für kein Limit.
+settings-cache-total = { $count } Torrents im Cache, { $size }
+settings-cache-current = Aktuell
+settings-cache-roll-back = Zurücksetzen
+settings-cache-roll-back-hover = Zu dieser Version zurückkehren. Lege die Version fest, um bei ihr zu bleiben, sonst bietet die nächste Prüfung wieder die neueste an.
+settings-cache-roll-back-warning = Der Download-Ordner wird auf diese Version gebracht. Dateien, die nur die neuere Version hat, werden gelöscht, außer in geschützten Pfaden.
+settings-cache-roll-back-read-only = Der Download-Ordner wird auf diese Version gebracht. Im Nur-Lesen-Modus bleiben Dateien, die nur die neuere Version hat, erhalten und werden als zusätzlich gemeldet.
+settings-cache-roll-back-confirm = Zurücksetzen
+settings-cache-refresh = Aktualisieren
+settings-cache-clear = Cache leeren
+settings-cache-compare = Versionen vergleichen
+settings-cache-compare-hover = Zeigt die Dateien, die eine Version gegenüber einer anderen hinzugefügt, entfernt oder in der Größe geändert hat.
+settings-cache-cleared = { $count } Torrents aus dem Cache entfernt
+settings-cache-clear-failed = Cache konnte nicht geleert werden: { $error }
+settings-quarantine = Quarantäne
+settings-quarantine-enable = Gelöschte Dateien in die Quarantäne verschieben
+settings-quarantine-enable-hover = Gelöschte zusätzliche Dateien werden stattdessen in einen Ordner im Cache verschoben und können wiederhergestellt werden
+settings-quarantine-days = Tage aufbewahren:
+settings-quarantine-empty = Nichts in Quarantäne.
+settings-quarantine-batch = { $count ->
+    [one] 1 Datei
+   *[other] { $count } Dateien
+}, { $size }
+settings-quarantine-restore = Wiederherstellen
+settings-quarantine-discard = Löschen
+settings-quarantine-restored = { $count ->
+    [one] 1 Datei wiederhergestellt
+   *[other] { $count } Dateien wiederhergestellt
+}
+settings-quarantine-failed = Quarantäne-Aktion fehlgeschlagen: { $error }
+settings-sftp-key = SSH-Schlüsseldatei:
+settings-sftp-key-hover = Privater Schlüssel für sftp://-Torrent-URLs und die rsync-Übertragung. Leer lassen, um den SSH-Agent oder ~/.ssh/config zu verwenden.
+settings-credentials = Zugangsdaten
+settings-credentials-hint = Werden für dav://- und davs://-Torrent-URLs auf dem passenden Host verwendet
+settings-credentials-host = Host
+settings-credentials-user = Benutzer
+settings-credentials-password = Passwort
+settings-credentials-add = Zugang hinzufügen
+settings-git = Git-Repository
+settings-git-hint = Wird in einen Unterordner des Download-Ordners geklont und bei jeder Update-Prüfung aktualisiert. Der Bereiniger lässt den Ordner in Ruhe.
+settings-git-url = Repository-URL
+settings-git-branch = Branch
+settings-git-folder = Ordner
+settings-download-path = Download-Pfad:
+settings-recent-paths = Zuletzt
+settings-recent-paths-hover = Zuvor verwendete Download-Ordner
+settings-shared-store = Gemeinsamer Speicher:
+settings-shared-store-off = Aus
+settings-shared-store-hover = Ordner, den andere Profile oder Benutzer auf diesem Computer mitbenutzen. Fertige Dateien werden per Hardlink darin abgelegt und wiederverwendet, statt erneut heruntergeladen zu werden. Muss auf demselben Laufwerk wie der Download-Ordner liegen.
+settings-seeding = Seeding:
+settings-seed-window = Nur seeden zwischen:
+settings-seed-window-hover = Außerhalb dieser Zeiten wird der fertige Torrent pausiert. Downloads laufen jederzeit. Leer lassen, um den ganzen Tag zu seeden.
+settings-seed-window-invalid = Kein Zeitfenster, schreib es wie 22:00-08:00
+settings-read-only = Nur lesen: nie Dateien löschen
+settings-read-only-hover = Mods herunterladen und reparieren, aber nie etwas im Download-Ordner löschen. Zusätzliche Dateien werden weiter aufgelistet.
+settings-strict-mirror = Strikter Spiegel: zusätzliche Dateien nach Updates löschen
+settings-strict-mirror-hover = Für dedizierte Server. Nach jedem Update werden Dateien, die nicht zum Repo gehören, ohne Nachfrage gelöscht. Geschützte Pfade werden nie gelöscht.
+settings-strict-mirror-read-only = Im Nur-Lesen-Modus nicht verfügbar
+settings-strict-mirror-warning = Nach jedem Update werden alle Dateien im Download-Ordner, die nicht zum Repo gehören, ohne Nachfrage gelöscht. Füge zuerst alles, was bleiben soll, zu den geschützten Pfaden hinzu.
+settings-strict-mirror-confirm = Strikten Spiegel aktivieren
+settings-save = Speichern
+settings-saved = Einstellungen gespeichert
+settings-reset = Zurücksetzen
+settings-reset-done = Einstellungen auf Standardwerte zurückgesetzt
+settings-close = Schließen
+settings-appearance = Darstellung
+settings-theme = Design:
+settings-theme-dark = Dunkel
+settings-theme-light = Hell
+settings-theme-system = System
+settings-theme-system-hover = Dunkel oder hell wie in deinem Betriebssystem eingestellt, und wechselt mit.
+settings-custom-accent = Eigene Akzentfarbe
+settings-accent-hover = Akzentfarbe
+settings-display = Anzeige
+settings-ui-scale = UI-Skalierung:
+settings-font-size = Schriftgröße:
+settings-units = Größen in:
+settings-units-binary = KiB, MiB, GiB
+settings-units-si = kB, MB, GB
+settings-display-reset = Anzeige zurücksetzen
+settings-notifications = Benachrichtigungen
+settings-play-sounds = Töne abspielen
+settings-play-sounds-hover = Einen Ton abspielen, wenn eine Synchronisierung fertig ist oder ein Update gefunden wurde.
+settings-quiet-hours = Ruhezeit:
+settings-quiet-hours-hover = Keine Töne und Desktop-Benachrichtigungen zwischen diesen Uhrzeiten, etwa 22:00-08:00. Leer lassen für keine.
+settings-discord = Aktivität in Discord anzeigen
+settings-discord-hover = Zeigt „Syncing … — 72%“ oder „Seeding 38 GiB“ in deinem Discord-Profil, solange Discord läuft.
+settings-discord-client-id = Discord-Anwendungs-ID:
+settings-discord-client-id-hover = ID der Discord-Anwendung deiner Community. Ihr Name wird als das Spiel angezeigt, das du spielst.
+settings-telemetry = Abgeschlossene Syncs dem Admin melden
+settings-telemetry-hover = Sendet nach einem fertigen Download die erreichte Version, die Dauer und die übertragene Menge, damit Admins sehen, wie viele Mitglieder aktuell sind. Berichte enthalten eine zufällige ID und sonst nichts über deinen Computer.
+settings-telemetry-endpoint = Berichts-URL:
+settings-telemetry-endpoint-hover = Adresse, unter der dein Admin Berichte sammelt. Ohne sie wird nichts gesendet.
+settings-load-failed = Einstellungen konnten nicht geladen werden: { $error }
+settings-save-failed = Einstellungen konnten nicht gespeichert werden: { $error }
+settings-save-invalid = Korrigiere zuerst die Torrent-URL oder den Download-Pfad
+validate-url-invalid = Keine gültige URL, z. B. https://example.com/mods.torrent
+validate-url-scheme = { $scheme }://-URLs werden nicht unterstützt
+validate-path-empty = Wähle einen Download-Ordner
+validate-path-not-folder = Das ist eine Datei, kein Ordner
+validate-path-system = Systemordner können nicht für Downloads verwendet werden
+validate-path-not-writable = In diesen Ordner kann nicht geschrieben werden
+validate-path-space = Nicht genug freier Speicher: { $needed } benötigt, { $free } frei
+settings-reset-failed = Einstellungen konnten nicht zurückgesetzt werden: { $error }
+
+## Fleet window
+
+fleet-title = Flotte
+fleet-refresh = Aktualisieren
+fleet-check-all = Alle nach Updates prüfen
+fleet-col-node = Knoten
+fleet-col-status = Status
+fleet-col-version = Version
+fleet-col-progress = Fortschritt
+fleet-col-seed-ratio = Seed-Verhältnis
+fleet-col-update = Update
+fleet-update-available = Verfügbar
+fleet-offline = Offline
+fleet-remove = Entfernen
+fleet-remove-hover = { $node } aus der Flotte entfernen
+fleet-node-hover = Steueradresse eines mit --grpc gestarteten Daemons
+fleet-add-node = Knoten hinzufügen
+fleet-check-started = Update-Prüfung auf { $count } Knoten gestartet
+fleet-check-failed = Update-Prüfung fehlgeschlagen auf: { $nodes }
+fleet-no-runtime = Keine Async-Laufzeit für Flottenanfragen verfügbar
+
+## Keyboard shortcuts
+
+shortcuts-title = Tastenkürzel
+shortcut-check-updates = Nach Updates suchen
+shortcut-verify = Lokale Dateien prüfen
+shortcut-pause = Pausieren oder fortsetzen
+shortcut-open-folder = Download-Ordner öffnen
+shortcut-open-settings = Einstellungen öffnen
+shortcut-show-shortcuts = Diese Liste anzeigen
+shortcut-command-palette = Befehle suchen
+shortcut-gaming-mode = Spielmodus umschalten
+shortcut-close-dialog = Dialog schließen
+shortcut-confirm-dialog = Dialog bestätigen
+palette-title = Befehle
+palette-hint = Befehl eingeben …
+palette-no-match = Kein passender Befehl
+palette-go-to = Gehe zu { $tab }
+palette-preset = Geschwindigkeits-Preset: { $name }
+
+## Progress bar
+
+progress-overall = Download-Fortschritt: { $percent } %
+progress-file-tooltip = Datei { $index }: { $done } / { $total } ({ $percent } %)
+
+## Files tab
+
+app-tab-overview = Übersicht
+app-tab-files = Dateien
+files-tree = Baum
+files-list = Liste
+files-filter = Filter:
+files-filter-hint = Teil eines Dateinamens oder Pfads
+files-filter-clear = Filter leeren
+files-empty = Noch kein Torrent geladen.
+files-count = { $shown } von { $total } Dateien
+files-col-name = Name
+files-col-size = Größe
+files-col-progress = Fortschritt
+files-sort-hover = Nach dieser Spalte sortieren; erneut klicken zum Umkehren
+files-transferred = { $done } von { $total } heruntergeladen
+
+## Graph tab
+
+app-tab-graph = Graph
+graph-complete = Vollständig ({ $count })
+graph-partial = Wird geladen ({ $count })
+graph-missing = Fehlend ({ $count })
+graph-extra = Zusätzlich ({ $count })
+graph-not-verified = Eine Prüfung starten, um fehlende und zusätzliche Dateien zu finden
+graph-reset-view = Ansicht zurücksetzen
+graph-reset-view-hover = Den ganzen Graphen ins Fenster einpassen
+graph-collapse-all = Alle einklappen
+graph-expand-all = Alle ausklappen
+graph-expand-all-hover = Jede Datei anzeigen; kann bei sehr großen Modpaketen langsam sein
+graph-help = Einen Ordner anklicken, um ihn ein- oder auszuklappen. Ziehen zum Verschieben, Scrollen zum Zoomen.
+graph-collapsed-label = { $name } ({ $count } Dateien)
+graph-folder-files = { $count } Dateien
+
+## File context menu
+
+menu-open-location = Enthaltenden Ordner öffnen
+menu-copy-path = Relativen Pfad kopieren
+menu-ignore = Vor Bereinigung schützen
+menu-ignore-hover = Dateien in oder unter diesem Pfad werden nie als zusätzliche Dateien gemeldet
+menu-reveal-in-graph = Im Graphen zeigen
+settings-ignored = Geschützte Pfade
+settings-ignored-empty = Keine geschützten Pfade. Sie können im Kontextmenü des Dateien-Tabs hinzugefügt werden.
+settings-ignored-remove = Entfernen
+settings-banned-peers = Gesperrte Peers
+settings-banned-peers-hover = Im Peers-Tab gesperrte Adressen. Änderungen gelten ab dem nächsten Start von ModSync und brauchen einen Cache-Ordner.
+settings-banned-peers-empty = Keine gesperrten Peers.
+settings-banned-peers-unban = Entsperren
+
+## Status bar
+
+statusbar-download = Runter { $speed }
+statusbar-upload = Hoch { $speed }
+statusbar-peers = { $count } Peers
+statusbar-private = Privater Torrent
+statusbar-private-hover = Der Torrent ist als privat markiert, Peers kommen daher nur von seinem Tracker. DHT und Peer-Austausch sind für ihn aus.
+statusbar-swarm = Schwarm: { $seeders } Seeder, { $leechers } Leecher
+statusbar-swarm-hover = Wie die Tracker des Torrents ihn melden. Abgeschlossene Downloads: { $downloaded }
+statusbar-free-space = { $free } frei
+statusbar-free-space-hover = Freier Speicher auf dem Laufwerk des Download-Ordners
+statusbar-last-check = Zuletzt vor { $elapsed } geprüft
+statusbar-remote-source = Geliefert von { $url }
+statusbar-never-checked = Noch nicht nach Updates gesucht
+statusbar-next-check = Nächste Prüfung in { $remaining }
+statusbar-check-now = Jetzt prüfen
+statusbar-check-now-hover = Die gespeicherte Torrent-URL sofort auf Updates prüfen
+statusbar-git = Git { $commit }
+statusbar-git-failed = Git { $commit } (Pull fehlgeschlagen)
+
+## Lifetime statistics
+stats-title = Statistik
+stats-downloaded = Heruntergeladen (gesamt)
+stats-uploaded = Hochgeladen (gesamt)
+stats-ratio = Verhältnis
+stats-ratio-hover = Hochgeladene geteilt durch heruntergeladene Bytes über alle Sitzungen
+storage-title = Speicher
+storage-repo = Synchronisiertes Repo
+storage-extra = Zusätzliche Dateien
+storage-extra-hover = Dateien, die nicht zum Repo gehören und nicht geschützt sind; der Cleaner bietet an, sie zu löschen
+storage-protected = Geschützte Dateien
+storage-protected-hover = Ignorierte Pfade, entpackte Archive und andere Dateien, die der Cleaner nicht anrührt
+storage-free = Frei auf dem Laufwerk
+storage-scanned = Vor { $elapsed } gescannt
+
+## History tab
+app-tab-history = Verlauf
+history-export = Als CSV exportieren
+history-exported = Verlauf nach { $path } exportiert
+history-export-failed = Export des Verlaufs fehlgeschlagen: { $error }
+history-empty = Noch keine Synchronisierungen aufgezeichnet
+history-col-started = Gestartet
+history-col-trigger = Auslöser
+history-col-duration = Dauer
+history-col-transferred = Runter / Hoch
+history-col-files = Geänderte Dateien
+history-col-result = Ergebnis
+history-trigger-check = Update-Prüfung
+history-trigger-scheduled = Geplante Prüfung
+history-trigger-update = Update anwenden
+history-trigger-verify = Überprüfen
+history-trigger-repair = Reparieren
+history-result-ok = OK
+history-result-failed = Fehlgeschlagen
+history-result-running = Läuft
+
+## Peers tab
+app-tab-peers = Peers
+peers-hint = Mit dem Torrent verbundene Peers. Gezählt wird nur, was jeder Peer gesendet hat; librqbit zählt Uploads nicht pro Peer. Eine gesperrte Adresse wird ab dem nächsten Start von ModSync abgewiesen.
+peers-empty = Keine Peers verbunden
+peers-col-address = Adresse
+peers-col-downloaded = Heruntergeladen
+peers-col-connections = Verbindungen
+peers-col-errors = Fehler
+peers-ban = Sperren
+peers-ban-hover = Diese Adresse ab dem nächsten Start abweisen. Entsperren kannst du sie in den Einstellungen.
+peers-banned = Gesperrt
+
+## Trackers tab
+app-tab-trackers = Tracker
+app-tab-log = Protokoll
+log-empty = Noch nichts protokolliert.
+log-detached = Das Protokoll wird in einem eigenen Fenster angezeigt.
+popout-hover = In einem eigenen Fenster anzeigen
+popout-attach = Zurückholen
+trackers-empty = Der Torrent hat keine Tracker. Peers kommen nur über DHT.
+trackers-col-url = Announce-URL
+trackers-col-source = Quelle
+trackers-source-torrent = Torrent
+trackers-source-config = Einstellungen
+trackers-replaced = Ersetzt
+
+## Version diff
+version-diff-title = Versionen vergleichen
+version-diff-old = Ältere Version:
+version-diff-new = Neuere Version:
+version-diff-no-versions = Zum Vergleichen müssen mindestens zwei Torrent-Versionen im Cache sein.
+version-diff-failed = Die Versionen konnten nicht verglichen werden: { $error }
+version-diff-none = Die Versionen haben dieselben Dateien ({ $unchanged }).
+version-diff-summary = { $added } hinzugefügt, { $removed } entfernt, { $resized } in der Größe geändert, { $unchanged } unverändert. Größenänderung: { $size }
+version-diff-added = Hinzugefügt ({ $count })
+version-diff-removed = Entfernt ({ $count })
+version-diff-resized = Größe geändert ({ $count })
+
+## Remote endpoint health
+health-unknown = Noch nicht geprüft
+health-good = Erreichbar
+health-degraded = Erreichbar, aber zuletzt langsam oder fehlerhaft
+health-down = Letzte Prüfung fehlgeschlagen
+health-latency = Letzte Antwort nach { $ms } ms
+health-last-error = Letzter Fehler: { $error }
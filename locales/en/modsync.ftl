@@ -0,0 +1,542 @@
+# English (baseline). Every message used by the UI must be defined here;
+# other languages fall back to these strings for anything they don't define.
+
+## Main window
+
+app-settings = Settings
+app-shortcuts = Shortcuts
+app-fleet = Fleet
+app-fleet-hover = Status of remote seedboxes
+app-url-hint = Torrent URL
+app-load = Load
+app-load-hover = Save these settings and check the URL for updates
+app-folder-hint = Download folder
+app-browse = Browse
+app-browse-hover = Choose the download folder
+app-not-available = Not available yet
+app-check-updates = Check for updates
+app-verify = Check
+app-force-recheck = Force full re-check
+app-force-recheck-hover = Hash every file again, for when files may be corrupted. Takes a while for large mod sets.
+app-forget-torrent = Forget torrent (keep files)
+app-forget-torrent-hover = Stop syncing this torrent. The downloaded files stay in the folder.
+app-remove-torrent = Remove torrent and files…
+app-remove-torrent-title = Remove torrent and files
+app-remove-torrent-warning = This deletes every file of the torrent in { $folder } and all cached torrent versions. It can't be undone.
+app-remove-torrent-confirm = Delete files
+app-cancel = Cancel
+app-copy-diagnostics = Copy diagnostics
+app-copy-diagnostics-hover = Copy the version, settings without passwords, status and recent activity, to paste into a support request.
+app-diagnostics-copied = Diagnostic info copied to the clipboard
+app-help = Help
+app-crash-title = ModSync crashed
+app-crash-found = { $count ->
+    [one] ModSync closed unexpectedly last time. A crash report was saved; please attach it to a support request.
+   *[other] ModSync closed unexpectedly { $count } times. Crash reports were saved; please attach the latest to a support request.
+}
+app-crash-open = Open report
+app-crash-open-folder = Open folder
+app-crash-dismiss = Dismiss
+app-save-diagnostics = Save diagnostic bundle…
+app-save-diagnostics-hover = Save a zip with the diagnostic info, the activity journal, saved state and the torrent file, to attach to a support request.
+app-diagnostics-saved = Diagnostic bundle saved to { $path }
+app-diagnostics-save-failed = Failed to save the diagnostic bundle: { $error }
+app-launch = Launch
+app-join = Join
+app-enable-seeding = Enable seeding
+app-max-upload = Max upload (KB/s):
+app-max-download = Max download (KB/s):
+app-preset-custom = Custom limits
+app-preset-hover = Switch between the bandwidth presets from the settings
+app-gaming-mode = Gaming mode
+app-gaming-mode-hover = Hold downloads, cap uploads and hide notices while you play. { $shortcut }
+app-language = Language:
+app-status = Status: { $status }
+error-hint-network = Check your internet connection and the torrent URL, then check for updates again.
+error-hint-disk-full = The drive is full. Free up space or choose a download folder on another drive.
+error-hint-permission = ModSync isn't allowed to change these files. Choose another download folder or fix its permissions.
+error-hint-torrent = The torrent file is damaged or isn't a torrent. Ask the repo maintainer to check it.
+error-hint-engine = Try a full re-check, or restart ModSync.
+error-hint-config = Check the settings.
+disk-full-title = Disk full
+disk-full-message = The drive of { $folder } is full, so downloading has stopped.
+disk-full-free = Free space: { $free }
+disk-full-free-unknown = unknown
+disk-full-advice = Free up space on the drive and resume, or choose a download folder on another drive.
+disk-full-open-folder = Open folder
+disk-full-change-folder = Choose another folder…
+disk-full-resume = Resume
+disk-full-dismiss = Dismiss
+app-inconsistent-torrent = The loaded torrent ({ $loaded }) is not the cached one ({ $cached }). It has been paused.
+app-inconsistent-folder = The loaded torrent writes to { $loaded }, not the download folder { $configured }. It has been paused.
+app-swarm-empty = 0 seeders — download may stall
+app-swarm-empty-hint = The trackers know of nobody with the whole mod pack. This is the swarm, not your setup: the download continues once a seeder comes online.
+stall-title = Download stalled: no progress for { $minutes } minutes
+stall-dismiss = Dismiss
+stall-peers = Peers connected: { $count }
+stall-trackers-answered = Trackers answered: { $seeders } seeders, { $leechers } leechers
+stall-trackers-silent = Trackers: no answer
+stall-trackers-none = Trackers: the torrent has none, peers only come from DHT
+stall-port-open = Incoming connections: accepted on port { $port }
+stall-port-closed = Incoming connections: off, ModSync only connects out to peers
+stall-suggest-wait = Nobody online has the whole mod pack. The download continues once a seeder comes online.
+stall-suggest-trackers = Check your internet connection. If it works, the trackers may be down; an extra tracker can be added in the settings.
+stall-suggest-firewall = No peer is connected. Check that a firewall or antivirus isn't blocking ModSync.
+stall-suggest-rsync = An rsync server is configured. Choosing "Transfer files with: rsync" in the settings downloads from it instead.
+app-repair-torrent = Repair
+app-repair-torrent-hover = Re-add the cached torrent with the current settings
+app-file-summary = { $missing } missing files, { $extra } extra files
+app-read-only-extras = Read-only mode: extra files are listed but never deleted
+app-read-only-hover = Read-only mode is on in the settings
+app-update-available = A new version of the mod pack is available.
+app-pinned = Pinned to the current version. Updates are ignored until you unpin it in the settings.
+app-power-paused-metered = Paused while the connection is metered.
+app-power-paused-battery = Paused while the computer runs on battery.
+app-power-limited-metered = Slowed down while the connection is metered.
+app-power-limited-battery = Slowed down while the computer runs on battery.
+app-seed-window-closed = Seeding resumes at { $start }.
+app-update-summary = { $name } — { $size }, { $files } files
+app-update-unnamed = Unnamed torrent
+app-update-info-hash = Infohash { $hash }
+app-apply-update = Apply update
+toast-later = Later
+header-tagline = Background sync manager
+app-open-folder-failed = Failed to open folder: { $error }
+
+## Daemon connection
+
+daemon-connected = Connected to daemon
+daemon-connecting = Connecting to daemon...
+daemon-none = Not connected to a daemon
+daemon-lost = Lost connection to the daemon, reconnecting
+daemon-stopped = Daemon connection has stopped
+
+## Sync status
+
+status-idle = Idle
+status-restoring-session = Restoring session...
+status-checking-remote = Checking for updates...
+status-updating-torrent = Updating torrent...
+status-checking-local = Checking local files...
+status-local-active = Up to date
+status-remote-changed = Update available
+status-error = Error: { $error }
+check-progress = Checked { $checked } of { $total }
+check-speed = { $speed }/s, about { $remaining } left
+check-slow = Checking files is unusually slow. This is often antivirus software scanning every file read; excluding the download folder from scanning helps.
+restore-title = Restoring previous session…
+restore-title-checking = Restoring previous session — checking { $size }
+restore-explanation = The files already downloaded are checked against the torrent before syncing continues. This can take a while for large mod sets.
+
+## Settings
+
+settings-title = Application Settings
+settings-torrent-url = Torrent URL:
+settings-mirrors = Mirror URLs:
+settings-mirrors-hint = One URL per line
+settings-mirrors-hover = Tried in order when the torrent URL can't be reached. The mirror that worked last is tried first next time.
+settings-trackers = Extra trackers:
+settings-trackers-hover = Announce URLs added to the torrent's own trackers when it is added.
+settings-replace-trackers = Replace the torrent's trackers
+settings-replace-trackers-hover = Use only the extra trackers, for communities that moved their tracker without re-issuing the torrent. The info hash doesn't change. Private torrents only use the first tracker.
+settings-stall-minutes = Stalled after (minutes):
+settings-stall-minutes-hover = Warn when a download makes no progress for this long. 0 turns the warning off.
+settings-check-jitter = Check jitter (seconds):
+settings-check-jitter-hover = Each update check waits up to this much longer at random, so members don't all ask the web host at once. 0 checks exactly every 10 minutes.
+settings-check-splay = Startup splay (seconds):
+settings-check-splay-hover = Spread the first update check after starting over this many seconds. Useful when many members start modsync at the same time.
+settings-version-url = Version URL:
+settings-version-url-hint = Only for URLs with {"{"}latest{"}"}
+settings-version-url-hover = Returns the current version, as plain text or as JSON with a "latest" field. It replaces {"{"}latest{"}"} in the torrent and mirror URLs, for repositories that keep each release under its own path.
+settings-pinned = Pin to the current version
+settings-pinned-hover = Ignore updates until unpinned, for example while a campaign stays on an older mod pack. The repo is still checked, and a waiting update is shown once unpinned.
+settings-max-redirects = Max redirects:
+settings-max-redirects-hover = How many redirects to follow when fetching the torrent, e.g. through short links
+settings-cross-host-redirects = Allow redirects to other hosts
+settings-cross-host-redirects-hover = Short links usually redirect to a different host
+settings-s3 = S3 storage
+settings-s3-hint = Used for torrent URLs like s3://bucket/key
+settings-s3-endpoint = Endpoint:
+settings-s3-region = Region:
+settings-s3-access-key = Access key ID:
+settings-s3-secret-key = Secret access key:
+settings-s3-signing-endpoint = Signing endpoint (optional):
+settings-s3-path-style = Path-style addressing
+settings-s3-path-style-hover = Put the bucket in the URL path, as MinIO and most self-hosted services expect
+settings-ipfs-gateway = IPFS gateway:
+settings-ipfs-gateway-hover = Gateway used for ipfs:// torrent URLs (experimental). Every block is checked against its CID, so any public gateway will do.
+settings-engine = Transfer files with:
+settings-engine-torrent = Torrent
+settings-engine-rsync = rsync
+settings-engine-rsync-hover = Copy the files from an rsync server over SSH before adding the torrent. The torrent still verifies them and fills in anything rsync missed.
+settings-rsync-source = rsync source:
+settings-mappings = Subfolders
+settings-mappings-hint = Store a top-level folder of the torrent, or the whole torrent, in a subfolder of the download folder. Applies when the torrent is next added.
+settings-mappings-folder = Torrent folder
+settings-mappings-target = Store in
+settings-mappings-whole-torrent = (whole torrent)
+settings-mappings-add = Add subfolder
+settings-filters = Skipped files
+settings-filters-hint = Torrent files that are never downloaded, such as *.wav or /optional/**. A pattern without a / matches file names in any folder, ** any number of folders. With a size, only larger files are skipped. Local copies of skipped files are not reported as extra.
+settings-filters-pattern = Pattern
+settings-filters-min-size = Over (MiB)
+settings-filters-any-size = any size
+settings-filters-add = Add filter
+settings-extract = Archives
+settings-extract-hint = Zip files in the repo to unpack once the download has finished, paths relative to the download folder. Extracted files are not reported as extra.
+settings-extract-archive = Archive
+settings-extract-target = Extract into
+settings-extract-add = Add archive
+settings-keys-dir = Keys folder:
+settings-keys-dir-hover = Folder the mods' .bikey files are copied into after each download, such as the server's keys folder. Keys of removed mods are deleted again; other keys there are left alone.
+settings-hooks = Hooks
+settings-hooks-hint = Shell commands run from the download folder. MODSYNC_HOOK, MODSYNC_DOWNLOAD_PATH and the variables below describe the event.
+settings-hooks-pre-update = Before update:
+settings-hooks-pre-update-hover = Runs before an update is applied, with MODSYNC_TORRENT_BYTES set. A non-zero exit skips the update.
+settings-hooks-post-update = After update:
+settings-hooks-post-update-hover = Runs when an update finishes, with MODSYNC_RESULT, MODSYNC_ERROR, MODSYNC_DOWNLOADED_BYTES, MODSYNC_UPLOADED_BYTES and MODSYNC_CHANGED_FILES set.
+settings-hooks-post-verify = After verify:
+settings-hooks-post-verify-hover = Runs when a verification or repair finishes, with the same variables as after an update.
+settings-script = Script:
+settings-script-hover = Rhai script reacting to sync events, loaded when the daemon starts. Restart the daemon after changing it.
+settings-presets = Bandwidth presets
+settings-presets-hint = Named speed limits to switch between from the header, such as Gaming or Overnight. Leave a limit empty for unlimited.
+settings-presets-name = Name
+settings-presets-name-hint = Gaming
+settings-presets-unlimited = unlimited
+settings-presets-add = Add preset
+settings-gaming = Gaming mode
+settings-gaming-hint = Gaming mode holds downloads, caps uploads to a trickle and hides notices until it is turned off again. The shortcut works while the ModSync window has focus.
+settings-gaming-upload = Upload cap (KB/s):
+settings-gaming-shortcut = Shortcut:
+settings-gaming-shortcut-invalid = Not a shortcut, write it like Ctrl+Shift+G
+settings-power = Metered connections and battery
+settings-power-hint = ModSync checks every minute whether the connection is metered or the computer runs on battery. Low power holds transfers to the caps below. macOS doesn't report metered connections.
+settings-power-metered = On a metered connection:
+settings-power-battery = On battery power:
+settings-power-ignore = Keep syncing
+settings-power-low-power = Low power
+settings-power-pause = Pause
+settings-power-upload = Low-power upload cap (KB/s):
+settings-power-download = Low-power download cap (KB/s):
+settings-check = Background checks
+settings-check-hint = Checking the files of the torrent reads all of them, which can make games stutter. Slow it down here; downloading and seeding are not affected. Applies when the torrent is next added.
+settings-check-max = Read at most (MB/s):
+settings-check-low-priority = Low disk priority
+settings-check-low-priority-hover = Let other programs use the disk first while checking. Only supported on Linux.
+settings-check-fastresume = Skip the check on restart
+settings-check-fastresume-hover = Remember which pieces were verified, so restarting doesn't check every file again. Applies after a restart.
+settings-memory = Memory
+settings-memory-hint = Leave these empty to use the least memory, such as on a NAS with 1-2 GB of RAM. The write buffer, checks at once and hashing threads apply after a restart.
+settings-memory-write-buffer = Write buffer (MiB):
+settings-memory-write-buffer-hover = Buffer downloaded data in memory before writing it, which helps slow disks. Empty writes straight to disk.
+settings-memory-piece-cache = Piece cache (MiB):
+settings-memory-piece-cache-hover = Keep recently downloaded pieces in memory so verifying them doesn't read them back from disk. Empty turns the cache off.
+settings-memory-concurrent-checks = Checks at once:
+settings-memory-concurrent-checks-hover = How many torrents are checked at the same time. Empty uses 1.
+settings-memory-hashing-threads = Hashing threads:
+settings-memory-hashing-threads-hover = How many CPU cores verify downloaded data. Empty uses half of them, leaving the rest for games.
+settings-cache = Torrent cache
+settings-cache-max = Size limit (MiB):
+settings-cache-max-hover = Every torrent version seen is kept in the cache folder; past this size the least recently seen ones are removed. Leave empty for no limit.
+settings-cache-versions = Versions kept:
+settings-cache-versions-hover = How many torrent versions are kept to roll back to; older ones are removed. Leave empty for no limit.
+settings-cache-total = { $count } cached torrents, { $size }
+settings-cache-current = Current
+settings-cache-roll-back = Roll back
+settings-cache-roll-back-hover = Go back to this version. Pin the version to stay on it, or the next check offers the newest one again.
+settings-cache-roll-back-warning = The download folder will be synced to this version. Files that only the newer version has are deleted, apart from the protected paths.
+settings-cache-roll-back-read-only = The download folder will be synced to this version. In read-only mode, files that only the newer version has are kept and reported as extra.
+settings-cache-roll-back-confirm = Roll back
+settings-cache-refresh = Refresh
+settings-cache-clear = Clear cache
+settings-cache-compare = Compare versions
+settings-cache-compare-hover = Show the files a version added, removed or resized compared to another.
+settings-cache-cleared = Removed { $count } cached torrents
+settings-cache-clear-failed = Failed to clear the cache: { $error }
+settings-quarantine = Quarantine
+settings-quarantine-enable = Move deleted files to quarantine
+settings-quarantine-enable-hover = Deleted extra files are moved into a folder in the cache instead, so they can be restored
+settings-quarantine-days = Keep for days:
+settings-quarantine-empty = Nothing in quarantine.
+settings-quarantine-batch = { $count ->
+    [one] 1 file
+   *[other] { $count } files
+}, { $size }
+settings-quarantine-restore = Restore
+settings-quarantine-discard = Delete
+settings-quarantine-restored = { $count ->
+    [one] Restored 1 file
+   *[other] Restored { $count } files
+}
+settings-quarantine-failed = Quarantine action failed: { $error }
+settings-sftp-key = SSH key file:
+settings-sftp-key-hover = Private key for sftp:// torrent URLs and the rsync engine. Leave empty to use the SSH agent or ~/.ssh/config.
+settings-credentials = Logins
+settings-credentials-hint = Used for dav:// and davs:// torrent URLs on the matching host
+settings-credentials-host = Host
+settings-credentials-user = User
+settings-credentials-password = Password
+settings-credentials-add = Add login
+settings-git = Git repository
+settings-git-hint = Cloned into a subfolder of the download folder and pulled on every update check. The cleaner leaves the folder alone.
+settings-git-url = Repository URL
+settings-git-branch = Branch
+settings-git-folder = Folder
+settings-download-path = Download path:
+settings-recent-paths = Recent
+settings-recent-paths-hover = Download folders used before
+settings-shared-store = Shared store:
+settings-shared-store-off = Off
+settings-shared-store-hover = Folder shared with other profiles or users on this computer. Finished files are hard linked into it and reused instead of downloaded again. Must be on the same drive as the download folder.
+settings-seeding = Seeding:
+settings-seed-window = Seed only between:
+settings-seed-window-hover = Outside these hours the finished torrent is paused. Downloads run at any time. Leave empty to seed all day.
+settings-seed-window-invalid = Not a time window, write it like 22:00-08:00
+settings-read-only = Read-only: never delete files
+settings-read-only-hover = Download and repair the mods, but never delete anything in the download folder. Extra files are still listed.
+settings-strict-mirror = Strict mirror: delete extra files after updates
+settings-strict-mirror-hover = For dedicated servers. After each update, files that aren't part of the repo are deleted without asking. Protected paths are never deleted.
+settings-strict-mirror-read-only = Not available in read-only mode
+settings-strict-mirror-warning = After every update, all files in the download folder that aren't part of the repo will be deleted without asking. Add anything to keep to the protected paths first.
+settings-strict-mirror-confirm = Enable strict mirror
+settings-save = Save
+settings-saved = Settings saved
+settings-reset = Reset
+settings-reset-done = Settings reset to defaults
+settings-close = Close
+settings-appearance = Appearance
+settings-theme = Theme:
+settings-theme-dark = Dark
+settings-theme-light = Light
+settings-theme-system = System
+settings-theme-system-hover = Dark or light as your operating system is set, switching along with it.
+settings-custom-accent = Custom accent color
+settings-accent-hover = Accent color
+settings-display = Display
+settings-ui-scale = UI scale:
+settings-font-size = Font size:
+settings-units = Sizes in:
+settings-units-binary = KiB, MiB, GiB
+settings-units-si = kB, MB, GB
+settings-display-reset = Reset display
+settings-notifications = Notifications
+settings-play-sounds = Play sounds
+settings-play-sounds-hover = Play a sound when a sync completes or an update is found.
+settings-quiet-hours = Quiet hours:
+settings-quiet-hours-hover = No sounds or desktop notifications between these times, like 22:00-08:00. Leave empty for none.
+settings-discord = Show activity in Discord
+settings-discord-hover = Show "Syncing … — 72%" or "Seeding 38 GiB" on your Discord profile while Discord is running.
+settings-discord-client-id = Discord application ID:
+settings-discord-client-id-hover = ID of your community's Discord application. Its name is shown as the game you are playing.
+settings-telemetry = Report finished syncs to the admin
+settings-telemetry-hover = When a download finishes, send the version reached, how long it took and how much was transferred, so admins can see how many members are up to date. Reports carry a random id and nothing else about your computer.
+settings-telemetry-endpoint = Report URL:
+settings-telemetry-endpoint-hover = Address your admin collects reports at. Nothing is sent without one.
+settings-load-failed = Failed to load settings: { $error }
+settings-save-failed = Failed to save settings: { $error }
+settings-save-invalid = Fix the torrent URL or download path first
+validate-url-invalid = Not a valid URL, e.g. https://example.com/mods.torrent
+validate-url-scheme = { $scheme }:// URLs aren't supported
+validate-path-empty = Choose a download folder
+validate-path-not-folder = This is a file, not a folder
+validate-path-system = System folders can't be used for downloads
+validate-path-not-writable = This folder can't be written to
+validate-path-space = Not enough free space: { $needed } needed, { $free } free
+settings-reset-failed = Failed to reset settings: { $error }
+
+## Fleet window
+
+fleet-title = Fleet
+fleet-refresh = Refresh
+fleet-check-all = Check all for updates
+fleet-col-node = Node
+fleet-col-status = Status
+fleet-col-version = Version
+fleet-col-progress = Progress
+fleet-col-seed-ratio = Seed ratio
+fleet-col-update = Update
+fleet-update-available = Available
+fleet-offline = Offline
+fleet-remove = Remove
+fleet-remove-hover = Remove { $node } from the fleet
+fleet-node-hover = Control address of a daemon started with --grpc
+fleet-add-node = Add node
+fleet-check-started = Update check started on { $count } nodes
+fleet-check-failed = Update check failed on: { $nodes }
+fleet-no-runtime = No async runtime available for fleet requests
+
+## Keyboard shortcuts
+
+shortcuts-title = Keyboard shortcuts
+shortcut-check-updates = Check for updates
+shortcut-verify = Check local files
+shortcut-pause = Pause or resume
+shortcut-open-folder = Open download folder
+shortcut-open-settings = Open settings
+shortcut-show-shortcuts = Show this list
+shortcut-command-palette = Search commands
+shortcut-gaming-mode = Toggle gaming mode
+shortcut-close-dialog = Close dialog
+shortcut-confirm-dialog = Confirm dialog
+palette-title = Commands
+palette-hint = Type a command…
+palette-no-match = No matching command
+palette-go-to = Go to { $tab }
+palette-preset = Speed preset: { $name }
+
+## Progress bar
+
+progress-overall = Download progress: { $percent }%
+progress-file-tooltip = File { $index }: { $done } / { $total } ({ $percent }%)
+
+## Files tab
+
+app-tab-overview = Overview
+app-tab-files = Files
+files-tree = Tree
+files-list = List
+files-filter = Filter:
+files-filter-hint = Part of a file name or path
+files-filter-clear = Clear the filter
+files-empty = No torrent loaded yet.
+files-count = Showing { $shown } of { $total } files
+files-col-name = Name
+files-col-size = Size
+files-col-progress = Progress
+files-sort-hover = Sort by this column; click again to reverse
+files-transferred = { $done } of { $total } downloaded
+
+## Graph tab
+
+app-tab-graph = Graph
+graph-complete = Complete ({ $count })
+graph-partial = Downloading ({ $count })
+graph-missing = Missing ({ $count })
+graph-extra = Extra ({ $count })
+graph-not-verified = Run a check to find missing and extra files
+graph-reset-view = Reset view
+graph-reset-view-hover = Fit the whole graph in the window
+graph-collapse-all = Collapse all
+graph-expand-all = Expand all
+graph-expand-all-hover = Show every file; may be slow for very large mod packs
+graph-help = Click a folder to collapse or expand it. Drag to pan, scroll to zoom.
+graph-collapsed-label = { $name } ({ $count } files)
+graph-folder-files = { $count } files
+
+## File context menu
+
+menu-open-location = Open containing folder
+menu-copy-path = Copy relative path
+menu-ignore = Protect from cleanup
+menu-ignore-hover = Files at or below this path are never reported as extra files
+menu-reveal-in-graph = Reveal in graph
+settings-ignored = Protected paths
+settings-ignored-empty = No protected paths. Add some from the Files tab context menu.
+settings-ignored-remove = Remove
+settings-banned-peers = Banned peers
+settings-banned-peers-hover = Addresses banned from the Peers tab. Changes apply from the next start of ModSync and need a cache folder.
+settings-banned-peers-empty = No banned peers.
+settings-banned-peers-unban = Unban
+
+## Status bar
+
+statusbar-download = Down { $speed }
+statusbar-upload = Up { $speed }
+statusbar-peers = { $count } peers
+statusbar-private = Private torrent
+statusbar-private-hover = The torrent is marked private, so peers only come from its tracker. DHT and peer exchange are off for it.
+statusbar-swarm = Swarm: { $seeders } seeders, { $leechers } leechers
+statusbar-swarm-hover = As the torrent's trackers report it. Completed downloads: { $downloaded }
+statusbar-free-space = { $free } free
+statusbar-free-space-hover = Free space on the drive holding the download folder
+statusbar-last-check = Last checked { $elapsed } ago
+statusbar-remote-source = Served by { $url }
+statusbar-never-checked = Not checked for updates yet
+statusbar-next-check = Next check in { $remaining }
+statusbar-check-now = Check now
+statusbar-check-now-hover = Check the saved torrent URL for updates right away
+statusbar-git = Git { $commit }
+statusbar-git-failed = Git { $commit } (pull failed)
+
+## Lifetime statistics
+stats-title = Statistics
+stats-downloaded = Downloaded (all time)
+stats-uploaded = Uploaded (all time)
+stats-ratio = Share ratio
+stats-ratio-hover = Uploaded divided by downloaded bytes across all sessions
+storage-title = Storage
+storage-repo = Synced repo
+storage-extra = Extra files
+storage-extra-hover = Files that are not part of the repo and not protected; the cleaner offers to delete them
+storage-protected = Protected files
+storage-protected-hover = Ignored paths, extracted archives and other files the cleaner leaves alone
+storage-free = Free on volume
+storage-scanned = Scanned { $elapsed } ago
+
+## History tab
+app-tab-history = History
+history-export = Export CSV
+history-exported = History exported to { $path }
+history-export-failed = Failed to export history: { $error }
+history-empty = No sync runs recorded yet
+history-col-started = Started
+history-col-trigger = Trigger
+history-col-duration = Duration
+history-col-transferred = Down / Up
+history-col-files = Files changed
+history-col-result = Result
+history-trigger-check = Update check
+history-trigger-scheduled = Scheduled check
+history-trigger-update = Apply update
+history-trigger-verify = Verify
+history-trigger-repair = Repair
+history-result-ok = OK
+history-result-failed = Failed
+history-result-running = Running
+
+## Peers tab
+app-tab-peers = Peers
+peers-hint = Peers connected for the torrent. Only what each peer sent is counted; librqbit doesn't count uploads per peer. A banned address is refused from the next start of ModSync.
+peers-empty = No peers connected
+peers-col-address = Address
+peers-col-downloaded = Downloaded
+peers-col-connections = Connections
+peers-col-errors = Errors
+peers-ban = Ban
+peers-ban-hover = Refuse this address from the next start on. Unban it in the settings.
+peers-banned = Banned
+
+## Trackers tab
+app-tab-trackers = Trackers
+app-tab-log = Log
+log-empty = Nothing logged yet.
+log-detached = The log is shown in its own window.
+popout-hover = Show in a separate window
+popout-attach = Bring back
+trackers-empty = The torrent has no trackers. Peers come from DHT only.
+trackers-col-url = Announce URL
+trackers-col-source = Source
+trackers-source-torrent = Torrent
+trackers-source-config = Settings
+trackers-replaced = Replaced
+
+## Version diff
+version-diff-title = Compare versions
+version-diff-old = Older version:
+version-diff-new = Newer version:
+version-diff-no-versions = At least two torrent versions have to be cached to compare them.
+version-diff-failed = Failed to compare the versions: { $error }
+version-diff-none = The versions have the same files ({ $unchanged }).
+version-diff-summary = { $added } added, { $removed } removed, { $resized } resized, { $unchanged } unchanged. Size change: { $size }
+version-diff-added = Added ({ $count })
+version-diff-removed = Removed ({ $count })
+version-diff-resized = Resized ({ $count })
+
+## Remote endpoint health
+health-unknown = Not checked yet
+health-good = Reachable
+health-degraded = Reachable, but recently slow or failing
+health-down = Last check failed
+health-latency = Last response in { $ms } ms
+health-last-error = Last error: { $error }
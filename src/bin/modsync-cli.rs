@@ -1,7 +1,164 @@
+use anyhow::{anyhow, Context, Result};
 use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    println!("modsync-cli: called with args: {:?}", args);
-    println!("This is a placeholder CLI. No actions implemented yet.");
+use modsync::settings::AppSettings;
+use modsync::sync::create::{create_torrent, CreateOptions};
+use modsync::sync::journal::{describe_event, describe_record, replay_commands, EventJournal, JOURNAL_FILE_NAME};
+use modsync::sync::{memory, run_sync_manager, Backends, SyncConfig};
+use modsync::ModSyncClient;
+
+const USAGE: &str = "Usage:
+  modsync-cli [--config-dir <DIR>] daemon [--grpc <ADDR>]
+  modsync-cli [--config-dir <DIR>] journal dump [JOURNAL]
+  modsync-cli [--config-dir <DIR>] journal replay [JOURNAL] --output <DIR>
+  modsync-cli create <FOLDER> --output <FILE> [--tracker <URL>] [--private]
+
+The data directory defaults to $MODSYNC_CONFIG_DIR, else the executable's.";
+
+fn main() -> Result<()> {
+    modsync::crash::install_hook("cli");
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    AppSettings::apply_config_dir_arg(&mut args).with_context(|| USAGE)?;
+    // librqbit hashes on the runtime's threads, so size it by the settings
+    let hashing_threads = AppSettings::load().map(|s| s.hashing_threads).unwrap_or_default();
+    memory::runtime(hashing_threads)
+        .context("Failed to build tokio runtime")?
+        .block_on(run(args))
+}
+
+async fn run(args: Vec<String>) -> Result<()> {
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["daemon", rest @ ..] => daemon(option_value(rest, "--grpc")).await,
+        ["journal", "dump", rest @ ..] => journal_dump(journal_path(rest)?),
+        ["journal", "replay", rest @ ..] => {
+            let output = option_value(rest, "--output")
+                .ok_or_else(|| anyhow!("journal replay requires --output <DIR>\n{}", USAGE))?;
+            journal_replay(journal_path(rest)?, PathBuf::from(output)).await
+        }
+        ["create", folder, rest @ ..] if !folder.starts_with("--") => {
+            let output = option_value(rest, "--output").ok_or_else(|| anyhow!("create requires --output <FILE>\n{}", USAGE))?;
+            let options = CreateOptions { tracker: option_value(rest, "--tracker"), private: rest.contains(&"--private") };
+            create(PathBuf::from(folder), PathBuf::from(output), options).await
+        }
+        _ => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+/// First positional argument, or the journal in the application data dir.
+fn journal_path(args: &[&str]) -> Result<PathBuf> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg.starts_with("--") {
+            iter.next();
+            continue;
+        }
+        return Ok(PathBuf::from(arg));
+    }
+    Ok(AppSettings::data_dir()?.join(JOURNAL_FILE_NAME))
+}
+
+fn option_value<'a>(args: &[&'a str], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| *a == name)
+        .and_then(|i| args.get(i + 1).copied())
+}
+
+/// Run headless with the saved settings, logging events to stdout. The GUI
+/// attaches over local IPC; the gRPC control interface is optional.
+async fn daemon(grpc_addr: Option<&str>) -> Result<()> {
+    let settings = AppSettings::load()?;
+    let journal = EventJournal::open(AppSettings::data_dir()?.join(JOURNAL_FILE_NAME))?;
+    let client = Arc::new(ModSyncClient::with_journal(settings.to_sync_config()?, Some(journal)).await?);
+    let _logger = client.on_event(|event| println!("event {}", describe_event(&event)));
+    let ipc = tokio::spawn(modsync::ipc::serve(Arc::clone(&client), modsync::ipc::endpoint()?));
+    client.check_remote().await?;
+
+    let grpc = async {
+        match grpc_addr {
+            Some(addr) => {
+                let addr = addr.parse().with_context(|| format!("Invalid gRPC listen address: {}", addr))?;
+                serve_grpc(Arc::clone(&client), addr).await
+            }
+            None => std::future::pending().await,
+        }
+    };
+
+    let result = tokio::select! {
+        res = ipc => res.context("IPC server task failed").and_then(|res| res),
+        res = grpc => res,
+        res = tokio::signal::ctrl_c() => res.context("Failed to wait for Ctrl+C"),
+    };
+    client.save_lifetime_stats();
+    client.release_session_lock();
+    result
+}
+
+#[cfg(feature = "grpc")]
+async fn serve_grpc(client: Arc<ModSyncClient>, addr: std::net::SocketAddr) -> Result<()> {
+    modsync::grpc::serve(client, addr).await
+}
+
+#[cfg(not(feature = "grpc"))]
+async fn serve_grpc(_client: Arc<ModSyncClient>, _addr: std::net::SocketAddr) -> Result<()> {
+    Err(anyhow!("modsync-cli was built without the grpc feature"))
+}
+
+/// Write the torrent of `folder` to `output`.
+async fn create(folder: PathBuf, output: PathBuf, options: CreateOptions<'_>) -> Result<()> {
+    if options.private && options.tracker.is_none() {
+        eprintln!("Warning: a private torrent without --tracker can't find any peers");
+    }
+    let private = options.private;
+    let torrent = create_torrent(&folder, options).await?;
+    tokio::fs::write(&output, &torrent)
+        .await
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    println!("Created {}{} ({} bytes)", output.display(), if private { ", private" } else { "" }, torrent.len());
+    Ok(())
+}
+
+fn journal_dump(path: PathBuf) -> Result<()> {
+    let entries = EventJournal::read_entries(&path)?;
+    for entry in &entries {
+        println!("{} {}", entry.timestamp, describe_record(&entry.record));
+    }
+    println!("{} entries in {}", entries.len(), path.display());
+    Ok(())
+}
+
+/// Feed the recorded commands into a fresh manager backed by a throwaway
+/// session writing to `output`, printing the events it produces. Replayed
+/// commands are confined to `output`.
+async fn journal_replay(path: PathBuf, output: PathBuf) -> Result<()> {
+    let entries = EventJournal::read_entries(&path)?;
+    tokio::fs::create_dir_all(&output)
+        .await
+        .with_context(|| format!("Failed to create output dir: {}", output.display()))?;
+
+    let config = SyncConfig {
+        download_path: output.clone(),
+        ..Default::default()
+    };
+    let session = librqbit::Session::new(output).await.context("Failed to create librqbit session")?;
+    let api = librqbit::Api::new(Arc::clone(&session), None);
+
+    let (ui_tx, mut ui_rx) = mpsc::unbounded_channel();
+    let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+    tokio::spawn(run_sync_manager(api, config.clone(), ui_tx, cmd_rx, None, Arc::new(Backends::default())));
+
+    let sent = replay_commands(&entries, &config, &cmd_tx)?;
+    println!("Replaying {} commands from {}", sent, path.display());
+
+    // Print events until the manager has been quiet for a few seconds
+    while let Ok(Some(event)) = tokio::time::timeout(Duration::from_secs(5), ui_rx.recv()).await {
+        println!("event {}", describe_event(&event));
+    }
+    Ok(())
 }
@@ -1,4 +1,9 @@
 fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = modsync::settings::AppSettings::apply_config_dir_arg(&mut args) {
+        eprintln!("UI: {:#}", e);
+        std::process::exit(2);
+    }
     // Start the graphical UI on launch
     modsync::ui::run_ui();
 }
@@ -0,0 +1,194 @@
+// src/blocking.rs
+
+//! Synchronous wrappers around `ModSyncClient` for scripts and other callers
+//! that don't run an async runtime. Each call drives its own tokio runtime.
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+
+use crate::client::ModSyncClient;
+use crate::sync::memory;
+use crate::sync::stall::{self, StallChange, StallDetector};
+use crate::sync::status::SyncStatus;
+use crate::sync::{SyncConfig, SyncError, SyncEvent};
+
+/// Outcome of a `sync_once` run.
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    /// The remote torrent differed from the cached copy.
+    pub update_found: bool,
+    /// The new torrent was added to the session.
+    pub update_applied: bool,
+    /// Bytes present locally once the torrent completed.
+    pub progress_bytes: u64,
+    pub total_bytes: u64,
+    /// Files listed in the torrent but absent from the download path.
+    pub missing_files: HashSet<PathBuf>,
+    /// Files in the download path that are not part of the torrent.
+    pub extra_files: Vec<PathBuf>,
+    /// Errors reported by the manager along the way.
+    pub errors: Vec<SyncError>,
+}
+
+impl SyncReport {
+    pub fn is_success(&self) -> bool {
+        self.errors.is_empty() && self.missing_files.is_empty()
+    }
+}
+
+/// How often progress is checked while waiting for a download.
+const PROGRESS_POLL: Duration = Duration::from_secs(1);
+
+/// Build the runtime each blocking call drives, sized for `config`'s
+/// hashing threads.
+fn runtime(config: &SyncConfig) -> Result<tokio::runtime::Runtime> {
+    memory::runtime(config.hashing_threads).context("Failed to build tokio runtime")
+}
+
+/// Check the remote, apply any update, wait for the download to finish and
+/// verify the folder, all before returning. A download that makes no
+/// progress for the stall time of `config` is given up on, with the error
+/// in the report; with stall detection off it is waited for however long
+/// it takes.
+pub fn sync_once(config: SyncConfig) -> Result<SyncReport> {
+    runtime(&config)?.block_on(sync_once_async(config))
+}
+
+async fn sync_once_async(config: SyncConfig) -> Result<SyncReport> {
+    let client = ModSyncClient::new(config).await?;
+    // Shut down whatever the outcome, so the session lock is left clean
+    let report = run_once(&client).await;
+    client.shutdown().await;
+    report
+}
+
+async fn run_once(client: &ModSyncClient) -> Result<SyncReport> {
+    let mut events = client.subscribe_events();
+    let mut report = SyncReport::default();
+
+    // 1. Remote check
+    if !client.config().torrent_url.is_empty() {
+        client.check_remote().await?;
+        let mut checking = false;
+        while let Some(event) = next_event(&mut events).await {
+            match event {
+                SyncEvent::RemoteUpdateFound(torrent, _) => {
+                    report.update_found = true;
+                    client.apply_update(torrent).await?;
+                    break;
+                }
+                SyncEvent::StatusUpdate(SyncStatus::CheckingRemote) => checking = true,
+                SyncEvent::StatusUpdate(SyncStatus::Idle) if checking => break,
+                SyncEvent::Error(err) => {
+                    report.errors.push(err);
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // 2. Wait for the new torrent to be added
+    if report.update_found {
+        while let Some(event) = next_event(&mut events).await {
+            match event {
+                SyncEvent::TorrentAdded(_) => {
+                    report.update_applied = true;
+                    break;
+                }
+                SyncEvent::Error(err) => {
+                    report.errors.push(err);
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // 3. Download whatever the session manages to completion, once the
+    // cached torrent is back in it
+    client.wait_restored().await;
+    let session = client.session().clone();
+    let handles = session.with_torrents(|iter| iter.map(|(_, h)| h.clone()).collect::<Vec<_>>());
+    let config = client.config();
+    let mut gave_up = false;
+    for handle in &handles {
+        if handle.is_paused() {
+            session.unpause(handle).await.context("Failed to start torrent")?;
+        }
+        let stalled = wait_completed(&config, handle).await?;
+        let stats = handle.stats();
+        report.progress_bytes += stats.progress_bytes;
+        report.total_bytes += stats.total_bytes;
+        if let Some(stalled_for) = stalled {
+            gave_up = true;
+            report.errors.push(SyncError::Network(format!(
+                "Download made no progress for {} minutes, giving up",
+                stalled_for.as_secs() / 60
+            )));
+        }
+    }
+
+    // 4. Verify the folder (needs an active torrent that finished)
+    if !handles.is_empty() && !gave_up {
+        client.verify().await?;
+        while let Some(event) = next_event(&mut events).await {
+            match event {
+                SyncEvent::MissingFilesFound(missing) => report.missing_files = missing,
+                SyncEvent::ExtraFilesFound(extra) => {
+                    report.extra_files = extra;
+                    break;
+                }
+                SyncEvent::Error(err) => {
+                    report.errors.push(err);
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Wait for `handle` to complete. Returns how long it went without
+/// progress instead if that reached the stall time of `config`.
+async fn wait_completed(config: &SyncConfig, handle: &librqbit::ManagedTorrent) -> Result<Option<Duration>> {
+    let completed = handle.wait_until_completed();
+    let Some(after) = stall::stall_after(config) else {
+        completed.await.context("Torrent failed before completing")?;
+        return Ok(None);
+    };
+    tokio::pin!(completed);
+    let mut detector = StallDetector::default();
+    let mut poll = tokio::time::interval(PROGRESS_POLL);
+    loop {
+        tokio::select! {
+            result = &mut completed => {
+                result.context("Torrent failed before completing")?;
+                return Ok(None);
+            }
+            _ = poll.tick() => {
+                let stats = handle.stats();
+                let downloading = matches!(stats.state, librqbit::TorrentStatsState::Live) && !stats.finished;
+                if let Some(StallChange::Stalled(stalled_for)) = detector.update(downloading, stats.progress_bytes, Instant::now(), after) {
+                    return Ok(Some(stalled_for));
+                }
+            }
+        }
+    }
+}
+
+async fn next_event(events: &mut broadcast::Receiver<SyncEvent>) -> Option<SyncEvent> {
+    loop {
+        match events.recv().await {
+            Ok(event) => return Some(event),
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
@@ -0,0 +1,553 @@
+// src/client.rs
+
+//! High-level embedding API.
+//!
+//! `ModSyncClient` owns a librqbit session and the sync manager task, and
+//! exposes the command surface as methods so other applications can drive
+//! ModSync without the egui UI or the raw channel plumbing.
+
+use anyhow::{anyhow, Context, Result};
+use futures::Stream;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::sync::journal::EventJournal;
+use crate::sync::memory;
+use crate::sync::power::PowerRestriction;
+use crate::sync::stall::StallDiagnosis;
+use crate::sync::swarm::SwarmHealth;
+use crate::sync::trackers::TrackerInfo;
+use crate::sync::recovery::{self, SessionLock};
+use crate::sync::status::SyncStatus;
+use crate::sync::{
+    run_sync_manager, Backends, DiskUsage, EndpointHealth, Inconsistency, GitSourceStatus, LifetimeCounter, LifetimeStats, SyncCommand, SyncConfig, SyncEvent, SyncHistory,
+    SyncStats, TorrentFile, TorrentSnapshot,
+};
+
+/// Number of events buffered per subscriber before the oldest are dropped.
+const EVENT_BUFFER: usize = 256;
+
+/// Minimum time between writes of the lifetime totals while transferring.
+const LIFETIME_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Latest values seen on the event channel.
+#[derive(Debug, Default)]
+struct TrackedState {
+    status: SyncStatus,
+    stats: Option<TorrentSnapshot>,
+    /// Id of the managed torrent in the session.
+    torrent_id: Option<usize>,
+    files: Vec<TorrentFile>,
+    trackers: Vec<TrackerInfo>,
+    pending_update: Option<Vec<u8>>,
+    last_remote_check: Option<chrono::DateTime<chrono::Utc>>,
+    lifetime: LifetimeCounter,
+    lifetime_saved: Option<Instant>,
+    history: SyncHistory,
+    next_check_at: Option<String>,
+    health: EndpointHealth,
+    git_source: Option<GitSourceStatus>,
+    disk_usage: Option<DiskUsage>,
+    inconsistencies: Vec<Inconsistency>,
+    gaming_mode: bool,
+    power: Option<PowerRestriction>,
+    swarm: Option<SwarmHealth>,
+    stalled: Option<StallDiagnosis>,
+}
+
+pub struct ModSyncClient {
+    config: Mutex<SyncConfig>,
+    session: Arc<librqbit::Session>,
+    cmd_tx: mpsc::UnboundedSender<SyncCommand>,
+    events_tx: broadcast::Sender<SyncEvent>,
+    tracked: Arc<Mutex<TrackedState>>,
+    /// Turns `true` once the manager has restored the cached torrent.
+    restored: watch::Receiver<bool>,
+    session_lock: Option<SessionLock>,
+    /// What was done about an unclean shutdown of the previous session.
+    recovery: Option<String>,
+    manager_task: JoinHandle<()>,
+    #[cfg(feature = "scripting")]
+    script_task: Option<JoinHandle<()>>,
+}
+
+impl ModSyncClient {
+    /// Start a session for `config` and spawn the sync manager. If the config
+    /// names a cached torrent that exists, the manager adds it once it runs,
+    /// so this returns without waiting for the torrent to be checked.
+    pub async fn new(config: SyncConfig) -> Result<Self> {
+        Self::with_journal(config, None).await
+    }
+
+    /// Like `new`, additionally recording commands and events to `journal`.
+    pub async fn with_journal(config: SyncConfig, journal: Option<EventJournal>) -> Result<Self> {
+        if config.download_path.as_os_str().is_empty() {
+            return Err(anyhow!("Download path not configured"));
+        }
+        tokio::fs::create_dir_all(&config.download_path)
+            .await
+            .with_context(|| format!("Failed to create download path: {}", config.download_path.display()))?;
+
+        // The previous session may have left fastresume state that doesn't
+        // match the files, so it must go before the new session loads it
+        let session_lock = acquire_session_lock(&config);
+        let recovery = session_lock.as_ref().and_then(|lock| lock.previous.as_ref()).map(|previous| {
+            let discarded = recovery::discard_fastresume(&config).unwrap_or_else(|e| {
+                eprintln!("Client: {:#}", e);
+                false
+            });
+            format!(
+                "ModSync didn't shut down cleanly last time ({}).{} The download folder is verified once the torrent is loaded.",
+                previous,
+                if discarded { " Its fastresume state was discarded, so every file is checked again." } else { "" }
+            )
+        });
+
+        let session = librqbit::Session::new_with_opts(config.download_path.clone(), memory::session_options(&config))
+            .await
+            .context("Failed to create librqbit session")?;
+        let api = librqbit::Api::new(Arc::clone(&session), None);
+
+        let (ui_tx, mut ui_rx) = mpsc::unbounded_channel();
+        let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+        let (events_tx, _) = broadcast::channel(EVENT_BUFFER);
+
+        let backends = Arc::new(Backends::default());
+
+        // Track the latest state and fan manager events out to every subscriber
+        let fanout_tx = events_tx.clone();
+        let lifetime = match &config.lifetime_stats_path {
+            Some(path) => LifetimeStats::load(path).unwrap_or_else(|e| {
+                eprintln!("Client: Failed to load lifetime stats, starting from zero: {:#}", e);
+                LifetimeStats::default()
+            }),
+            None => LifetimeStats::default(),
+        };
+        let tracked = Arc::new(Mutex::new(TrackedState {
+            lifetime: LifetimeCounter::new(lifetime),
+            ..TrackedState::default()
+        }));
+        let fanout_tracked = Arc::clone(&tracked);
+        let (restored_tx, restored) = watch::channel(false);
+        let lifetime_path = config.lifetime_stats_path.clone();
+        tokio::spawn(async move {
+            while let Some(event) = ui_rx.recv().await {
+                {
+                    let mut tracked = fanout_tracked.lock().unwrap();
+                    match &event {
+                        SyncEvent::StatusUpdate(status) => {
+                            if *status == SyncStatus::CheckingRemote {
+                                tracked.last_remote_check = Some(chrono::Utc::now());
+                            }
+                            tracked.status = status.clone();
+                        }
+                        SyncEvent::ManagedTorrentUpdate(update) => {
+                            tracked.stats = update.as_ref().map(|(_, stats)| stats.clone());
+                            tracked.torrent_id = update.as_ref().map(|(id, _)| *id);
+                            if let Some((id, stats)) = update
+                                && tracked.lifetime.observe(*id, stats)
+                                && let Some(path) = &lifetime_path
+                                && tracked.lifetime_saved.is_none_or(|t| t.elapsed() >= LIFETIME_SAVE_INTERVAL)
+                            {
+                                tracked.lifetime_saved = Some(Instant::now());
+                                save_lifetime(path, tracked.lifetime.totals());
+                            }
+                        }
+                        SyncEvent::RemoteUpdateFound(torrent, _) => tracked.pending_update = Some(torrent.clone()),
+                        SyncEvent::TorrentAdded(_) => {
+                            tracked.pending_update = None;
+                            tracked.swarm = None;
+                            tracked.stalled = None;
+                        }
+                        SyncEvent::Stalled(stalled) => tracked.stalled = stalled.clone(),
+                        SyncEvent::Swarm(swarm) => tracked.swarm = *swarm,
+                        SyncEvent::TorrentFiles(files) => tracked.files = files.clone(),
+                        SyncEvent::Trackers(trackers) => tracked.trackers = trackers.clone(),
+                        SyncEvent::NextCheckAt(at) => tracked.next_check_at = at.clone(),
+                        SyncEvent::RemoteAttempt(attempt) => tracked.health.record(attempt.clone()),
+                        SyncEvent::GitSourceStatus(status) => tracked.git_source = Some(status.clone()),
+                        SyncEvent::DiskUsage(usage) => tracked.disk_usage = Some(usage.clone()),
+                        SyncEvent::ConsistencyCheck(problems) => tracked.inconsistencies = problems.clone(),
+                        SyncEvent::GamingMode(enabled) => tracked.gaming_mode = *enabled,
+                        SyncEvent::PowerRestriction(power) => tracked.power = *power,
+                        SyncEvent::SessionRestored => {
+                            restored_tx.send_replace(true);
+                        }
+                        _ => {}
+                    }
+                    let totals = tracked.lifetime.totals();
+                    tracked.history.observe(&event, totals);
+                }
+                let _ = fanout_tx.send(event);
+            }
+        });
+
+        if let Some(message) = &recovery {
+            eprintln!("Client: {}", message);
+            let _ = ui_tx.send(SyncEvent::Notice(message.clone()));
+            // Queued behind the restore of the cached torrent
+            if config.cached_torrent_path.as_ref().is_some_and(|cached| cached.exists()) {
+                let _ = cmd_tx.send(SyncCommand::VerifyFolder);
+            }
+        }
+
+        let manager_config = config.clone();
+        let manager_task = tokio::spawn(async move {
+            if let Err(e) = run_sync_manager(api, manager_config, ui_tx, cmd_rx, journal, backends).await {
+                eprintln!("Sync: Manager stopped with error: {}", e);
+            }
+        });
+
+        #[cfg(feature = "scripting")]
+        let script_task = match &config.script_path {
+            Some(path) => match crate::sync::scripting::spawn(
+                path,
+                events_tx.subscribe(),
+                events_tx.clone(),
+                cmd_tx.clone(),
+                config.torrent_url.clone(),
+            ) {
+                Ok(task) => Some(task),
+                Err(e) => {
+                    eprintln!("Client: {:#}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            config: Mutex::new(config),
+            session,
+            cmd_tx,
+            events_tx,
+            tracked,
+            restored,
+            session_lock,
+            recovery,
+            manager_task,
+            #[cfg(feature = "scripting")]
+            script_task,
+        })
+    }
+
+    /// Wait until the manager has added the cached torrent, if any. Commands
+    /// sent before are handled after it anyway; this is for callers that use
+    /// the session directly.
+    pub async fn wait_restored(&self) {
+        let mut restored = self.restored.clone();
+        let _ = restored.wait_for(|restored| *restored).await;
+    }
+
+    pub fn config(&self) -> SyncConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Status from the most recent `StatusUpdate` event.
+    pub fn status(&self) -> SyncStatus {
+        self.tracked.lock().unwrap().status.clone()
+    }
+
+    /// Torrent bytes from the last `RemoteUpdateFound` event that has not
+    /// been applied yet.
+    pub fn pending_update(&self) -> Option<Vec<u8>> {
+        self.tracked.lock().unwrap().pending_update.clone()
+    }
+
+    /// Torrent stats from the most recent `ManagedTorrentUpdate` event.
+    pub fn last_torrent_stats(&self) -> Option<TorrentSnapshot> {
+        self.tracked.lock().unwrap().stats.clone()
+    }
+
+    /// Live progress, speeds and per-file progress of the managed torrent,
+    /// read from the session now rather than the last event. `None` while
+    /// no torrent is loaded.
+    pub fn stats(&self) -> Option<SyncStats> {
+        let (id, last, files) = {
+            let tracked = self.tracked.lock().unwrap();
+            (tracked.torrent_id, tracked.stats.clone(), tracked.files.clone())
+        };
+        let live = id.and_then(|id| self.session.get(id.into())).map(|handle| TorrentSnapshot::from(&handle.stats()));
+        live.or(last).map(|snapshot| SyncStats::new(&snapshot, &files))
+    }
+
+    /// When the manager last started checking the remote torrent.
+    pub fn last_remote_check(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.tracked.lock().unwrap().last_remote_check
+    }
+
+    /// RFC 3339 time of the next periodic remote check, from the most recent
+    /// `NextCheckAt` event.
+    pub fn next_check_at(&self) -> Option<String> {
+        self.tracked.lock().unwrap().next_check_at.clone()
+    }
+
+    /// Outcome of recent torrent downloads per remote URL.
+    pub fn endpoint_health(&self) -> EndpointHealth {
+        self.tracked.lock().unwrap().health.clone()
+    }
+
+    /// Outcome of the latest pull of the git source, from the most recent
+    /// `GitSourceStatus` event.
+    pub fn git_source_status(&self) -> Option<GitSourceStatus> {
+        self.tracked.lock().unwrap().git_source.clone()
+    }
+
+    /// Latest breakdown of the download folder's size, from the most
+    /// recent `DiskUsage` event.
+    pub fn disk_usage(&self) -> Option<DiskUsage> {
+        self.tracked.lock().unwrap().disk_usage.clone()
+    }
+
+    /// Problems from the latest consistency check of the loaded torrent.
+    pub fn inconsistencies(&self) -> Vec<Inconsistency> {
+        self.tracked.lock().unwrap().inconsistencies.clone()
+    }
+
+    /// What was done on start about an unclean shutdown of the previous
+    /// session, if there was one.
+    pub fn recovery(&self) -> Option<String> {
+        self.recovery.clone()
+    }
+
+    /// Mark this session as shut down cleanly, so the next start trusts its
+    /// fastresume state. `shutdown` does this; processes that exit without
+    /// it call this first.
+    pub fn release_session_lock(&self) {
+        if let Some(lock) = &self.session_lock {
+            lock.release();
+        }
+    }
+
+    /// Whether gaming mode is on.
+    pub fn gaming_mode(&self) -> bool {
+        self.tracked.lock().unwrap().gaming_mode
+    }
+
+    /// Restriction in force for a metered connection or battery power.
+    pub fn power_restriction(&self) -> Option<PowerRestriction> {
+        self.tracked.lock().unwrap().power
+    }
+
+    /// Bytes downloaded and uploaded across all sessions, including this one.
+    pub fn lifetime_stats(&self) -> LifetimeStats {
+        self.tracked.lock().unwrap().lifetime.totals()
+    }
+
+    /// Recent sync runs, built from the manager's run boundary events.
+    pub fn history(&self) -> SyncHistory {
+        self.tracked.lock().unwrap().history.clone()
+    }
+
+    /// Write the lifetime totals to the configured stats file, if any. They
+    /// are otherwise only saved periodically while transferring.
+    pub fn save_lifetime_stats(&self) {
+        if let Some(path) = &self.config().lifetime_stats_path {
+            save_lifetime(path, self.lifetime_stats());
+        }
+    }
+
+    /// Files of the managed torrent from the most recent `TorrentFiles` event.
+    pub fn torrent_files(&self) -> Vec<TorrentFile> {
+        self.tracked.lock().unwrap().files.clone()
+    }
+
+    /// Why the download stalled, `None` while it is moving.
+    pub fn stalled(&self) -> Option<StallDiagnosis> {
+        self.tracked.lock().unwrap().stalled.clone()
+    }
+
+    /// Swarm of the managed torrent from the latest tracker scrape.
+    pub fn swarm(&self) -> Option<SwarmHealth> {
+        self.tracked.lock().unwrap().swarm
+    }
+
+    /// Trackers of the managed torrent from the most recent `Trackers` event.
+    pub fn trackers(&self) -> Vec<TrackerInfo> {
+        self.tracked.lock().unwrap().trackers.clone()
+    }
+
+    /// The underlying librqbit session, for consumers that need engine access.
+    pub fn session(&self) -> &Arc<librqbit::Session> {
+        &self.session
+    }
+
+    /// Raw command channel, for callers that need to send commands from their
+    /// own tasks.
+    pub fn command_sender(&self) -> mpsc::UnboundedSender<SyncCommand> {
+        self.cmd_tx.clone()
+    }
+
+    /// Receive every event emitted by the manager from now on.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SyncEvent> {
+        self.events_tx.subscribe()
+    }
+
+    /// Events as a `Stream`, ending when the manager stops. A subscriber that
+    /// falls more than the buffer size behind skips the missed events.
+    pub fn event_stream(&self) -> impl Stream<Item = SyncEvent> + Send + 'static {
+        futures::stream::unfold(self.subscribe_events(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(RecvError::Lagged(skipped)) => {
+                        eprintln!("Client: Event stream lagged, skipped {} events", skipped);
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Invoke `callback` for every event on a background task. Abort the
+    /// returned handle to unregister.
+    pub fn on_event<F>(&self, mut callback: F) -> JoinHandle<()>
+    where
+        F: FnMut(SyncEvent) + Send + 'static,
+    {
+        let stream = self.event_stream();
+        tokio::spawn(async move {
+            futures::pin_mut!(stream);
+            while let Some(event) = futures::StreamExt::next(&mut stream).await {
+                callback(event);
+            }
+        })
+    }
+
+    /// Download the remote torrent and compare it with the cached copy. A
+    /// `RemoteUpdateFound` event carries the new torrent if it changed.
+    pub async fn check_remote(&self) -> Result<()> {
+        self.send(SyncCommand::DownloadAndCompare(self.config().torrent_url))
+    }
+
+    /// Replace the managed torrent with `torrent_content`, typically the bytes
+    /// from a `RemoteUpdateFound` event.
+    pub async fn apply_update(&self, torrent_content: Vec<u8>) -> Result<()> {
+        self.send(SyncCommand::ApplyUpdate(torrent_content))
+    }
+
+    /// Go back to `torrent_content`, an older version from the torrent
+    /// cache, deleting the files of the newer version.
+    pub async fn roll_back(&self, torrent_content: Vec<u8>) -> Result<()> {
+        self.send(SyncCommand::RollBack(torrent_content))
+    }
+
+    /// Check the download folder for missing and extra files.
+    pub async fn verify(&self) -> Result<()> {
+        self.send(SyncCommand::VerifyFolder)
+    }
+
+    pub async fn fix_missing_files(&self) -> Result<()> {
+        self.send(SyncCommand::FixMissingFiles)
+    }
+
+    /// Hash every file of the torrent again, as if it had never been checked.
+    pub async fn force_recheck(&self) -> Result<()> {
+        self.send(SyncCommand::ForceRecheck)
+    }
+
+    /// Stop managing the torrent, leaving its files in the download folder.
+    pub async fn forget_torrent(&self) -> Result<()> {
+        self.send(SyncCommand::ForgetTorrent)
+    }
+
+    /// Stop managing the torrent and delete its files and cached versions.
+    pub async fn remove_torrent_and_data(&self) -> Result<()> {
+        self.send(SyncCommand::RemoveTorrentAndData)
+    }
+
+    /// Stop downloading and seeding the managed torrent.
+    pub async fn pause(&self) -> Result<()> {
+        self.send(SyncCommand::SetPaused(true))
+    }
+
+    pub async fn resume(&self) -> Result<()> {
+        self.send(SyncCommand::SetPaused(false))
+    }
+
+    /// Change the speed limits in KB/s while the torrent runs; `None` is
+    /// unlimited.
+    pub async fn set_speed_limits(&self, max_upload_speed: Option<u32>, max_download_speed: Option<u32>) -> Result<()> {
+        self.send(SyncCommand::SetSpeedLimits {
+            max_upload_speed,
+            max_download_speed,
+        })
+    }
+
+    /// Turn gaming mode on or off, see `SyncCommand::SetGamingMode`.
+    pub async fn set_gaming_mode(&self, enabled: bool) -> Result<()> {
+        self.send(SyncCommand::SetGamingMode(enabled))
+    }
+
+    pub async fn delete_files(&self, files: Vec<std::path::PathBuf>) -> Result<()> {
+        self.send(SyncCommand::DeleteFiles(files))
+    }
+
+    /// Apply a new config to subsequent operations.
+    pub async fn update_config(&self, config: SyncConfig) -> Result<()> {
+        *self.config.lock().unwrap() = config.clone();
+        self.send(SyncCommand::UpdateConfig(Box::new(config)))
+    }
+
+    /// Stop the manager task and the session, saving the lifetime totals.
+    /// A client dropped without this still leaves the session lock clean,
+    /// but the session isn't stopped and the totals aren't saved.
+    pub async fn shutdown(mut self) {
+        self.save_lifetime_stats();
+        self.release_session_lock();
+        #[cfg(feature = "scripting")]
+        if let Some(task) = &self.script_task {
+            task.abort();
+        }
+        self.manager_task.abort();
+        let _ = (&mut self.manager_task).await;
+        self.session.stop().await;
+    }
+
+    fn send(&self, cmd: SyncCommand) -> Result<()> {
+        self.cmd_tx
+            .send(cmd)
+            .map_err(|_| anyhow!("Sync manager is no longer running"))
+    }
+}
+
+impl Drop for ModSyncClient {
+    // Best effort for owners that never call `shutdown`, so dropping a
+    // client isn't mistaken for a crash on the next start
+    fn drop(&mut self) {
+        self.release_session_lock();
+        #[cfg(feature = "scripting")]
+        if let Some(task) = &self.script_task {
+            task.abort();
+        }
+        self.manager_task.abort();
+    }
+}
+
+/// Lock the session in `config`'s cache folder. Without a cache folder,
+/// or while another session holds the lock, unclean shutdowns aren't
+/// detected.
+fn acquire_session_lock(config: &SyncConfig) -> Option<SessionLock> {
+    let dir = config.cache_dir.as_ref()?;
+    match SessionLock::acquire(dir) {
+        Ok(Some(lock)) => Some(lock),
+        Ok(None) => {
+            println!("Client: Another session is running in {}", dir.display());
+            None
+        }
+        Err(e) => {
+            eprintln!("Client: Failed to lock the session: {:#}", e);
+            None
+        }
+    }
+}
+
+fn save_lifetime(path: &Path, stats: LifetimeStats) {
+    if let Err(e) = stats.save(path) {
+        eprintln!("Client: Failed to save lifetime stats: {:#}", e);
+    }
+}
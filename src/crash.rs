@@ -0,0 +1,107 @@
+// src/crash.rs
+
+//! Crash reports for panics.
+//!
+//! `install_hook` writes a report with the panic message, a backtrace and
+//! the recent event journal to the `crashes` folder before the process goes
+//! away, so a window vanishing mid-sync leaves a trace. The UI lists the
+//! reports it hasn't shown yet on the next start; `acknowledge` marks one as
+//! seen.
+
+use anyhow::{Context, Result};
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::diagnostics::{self, LOG_LINES};
+use crate::settings::AppSettings;
+
+/// Folder for crash reports inside the application data dir.
+pub const CRASH_DIR_NAME: &str = "crashes";
+
+/// Suffix of reports that were already shown.
+const SEEN_SUFFIX: &str = ".seen.txt";
+
+/// Write a crash report whenever `process`, e.g. "ui" or "daemon", panics,
+/// then run the previous hook as before.
+pub fn install_hook(process: &'static str) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = format_report(process, &info.to_string(), &Backtrace::force_capture().to_string(), &diagnostics::recent_log(LOG_LINES));
+        match write_report(process, &report) {
+            Ok(path) => eprintln!("Crash: Report written to {}", path.display()),
+            Err(e) => eprintln!("Crash: Failed to write report: {:#}", e),
+        }
+        previous(info);
+    }));
+}
+
+fn format_report(process: &str, panic: &str, backtrace: &str, log: &[String]) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "ModSync {} {} crashed at {}", env!("CARGO_PKG_VERSION"), process, chrono::Utc::now().to_rfc3339());
+    let _ = writeln!(report, "OS: {} {}", std::env::consts::OS, std::env::consts::ARCH);
+    let _ = writeln!(report, "Thread: {}", std::thread::current().name().unwrap_or("unnamed"));
+    let _ = writeln!(report, "\n{}\n\nBacktrace:\n{}\nRecent activity:", panic, backtrace);
+    for line in log {
+        let _ = writeln!(report, "{}", line);
+    }
+    report
+}
+
+fn write_report(process: &str, report: &str) -> Result<PathBuf> {
+    let dir = crash_dir()?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("crash-{}-{}.txt", process, chrono::Utc::now().format("%Y%m%d-%H%M%S%.3f")));
+    std::fs::write(&path, report).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+pub fn crash_dir() -> Result<PathBuf> {
+    Ok(AppSettings::data_dir()?.join(CRASH_DIR_NAME))
+}
+
+/// Reports in `dir` that weren't acknowledged yet, oldest first.
+pub fn pending_reports(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("crash-") && name.ends_with(".txt") && !name.ends_with(SEEN_SUFFIX))
+        })
+        .collect();
+    reports.sort();
+    reports
+}
+
+/// Mark the report at `path` as shown, keeping it for later reference.
+/// Returns where it now is.
+pub fn acknowledge(path: &Path) -> Result<PathBuf> {
+    let name = path.file_name().and_then(|n| n.to_str()).context("Not a crash report")?;
+    let seen = path.with_file_name(format!("{}{}", name.trim_end_matches(".txt"), SEEN_SUFFIX));
+    std::fs::rename(path, &seen).with_context(|| format!("Failed to rename {}", path.display()))?;
+    Ok(seen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_and_acknowledge() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = format_report("ui", "panicked at 'boom'", "0: main", &["event Notice".to_string()]);
+        assert!(report.contains("boom") && report.contains("event Notice"));
+        let path = dir.path().join("crash-ui-20250101-000000.000.txt");
+        std::fs::write(&path, report).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        assert_eq!(pending_reports(dir.path()), vec![path.clone()]);
+
+        let seen = acknowledge(&path).unwrap();
+        assert!(seen.exists());
+        assert!(pending_reports(dir.path()).is_empty());
+    }
+}
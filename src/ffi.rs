@@ -0,0 +1,400 @@
+// src/ffi.rs
+
+//! C ABI for embedding the sync engine in non-Rust launchers.
+//!
+//! Enabled with the `modsync-ffi` feature. Build a shared library with
+//! `cargo rustc --release --lib --features modsync-ffi --crate-type cdylib`
+//! and include `include/modsync.h` (regenerate it with
+//! `cbindgen --config cbindgen.toml --output include/modsync.h`).
+//!
+//! Every handle owns its own tokio runtime. Events are consumed on that
+//! runtime and folded into a status snapshot that callers poll. A panic
+//! never unwinds into the caller: the function returns `MODSYNC_ERR_PANIC`
+//! or null instead.
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::path::PathBuf;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::client::ModSyncClient;
+use crate::sync::memory;
+use crate::sync::status::SyncStatus;
+use crate::sync::{SyncConfig, SyncEvent};
+
+pub const MODSYNC_OK: c_int = 0;
+pub const MODSYNC_ERR_INVALID_ARGUMENT: c_int = -1;
+pub const MODSYNC_ERR_ENGINE: c_int = -2;
+pub const MODSYNC_ERR_NO_UPDATE: c_int = -3;
+pub const MODSYNC_ERR_PANIC: c_int = -4;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModSyncState {
+    Idle = 0,
+    CheckingRemote = 1,
+    UpdatingTorrent = 2,
+    CheckingLocal = 3,
+    LocalActive = 4,
+    RemoteChanged = 5,
+    Error = 6,
+    RestoringSession = 7,
+}
+
+impl From<&SyncStatus> for ModSyncState {
+    fn from(status: &SyncStatus) -> Self {
+        match status {
+            SyncStatus::Idle => ModSyncState::Idle,
+            SyncStatus::CheckingRemote => ModSyncState::CheckingRemote,
+            SyncStatus::UpdatingTorrent => ModSyncState::UpdatingTorrent,
+            SyncStatus::CheckingLocal => ModSyncState::CheckingLocal,
+            SyncStatus::LocalActive => ModSyncState::LocalActive,
+            SyncStatus::RemoteChanged => ModSyncState::RemoteChanged,
+            SyncStatus::Error(_) => ModSyncState::Error,
+            SyncStatus::RestoringSession => ModSyncState::RestoringSession,
+        }
+    }
+}
+
+/// Snapshot returned by `modsync_poll_status`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ModSyncStatus {
+    pub state: ModSyncState,
+    pub progress_bytes: u64,
+    pub total_bytes: u64,
+    /// Non-zero when a remote update is waiting to be applied.
+    pub update_available: u8,
+    /// Number of missing files reported by the last verification.
+    pub missing_files: u64,
+    /// Number of extra files reported by the last verification.
+    pub extra_files: u64,
+}
+
+#[derive(Default)]
+struct Shared {
+    status: SyncStatus,
+    progress_bytes: u64,
+    total_bytes: u64,
+    pending_update: Option<Vec<u8>>,
+    auto_apply: bool,
+    missing_files: u64,
+    extra_files: u64,
+    last_error: Option<String>,
+}
+
+/// Opaque handle owned by the caller; release with `modsync_client_free`.
+pub struct ModSyncHandle {
+    runtime: tokio::runtime::Runtime,
+    client: Option<ModSyncClient>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl ModSyncHandle {
+    fn set_error(&self, msg: String) {
+        lock(&self.shared).last_error = Some(msg);
+    }
+}
+
+/// The shared state, even if a panic poisoned its lock: every field is
+/// valid on its own, so a half-done update is still safe to read.
+fn lock(shared: &Mutex<Shared>) -> MutexGuard<'_, Shared> {
+    shared.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Run the body of an exported function, returning `on_panic` if it panics.
+fn guard<T>(on_panic: T, f: impl FnOnce() -> T) -> T {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        eprintln!("FFI: Panicked, returning an error to the caller");
+        on_panic
+    })
+}
+
+/// Read a required UTF-8 C string argument.
+unsafe fn required_str(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    unsafe { CStr::from_ptr(ptr) }.to_str().ok().map(str::to_owned)
+}
+
+/// Create a client and start its session.
+///
+/// `cached_torrent_path` may be null. Returns null on failure.
+///
+/// # Safety
+/// String arguments must be null or point to valid NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn modsync_client_new(
+    torrent_url: *const c_char,
+    download_path: *const c_char,
+    cached_torrent_path: *const c_char,
+) -> *mut ModSyncHandle {
+    guard(std::ptr::null_mut(), || {
+        let (Some(torrent_url), Some(download_path)) =
+            (unsafe { required_str(torrent_url) }, unsafe { required_str(download_path) })
+        else {
+            return std::ptr::null_mut();
+        };
+        let cached_torrent_path = unsafe { required_str(cached_torrent_path) }.map(PathBuf::from);
+
+        let runtime = match memory::runtime(None) {
+            Ok(rt) => rt,
+            Err(e) => {
+                eprintln!("FFI: Failed to build runtime: {}", e);
+                return std::ptr::null_mut();
+            }
+        };
+
+        let config = SyncConfig {
+            torrent_url,
+            download_path: PathBuf::from(download_path),
+            cached_torrent_path,
+            ..Default::default()
+        };
+        let client = match runtime.block_on(ModSyncClient::new(config)) {
+            Ok(client) => client,
+            Err(e) => {
+                eprintln!("FFI: Failed to create client: {:#}", e);
+                return std::ptr::null_mut();
+            }
+        };
+
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let mut events = client.subscribe_events();
+        let event_shared = Arc::clone(&shared);
+        let cmd_tx = client.command_sender();
+        runtime.spawn(async move {
+            while let Ok(event) = events.recv().await {
+                let mut shared = lock(&event_shared);
+                match event {
+                    SyncEvent::StatusUpdate(status) => {
+                        if let SyncStatus::Error(msg) = &status {
+                            shared.last_error = Some(msg.clone());
+                        }
+                        shared.status = status;
+                    }
+                    SyncEvent::ManagedTorrentUpdate(Some((_, stats))) => {
+                        shared.progress_bytes = stats.progress_bytes;
+                        shared.total_bytes = stats.total_bytes;
+                    }
+                    SyncEvent::RemoteUpdateFound(torrent, _) => {
+                        if shared.auto_apply {
+                            let _ = cmd_tx.send(crate::sync::SyncCommand::ApplyUpdate(torrent));
+                        } else {
+                            shared.pending_update = Some(torrent);
+                        }
+                    }
+                    SyncEvent::MissingFilesFound(missing) => shared.missing_files = missing.len() as u64,
+                    SyncEvent::ExtraFilesFound(extra) => shared.extra_files = extra.len() as u64,
+                    SyncEvent::Error(err) => shared.last_error = Some(err.to_string()),
+                    _ => {}
+                }
+            }
+        });
+
+        Box::into_raw(Box::new(ModSyncHandle {
+            runtime,
+            client: Some(client),
+            shared,
+        }))
+    })
+}
+
+/// Check the remote and apply any update automatically.
+///
+/// # Safety
+/// `handle` must come from `modsync_client_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn modsync_start_sync(handle: *mut ModSyncHandle) -> c_int {
+    guard(MODSYNC_ERR_PANIC, || {
+        let Some(handle) = (unsafe { handle.as_ref() }) else {
+            return MODSYNC_ERR_INVALID_ARGUMENT;
+        };
+        lock(&handle.shared).auto_apply = true;
+        run(handle, |client| Box::pin(client.check_remote()))
+    })
+}
+
+/// Check the remote without applying; poll `update_available` afterwards.
+///
+/// # Safety
+/// `handle` must come from `modsync_client_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn modsync_check_remote(handle: *mut ModSyncHandle) -> c_int {
+    guard(MODSYNC_ERR_PANIC, || {
+        let Some(handle) = (unsafe { handle.as_ref() }) else {
+            return MODSYNC_ERR_INVALID_ARGUMENT;
+        };
+        run(handle, |client| Box::pin(client.check_remote()))
+    })
+}
+
+/// Apply the update found by the last remote check.
+///
+/// # Safety
+/// `handle` must come from `modsync_client_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn modsync_apply_update(handle: *mut ModSyncHandle) -> c_int {
+    guard(MODSYNC_ERR_PANIC, || {
+        let Some(handle) = (unsafe { handle.as_ref() }) else {
+            return MODSYNC_ERR_INVALID_ARGUMENT;
+        };
+        let Some(torrent) = lock(&handle.shared).pending_update.take() else {
+            return MODSYNC_ERR_NO_UPDATE;
+        };
+        run(handle, move |client| Box::pin(client.apply_update(torrent)))
+    })
+}
+
+/// Verify the download folder against the active torrent.
+///
+/// # Safety
+/// `handle` must come from `modsync_client_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn modsync_verify(handle: *mut ModSyncHandle) -> c_int {
+    guard(MODSYNC_ERR_PANIC, || {
+        let Some(handle) = (unsafe { handle.as_ref() }) else {
+            return MODSYNC_ERR_INVALID_ARGUMENT;
+        };
+        run(handle, |client| Box::pin(client.verify()))
+    })
+}
+
+/// Fill `out` with the latest status snapshot.
+///
+/// # Safety
+/// `handle` must come from `modsync_client_new` and `out` must be writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn modsync_poll_status(handle: *mut ModSyncHandle, out: *mut ModSyncStatus) -> c_int {
+    guard(MODSYNC_ERR_PANIC, || {
+        let (Some(handle), Some(out)) = (unsafe { handle.as_ref() }, unsafe { out.as_mut() }) else {
+            return MODSYNC_ERR_INVALID_ARGUMENT;
+        };
+        let shared = lock(&handle.shared);
+        *out = ModSyncStatus {
+            state: ModSyncState::from(&shared.status),
+            progress_bytes: shared.progress_bytes,
+            total_bytes: shared.total_bytes,
+            update_available: shared.pending_update.is_some() as u8,
+            missing_files: shared.missing_files,
+            extra_files: shared.extra_files,
+        };
+        MODSYNC_OK
+    })
+}
+
+/// Most recent error message, or null if none. Free with `modsync_string_free`.
+///
+/// # Safety
+/// `handle` must come from `modsync_client_new` and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn modsync_last_error(handle: *mut ModSyncHandle) -> *mut c_char {
+    guard(std::ptr::null_mut(), || {
+        let Some(handle) = (unsafe { handle.as_ref() }) else {
+            return std::ptr::null_mut();
+        };
+        match &lock(&handle.shared).last_error {
+            Some(msg) => CString::new(msg.replace('\0', " "))
+                .map(CString::into_raw)
+                .unwrap_or(std::ptr::null_mut()),
+            None => std::ptr::null_mut(),
+        }
+    })
+}
+
+/// Release a string returned by this library.
+///
+/// # Safety
+/// `s` must be null or a pointer returned by `modsync_last_error`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn modsync_string_free(s: *mut c_char) {
+    guard((), || {
+        if !s.is_null() {
+            drop(unsafe { CString::from_raw(s) });
+        }
+    })
+}
+
+/// Stop the session and release the handle.
+///
+/// # Safety
+/// `handle` must be null or come from `modsync_client_new`, and must not be
+/// used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn modsync_client_free(handle: *mut ModSyncHandle) {
+    guard((), || {
+        if handle.is_null() {
+            return;
+        }
+        let mut handle = unsafe { Box::from_raw(handle) };
+        if let Some(client) = handle.client.take() {
+            handle.runtime.block_on(client.shutdown());
+        }
+    })
+}
+
+type ClientFuture<'a> = std::pin::Pin<Box<dyn std::future::Future<Output = anyhow::Result<()>> + 'a>>;
+
+fn run(handle: &ModSyncHandle, f: impl for<'a> FnOnce(&'a ModSyncClient) -> ClientFuture<'a>) -> c_int {
+    // The client is only taken when the handle is freed
+    let Some(client) = handle.client.as_ref() else {
+        return MODSYNC_ERR_INVALID_ARGUMENT;
+    };
+    match handle.runtime.block_on(f(client)) {
+        Ok(()) => MODSYNC_OK,
+        Err(e) => {
+            handle.set_error(format!("{:#}", e));
+            MODSYNC_ERR_ENGINE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_null_arguments_are_rejected() {
+        unsafe {
+            assert!(modsync_client_new(std::ptr::null(), std::ptr::null(), std::ptr::null()).is_null());
+            assert_eq!(modsync_verify(std::ptr::null_mut()), MODSYNC_ERR_INVALID_ARGUMENT);
+            assert!(modsync_last_error(std::ptr::null_mut()).is_null());
+            modsync_client_free(std::ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_panics_and_poisoned_locks_become_errors() {
+        assert_eq!(guard(MODSYNC_ERR_PANIC, || panic!("boom")), MODSYNC_ERR_PANIC);
+
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let poisoner = Arc::clone(&shared);
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poison");
+        })
+        .join();
+        assert!(shared.is_poisoned());
+        lock(&shared).missing_files = 3;
+        assert_eq!(lock(&shared).missing_files, 3);
+    }
+
+    #[test]
+    fn test_client_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = CString::new("").unwrap();
+        let path = CString::new(dir.path().join("downloads").to_string_lossy().into_owned()).unwrap();
+        unsafe {
+            let handle = modsync_client_new(url.as_ptr(), path.as_ptr(), std::ptr::null());
+            assert!(!handle.is_null());
+            assert_eq!(modsync_apply_update(handle), MODSYNC_ERR_NO_UPDATE);
+
+            let mut status = std::mem::zeroed::<ModSyncStatus>();
+            assert_eq!(modsync_poll_status(handle, &mut status), MODSYNC_OK);
+            assert_eq!(status.update_available, 0);
+
+            modsync_client_free(handle);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// src/fleet.rs
+
+//! Talk to several remote daemons at once through their gRPC control
+//! interface, for admins running more than one seedbox.
+//!
+//! Enabled with the `grpc` feature. Nodes are addressed by their control URL,
+//! e.g. `http://seedbox-1:50051`, as stored in `AppSettings::fleet_nodes`.
+
+use anyhow::{Context, Result};
+use futures::future::join_all;
+
+use crate::grpc::proto;
+use crate::grpc::proto::mod_sync_control_client::ModSyncControlClient;
+
+/// Status of one remote daemon as reported by `GetStatus`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeStatus {
+    pub status: String,
+    pub error: String,
+    pub version: String,
+    pub update_available: bool,
+    pub progress_bytes: u64,
+    pub total_bytes: u64,
+    pub uploaded_bytes: u64,
+}
+
+impl NodeStatus {
+    /// Uploaded bytes relative to the size of the mod pack.
+    pub fn seed_ratio(&self) -> f64 {
+        if self.total_bytes == 0 {
+            0.0
+        } else {
+            self.uploaded_bytes as f64 / self.total_bytes as f64
+        }
+    }
+}
+
+impl From<proto::StatusReply> for NodeStatus {
+    fn from(reply: proto::StatusReply) -> Self {
+        Self {
+            status: reply.status,
+            error: reply.error,
+            version: reply.version,
+            update_available: reply.update_available,
+            progress_bytes: reply.progress_bytes,
+            total_bytes: reply.total_bytes,
+            uploaded_bytes: reply.uploaded_bytes,
+        }
+    }
+}
+
+async fn connect(addr: &str) -> Result<ModSyncControlClient<tonic::transport::Channel>> {
+    ModSyncControlClient::connect(addr.to_string())
+        .await
+        .with_context(|| format!("Failed to connect to {}", addr))
+}
+
+/// Fetch the status of the daemon at `addr`.
+pub async fn query_node(addr: &str) -> Result<NodeStatus> {
+    let reply = connect(addr)
+        .await?
+        .get_status(proto::Empty {})
+        .await
+        .with_context(|| format!("GetStatus failed on {}", addr))?;
+    Ok(reply.into_inner().into())
+}
+
+/// Ask the daemon at `addr` to check its remote torrent for updates.
+pub async fn check_node(addr: &str) -> Result<()> {
+    connect(addr)
+        .await?
+        .check_remote(proto::Empty {})
+        .await
+        .with_context(|| format!("CheckRemote failed on {}", addr))?;
+    Ok(())
+}
+
+/// Query every node concurrently. Results are in the order of `addrs`.
+pub async fn query_fleet(addrs: &[String]) -> Vec<(String, Result<NodeStatus>)> {
+    join_all(addrs.iter().map(|addr| async move { (addr.clone(), query_node(addr).await) })).await
+}
+
+/// Trigger a remote check on every node concurrently. Results are in the
+/// order of `addrs`.
+pub async fn check_fleet(addrs: &[String]) -> Vec<(String, Result<()>)> {
+    join_all(addrs.iter().map(|addr| async move { (addr.clone(), check_node(addr).await) })).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ModSyncClient;
+    use crate::sync::SyncConfig;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_query_fleet_reports_each_node() -> Result<()> {
+        let dir = tempdir()?;
+        let config = SyncConfig {
+            download_path: dir.path().join("downloads"),
+            ..Default::default()
+        };
+        let client = Arc::new(ModSyncClient::new(config).await?);
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        drop(listener);
+        tokio::spawn(crate::grpc::serve(client, addr));
+
+        let live = format!("http://{}", addr);
+        let nodes = vec![live.clone(), "http://127.0.0.1:1".to_string()];
+        let mut results = query_fleet(&nodes).await;
+        for _ in 0..50 {
+            if results[0].1.is_ok() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            results = query_fleet(&nodes).await;
+        }
+
+        assert_eq!(results[0].0, live);
+        let status = results[0].1.as_ref().expect("live node answers");
+        assert_eq!(status.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(status.seed_ratio(), 0.0);
+        assert!(results[1].1.is_err());
+        Ok(())
+    }
+}
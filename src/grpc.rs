@@ -0,0 +1,181 @@
+// src/grpc.rs
+
+//! gRPC control interface for the headless daemon.
+//!
+//! Enabled with the `grpc` feature. The service is generated from
+//! `proto/modsync.proto` and forwards every call to a shared `ModSyncClient`.
+//! Calls aren't authenticated, so what decides which files get written or
+//! deleted can only be changed on the daemon's machine: the torrent source
+//! (`torrent_url`), `download_path`, `strict_mirror` and `read_only`. Only
+//! an update the daemon found itself can be applied.
+
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::client::ModSyncClient;
+use crate::sync::journal::describe_event;
+use crate::sync::status::SyncStatus;
+use crate::sync::SyncConfig;
+
+pub mod proto {
+    tonic::include_proto!("modsync.v1");
+}
+
+use proto::mod_sync_control_server::{ModSyncControl, ModSyncControlServer};
+
+pub struct ControlService {
+    client: Arc<ModSyncClient>,
+}
+
+impl ControlService {
+    pub fn new(client: Arc<ModSyncClient>) -> Self {
+        Self { client }
+    }
+}
+
+fn engine_error(e: anyhow::Error) -> Status {
+    Status::unavailable(format!("{:#}", e))
+}
+
+#[tonic::async_trait]
+impl ModSyncControl for ControlService {
+    async fn get_status(&self, _request: Request<proto::Empty>) -> Result<Response<proto::StatusReply>, Status> {
+        let status = self.client.status();
+        let stats = self.client.last_torrent_stats();
+        let (status, error) = match status {
+            SyncStatus::Error(msg) => ("Error".to_string(), msg),
+            other => (format!("{:?}", other), String::new()),
+        };
+        Ok(Response::new(proto::StatusReply {
+            status,
+            error,
+            update_available: self.client.pending_update().is_some(),
+            progress_bytes: stats.as_ref().map(|s| s.progress_bytes).unwrap_or(0),
+            total_bytes: stats.as_ref().map(|s| s.total_bytes).unwrap_or(0),
+            uploaded_bytes: stats.as_ref().map(|s| s.uploaded_bytes).unwrap_or(0),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }))
+    }
+
+    async fn check_remote(&self, _request: Request<proto::Empty>) -> Result<Response<proto::Ack>, Status> {
+        self.client.check_remote().await.map_err(engine_error)?;
+        Ok(Response::new(proto::Ack {}))
+    }
+
+    async fn apply_update(&self, _request: Request<proto::Empty>) -> Result<Response<proto::Ack>, Status> {
+        let torrent = self
+            .client
+            .pending_update()
+            .ok_or_else(|| Status::failed_precondition("No pending update to apply"))?;
+        self.client.apply_update(torrent).await.map_err(engine_error)?;
+        Ok(Response::new(proto::Ack {}))
+    }
+
+    async fn verify(&self, _request: Request<proto::Empty>) -> Result<Response<proto::Ack>, Status> {
+        self.client.verify().await.map_err(engine_error)?;
+        Ok(Response::new(proto::Ack {}))
+    }
+
+    async fn get_config(&self, _request: Request<proto::Empty>) -> Result<Response<proto::Config>, Status> {
+        let config = self.client.config();
+        Ok(Response::new(proto::Config {
+            torrent_url: config.torrent_url,
+            download_path: config.download_path.to_string_lossy().into_owned(),
+            max_upload_speed: config.max_upload_speed,
+            max_download_speed: config.max_download_speed,
+            should_seed: config.should_seed,
+        }))
+    }
+
+    async fn update_config(&self, request: Request<proto::Config>) -> Result<Response<proto::Ack>, Status> {
+        let update = request.into_inner();
+        let current = self.client.config();
+        // Empty leaves the source and the folder as they are
+        if !update.torrent_url.is_empty() && update.torrent_url != current.torrent_url {
+            return Err(Status::permission_denied("The torrent URL can't be changed over gRPC"));
+        }
+        if !update.download_path.is_empty() && Path::new(&update.download_path) != current.download_path {
+            return Err(Status::permission_denied("The download folder can't be changed over gRPC"));
+        }
+        // strict_mirror and read_only aren't in the message and stay as they are
+        let config = SyncConfig {
+            max_upload_speed: update.max_upload_speed,
+            max_download_speed: update.max_download_speed,
+            should_seed: update.should_seed,
+            ..current
+        };
+        self.client.update_config(config).await.map_err(engine_error)?;
+        Ok(Response::new(proto::Ack {}))
+    }
+
+    type WatchEventsStream = BoxStream<'static, Result<proto::Event, Status>>;
+
+    async fn watch_events(
+        &self,
+        _request: Request<proto::Empty>,
+    ) -> Result<Response<Self::WatchEventsStream>, Status> {
+        let stream = self.client.event_stream().map(|event| {
+            Ok(proto::Event {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                kind: event.kind().to_string(),
+                description: describe_event(&event),
+            })
+        });
+        Ok(Response::new(stream.boxed()))
+    }
+}
+
+/// Serve the control interface on `addr` until the process exits.
+pub async fn serve(client: Arc<ModSyncClient>, addr: SocketAddr) -> anyhow::Result<()> {
+    println!("gRPC: Listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(ModSyncControlServer::new(ControlService::new(client)))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_status_and_config_round_trip() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let config = SyncConfig {
+            download_path: dir.path().join("downloads"),
+            ..Default::default()
+        };
+        let client = Arc::new(ModSyncClient::new(config).await?);
+        let service = ControlService::new(Arc::clone(&client));
+
+        let status = service.get_status(Request::new(proto::Empty {})).await?.into_inner();
+        assert!(!status.update_available);
+        assert_eq!(status.version, env!("CARGO_PKG_VERSION"));
+
+        let mut config = service.get_config(Request::new(proto::Empty {})).await?.into_inner();
+        config.max_upload_speed = Some(128);
+        service.update_config(Request::new(config.clone())).await?;
+        assert_eq!(client.config().max_upload_speed, Some(128));
+
+        let mut moved = config.clone();
+        moved.download_path = dir.path().join("elsewhere").to_string_lossy().into_owned();
+        let err = service.update_config(Request::new(moved)).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+        assert_eq!(client.config().download_path, dir.path().join("downloads"));
+
+        config.torrent_url = "https://example.com/repo.torrent".to_string();
+        let err = service.update_config(Request::new(config)).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+        assert_eq!(client.config().torrent_url, "");
+
+        let err = service.apply_update(Request::new(proto::Empty {})).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+// src/i18n.rs
+
+//! Translated UI strings.
+//!
+//! Strings live in Fluent files under `locales/<lang>/modsync.ftl`, which are
+//! compiled into the binary. English is the baseline: a message missing from
+//! the selected language falls back to English, and a message missing from
+//! English falls back to its id. The language can be switched at runtime with
+//! `set_language`; the UI picks it up on the next frame.
+
+use anyhow::{anyhow, Result};
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+/// Language used when none is configured and for missing messages.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Languages shipped with ModSync as (code, native name, Fluent source).
+const LANGUAGES: &[(&str, &str, &str)] = &[
+    ("en", "English", include_str!("../locales/en/modsync.ftl")),
+    ("de", "Deutsch", include_str!("../locales/de/modsync.ftl")),
+];
+
+static BUNDLES: OnceLock<HashMap<&'static str, FluentBundle<FluentResource>>> = OnceLock::new();
+static CURRENT: RwLock<&'static str> = RwLock::new(DEFAULT_LANGUAGE);
+
+fn bundles() -> &'static HashMap<&'static str, FluentBundle<FluentResource>> {
+    BUNDLES.get_or_init(|| {
+        LANGUAGES
+            .iter()
+            .map(|(code, _, source)| (*code, build_bundle(code, source)))
+            .collect()
+    })
+}
+
+fn build_bundle(code: &str, source: &str) -> FluentBundle<FluentResource> {
+    let langid = code.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Unicode isolation marks show up as boxes in egui's default fonts
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, errors)| {
+        eprintln!("i18n: Errors parsing {} translations: {:?}", code, errors);
+        resource
+    });
+    if let Err(errors) = bundle.add_resource(resource) {
+        eprintln!("i18n: Errors loading {} translations: {:?}", code, errors);
+    }
+    bundle
+}
+
+/// Shipped languages as (code, native name).
+pub fn available_languages() -> impl Iterator<Item = (&'static str, &'static str)> {
+    LANGUAGES.iter().map(|(code, name, _)| (*code, *name))
+}
+
+pub fn current_language() -> &'static str {
+    *CURRENT.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Switch the UI language. Fails for languages that aren't shipped.
+pub fn set_language(code: &str) -> Result<()> {
+    let (code, _, _) = LANGUAGES
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .ok_or_else(|| anyhow!("Unsupported language: {}", code))?;
+    *CURRENT.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = code;
+    Ok(())
+}
+
+/// Translate the message `id` in the current language.
+pub fn tr(id: &str) -> String {
+    format(id, None)
+}
+
+/// Translate the message `id`, substituting `{ $name }` placeholders.
+pub fn tr_args(id: &str, args: &[(&str, FluentValue<'_>)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    format(id, Some(&fluent_args))
+}
+
+fn format(id: &str, args: Option<&FluentArgs>) -> String {
+    let bundles = bundles();
+    for code in [current_language(), DEFAULT_LANGUAGE] {
+        let Some(bundle) = bundles.get(code) else { continue };
+        let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) else { continue };
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, args, &mut errors);
+        if !errors.is_empty() {
+            eprintln!("i18n: Errors formatting {} ({}): {:?}", id, code, errors);
+        }
+        return text.into_owned();
+    }
+    id.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Message ids defined in a Fluent source.
+    fn message_ids(source: &str) -> Vec<&str> {
+        source
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_language_defines_every_english_message() {
+        let english = message_ids(LANGUAGES[0].2);
+        assert!(!english.is_empty());
+        for (code, _, source) in &LANGUAGES[1..] {
+            let ids = message_ids(source);
+            let missing: Vec<_> = english.iter().filter(|id| !ids.contains(id)).collect();
+            assert!(missing.is_empty(), "{} is missing {:?}", code, missing);
+        }
+    }
+
+    #[test]
+    fn test_switching_language_and_fallbacks() {
+        assert_eq!(
+            tr_args("fleet-check-started", &[("count", 3.into())]),
+            "Update check started on 3 nodes"
+        );
+        assert_eq!(tr("no-such-message"), "no-such-message");
+        assert!(set_language("xx").is_err());
+
+        set_language("de").unwrap();
+        assert_eq!(tr("app-load"), "Laden");
+        set_language(DEFAULT_LANGUAGE).unwrap();
+        assert_eq!(tr("app-load"), "Load");
+    }
+}
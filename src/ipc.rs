@@ -0,0 +1,442 @@
+// src/ipc.rs
+
+//! Local IPC between the GUI and the background daemon.
+//!
+//! The daemon owns the `ModSyncClient` (and with it the librqbit session), so
+//! seeding continues when the GUI window is closed. It listens on a unix
+//! socket in the data directory, or a named pipe on Windows. Each connection
+//! speaks JSON lines: the GUI writes `SyncCommand`s and the daemon writes
+//! `DaemonMessage`s, starting with a `State` snapshot so a reopened GUI can
+//! pick up where the daemon is.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+
+use crate::client::ModSyncClient;
+use crate::settings::AppSettings;
+use crate::sync::power::PowerRestriction;
+use crate::sync::stall::StallDiagnosis;
+use crate::sync::swarm::SwarmHealth;
+use crate::sync::trackers::TrackerInfo;
+use crate::sync::status::SyncStatus;
+use crate::sync::{DiskUsage, EndpointHealth, Inconsistency, GitSourceStatus, LifetimeStats, SyncCommand, SyncEvent, SyncHistory, TorrentFile, TorrentSnapshot};
+
+/// Socket file created in the data directory on unix.
+pub const SOCKET_FILE_NAME: &str = "modsync.sock";
+
+/// Start of the named pipe used on Windows, followed by a hash of the data
+/// directory so each profile's daemon gets its own pipe.
+pub const PIPE_PREFIX: &str = r"\\.\pipe\modsync-";
+
+/// How long the GUI waits between connection attempts.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What the daemon knows so far, so a newly connected GUI can catch up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonState {
+    pub status: SyncStatus,
+    pub stats: Option<TorrentSnapshot>,
+    pub files: Vec<TorrentFile>,
+    #[serde(default)]
+    pub trackers: Vec<TrackerInfo>,
+    pub pending_update: Option<Vec<u8>>,
+    /// RFC 3339 time the daemon last started a remote check.
+    pub last_remote_check: Option<String>,
+    /// RFC 3339 time of the next periodic remote check.
+    pub next_check_at: Option<String>,
+    /// Lifetime totals including the current session's counters in `stats`.
+    pub lifetime: LifetimeStats,
+    pub history: SyncHistory,
+    pub health: EndpointHealth,
+    #[serde(default)]
+    pub git_source: Option<GitSourceStatus>,
+    #[serde(default)]
+    pub disk_usage: Option<DiskUsage>,
+    #[serde(default)]
+    pub inconsistencies: Vec<Inconsistency>,
+    #[serde(default)]
+    pub gaming_mode: bool,
+    /// What the daemon did on start about an unclean shutdown.
+    #[serde(default)]
+    pub recovery: Option<String>,
+    #[serde(default)]
+    pub power: Option<PowerRestriction>,
+    #[serde(default)]
+    pub swarm: Option<SwarmHealth>,
+    #[serde(default)]
+    pub stalled: Option<StallDiagnosis>,
+}
+
+/// Messages sent from the daemon to a connected GUI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DaemonMessage {
+    /// Current state, sent once right after connecting.
+    State(Box<DaemonState>),
+    Event(SyncEvent),
+}
+
+/// What the GUI side of the connection reports to the app.
+#[derive(Debug, Clone)]
+pub enum ConnectionUpdate {
+    Connected,
+    Disconnected,
+    Message(DaemonMessage),
+}
+
+/// Where the daemon listens: the socket path on unix, the pipe name on Windows.
+pub fn endpoint() -> Result<PathBuf> {
+    let data_dir = AppSettings::data_dir()?;
+    if cfg!(windows) {
+        Ok(pipe_name(&data_dir))
+    } else {
+        Ok(data_dir.join(SOCKET_FILE_NAME))
+    }
+}
+
+/// Named pipe of the daemon of `data_dir`. Pipes don't live in a folder, so
+/// the name carries a hash of the directory instead.
+fn pipe_name(data_dir: &Path) -> PathBuf {
+    let data_dir = data_dir.canonicalize().unwrap_or_else(|_| data_dir.to_path_buf());
+    let hash = format!("{:x}", Sha256::digest(data_dir.as_os_str().as_encoded_bytes()));
+    PathBuf::from(format!("{}{}", PIPE_PREFIX, &hash[..16]))
+}
+
+/// Accept GUI connections on `endpoint` until an error occurs.
+#[cfg(unix)]
+pub async fn serve(client: Arc<ModSyncClient>, endpoint: PathBuf) -> Result<()> {
+    if endpoint.exists() {
+        if tokio::net::UnixStream::connect(&endpoint).await.is_ok() {
+            anyhow::bail!("Another daemon is already listening on {}", endpoint.display());
+        }
+        // Left behind by a daemon that didn't shut down cleanly
+        std::fs::remove_file(&endpoint)
+            .with_context(|| format!("Failed to remove stale socket: {}", endpoint.display()))?;
+    }
+    let listener = tokio::net::UnixListener::bind(&endpoint)
+        .with_context(|| format!("Failed to listen on {}", endpoint.display()))?;
+    println!("IPC: Listening on {}", endpoint.display());
+
+    loop {
+        let (stream, _) = listener.accept().await.context("Failed to accept IPC connection")?;
+        tokio::spawn(handle_connection(Arc::clone(&client), stream));
+    }
+}
+
+/// Accept GUI connections on `endpoint` until an error occurs.
+#[cfg(windows)]
+pub async fn serve(client: Arc<ModSyncClient>, endpoint: PathBuf) -> Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = endpoint.as_os_str();
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(name)
+        .with_context(|| format!("Failed to listen on {}", endpoint.display()))?;
+    println!("IPC: Listening on {}", endpoint.display());
+
+    loop {
+        server.connect().await.context("Failed to accept IPC connection")?;
+        let connected = server;
+        server = ServerOptions::new()
+            .create(name)
+            .with_context(|| format!("Failed to listen on {}", endpoint.display()))?;
+        tokio::spawn(handle_connection(Arc::clone(&client), connected));
+    }
+}
+
+/// Send the state snapshot, then forward events to the GUI and commands to
+/// the client until either side goes away.
+async fn handle_connection<S>(client: Arc<ModSyncClient>, stream: S)
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    println!("IPC: GUI connected");
+    // Subscribe before taking the snapshot so no event falls in between
+    let events = client.event_stream();
+    futures::pin_mut!(events);
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+
+    let state = DaemonMessage::State(Box::new(DaemonState {
+        status: client.status(),
+        stats: client.last_torrent_stats(),
+        files: client.torrent_files(),
+        trackers: client.trackers(),
+        pending_update: client.pending_update(),
+        last_remote_check: client.last_remote_check().map(|t| t.to_rfc3339()),
+        next_check_at: client.next_check_at(),
+        lifetime: client.lifetime_stats(),
+        history: client.history(),
+        health: client.endpoint_health(),
+        git_source: client.git_source_status(),
+        disk_usage: client.disk_usage(),
+        inconsistencies: client.inconsistencies(),
+        gaming_mode: client.gaming_mode(),
+        recovery: client.recovery(),
+        power: client.power_restriction(),
+        swarm: client.swarm(),
+        stalled: client.stalled(),
+    }));
+    if let Err(e) = write_line(&mut writer, &state).await {
+        eprintln!("IPC: Failed to send state: {:#}", e);
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => {
+                    if let Err(e) = handle_command_line(&client, &line).await {
+                        eprintln!("IPC: {:#}", e);
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    eprintln!("IPC: Failed to read from GUI: {}", e);
+                    break;
+                }
+            },
+            event = futures::StreamExt::next(&mut events) => match event {
+                Some(event) => {
+                    if let Err(e) = write_line(&mut writer, &DaemonMessage::Event(event)).await {
+                        eprintln!("IPC: Failed to send event: {:#}", e);
+                        break;
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+    println!("IPC: GUI disconnected");
+}
+
+async fn handle_command_line(client: &ModSyncClient, line: &str) -> Result<()> {
+    if line.trim().is_empty() {
+        return Ok(());
+    }
+    let cmd: SyncCommand = serde_json::from_str(line).context("Ignoring unreadable command")?;
+    match cmd {
+        // Keep the client's copy of the config in step with the manager's
+        SyncCommand::UpdateConfig(config) => client.update_config(*config).await,
+        other => client
+            .command_sender()
+            .send(other)
+            .map_err(|_| anyhow::anyhow!("Sync manager is no longer running")),
+    }
+}
+
+async fn write_line<W, T>(writer: &mut W, message: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut line = serde_json::to_vec(message).context("Failed to serialize IPC message")?;
+    line.push(b'\n');
+    writer.write_all(&line).await.context("Failed to write IPC message")?;
+    writer.flush().await.context("Failed to flush IPC connection")
+}
+
+/// Keep a connection to the daemon at `endpoint` open for as long as the
+/// returned command sender is alive, reconnecting whenever it drops. If no
+/// daemon is reachable one is started. Commands sent while disconnected are
+/// delivered once the connection is back. Must be called inside a tokio
+/// runtime.
+pub fn connect(
+    endpoint: PathBuf,
+) -> (mpsc::UnboundedSender<SyncCommand>, mpsc::UnboundedReceiver<ConnectionUpdate>) {
+    let (cmd_tx, mut cmd_rx) = mpsc::unbounded_channel();
+    let (update_tx, update_rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut daemon: Option<Child> = None;
+        loop {
+            match open_stream(&endpoint).await {
+                Ok(stream) => {
+                    if update_tx.send(ConnectionUpdate::Connected).is_err() {
+                        return;
+                    }
+                    let gui_gone = run_connection(stream, &mut cmd_rx, &update_tx).await;
+                    if gui_gone || update_tx.send(ConnectionUpdate::Disconnected).is_err() {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    // Only start another daemon once the last one has exited
+                    let running = daemon
+                        .as_mut()
+                        .is_some_and(|child| matches!(child.try_wait(), Ok(None)));
+                    if !running {
+                        eprintln!("IPC: Daemon not reachable ({:#}), starting it", e);
+                        match spawn_daemon() {
+                            Ok(child) => daemon = Some(child),
+                            Err(e) => eprintln!("IPC: {:#}", e),
+                        }
+                    }
+                }
+            }
+            if update_tx.is_closed() {
+                return;
+            }
+            tokio::time::sleep(RECONNECT_INTERVAL).await;
+        }
+    });
+
+    (cmd_tx, update_rx)
+}
+
+/// Pump one connection. Returns true when the GUI has dropped its end of the
+/// channels and the connection task should stop.
+async fn run_connection<S>(
+    stream: S,
+    cmd_rx: &mut mpsc::UnboundedReceiver<SyncCommand>,
+    update_tx: &mpsc::UnboundedSender<ConnectionUpdate>,
+) -> bool
+where
+    S: AsyncRead + AsyncWrite,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => match serde_json::from_str::<DaemonMessage>(&line) {
+                    Ok(message) => {
+                        if update_tx.send(ConnectionUpdate::Message(message)).is_err() {
+                            return true;
+                        }
+                    }
+                    Err(e) => eprintln!("IPC: Ignoring unreadable message from daemon: {}", e),
+                },
+                Ok(None) => return false,
+                Err(e) => {
+                    eprintln!("IPC: Failed to read from daemon: {}", e);
+                    return false;
+                }
+            },
+            cmd = cmd_rx.recv() => match cmd {
+                Some(cmd) => {
+                    if let Err(e) = write_line(&mut writer, &cmd).await {
+                        eprintln!("IPC: Failed to send command: {:#}", e);
+                        return false;
+                    }
+                }
+                None => return true,
+            },
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn open_stream(endpoint: &Path) -> Result<tokio::net::UnixStream> {
+    tokio::net::UnixStream::connect(endpoint)
+        .await
+        .with_context(|| format!("Failed to connect to {}", endpoint.display()))
+}
+
+#[cfg(windows)]
+async fn open_stream(endpoint: &Path) -> Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    tokio::net::windows::named_pipe::ClientOptions::new()
+        .open(endpoint.as_os_str())
+        .with_context(|| format!("Failed to connect to {}", endpoint.display()))
+}
+
+/// Start `modsync-cli daemon` from the directory of the running executable,
+/// detached so it outlives the GUI.
+fn spawn_daemon() -> Result<Child> {
+    let exe = std::env::current_exe()
+        .context("Failed to determine current exe path")?
+        .with_file_name(format!("modsync-cli{}", std::env::consts::EXE_SUFFIX));
+    let mut command = Command::new(&exe);
+    // The daemon must share the data directory, which holds the endpoint
+    command
+        .arg("--config-dir")
+        .arg(AppSettings::data_dir()?)
+        .arg("daemon")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    {
+        // Own process group, so Ctrl+C in the GUI's terminal doesn't reach it
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(DETACHED_PROCESS | CREATE_NO_WINDOW);
+    }
+    command
+        .spawn()
+        .with_context(|| format!("Failed to start daemon: {}", exe.display()))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::sync::{SyncConfig, SyncError};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_each_data_dir_has_its_own_pipe() -> Result<()> {
+        let first = tempdir()?;
+        let second = tempdir()?;
+        let pipe = pipe_name(first.path());
+        assert!(pipe.to_string_lossy().starts_with(PIPE_PREFIX));
+        assert_eq!(pipe, pipe_name(&first.path().join(".")));
+        assert_ne!(pipe, pipe_name(second.path()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_gui_receives_state_and_events() -> Result<()> {
+        let dir = tempdir()?;
+        let config = SyncConfig {
+            download_path: dir.path().join("downloads"),
+            ..Default::default()
+        };
+        let client = Arc::new(ModSyncClient::new(config).await?);
+        let endpoint = dir.path().join(SOCKET_FILE_NAME);
+        tokio::spawn(serve(Arc::clone(&client), endpoint.clone()));
+
+        // Give the listener a moment to bind
+        let stream = loop {
+            if let Ok(stream) = open_stream(&endpoint).await {
+                break stream;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        };
+        let (cmd_tx, mut cmd_rx) = mpsc::unbounded_channel();
+        let (update_tx, mut update_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move { run_connection(stream, &mut cmd_rx, &update_tx).await });
+
+        let first = update_rx.recv().await;
+        assert!(matches!(
+            first,
+            Some(ConnectionUpdate::Message(DaemonMessage::State(state))) if state.status == SyncStatus::Idle
+        ));
+
+        // Verifying without a torrent reports an error back through the socket
+        cmd_tx.send(SyncCommand::VerifyFolder)?;
+        let error = tokio::time::timeout(Duration::from_secs(10), async {
+            while let Some(update) = update_rx.recv().await {
+                if let ConnectionUpdate::Message(DaemonMessage::Event(SyncEvent::Error(msg))) = update {
+                    return Some(msg);
+                }
+            }
+            None
+        })
+        .await?;
+        assert_eq!(error, Some(SyncError::Other("No active torrent to verify against".to_string())));
+        Ok(())
+    }
+}
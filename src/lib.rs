@@ -1,8 +1,21 @@
 // This file makes the crate a library and declares modules for use
 // by the binary (main.rs) and integration tests.
 
+pub mod blocking;
+pub mod client;
+pub mod crash;
+pub mod diagnostics;
+#[cfg(feature = "modsync-ffi")]
+pub mod ffi;
+#[cfg(feature = "grpc")]
+pub mod fleet;
+pub mod i18n;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod ipc;
 pub mod sync;
 pub mod ui;
 pub mod settings;
 
+pub use client::ModSyncClient;
 pub use librqbit;
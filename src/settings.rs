@@ -1,39 +1,501 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
-/// Application settings stored as TOML next to the executable.
+use crate::sync::credentials::CredentialStore;
+use crate::sync::git::GitSourceConfig;
+use crate::sync::extract::ExtractRule;
+use crate::sync::filters::DownloadFilter;
+use crate::sync::mapping::FolderMapping;
+use crate::sync::power::PowerConfig;
+use crate::sync::discord::DiscordConfig;
+use crate::sync::telemetry::TelemetryConfig;
+use crate::sync::seed_window::SeedWindow;
+use crate::sync::hooks::HooksConfig;
+use crate::sync::http::RedirectConfig;
+use crate::sync::ipfs::IpfsConfig;
+use crate::sync::rsync::{RsyncConfig, TransferEngine};
+use crate::sync::lifetime::LIFETIME_STATS_FILE_NAME;
+use crate::sync::s3::S3Config;
+use crate::sync::sftp::SftpConfig;
+use crate::sync::utils::calculate_torrent_hash;
+use crate::sync::SyncConfig;
+
+/// Environment variable naming the data directory, like `--config-dir`.
+pub const CONFIG_DIR_ENV: &str = "MODSYNC_CONFIG_DIR";
+
+/// Data directory given with `--config-dir`, see `AppSettings::set_data_dir`.
+static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Download folders kept in `AppSettings::recent_paths`.
+pub const MAX_RECENT_PATHS: usize = 5;
+
+/// Cached torrent file name used before torrents were cached per URL.
+const LEGACY_CACHED_TORRENT_FILE_NAME: &str = "cached.torrent";
+
+/// File name of the cached torrent for `torrent_url`, keyed by a hash of the
+/// trimmed URL.
+fn cached_torrent_file_name(torrent_url: &str) -> String {
+    let hash = calculate_torrent_hash(torrent_url.trim().as_bytes());
+    format!("cached-{}.torrent", &hash[..16])
+}
+
+/// Application settings stored as TOML next to the executable. Fields
+/// missing from the file, as in one saved by an older version, keep their
+/// defaults.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
 pub struct AppSettings {
     pub torrent_url: String,
+    /// Fallback URLs for the torrent, tried in order.
+    #[serde(default)]
+    pub torrent_mirrors: Vec<String>,
+    /// Endpoint returning the version that replaces `{latest}` in the
+    /// torrent URLs.
+    #[serde(default)]
+    pub version_url: String,
     pub download_path: PathBuf,
+    /// Download folders used before, most recent first.
+    #[serde(default)]
+    pub recent_paths: Vec<PathBuf>,
     pub max_upload_speed: Option<u32>,
     pub max_download_speed: Option<u32>,
     pub should_seed: bool,
+    /// Control URLs of remote daemons shown in the fleet view.
+    #[serde(default)]
+    pub fleet_nodes: Vec<String>,
+    /// UI language code, one of `i18n::available_languages`.
+    #[serde(default = "default_language")]
+    pub language: String,
+    #[serde(default)]
+    pub theme: ThemeSettings,
+    #[serde(default)]
+    pub display: DisplaySettings,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    /// Paths relative to the download folder that are never reported as
+    /// extra files, such as local configs kept inside mod folders.
+    #[serde(default)]
+    pub ignored_paths: Vec<PathBuf>,
+    /// How redirects are followed when fetching the torrent.
+    #[serde(default)]
+    pub redirects: RedirectConfig,
+    /// Storage and credentials for `s3://` torrent URLs.
+    #[serde(default)]
+    pub s3: S3Config,
+    /// Login for `sftp://` torrent URLs.
+    #[serde(default)]
+    pub sftp: SftpConfig,
+    /// Logins for remote sources, looked up by host.
+    #[serde(default)]
+    pub credentials: CredentialStore,
+    /// Gateway for `ipfs://` torrent URLs.
+    #[serde(default)]
+    pub ipfs: IpfsConfig,
+    /// Git repository pulled into the download folder alongside the torrent.
+    #[serde(default)]
+    pub git_source: GitSourceConfig,
+    #[serde(default)]
+    pub engine: TransferEngine,
+    #[serde(default)]
+    pub rsync: RsyncConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Automation script run by the daemon, see `sync::scripting`.
+    #[serde(default)]
+    pub script_path: Option<PathBuf>,
+    /// Archives in the repo to unpack after downloading.
+    #[serde(default)]
+    pub extract: Vec<ExtractRule>,
+    /// Folder the mods' `.bikey` files are collected into.
+    #[serde(default)]
+    pub keys_dir: Option<PathBuf>,
+    /// Subfolders the torrent or its top-level folders go to.
+    #[serde(default)]
+    pub folder_mappings: Vec<FolderMapping>,
+    /// Torrent files that are never downloaded.
+    #[serde(default)]
+    pub download_filters: Vec<DownloadFilter>,
+    /// Size cap of the torrent cache in MiB; unlimited when unset.
+    #[serde(default)]
+    pub cache_max_mb: Option<u64>,
+    /// Torrent versions kept to roll back to; unlimited when unset.
+    #[serde(default)]
+    pub cache_versions: Option<usize>,
+    /// Pause or slow down on a metered connection or battery power.
+    #[serde(default)]
+    pub power: PowerConfig,
+    /// Only seed between these hours.
+    #[serde(default)]
+    pub seed_window: Option<SeedWindow>,
+    /// Peers banned from the Peers tab.
+    #[serde(default)]
+    pub banned_peers: Vec<IpAddr>,
+    /// Peers to connect to directly, such as a seeder on the LAN.
+    #[serde(default)]
+    pub peers: Vec<SocketAddr>,
+    /// Announce URLs added to, or replacing, the torrent's.
+    #[serde(default)]
+    pub extra_trackers: Vec<String>,
+    #[serde(default)]
+    pub replace_trackers: bool,
+    /// Minutes without progress before warning of a stalled download; ten
+    /// when unset, never at zero.
+    #[serde(default)]
+    pub stall_minutes: Option<u64>,
+    /// Random delay added to each update check, in seconds; a minute when
+    /// unset.
+    #[serde(default)]
+    pub check_jitter_secs: Option<u64>,
+    /// Seconds the first update check after starting is spread over.
+    #[serde(default)]
+    pub check_splay_secs: Option<u64>,
+    /// Show what the sync is doing as Discord Rich Presence.
+    #[serde(default)]
+    pub discord: DiscordConfig,
+    /// Report finished downloads to the admin's endpoint; off by default.
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    /// Named speed limits switched between from the header.
+    #[serde(default)]
+    pub bandwidth_presets: Vec<BandwidthPreset>,
+    /// Upload cap in KB/s while gaming mode is on; a small default when unset.
+    #[serde(default)]
+    pub gaming_upload_speed: Option<u32>,
+    /// Key combination toggling gaming mode, like `Ctrl+G`; empty for none.
+    #[serde(default = "default_gaming_mode_shortcut")]
+    pub gaming_mode_shortcut: String,
+    /// Read cap in MB/s for hash checks; unlimited when unset.
+    #[serde(default)]
+    pub check_max_mb_per_sec: Option<u32>,
+    /// Hash check at idle I/O priority.
+    #[serde(default)]
+    pub check_low_io_priority: bool,
+    /// Write buffer in MiB; unbuffered when unset.
+    #[serde(default)]
+    pub write_buffer_mb: Option<usize>,
+    /// Cache of written pieces in MiB; off when unset.
+    #[serde(default)]
+    pub piece_cache_mb: Option<u64>,
+    /// Torrents hash checked at once; one when unset.
+    #[serde(default)]
+    pub concurrent_checks: Option<usize>,
+    /// Threads hashing pieces; half the cores when unset.
+    #[serde(default)]
+    pub hashing_threads: Option<usize>,
+    /// Skip the full hash check on restart.
+    #[serde(default)]
+    pub fastresume: bool,
+    /// Never delete files in the download folder.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Delete extra files after each update without asking.
+    #[serde(default)]
+    pub strict_mirror: bool,
+    /// Ignore updates until unpinned.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Move deleted files into the quarantine folder instead.
+    #[serde(default)]
+    pub quarantine: bool,
+    /// Days quarantined files are kept; 14 when unset.
+    #[serde(default)]
+    pub quarantine_days: Option<u32>,
+    /// Download store shared with other profiles; off when unset.
+    #[serde(default)]
+    pub shared_store: Option<PathBuf>,
+}
+
+/// Speed limits saved under a name, such as "Gaming" or "Overnight".
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct BandwidthPreset {
+    pub name: String,
+    /// KB/s, unlimited when unset.
+    #[serde(default)]
+    pub max_upload_speed: Option<u32>,
+    /// KB/s, unlimited when unset.
+    #[serde(default)]
+    pub max_download_speed: Option<u32>,
+}
+
+impl BandwidthPreset {
+    /// Whether these are the limits `max_upload_speed` and
+    /// `max_download_speed`, so the preset is the one in use.
+    pub fn is_active(&self, max_upload_speed: Option<u32>, max_download_speed: Option<u32>) -> bool {
+        self.max_upload_speed == max_upload_speed && self.max_download_speed == max_download_speed
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemePreset {
+    /// Dark or light as the operating system is set, following changes.
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+/// Units sizes and speeds are shown in.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnitSystem {
+    /// Powers of 1024: KiB, MiB, GiB.
+    #[default]
+    Binary,
+    /// Powers of 1000: kB, MB, GB.
+    Si,
+}
+
+/// UI scaling, for displays where the default sizes are too small or large.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct DisplaySettings {
+    /// Zoom factor applied on top of the system's pixels-per-point.
+    pub ui_scale: f32,
+    /// Body text size in points; headings and small text scale with it.
+    pub font_size: f32,
+    pub units: UnitSystem,
+}
+
+impl DisplaySettings {
+    pub const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.5..=3.0;
+    pub const FONT_SIZE_RANGE: std::ops::RangeInclusive<f32> = 9.0..=32.0;
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            font_size: 15.0,
+            units: UnitSystem::default(),
+        }
+    }
+}
+
+/// Sounds and desktop notifications of the GUI.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Play a sound when a sync completes or an update is found.
+    pub sound: bool,
+    /// Hours without sounds or desktop notifications, like `22:00-08:00`.
+    pub quiet_hours: Option<SeedWindow>,
+}
+
+impl NotificationSettings {
+    pub fn is_quiet(&self) -> bool {
+        self.quiet_hours.is_some_and(|hours| hours.is_open())
+    }
+}
+
+/// UI color scheme: a preset plus an optional custom accent color.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct ThemeSettings {
+    pub preset: ThemePreset,
+    /// RGB accent overriding the preset's, if set.
+    pub accent: Option<[u8; 3]>,
+}
+
+fn default_language() -> String {
+    crate::i18n::DEFAULT_LANGUAGE.to_string()
+}
+
+fn default_gaming_mode_shortcut() -> String {
+    "Ctrl+Shift+G".to_string()
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             torrent_url: String::new(),
+            torrent_mirrors: Vec::new(),
+            version_url: String::new(),
             download_path: PathBuf::from("downloads"),
+            recent_paths: Vec::new(),
             max_upload_speed: None,
             max_download_speed: None,
             should_seed: false,
+            fleet_nodes: Vec::new(),
+            language: default_language(),
+            theme: ThemeSettings::default(),
+            display: DisplaySettings::default(),
+            notifications: NotificationSettings::default(),
+            ignored_paths: Vec::new(),
+            redirects: RedirectConfig::default(),
+            s3: S3Config::default(),
+            sftp: SftpConfig::default(),
+            credentials: CredentialStore::default(),
+            ipfs: IpfsConfig::default(),
+            git_source: GitSourceConfig::default(),
+            engine: TransferEngine::default(),
+            rsync: RsyncConfig::default(),
+            hooks: HooksConfig::default(),
+            script_path: None,
+            extract: Vec::new(),
+            keys_dir: None,
+            folder_mappings: Vec::new(),
+            download_filters: Vec::new(),
+            cache_max_mb: None,
+            cache_versions: None,
+            power: PowerConfig::default(),
+            seed_window: None,
+            banned_peers: Vec::new(),
+            peers: Vec::new(),
+            extra_trackers: Vec::new(),
+            replace_trackers: false,
+            stall_minutes: None,
+            check_jitter_secs: None,
+            check_splay_secs: None,
+            discord: DiscordConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            bandwidth_presets: Vec::new(),
+            gaming_upload_speed: None,
+            gaming_mode_shortcut: default_gaming_mode_shortcut(),
+            check_max_mb_per_sec: None,
+            check_low_io_priority: false,
+            write_buffer_mb: None,
+            piece_cache_mb: None,
+            concurrent_checks: None,
+            hashing_threads: None,
+            fastresume: false,
+            read_only: false,
+            strict_mirror: false,
+            pinned: false,
+            quarantine: false,
+            quarantine_days: None,
+            shared_store: None,
         }
     }
 }
 
 impl AppSettings {
-    /// Determine the settings file path next to the running executable.
-    pub fn settings_file_path() -> Result<PathBuf> {
+    /// Directory where settings and other persistent data (such as the
+    /// event journal) are stored: the one from `set_data_dir`, else
+    /// `MODSYNC_CONFIG_DIR`, else the directory of the running executable.
+    /// A chosen directory is created if missing.
+    pub fn data_dir() -> Result<PathBuf> {
+        let chosen = DATA_DIR_OVERRIDE
+            .get()
+            .cloned()
+            .or_else(|| std::env::var_os(CONFIG_DIR_ENV).filter(|dir| !dir.is_empty()).map(PathBuf::from));
+        if let Some(dir) = chosen {
+            fs::create_dir_all(&dir).with_context(|| format!("Failed to create data directory: {}", dir.display()))?;
+            return Ok(dir);
+        }
         let exe = std::env::current_exe().context("Failed to determine current exe path")?;
         let dir = exe
             .parent()
             .map(|p| p.to_path_buf())
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
-        Ok(dir.join("modsync-settings.toml"))
+        Ok(dir)
+    }
+
+    /// Use `dir` as the data directory for the rest of the process, taking
+    /// precedence over `MODSYNC_CONFIG_DIR`. Can only be set once.
+    pub fn set_data_dir(dir: PathBuf) -> Result<()> {
+        DATA_DIR_OVERRIDE
+            .set(dir)
+            .map_err(|dir| anyhow!("Data directory already set, ignoring {}", dir.display()))
+    }
+
+    /// Remove `--config-dir <DIR>` from `args` and make `DIR` the data
+    /// directory, for the binaries' command lines.
+    pub fn apply_config_dir_arg(args: &mut Vec<String>) -> Result<()> {
+        let Some(i) = args.iter().position(|arg| arg == "--config-dir") else {
+            return Ok(());
+        };
+        let dir = args.get(i + 1).cloned().context("--config-dir requires a directory")?;
+        args.drain(i..=i + 1);
+        Self::set_data_dir(PathBuf::from(dir))
+    }
+
+    /// Determine the settings file path in the data directory.
+    pub fn settings_file_path() -> Result<PathBuf> {
+        Ok(Self::data_dir()?.join("modsync-settings.toml"))
+    }
+
+    /// Location of the cached copy of the torrent served at `torrent_url`,
+    /// so switching URLs doesn't clobber another URL's torrent. The single
+    /// `cached.torrent` of older versions is taken over by the first URL
+    /// that asks.
+    pub fn cached_torrent_path(torrent_url: &str) -> Result<PathBuf> {
+        let dir = Self::data_dir()?;
+        let path = dir.join(cached_torrent_file_name(torrent_url));
+        let legacy = dir.join(LEGACY_CACHED_TORRENT_FILE_NAME);
+        if !path.exists()
+            && legacy.exists()
+            && let Err(e) = fs::rename(&legacy, &path)
+        {
+            eprintln!("Settings: Failed to move {} to {}: {}", legacy.display(), path.display(), e);
+        }
+        Ok(path)
+    }
+
+    /// Folder keeping the torrent versions seen on the remote.
+    pub fn cache_dir() -> Result<PathBuf> {
+        Ok(Self::data_dir()?.join("cache"))
+    }
+
+    /// Build the config handed to the sync subsystem.
+    pub fn to_sync_config(&self) -> Result<SyncConfig> {
+        Ok(SyncConfig {
+            torrent_url: self.torrent_url.clone(),
+            torrent_mirrors: self.torrent_mirrors.clone(),
+            version_url: self.version_url.clone(),
+            download_path: self.download_path.clone(),
+            max_upload_speed: self.max_upload_speed,
+            max_download_speed: self.max_download_speed,
+            should_seed: self.should_seed,
+            cached_torrent_path: Some(Self::cached_torrent_path(&self.torrent_url)?),
+            ignored_paths: self.ignored_paths.clone(),
+            redirects: self.redirects.clone(),
+            s3: self.s3.clone(),
+            sftp: self.sftp.clone(),
+            credentials: self.credentials.clone(),
+            ipfs: self.ipfs.clone(),
+            git_source: self.git_source.clone(),
+            engine: self.engine,
+            rsync: self.rsync.clone(),
+            hooks: self.hooks.clone(),
+            script_path: self.script_path.clone(),
+            extract: self.extract.clone(),
+            keys_dir: self.keys_dir.clone(),
+            folder_mappings: self.folder_mappings.clone(),
+            download_filters: self.download_filters.clone(),
+            cache_dir: Some(Self::cache_dir()?),
+            cache_max_mb: self.cache_max_mb,
+            cache_versions: self.cache_versions,
+            power: self.power.clone(),
+            seed_window: self.seed_window,
+            banned_peers: self.banned_peers.clone(),
+            peers: self.peers.clone(),
+            extra_trackers: self.extra_trackers.clone(),
+            replace_trackers: self.replace_trackers,
+            stall_minutes: self.stall_minutes,
+            check_jitter_secs: self.check_jitter_secs,
+            check_splay_secs: self.check_splay_secs,
+            discord: self.discord.clone(),
+            telemetry: self.telemetry.clone(),
+            gaming_upload_speed: self.gaming_upload_speed,
+            check_max_mb_per_sec: self.check_max_mb_per_sec,
+            check_low_io_priority: self.check_low_io_priority,
+            write_buffer_mb: self.write_buffer_mb,
+            piece_cache_mb: self.piece_cache_mb,
+            concurrent_checks: self.concurrent_checks,
+            hashing_threads: self.hashing_threads,
+            fastresume: self.fastresume,
+            fastresume_dir: Some(Self::data_dir()?.join("session")),
+            read_only: self.read_only,
+            strict_mirror: self.strict_mirror,
+            pinned: self.pinned,
+            quarantine: self.quarantine,
+            quarantine_days: self.quarantine_days,
+            shared_store: self.shared_store.clone(),
+            lifetime_stats_path: Some(Self::data_dir()?.join(LIFETIME_STATS_FILE_NAME)),
+        })
     }
 
     /// Load settings if present, otherwise return defaults.
@@ -55,6 +517,17 @@ impl AppSettings {
         Ok(())
     }
 
+    /// Put `download_path` first in the recent folders, dropping the oldest
+    /// beyond `MAX_RECENT_PATHS`.
+    pub fn remember_download_path(&mut self) {
+        if self.download_path.as_os_str().is_empty() {
+            return;
+        }
+        self.recent_paths.retain(|path| *path != self.download_path);
+        self.recent_paths.insert(0, self.download_path.clone());
+        self.recent_paths.truncate(MAX_RECENT_PATHS);
+    }
+
     /// Reset settings to defaults by overwriting the file with default values.
     pub fn reset() -> Result<()> {
         let default = Self::default();
@@ -65,6 +538,9 @@ impl AppSettings {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::collection::vec;
+    use proptest::option;
+    use proptest::prelude::*;
     use tempfile::tempdir;
 
     #[test]
@@ -74,9 +550,12 @@ mod tests {
 
         // Temporarily override current_exe by creating a fake exe path (we can't change current_exe),
         // so we test save/load by writing directly to the path using the same toml format.
-        let mut s = AppSettings::default();
-        s.torrent_url = "https://example.com/torrent".into();
-        s.download_path = PathBuf::from("/tmp/downloads");
+        let s = AppSettings {
+            torrent_url: "https://example.com/torrent".into(),
+            download_path: PathBuf::from("/tmp/downloads"),
+            notifications: NotificationSettings { sound: true, quiet_hours: SeedWindow::parse("22:00-08:00") },
+            ..Default::default()
+        };
 
         let toml = toml::to_string_pretty(&s)?;
         fs::write(&path, toml)?;
@@ -86,4 +565,117 @@ mod tests {
         assert_eq!(s, loaded);
         Ok(())
     }
+
+    #[test]
+    fn test_remember_download_path() {
+        let mut s = AppSettings::default();
+        for i in 0..=MAX_RECENT_PATHS {
+            s.download_path = PathBuf::from(format!("mods{}", i));
+            s.remember_download_path();
+        }
+        s.download_path = PathBuf::from("mods2");
+        s.remember_download_path();
+        assert_eq!(s.recent_paths.len(), MAX_RECENT_PATHS);
+        assert_eq!(s.recent_paths[0], PathBuf::from("mods2"));
+        assert_eq!(s.recent_paths[1], PathBuf::from(format!("mods{}", MAX_RECENT_PATHS)));
+        assert!(!s.recent_paths.contains(&PathBuf::from("mods0")));
+    }
+
+    #[test]
+    fn test_cached_torrent_file_name_per_url() {
+        let name = cached_torrent_file_name("https://example.com/a.torrent");
+        assert_eq!(name, cached_torrent_file_name(" https://example.com/a.torrent\n"));
+        assert_ne!(name, cached_torrent_file_name("https://example.com/b.torrent"));
+        assert!(name.starts_with("cached-") && name.ends_with(".torrent"));
+    }
+
+    /// Any text, control characters and quotes included.
+    fn text() -> impl Strategy<Value = String> {
+        "(?s).{0,24}"
+    }
+
+    fn path() -> impl Strategy<Value = PathBuf> {
+        text().prop_map(PathBuf::from)
+    }
+
+    prop_compose! {
+        fn preset()(name in text(), up in any::<Option<u32>>(), down in any::<Option<u32>>()) -> BandwidthPreset {
+            BandwidthPreset { name, max_upload_speed: up, max_download_speed: down }
+        }
+    }
+
+    prop_compose! {
+        /// Settings with the fields edited in the settings panel varied and
+        /// the rest at their defaults.
+        fn settings()(
+            (torrent_url, torrent_mirrors, version_url, download_path, recent_paths) in
+                (text(), vec(text(), 0..4), text(), path(), vec(path(), 0..=MAX_RECENT_PATHS)),
+            (max_upload_speed, max_download_speed, should_seed, language, fleet_nodes) in
+                (any::<Option<u32>>(), any::<Option<u32>>(), any::<bool>(), text(), vec(text(), 0..3)),
+            (preset, accent, ui_scale, font_size, units) in (
+                prop_oneof![Just(ThemePreset::System), Just(ThemePreset::Dark), Just(ThemePreset::Light)],
+                any::<Option<[u8; 3]>>(),
+                DisplaySettings::UI_SCALE_RANGE,
+                DisplaySettings::FONT_SIZE_RANGE,
+                prop_oneof![Just(UnitSystem::Binary), Just(UnitSystem::Si)],
+            ),
+            (ignored_paths, banned_peers, extra_trackers, replace_trackers, seed_window) in (
+                vec(path(), 0..4),
+                vec(any::<IpAddr>(), 0..4),
+                vec(text(), 0..4),
+                any::<bool>(),
+                option::of((0..1440u32, 0..1440u32).prop_map(|(start, end)| SeedWindow { start, end })),
+            ),
+            // TOML integers are signed 64-bit
+            (cache_max_mb, cache_versions, stall_minutes, quarantine_days, shared_store) in
+                (option::of(0..=i64::MAX as u64), option::of(0..100usize), option::of(0..=i64::MAX as u64), any::<Option<u32>>(), option::of(path())),
+            (bandwidth_presets, gaming_mode_shortcut, read_only, strict_mirror, pinned, quarantine) in
+                (vec(preset(), 0..3), text(), any::<bool>(), any::<bool>(), any::<bool>(), any::<bool>()),
+        ) -> AppSettings {
+            AppSettings {
+                torrent_url, torrent_mirrors, version_url, download_path, recent_paths,
+                max_upload_speed, max_download_speed, should_seed, language, fleet_nodes,
+                theme: ThemeSettings { preset, accent },
+                display: DisplaySettings { ui_scale, font_size, units },
+                ignored_paths, banned_peers, extra_trackers, replace_trackers, seed_window,
+                cache_max_mb, cache_versions, stall_minutes, quarantine_days, shared_store,
+                bandwidth_presets, gaming_mode_shortcut, read_only, strict_mirror, pinned, quarantine,
+                ..Default::default()
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_settings_round_trip(s in settings()) {
+            let saved = toml::to_string_pretty(&s)?;
+            let loaded: AppSettings = toml::from_str(&saved)?;
+            prop_assert_eq!(&loaded, &s);
+            // Saving again writes the same file
+            prop_assert_eq!(toml::to_string_pretty(&loaded)?, saved);
+        }
+
+        /// A file with only some of the settings, like one from an older
+        /// version, or with settings from a newer one, loads with the
+        /// settings it has and defaults for the rest.
+        #[test]
+        fn test_partial_settings_load(s in settings(), keep in vec(any::<bool>(), 16)) {
+            let full = toml::Table::try_from(&s)?;
+            let mut partial: toml::Table = full
+                .iter()
+                .zip(keep.iter().cycle())
+                .filter(|(_, keep)| **keep)
+                .map(|((key, value), _)| (key.clone(), value.clone()))
+                .collect();
+            partial.insert("setting_from_a_newer_version".into(), toml::Value::Boolean(true));
+
+            let loaded: AppSettings = toml::from_str(&toml::to_string(&partial)?)?;
+            let loaded = toml::Table::try_from(&loaded)?;
+            let defaults = toml::Table::try_from(AppSettings::default())?;
+            for key in full.keys().chain(defaults.keys()) {
+                let expected = partial.get(key).or_else(|| defaults.get(key));
+                prop_assert_eq!(loaded.get(key), expected, "{}", key);
+            }
+        }
+    }
 }
@@ -0,0 +1,249 @@
+// src/sync/backend.rs
+
+//! Pluggable sources and transports.
+//!
+//! A `SyncSource` fetches the torrent that describes the repo and decides
+//! whether it is an update; the first registered source that handles a URL
+//! is used. A `Transport` brings the payload files into the download folder
+//! for one `TransferEngine`. `Backends::default()` registers the built-in
+//! ones, and `register_source` / `register_transport` add or override them.
+
+use anyhow::{anyhow, Result};
+use futures::future::BoxFuture;
+use std::sync::Arc;
+
+use super::rsync::TransferEngine;
+use super::types::SyncConfig;
+use super::utils::{calculate_torrent_hash, download_torrent};
+use super::{ipfs, rsync, s3, sftp, webdav};
+
+/// What a source gets to fetch with.
+pub struct SourceContext<'a> {
+    pub config: &'a SyncConfig,
+    pub http_client: &'a reqwest::Client,
+}
+
+pub trait SyncSource: Send + Sync {
+    /// Short name for logs.
+    fn name(&self) -> &'static str;
+
+    fn handles(&self, url: &str) -> bool;
+
+    /// Download the torrent named by `url`.
+    fn fetch<'a>(&'a self, url: &'a str, ctx: SourceContext<'a>) -> BoxFuture<'a, Result<Vec<u8>>>;
+
+    /// Whether `remote` differs from the cached torrent with hash
+    /// `cached_hash`; `None` means nothing is cached yet.
+    fn is_update(&self, remote: &[u8], cached_hash: Option<&str>) -> bool {
+        cached_hash != Some(calculate_torrent_hash(remote).as_str())
+    }
+}
+
+pub trait Transport: Send + Sync {
+    fn engine(&self) -> TransferEngine;
+
+    /// Bring the payload into the download folder ahead of adding the
+    /// torrent, which then hash-checks the files and fetches the rest.
+    fn fetch_payload<'a>(&'a self, config: &'a SyncConfig) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Registered sources and transports.
+#[derive(Clone)]
+pub struct Backends {
+    sources: Vec<Arc<dyn SyncSource>>,
+    transports: Vec<Arc<dyn Transport>>,
+}
+
+impl Default for Backends {
+    fn default() -> Self {
+        let mut backends = Backends {
+            sources: Vec::new(),
+            transports: Vec::new(),
+        };
+        // Registered last, so it is the fallback for anything else
+        backends.register_source(Arc::new(HttpSource));
+        for source in [Arc::new(S3Source) as Arc<dyn SyncSource>, Arc::new(IpfsSource), Arc::new(WebDavSource), Arc::new(SftpSource)] {
+            backends.register_source(source);
+        }
+        backends.register_transport(Arc::new(TorrentTransport));
+        backends.register_transport(Arc::new(RsyncTransport));
+        backends
+    }
+}
+
+impl Backends {
+    /// Add `source`, taking precedence over the ones registered before.
+    pub fn register_source(&mut self, source: Arc<dyn SyncSource>) {
+        self.sources.insert(0, source);
+    }
+
+    /// Add `transport`, replacing any registered for the same engine.
+    pub fn register_transport(&mut self, transport: Arc<dyn Transport>) {
+        self.transports.retain(|t| t.engine() != transport.engine());
+        self.transports.push(transport);
+    }
+
+    pub fn source_for(&self, url: &str) -> Result<&dyn SyncSource> {
+        self.sources
+            .iter()
+            .find(|s| s.handles(url))
+            .map(|s| s.as_ref())
+            .ok_or_else(|| anyhow!("No source handles {}", url))
+    }
+
+    pub fn transport_for(&self, engine: TransferEngine) -> Result<&dyn Transport> {
+        self.transports
+            .iter()
+            .find(|t| t.engine() == engine)
+            .map(|t| t.as_ref())
+            .ok_or_else(|| anyhow!("No transport registered for {:?}", engine))
+    }
+}
+
+/// Plain HTTP(S) downloads.
+struct HttpSource;
+
+impl SyncSource for HttpSource {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn handles(&self, url: &str) -> bool {
+        url.starts_with("http://") || url.starts_with("https://")
+    }
+
+    fn fetch<'a>(&'a self, url: &'a str, ctx: SourceContext<'a>) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(download_torrent(url, ctx.http_client))
+    }
+}
+
+/// `s3://` URLs, signed on every fetch.
+struct S3Source;
+
+impl SyncSource for S3Source {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    fn handles(&self, url: &str) -> bool {
+        url.starts_with(s3::SCHEME)
+    }
+
+    fn fetch<'a>(&'a self, url: &'a str, ctx: SourceContext<'a>) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(async move {
+            let signed = s3::presigned_url(url, &ctx.config.s3, ctx.http_client).await?;
+            download_torrent(&signed, ctx.http_client).await
+        })
+    }
+}
+
+struct IpfsSource;
+
+impl SyncSource for IpfsSource {
+    fn name(&self) -> &'static str {
+        "ipfs"
+    }
+
+    fn handles(&self, url: &str) -> bool {
+        url.starts_with(ipfs::SCHEME)
+    }
+
+    fn fetch<'a>(&'a self, url: &'a str, ctx: SourceContext<'a>) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(ipfs::download(url, &ctx.config.ipfs, ctx.http_client))
+    }
+}
+
+struct WebDavSource;
+
+impl SyncSource for WebDavSource {
+    fn name(&self) -> &'static str {
+        "webdav"
+    }
+
+    fn handles(&self, url: &str) -> bool {
+        webdav::is_webdav_url(url)
+    }
+
+    fn fetch<'a>(&'a self, url: &'a str, ctx: SourceContext<'a>) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(webdav::download(url, &ctx.config.credentials, ctx.http_client))
+    }
+}
+
+struct SftpSource;
+
+impl SyncSource for SftpSource {
+    fn name(&self) -> &'static str {
+        "sftp"
+    }
+
+    fn handles(&self, url: &str) -> bool {
+        url.starts_with(sftp::SCHEME)
+    }
+
+    fn fetch<'a>(&'a self, url: &'a str, ctx: SourceContext<'a>) -> BoxFuture<'a, Result<Vec<u8>>> {
+        Box::pin(sftp::download(url, &ctx.config.sftp))
+    }
+}
+
+/// The swarm fetches the payload once the torrent is added.
+struct TorrentTransport;
+
+impl Transport for TorrentTransport {
+    fn engine(&self) -> TransferEngine {
+        TransferEngine::Torrent
+    }
+
+    fn fetch_payload<'a>(&'a self, _config: &'a SyncConfig) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+struct RsyncTransport;
+
+impl Transport for RsyncTransport {
+    fn engine(&self) -> TransferEngine {
+        TransferEngine::Rsync
+    }
+
+    fn fetch_payload<'a>(&'a self, config: &'a SyncConfig) -> BoxFuture<'a, Result<()>> {
+        Box::pin(rsync::transfer(&config.rsync, &config.sftp, &config.download_path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct GopherSource;
+
+    impl SyncSource for GopherSource {
+        fn name(&self) -> &'static str {
+            "gopher"
+        }
+
+        fn handles(&self, url: &str) -> bool {
+            url.starts_with("gopher://") || url.starts_with("http://gopher.")
+        }
+
+        fn fetch<'a>(&'a self, _url: &'a str, _ctx: SourceContext<'a>) -> BoxFuture<'a, Result<Vec<u8>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+    }
+
+    #[test]
+    fn test_registered_sources_take_precedence() {
+        let mut backends = Backends::default();
+        assert_eq!(backends.source_for("sftp://host/repo.torrent").unwrap().name(), "sftp");
+        assert_eq!(backends.source_for("http://gopher.example/repo.torrent").unwrap().name(), "http");
+        assert!(backends.source_for("gopher://example/repo.torrent").is_err());
+
+        backends.register_source(Arc::new(GopherSource));
+        assert_eq!(backends.source_for("gopher://example/repo.torrent").unwrap().name(), "gopher");
+        assert_eq!(backends.source_for("http://gopher.example/repo.torrent").unwrap().name(), "gopher");
+        assert_eq!(backends.transport_for(TransferEngine::Rsync).unwrap().engine(), TransferEngine::Rsync);
+
+        let source = backends.source_for("https://example.com/repo.torrent").unwrap();
+        assert!(source.is_update(b"new", None));
+        assert!(!source.is_update(b"same", Some(&calculate_torrent_hash(b"same"))));
+    }
+}
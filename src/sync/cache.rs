@@ -0,0 +1,168 @@
+// src/sync/cache.rs
+
+//! Cache of the torrent versions seen on the remote.
+//!
+//! Each version is kept as `<hash>.torrent` in the cache folder, next to the
+//! single cached torrent the manager compares against. A file's modification
+//! time doubles as its last use, so when the folder grows past the size cap
+//! the least recently seen versions are evicted first. The versions kept
+//! are what a rollback can go back to.
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use super::types::SyncConfig;
+use super::utils::calculate_torrent_hash;
+
+const EXTENSION: &str = "torrent";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub last_used: SystemTime,
+}
+
+pub struct TorrentCache {
+    dir: PathBuf,
+}
+
+impl TorrentCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Keep `torrent`, or mark the copy already kept as used.
+    pub fn store(&self, torrent: &[u8]) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir).with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.dir.join(format!("{}.{}", calculate_torrent_hash(torrent), EXTENSION));
+        if path.exists() {
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(SystemTime::now()))
+                .with_context(|| format!("Failed to touch {}", path.display()))?;
+        } else {
+            std::fs::write(&path, torrent).with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok(path)
+    }
+
+    /// Cached torrents, most recently used first.
+    pub fn entries(&self) -> Result<Vec<CacheEntry>> {
+        let read_dir = match std::fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to list {}", self.dir.display())),
+        };
+        let mut entries = Vec::new();
+        for entry in read_dir.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == EXTENSION)
+                && let Ok(metadata) = entry.metadata()
+                && metadata.is_file()
+            {
+                entries.push(CacheEntry {
+                    path,
+                    size: metadata.len(),
+                    last_used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                });
+            }
+        }
+        entries.sort_by_key(|e| std::cmp::Reverse(e.last_used));
+        Ok(entries)
+    }
+
+    /// Evict the least recently used torrents until the rest fit in
+    /// `max_bytes`. The most recent one is always kept.
+    pub fn enforce_cap(&self, max_bytes: u64) -> Result<Vec<PathBuf>> {
+        let mut total = 0u64;
+        let mut evicted = Vec::new();
+        for (i, entry) in self.entries()?.into_iter().enumerate() {
+            total += entry.size;
+            if i > 0 && total > max_bytes {
+                std::fs::remove_file(&entry.path).with_context(|| format!("Failed to remove {}", entry.path.display()))?;
+                evicted.push(entry.path);
+            }
+        }
+        Ok(evicted)
+    }
+
+    /// Evict all but the `count` most recently used torrents, always
+    /// keeping at least one.
+    pub fn keep_latest(&self, count: usize) -> Result<Vec<PathBuf>> {
+        let mut evicted = Vec::new();
+        for entry in self.entries()?.into_iter().skip(count.max(1)) {
+            std::fs::remove_file(&entry.path).with_context(|| format!("Failed to remove {}", entry.path.display()))?;
+            evicted.push(entry.path);
+        }
+        Ok(evicted)
+    }
+
+    /// Remove every cached torrent, returning how many there were.
+    pub fn clear(&self) -> Result<usize> {
+        let entries = self.entries()?;
+        for entry in &entries {
+            std::fs::remove_file(&entry.path).with_context(|| format!("Failed to remove {}", entry.path.display()))?;
+        }
+        Ok(entries.len())
+    }
+}
+
+/// Keep `torrent` in the configured cache folder and apply the version
+/// and size caps. Failures are logged, as the cache is only a convenience.
+pub fn remember(config: &SyncConfig, torrent: &[u8]) {
+    let Some(dir) = &config.cache_dir else {
+        return;
+    };
+    let cache = TorrentCache::new(dir);
+    let result = cache.store(torrent).and_then(|path| {
+        println!("Sync: Cached torrent version {}", path.display());
+        let mut evicted = match config.cache_versions {
+            Some(count) => cache.keep_latest(count)?,
+            None => Vec::new(),
+        };
+        if let Some(max_mb) = config.cache_max_mb {
+            evicted.extend(cache.enforce_cap(max_mb.saturating_mul(1024 * 1024))?);
+        }
+        Ok(evicted)
+    });
+    match result {
+        Ok(evicted) => {
+            for path in evicted {
+                println!("Sync: Evicted cached torrent {}", path.display());
+            }
+        }
+        Err(e) => eprintln!("Sync: Failed to update torrent cache: {:#}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_enforce_cap_evicts_least_recently_used() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = TorrentCache::new(dir.path().join("cache"));
+        let old = cache.store(&[1u8; 100])?;
+        let middle = cache.store(&[2u8; 100])?;
+        let new = cache.store(&[3u8; 100])?;
+        let now = SystemTime::now();
+        for (path, age) in [(&old, 30), (&middle, 20), (&new, 10)] {
+            std::fs::File::options().write(true).open(path)?.set_modified(now - Duration::from_secs(age))?;
+        }
+        // Seeing the oldest version again makes it the most recent
+        assert_eq!(cache.store(&[1u8; 100])?, old);
+
+        assert_eq!(cache.keep_latest(3)?, Vec::<PathBuf>::new());
+        assert_eq!(cache.enforce_cap(250)?, vec![middle]);
+        assert_eq!(cache.entries()?.iter().map(|e| e.size).sum::<u64>(), 200);
+        assert_eq!(cache.keep_latest(0)?, vec![new]);
+        assert_eq!(cache.clear()?, 1);
+        assert!(cache.entries()?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+// src/sync/checking.rs
+
+//! Speed of librqbit's initial file check.
+//!
+//! While a torrent is initializing, its progress counts the bytes hashed so
+//! far. Following it over time gives the checking speed and how long the
+//! check still takes, and flags a check far slower than any drive should
+//! be, which usually means antivirus is scanning every read.
+
+use std::time::{Duration, Instant};
+
+use super::snapshot::TorrentSnapshot;
+
+/// Checking speed below which the check counts as abnormally slow.
+pub const SLOW_CHECK_BYTES_PER_SEC: f64 = 20.0 * 1024.0 * 1024.0;
+
+/// How long the check is followed before its speed is judged.
+const SETTLE_TIME: Duration = Duration::from_secs(20);
+
+/// Where the running check is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheckProgress {
+    pub checked_bytes: u64,
+    pub total_bytes: u64,
+    pub elapsed: Duration,
+    /// Average speed since the check was first seen; `None` until there are
+    /// two samples.
+    pub bytes_per_sec: Option<f64>,
+    pub remaining: Option<Duration>,
+    /// Whether the check has run long enough to judge and is slower than
+    /// `SLOW_CHECK_BYTES_PER_SEC`.
+    pub slow: bool,
+}
+
+/// Follows the snapshots of the managed torrent through its checks.
+#[derive(Debug, Default)]
+pub struct CheckMonitor {
+    /// When the running check was first seen and how far it was then.
+    start: Option<(Instant, u64)>,
+    latest: Option<(Instant, u64, u64)>,
+}
+
+impl CheckMonitor {
+    pub fn observe(&mut self, snapshot: &TorrentSnapshot, now: Instant) {
+        if snapshot.state != "initializing" {
+            *self = Self::default();
+            return;
+        }
+        // A check that starts over is a new check
+        if let Some((_, checked, _)) = self.latest
+            && snapshot.progress_bytes < checked
+        {
+            self.start = None;
+        }
+        self.start.get_or_insert((now, snapshot.progress_bytes));
+        self.latest = Some((now, snapshot.progress_bytes, snapshot.total_bytes));
+    }
+
+    /// The running check, if the torrent is being checked.
+    pub fn progress(&self) -> Option<CheckProgress> {
+        let (started, first_checked) = self.start?;
+        let (now, checked_bytes, total_bytes) = self.latest?;
+        let elapsed = now.duration_since(started);
+        let bytes_per_sec = (!elapsed.is_zero())
+            .then(|| checked_bytes.saturating_sub(first_checked) as f64 / elapsed.as_secs_f64());
+        let remaining = bytes_per_sec
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| Duration::from_secs_f64(total_bytes.saturating_sub(checked_bytes) as f64 / rate));
+        Some(CheckProgress {
+            checked_bytes,
+            total_bytes,
+            elapsed,
+            bytes_per_sec,
+            remaining,
+            slow: elapsed >= SETTLE_TIME && bytes_per_sec.is_some_and(|rate| rate < SLOW_CHECK_BYTES_PER_SEC),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(state: &str, progress_bytes: u64) -> TorrentSnapshot {
+        TorrentSnapshot {
+            state: state.to_string(),
+            progress_bytes,
+            total_bytes: 1 << 30,
+            ..TorrentSnapshot::default()
+        }
+    }
+
+    #[test]
+    fn test_check_speed_and_remaining_time() {
+        const MIB: u64 = 1024 * 1024;
+        let start = Instant::now();
+        let mut monitor = CheckMonitor::default();
+        monitor.observe(&snapshot("initializing", 0), start);
+        assert_eq!(monitor.progress().unwrap().bytes_per_sec, None);
+
+        monitor.observe(&snapshot("initializing", 100 * MIB), start + Duration::from_secs(10));
+        let progress = monitor.progress().unwrap();
+        assert_eq!(progress.bytes_per_sec, Some(10.0 * MIB as f64));
+        assert_eq!(progress.remaining, Some(Duration::from_secs_f64(92.4)));
+        assert!(!progress.slow);
+
+        monitor.observe(&snapshot("initializing", 200 * MIB), start + Duration::from_secs(20));
+        assert!(monitor.progress().unwrap().slow);
+
+        monitor.observe(&snapshot("live", 1 << 30), start + Duration::from_secs(30));
+        assert_eq!(monitor.progress(), None);
+    }
+}
@@ -5,9 +5,15 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Whether `relative_path` is one of `ignored_paths` or below one of them.
+pub fn is_ignored(relative_path: &Path, ignored_paths: &[PathBuf]) -> bool {
+    ignored_paths.iter().any(|ignored| relative_path.starts_with(ignored))
+}
+
 pub fn find_extra_files(
     download_path: &Path,
     expected_files: &HashSet<PathBuf>,
+    ignored_paths: &[PathBuf],
 ) -> Result<Vec<PathBuf>> {
     println!(
         "Cleaner: Scanning dir '{}' for extra files...",
@@ -30,7 +36,9 @@ pub fn find_extra_files(
                 let relative_path_buf = relative_path.to_path_buf();
                 local_files.insert(relative_path_buf.clone());
                 // If this local file is not in the expected set, it's extra
-                if !expected_files.contains(&relative_path_buf) {
+                if is_ignored(relative_path, ignored_paths) {
+                    println!("Cleaner: Ignoring protected file: {}", relative_path.display());
+                } else if !expected_files.contains(&relative_path_buf) {
                     println!(
                         "Cleaner: Found extra file: {}",
                         relative_path.display()
@@ -104,8 +112,13 @@ pub fn find_missing_files(
 
 use librqbit::api::TorrentDetailsResponse;
 
+use super::mapping::{map_path, FolderMapping};
+
+/// Paths of the included files in `details`, relative to the download
+/// folder after `mappings`.
 pub fn get_expected_files_from_details(
     details: &TorrentDetailsResponse,
+    mappings: &[FolderMapping],
 ) -> HashSet<PathBuf> {
     let mut expected = HashSet::new();
     if let Some(files) = &details.files {
@@ -116,6 +129,7 @@ pub fn get_expected_files_from_details(
                 for component in &file_detail.components {
                     current_path.push(component);
                 }
+                let current_path = map_path(mappings, &current_path);
                 println!("Cleaner: Adding expected relative path: {}", current_path.display());
                 expected.insert(current_path);
             }
@@ -163,7 +177,7 @@ mod tests {
             stats: None,
         };
 
-        let expected = get_expected_files_from_details(&details);
+        let expected = get_expected_files_from_details(&details, &[]);
         let mut expected_set = HashSet::new();
         expected_set.insert(PathBuf::from("file1.txt"));
         expected_set.insert(PathBuf::from("subdir/file2.dat"));
@@ -186,7 +200,7 @@ mod tests {
             stats: None,
         };
 
-        let expected = get_expected_files_from_details(&details);
+        let expected = get_expected_files_from_details(&details, &[]);
         let mut expected_set = HashSet::new();
         expected_set.insert(PathBuf::from("file1.txt"));
         expected_set.insert(PathBuf::from("subdir/file3.log"));
@@ -208,7 +222,7 @@ mod tests {
             stats: None,
         };
 
-        let expected = get_expected_files_from_details(&details);
+        let expected = get_expected_files_from_details(&details, &[]);
         let mut expected_set = HashSet::new();
         expected_set.insert(PathBuf::from("file1.txt"));
         assert_eq!(expected, expected_set);
@@ -238,7 +252,7 @@ mod tests {
         .collect();
 
         let dir = setup_test_dir(&["file1.txt", "subdir/file2.dat"])?;
-        let extra = find_extra_files(dir.path(), &expected_files_rel)?;
+        let extra = find_extra_files(dir.path(), &expected_files_rel, &[])?;
         assert!(extra.is_empty());
         Ok(())
     }
@@ -253,7 +267,7 @@ mod tests {
         .collect();
 
         let dir = setup_test_dir(&["file1.txt", "extra_file.log"])?;
-        let extra = find_extra_files(dir.path(), &expected_files_rel)?;
+        let extra = find_extra_files(dir.path(), &expected_files_rel, &[])?;
         
         assert_eq!(extra.len(), 1);
         assert!(extra[0].ends_with("extra_file.log"));
@@ -271,7 +285,7 @@ mod tests {
         .collect();
 
         let dir = setup_test_dir(&["file1.txt", "subdir/extra.tmp"])?;
-        let extra = find_extra_files(dir.path(), &expected_files_rel)?;
+        let extra = find_extra_files(dir.path(), &expected_files_rel, &[])?;
         
         assert_eq!(extra.len(), 1);
         assert!(extra[0].ends_with("subdir/extra.tmp"));
@@ -279,6 +293,18 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_find_extra_files_skips_ignored_paths() -> Result<()> {
+        let expected_files_rel: HashSet<PathBuf> = [PathBuf::from("file1.txt")].into();
+
+        let dir = setup_test_dir(&["file1.txt", "userconfig/cba.hpp", "notes.txt", "extra.log"])?;
+        let ignored = [PathBuf::from("userconfig"), PathBuf::from("notes.txt")];
+        let extra = find_extra_files(dir.path(), &expected_files_rel, &ignored)?;
+
+        assert_eq!(extra, vec![dir.path().join("extra.log")]);
+        Ok(())
+    }
+
     #[test]
     fn test_find_extra_files_multiple_extra() -> Result<()> {
         let expected_files_rel: HashSet<PathBuf> = [
@@ -289,7 +315,7 @@ mod tests {
         .collect();
 
         let dir = setup_test_dir(&["data/file.dat", "extra1.txt", "other/extra2.log"])?;
-        let mut extra = find_extra_files(dir.path(), &expected_files_rel)?;
+        let mut extra = find_extra_files(dir.path(), &expected_files_rel, &[])?;
         extra.sort(); // Sort for consistent assertion
         
         assert_eq!(extra.len(), 2);
@@ -311,7 +337,7 @@ mod tests {
 
         // Only create file1.txt locally, NO extra files
         let dir = setup_test_dir(&["file1.txt"])?;
-        let extra = find_extra_files(dir.path(), &expected_files_rel)?;
+        let extra = find_extra_files(dir.path(), &expected_files_rel, &[])?;
         
         // Should find no *extra* files
         assert!(extra.is_empty());
@@ -322,7 +348,7 @@ mod tests {
     fn test_find_extra_files_empty_dir() -> Result<()> {
         let expected_files_rel: HashSet<PathBuf> = HashSet::new();
         let dir = setup_test_dir(&[])?; // Empty dir
-        let extra = find_extra_files(dir.path(), &expected_files_rel)?;
+        let extra = find_extra_files(dir.path(), &expected_files_rel, &[])?;
         assert!(extra.is_empty());
         Ok(())
     }
@@ -331,7 +357,7 @@ mod tests {
     fn test_find_extra_files_non_existent_dir() -> Result<()> {
         let expected_files_rel: HashSet<PathBuf> = HashSet::new();
         let non_existent_path = PathBuf::from("surely_this_does_not_exist_12345");
-        let extra = find_extra_files(&non_existent_path, &expected_files_rel)?;
+        let extra = find_extra_files(&non_existent_path, &expected_files_rel, &[])?;
         assert!(extra.is_empty());
         Ok(())
     }
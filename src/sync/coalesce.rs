@@ -0,0 +1,82 @@
+// src/sync/coalesce.rs
+
+//! Drops status events that repeat the last one sent.
+//!
+//! Torrent stats are sent on every status refresh and again after each
+//! operation on the torrent, and the peer list on every refresh. A paused,
+//! finished or idle torrent would send the same values every ten seconds,
+//! waking the UI and every other subscriber for nothing, so only changes
+//! get through.
+
+use tokio::sync::mpsc;
+
+use super::messages::SyncEvent;
+use super::peers::PeerInfo;
+use super::snapshot::TorrentSnapshot;
+
+/// The last stats and peers let through.
+#[derive(Debug, Default)]
+pub struct Coalescer {
+    stats: Option<Option<(usize, TorrentSnapshot)>>,
+    peers: Option<Vec<PeerInfo>>,
+}
+
+impl Coalescer {
+    /// Whether `event` only repeats what was already sent; remembers it
+    /// otherwise.
+    pub fn is_repeat(&mut self, event: &SyncEvent) -> bool {
+        fn repeats<T: PartialEq + Clone>(last: &mut Option<T>, value: &T) -> bool {
+            if last.as_ref() == Some(value) {
+                return true;
+            }
+            *last = Some(value.clone());
+            false
+        }
+        match event {
+            SyncEvent::ManagedTorrentUpdate(update) => repeats(&mut self.stats, update),
+            SyncEvent::Peers(peers) => repeats(&mut self.peers, peers),
+            _ => false,
+        }
+    }
+}
+
+/// Wrap `ui_tx` so repeated stats and peer lists are dropped. Every other
+/// event is forwarded unchanged and in order.
+pub fn tap_events(ui_tx: mpsc::UnboundedSender<SyncEvent>) -> mpsc::UnboundedSender<SyncEvent> {
+    let (tap_tx, mut tap_rx) = mpsc::unbounded_channel::<SyncEvent>();
+    tokio::spawn(async move {
+        let mut coalescer = Coalescer::default();
+        while let Some(event) = tap_rx.recv().await {
+            if coalescer.is_repeat(&event) {
+                continue;
+            }
+            if ui_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+    tap_tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_changes_get_through() {
+        let mut coalescer = Coalescer::default();
+        let stats = |progress_bytes| SyncEvent::ManagedTorrentUpdate(Some((0, TorrentSnapshot { progress_bytes, ..Default::default() })));
+        assert!(!coalescer.is_repeat(&stats(10)));
+        assert!(coalescer.is_repeat(&stats(10)));
+        assert!(!coalescer.is_repeat(&stats(20)));
+        // The torrent going away, and coming back with the same stats
+        assert!(!coalescer.is_repeat(&SyncEvent::ManagedTorrentUpdate(None)));
+        assert!(coalescer.is_repeat(&SyncEvent::ManagedTorrentUpdate(None)));
+        assert!(!coalescer.is_repeat(&stats(20)));
+
+        assert!(!coalescer.is_repeat(&SyncEvent::Peers(Vec::new())));
+        assert!(coalescer.is_repeat(&SyncEvent::Peers(Vec::new())));
+        assert!(!coalescer.is_repeat(&SyncEvent::RunFinished));
+        assert!(!coalescer.is_repeat(&SyncEvent::RunFinished));
+    }
+}
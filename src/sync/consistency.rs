@@ -0,0 +1,103 @@
+// src/sync/consistency.rs
+
+//! Check that the torrent loaded in the session is the cached one and
+//! writes to the configured download path.
+//!
+//! A torrent keeps its output folder until it is re-added, so after the
+//! download path changes, or when the cache and session disagree, the
+//! manager would keep syncing to the wrong place. The manager pauses the
+//! torrent when this check finds a problem and re-adding it from the cache
+//! (the repair run) clears it.
+
+use librqbit::api::TorrentDetailsResponse;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::types::SyncConfig;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Inconsistency {
+    /// The loaded torrent is not the cached one.
+    InfoHash { cached: String, loaded: String },
+    /// The loaded torrent writes outside the configured download path.
+    OutputFolder { configured: PathBuf, loaded: PathBuf },
+}
+
+/// Compare the loaded torrent in `details` with the `cached` torrent and
+/// `download_path`. A cached torrent that can't be parsed is not compared.
+pub fn find_inconsistencies(cached: Option<&[u8]>, download_path: &Path, details: &TorrentDetailsResponse) -> Vec<Inconsistency> {
+    let mut problems = Vec::new();
+    if let Some(cached) = cached
+        && let Ok(meta) = librqbit::torrent_from_bytes::<librqbit::ByteBuf>(cached)
+    {
+        let cached = meta.info_hash.as_string();
+        if !cached.eq_ignore_ascii_case(&details.info_hash) {
+            problems.push(Inconsistency::InfoHash {
+                cached,
+                loaded: details.info_hash.clone(),
+            });
+        }
+    }
+    let loaded = Path::new(&details.output_folder);
+    if !same_folder(download_path, loaded) {
+        problems.push(Inconsistency::OutputFolder {
+            configured: download_path.to_path_buf(),
+            loaded: loaded.to_path_buf(),
+        });
+    }
+    problems
+}
+
+/// Whether `a` and `b` name the same folder, resolving links and relative
+/// paths where the folders exist.
+fn same_folder(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a.components().eq(b.components()),
+    }
+}
+
+/// Check torrent `id` against the cached torrent and download path of
+/// `config`. Problems reading either side are logged and not reported.
+pub async fn check(config: &SyncConfig, api: &librqbit::api::Api, id: usize) -> Vec<Inconsistency> {
+    let details = match api.api_torrent_details(id.into()) {
+        Ok(details) => details,
+        Err(e) => {
+            eprintln!("Sync: Skipping consistency check, failed to get torrent details: {}", e);
+            return Vec::new();
+        }
+    };
+    let cached = match &config.cached_torrent_path {
+        Some(path) => tokio::fs::read(path).await.ok(),
+        None => None,
+    };
+    find_inconsistencies(cached.as_deref(), &config.download_path, &details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_inconsistencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let details = TorrentDetailsResponse {
+            id: Some(1),
+            info_hash: "00".repeat(20),
+            name: None,
+            output_folder: dir.path().join("old").display().to_string(),
+            files: None,
+            stats: None,
+        };
+        // Not a torrent, so only the folders are compared
+        let problems = find_inconsistencies(Some(b"garbage"), &dir.path().join("new"), &details);
+        assert_eq!(
+            problems,
+            [Inconsistency::OutputFolder {
+                configured: dir.path().join("new"),
+                loaded: dir.path().join("old"),
+            }]
+        );
+        assert!(find_inconsistencies(None, &dir.path().join("old/."), &details).is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+// src/sync/create.rs
+
+//! Creating the torrent of a mod folder, for repository admins.
+//!
+//! A private torrent only gets peers from its tracker: clients skip DHT and
+//! peer exchange for it, as librqbit does when it loads one. It needs a
+//! tracker to find any peers at all.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+#[derive(Debug, Clone, Default)]
+pub struct CreateOptions<'a> {
+    /// Announce URL of the tracker.
+    pub tracker: Option<&'a str>,
+    /// Set the private flag in the metainfo.
+    pub private: bool,
+}
+
+/// Hash `folder` into a torrent and return its bytes.
+pub async fn create_torrent(folder: &Path, options: CreateOptions<'_>) -> Result<Vec<u8>> {
+    if !folder.exists() {
+        bail!("{} does not exist", folder.display());
+    }
+    let created = librqbit::create_torrent(folder, Default::default())
+        .await
+        .with_context(|| format!("Failed to create a torrent of {}", folder.display()))?;
+    let mut meta = created.as_info().clone();
+    meta.info.private = options.private;
+    meta.announce = options.tracker.map(|url| url.as_bytes().to_vec().into());
+    let mut torrent = Vec::new();
+    bencode::bencode_serialize_to_writer(&meta, &mut torrent).context("Failed to encode the torrent")?;
+    Ok(torrent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_private(torrent: &[u8]) -> Result<bool> {
+        Ok(librqbit::torrent_from_bytes::<librqbit::ByteBuf>(torrent)?.info.private)
+    }
+
+    #[tokio::test]
+    async fn test_create_private_torrent() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let folder = dir.path().join("@mod");
+        std::fs::create_dir_all(folder.join("addons"))?;
+        std::fs::write(folder.join("addons/a.pbo"), vec![7u8; 40_000])?;
+        std::fs::write(folder.join("mod.cpp"), "name = \"mod\";")?;
+
+        let public = create_torrent(&folder, CreateOptions::default()).await?;
+        assert!(!is_private(&public)?);
+
+        let tracker = "https://tracker.example.com/announce";
+        let private = create_torrent(&folder, CreateOptions { tracker: Some(tracker), private: true }).await?;
+        assert!(is_private(&private)?);
+        let meta = librqbit::torrent_from_bytes::<librqbit::ByteBuf>(&private)?;
+        assert_eq!(meta.announce.as_ref().map(|a| a.as_ref()), Some(tracker.as_bytes()));
+        assert_eq!(meta.info.iter_file_lengths()?.sum::<u64>(), 40_000 + 13);
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+// src/sync/credentials.rs
+
+//! Logins for remote sources, looked up by host name.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Credential {
+    /// Host name the login applies to, e.g. `cloud.example.com`.
+    pub host: String,
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CredentialStore {
+    pub entries: Vec<Credential>,
+}
+
+impl CredentialStore {
+    /// The login for `host`, compared case-insensitively.
+    pub fn for_host(&self, host: &str) -> Option<&Credential> {
+        self.entries.iter().find(|c| c.host.trim().eq_ignore_ascii_case(host))
+    }
+}
@@ -0,0 +1,109 @@
+// src/sync/diff.rs
+
+//! What changed between two torrent versions.
+//!
+//! Files are matched by their path in the torrent, so a renamed file shows
+//! up as removed under its old path and added under the new one. Padding
+//! files are left out.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResizedFile {
+    pub path: PathBuf,
+    pub old_length: u64,
+    pub new_length: u64,
+}
+
+/// Files added, removed and resized from an old to a new version, each
+/// sorted by path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TorrentDiff {
+    pub added: Vec<(PathBuf, u64)>,
+    pub removed: Vec<(PathBuf, u64)>,
+    pub resized: Vec<ResizedFile>,
+    /// Files in both versions with the same size.
+    pub unchanged: usize,
+}
+
+impl TorrentDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.resized.is_empty()
+    }
+
+    /// Change in total size from the old to the new version.
+    pub fn size_change(&self) -> i64 {
+        // Lengths come from the torrents, so saturate rather than overflow
+        let signed = |length: u64| i64::try_from(length).unwrap_or(i64::MAX);
+        let added = self.added.iter().fold(0i64, |sum, (_, length)| sum.saturating_add(signed(*length)));
+        let removed = self.removed.iter().fold(0i64, |sum, (_, length)| sum.saturating_add(signed(*length)));
+        let resized = self.resized.iter().fold(0i64, |sum, f| sum.saturating_add(signed(f.new_length).saturating_sub(signed(f.old_length))));
+        added.saturating_sub(removed).saturating_add(resized)
+    }
+}
+
+/// Paths and lengths of the files in `torrent`.
+fn torrent_files(torrent: &[u8]) -> Result<BTreeMap<PathBuf, u64>> {
+    let meta = librqbit::torrent_from_bytes::<librqbit::ByteBuf>(torrent)?;
+    let mut files = BTreeMap::new();
+    for details in meta.info.iter_file_details()? {
+        if !details.attrs().padding {
+            files.insert(details.filename.to_pathbuf()?, details.len);
+        }
+    }
+    Ok(files)
+}
+
+pub fn diff_torrents(old: &[u8], new: &[u8]) -> Result<TorrentDiff> {
+    let old = torrent_files(old)?;
+    let mut new = torrent_files(new)?;
+    let mut diff = TorrentDiff::default();
+    for (path, old_length) in old {
+        match new.remove(&path) {
+            None => diff.removed.push((path, old_length)),
+            Some(new_length) if new_length != old_length => diff.resized.push(ResizedFile { path, old_length, new_length }),
+            Some(_) => diff.unchanged += 1,
+        }
+    }
+    diff.added = new.into_iter().collect();
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Multi-file torrent of `files`, given as `/`-separated paths and
+    /// lengths, with a dummy piece hash.
+    fn torrent(files: &[(&str, u64)]) -> Vec<u8> {
+        let mut t = b"d4:infod5:filesl".to_vec();
+        for (path, length) in files {
+            t.extend(format!("d6:lengthi{}e4:pathl", length).into_bytes());
+            for part in path.split('/') {
+                t.extend(format!("{}:{}", part.len(), part).into_bytes());
+            }
+            t.extend(b"ee");
+        }
+        t.extend(b"e4:name4:mods12:piece lengthi16384e6:pieces20:");
+        t.extend([0u8; 20]);
+        t.extend(b"ee");
+        t
+    }
+
+    #[test]
+    fn test_diff_torrents() -> Result<()> {
+        let old = torrent(&[("@a/a.pbo", 100), ("@b/b.pbo", 200), ("@c/c.pbo", 300)]);
+        let new = torrent(&[("@a/a.pbo", 100), ("@b/b.pbo", 250), ("@d/d.pbo", 50)]);
+        let diff = diff_torrents(&old, &new)?;
+        assert_eq!(diff.added, vec![(PathBuf::from("@d/d.pbo"), 50)]);
+        assert_eq!(diff.removed, vec![(PathBuf::from("@c/c.pbo"), 300)]);
+        assert_eq!(diff.resized, vec![ResizedFile { path: PathBuf::from("@b/b.pbo"), old_length: 200, new_length: 250 }]);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.size_change(), -200);
+        assert!(diff_torrents(&old, &old)?.is_empty());
+        Ok(())
+    }
+}
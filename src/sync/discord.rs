@@ -0,0 +1,230 @@
+// src/sync/discord.rs
+
+//! Discord Rich Presence showing what the sync is doing.
+//!
+//! Discord's local RPC listens on `discord-ipc-N`, a Unix socket in the
+//! runtime or temp folder or a named pipe on Windows, for frames of a
+//! little-endian opcode and length followed by JSON. A handshake names the
+//! Discord application, whose name is shown as the game, so the community
+//! registers one and enters its id. Nothing is sent while Discord isn't
+//! running; the connection is retried now and then.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+
+/// How long to wait before trying to reach Discord again.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long Discord gets to answer a frame.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscordConfig {
+    pub enabled: bool,
+    /// Id of the community's Discord application.
+    pub client_id: String,
+}
+
+/// What the presence shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Activity {
+    Syncing { name: String, percent: u64 },
+    Seeding { name: String, bytes: u64 },
+    Paused { name: String },
+}
+
+impl Activity {
+    /// Activity of torrent `id`, `None` while it is being checked or failed.
+    pub fn of(api: &librqbit::Api, id: usize) -> Option<Self> {
+        let stats = api.api_stats_v1(id.into()).ok()?;
+        let name = api.mgr_handle(id.into()).ok().and_then(|handle| handle.name()).unwrap_or_else(|| "mods".to_string());
+        match stats.state {
+            librqbit::TorrentStatsState::Paused => Some(Activity::Paused { name }),
+            librqbit::TorrentStatsState::Live if stats.finished => Some(Activity::Seeding { name, bytes: stats.total_bytes }),
+            librqbit::TorrentStatsState::Live => {
+                let percent = (stats.progress_bytes * 100).checked_div(stats.total_bytes).unwrap_or(0);
+                Some(Activity::Syncing { name, percent })
+            }
+            _ => None,
+        }
+    }
+
+    fn details(&self) -> String {
+        match self {
+            Activity::Syncing { name, percent } => format!("Syncing {} — {}%", name, percent),
+            Activity::Seeding { bytes, .. } => format!("Seeding {}", size_text(*bytes)),
+            Activity::Paused { name } => format!("{} paused", name),
+        }
+    }
+
+    fn state(&self) -> Option<String> {
+        match self {
+            Activity::Seeding { name, .. } => Some(name.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// `bytes` in whole GiB, or MiB below one GiB.
+fn size_text(bytes: u64) -> String {
+    const MIB: u64 = 1024 * 1024;
+    if bytes >= 1024 * MIB {
+        format!("{} GiB", (bytes + 512 * MIB) / (1024 * MIB))
+    } else {
+        format!("{} MiB", bytes.div_ceil(MIB))
+    }
+}
+
+trait RpcStream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> RpcStream for T {}
+
+/// One frame as Discord reads it.
+fn encode_frame(op: u32, payload: &serde_json::Value) -> Vec<u8> {
+    let body = payload.to_string().into_bytes();
+    let mut frame = Vec::with_capacity(8 + body.len());
+    frame.extend(op.to_le_bytes());
+    frame.extend((body.len() as u32).to_le_bytes());
+    frame.extend(body);
+    frame
+}
+
+async fn exchange(stream: &mut Box<dyn RpcStream>, op: u32, payload: &serde_json::Value) -> Result<serde_json::Value> {
+    stream.write_all(&encode_frame(op, payload)).await?;
+    let reply = async {
+        let mut header = [0u8; 8];
+        stream.read_exact(&mut header).await?;
+        let len = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len];
+        stream.read_exact(&mut body).await?;
+        anyhow::Ok(serde_json::from_slice::<serde_json::Value>(&body)?)
+    };
+    let reply = tokio::time::timeout(REPLY_TIMEOUT, reply).await.context("Discord didn't answer")??;
+    if reply["evt"] == "ERROR" {
+        bail!("Discord refused: {}", reply["data"]["message"]);
+    }
+    Ok(reply)
+}
+
+#[cfg(unix)]
+async fn open() -> Result<Box<dyn RpcStream>> {
+    let dirs = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"].iter().filter_map(std::env::var_os).map(std::path::PathBuf::from);
+    for dir in dirs.chain(std::iter::once("/tmp".into())) {
+        for n in 0..10 {
+            if let Ok(stream) = tokio::net::UnixStream::connect(dir.join(format!("discord-ipc-{}", n))).await {
+                return Ok(Box::new(stream));
+            }
+        }
+    }
+    bail!("Discord is not running")
+}
+
+#[cfg(windows)]
+async fn open() -> Result<Box<dyn RpcStream>> {
+    for n in 0..10 {
+        if let Ok(pipe) = tokio::net::windows::named_pipe::ClientOptions::new().open(format!(r"\\.\pipe\discord-ipc-{}", n)) {
+            return Ok(Box::new(pipe));
+        }
+    }
+    bail!("Discord is not running")
+}
+
+/// Connection to Discord and the activity it shows.
+#[derive(Default)]
+pub struct Presence {
+    stream: Option<Box<dyn RpcStream>>,
+    /// Application the connection was made for.
+    client_id: String,
+    shown: Option<Activity>,
+    last_attempt: Option<Instant>,
+}
+
+impl Presence {
+    /// Show `activity`, or clear the presence for `None` or when `config`
+    /// turns it off.
+    pub async fn update(&mut self, config: &DiscordConfig, activity: Option<Activity>) {
+        let client_id = config.client_id.trim();
+        if !config.enabled || client_id.is_empty() || client_id != self.client_id {
+            if self.stream.is_some() {
+                let _ = self.send(None).await;
+                self.stream = None;
+                self.last_attempt = None;
+            }
+            if !config.enabled || client_id.is_empty() {
+                return;
+            }
+        }
+        if self.stream.is_none() {
+            if self.last_attempt.is_some_and(|t| t.elapsed() < RECONNECT_INTERVAL) {
+                return;
+            }
+            self.last_attempt = Some(Instant::now());
+            match self.connect(client_id).await {
+                Ok(()) => println!("Sync: Connected to Discord"),
+                Err(_) => return,
+            }
+        }
+        if self.shown == activity {
+            return;
+        }
+        if let Err(e) = self.send(activity.as_ref()).await {
+            eprintln!("Sync: Failed to update the Discord presence: {:#}", e);
+            self.stream = None;
+            return;
+        }
+        self.shown = activity;
+    }
+
+    async fn connect(&mut self, client_id: &str) -> Result<()> {
+        let mut stream = open().await?;
+        exchange(&mut stream, OP_HANDSHAKE, &json!({ "v": 1, "client_id": client_id })).await?;
+        self.stream = Some(stream);
+        self.client_id = client_id.to_string();
+        self.shown = None;
+        Ok(())
+    }
+
+    async fn send(&mut self, activity: Option<&Activity>) -> Result<()> {
+        let Some(stream) = &mut self.stream else {
+            return Ok(());
+        };
+        let activity = activity.map(|activity| {
+            let mut value = json!({ "details": activity.details() });
+            if let Some(state) = activity.state() {
+                value["state"] = json!(state);
+            }
+            value
+        });
+        let nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default().to_string();
+        let payload = json!({
+            "cmd": "SET_ACTIVITY",
+            "args": { "pid": std::process::id(), "activity": activity },
+            "nonce": nonce,
+        });
+        exchange(stream, OP_FRAME, &payload).await.map(drop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presence_text_and_frames() {
+        let syncing = Activity::Syncing { name: "Mod Repo".to_string(), percent: 72 };
+        assert_eq!(syncing.details(), "Syncing Mod Repo — 72%");
+        let seeding = Activity::Seeding { name: "Mod Repo".to_string(), bytes: 38 * 1024 * 1024 * 1024 };
+        assert_eq!(seeding.details(), "Seeding 38 GiB");
+        assert_eq!(seeding.state().as_deref(), Some("Mod Repo"));
+
+        let frame = encode_frame(OP_HANDSHAKE, &json!({ "v": 1 }));
+        assert_eq!(&frame[..8], &[0, 0, 0, 0, 7, 0, 0, 0]);
+        assert_eq!(&frame[8..], br#"{"v":1}"#);
+    }
+}
@@ -0,0 +1,171 @@
+// src/sync/error.rs
+
+//! Errors the sync manager reports to the UI and library consumers.
+//!
+//! Every error carries the message shown to the user; its variant says what
+//! kind of failure it was, so the UI can suggest what to do about it and
+//! consumers can react to, say, a full disk without parsing the message.
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::io::ErrorKind;
+
+/// OS error codes for a full disk: `ERROR_HANDLE_DISK_FULL` and
+/// `ERROR_DISK_FULL` on Windows, `ENOSPC` elsewhere.
+#[cfg(windows)]
+const DISK_FULL_OS_ERRORS: &[i32] = &[39, 112];
+#[cfg(not(windows))]
+const DISK_FULL_OS_ERRORS: &[i32] = &[28];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SyncError {
+    /// A remote source couldn't be reached or answered with an error.
+    Network(String),
+    /// The download folder's drive ran out of space.
+    DiskFull(String),
+    /// Files or folders couldn't be read or written for lack of permission.
+    PermissionDenied(String),
+    /// The torrent file is damaged or not a torrent.
+    TorrentParse(String),
+    /// The torrent engine failed to add, change or report on a torrent.
+    Engine(String),
+    /// The settings don't allow what was asked, or something is missing.
+    Config(String),
+    Other(String),
+}
+
+impl SyncError {
+    /// `message` as an error of `kind`, unless `cause` or one of its sources
+    /// shows the disk is full or access was denied, which is what the user
+    /// has to fix whatever was being done, or already is a `SyncError`.
+    pub fn caused_by(kind: fn(String) -> SyncError, message: String, cause: &(dyn Error + 'static)) -> Self {
+        let mut source = Some(cause);
+        while let Some(err) = source {
+            if let Some(sync) = err.downcast_ref::<SyncError>() {
+                return sync.with_message(message);
+            }
+            if let Some(io) = err.downcast_ref::<std::io::Error>() {
+                match io.kind() {
+                    ErrorKind::StorageFull | ErrorKind::QuotaExceeded => return SyncError::DiskFull(message),
+                    ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem => {
+                        return SyncError::PermissionDenied(message);
+                    }
+                    _ => {}
+                }
+            }
+            if err.is::<reqwest::Error>() {
+                return SyncError::Network(message);
+            }
+            source = err.source();
+        }
+        kind(message)
+    }
+
+    /// An error librqbit reported for a torrent. It only gives the text of
+    /// its error chain, so a full disk is recognised by the OS error code
+    /// in it.
+    pub fn from_engine(message: String) -> Self {
+        let disk_full = DISK_FULL_OS_ERRORS
+            .iter()
+            .any(|code| message.contains(&format!("(os error {})", code)));
+        if disk_full { SyncError::DiskFull(message) } else { SyncError::Engine(message) }
+    }
+
+    /// `message` as an error of the same kind as this one.
+    fn with_message(&self, message: String) -> Self {
+        match self {
+            SyncError::Network(_) => SyncError::Network(message),
+            SyncError::DiskFull(_) => SyncError::DiskFull(message),
+            SyncError::PermissionDenied(_) => SyncError::PermissionDenied(message),
+            SyncError::TorrentParse(_) => SyncError::TorrentParse(message),
+            SyncError::Engine(_) => SyncError::Engine(message),
+            SyncError::Config(_) => SyncError::Config(message),
+            SyncError::Other(_) => SyncError::Other(message),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            SyncError::Network(message)
+            | SyncError::DiskFull(message)
+            | SyncError::PermissionDenied(message)
+            | SyncError::TorrentParse(message)
+            | SyncError::Engine(message)
+            | SyncError::Config(message)
+            | SyncError::Other(message) => message,
+        }
+    }
+
+    /// Variant name, for logs and external interfaces.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SyncError::Network(_) => "Network",
+            SyncError::DiskFull(_) => "DiskFull",
+            SyncError::PermissionDenied(_) => "PermissionDenied",
+            SyncError::TorrentParse(_) => "TorrentParse",
+            SyncError::Engine(_) => "Engine",
+            SyncError::Config(_) => "Config",
+            SyncError::Other(_) => "Other",
+        }
+    }
+
+    /// Fluent id of the hint telling the user how to fix this kind of error.
+    pub fn hint_id(&self) -> Option<&'static str> {
+        match self {
+            SyncError::Network(_) => Some("error-hint-network"),
+            SyncError::DiskFull(_) => Some("error-hint-disk-full"),
+            SyncError::PermissionDenied(_) => Some("error-hint-permission"),
+            SyncError::TorrentParse(_) => Some("error-hint-torrent"),
+            SyncError::Engine(_) => Some("error-hint-engine"),
+            SyncError::Config(_) => Some("error-hint-config"),
+            SyncError::Other(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl Error for SyncError {}
+
+impl From<String> for SyncError {
+    fn from(message: String) -> Self {
+        SyncError::Other(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_caused_by_finds_io_errors() {
+        let full: anyhow::Result<()> = Err(std::io::Error::from(ErrorKind::StorageFull)).context("Failed to write a.pbo");
+        let err = full.unwrap_err();
+        let error = SyncError::caused_by(SyncError::Engine, format!("{:#}", err), &*err);
+        assert!(matches!(&error, SyncError::DiskFull(message) if message.starts_with("Failed to write a.pbo: ")));
+
+        let denied = std::io::Error::from(ErrorKind::PermissionDenied);
+        assert!(matches!(SyncError::caused_by(SyncError::Other, String::new(), &denied), SyncError::PermissionDenied(_)));
+
+        let refused = anyhow::Error::new(SyncError::TorrentParse("Not a torrent".to_string()));
+        let error = SyncError::caused_by(SyncError::Engine, "Failed to add: Not a torrent".to_string(), &*refused);
+        assert_eq!(error, SyncError::TorrentParse("Failed to add: Not a torrent".to_string()));
+
+        let missing = std::io::Error::from(ErrorKind::NotFound);
+        assert!(matches!(SyncError::caused_by(SyncError::Other, String::new(), &missing), SyncError::Other(_)));
+    }
+
+    #[test]
+    fn test_engine_errors_find_full_disk() {
+        let full = std::io::Error::from_raw_os_error(DISK_FULL_OS_ERRORS[0]);
+        let message = format!("error writing piece 12: {}", full);
+        assert_eq!(SyncError::from_engine(message.clone()), SyncError::DiskFull(message));
+        assert!(matches!(SyncError::from_engine("tracker unreachable".to_string()), SyncError::Engine(_)));
+    }
+}
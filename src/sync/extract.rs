@@ -0,0 +1,220 @@
+// src/sync/extract.rs
+
+//! Extraction of zip archives shipped in the repo.
+//!
+//! Each `ExtractRule` names an archive inside the download folder and the
+//! folder its contents go to. Once the torrent has finished, archives that
+//! are new or changed since their last extraction are unpacked, and the
+//! files written are recorded in a manifest in the download folder so the
+//! cleaner doesn't report them as extra. An archive that fails to extract
+//! is recorded too, so it isn't tried again until it changes.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use tokio::sync::mpsc;
+
+use super::error::SyncError;
+use super::messages::SyncEvent;
+use super::types::SyncConfig;
+use super::utils::{is_contained_relative_path, send_sync_event};
+
+/// Manifest of extracted files, relative to the download folder.
+pub const MANIFEST_FILE_NAME: &str = ".modsync-extracted.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractRule {
+    /// Zip archive, relative to the download folder.
+    pub archive: PathBuf,
+    /// Folder the archive is extracted into, relative to the download folder.
+    pub target: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct ExtractedArchive {
+    /// Size and modification time of the archive when it was extracted.
+    size: u64,
+    modified_secs: u64,
+    target: PathBuf,
+    files: Vec<PathBuf>,
+    /// Why this version of the archive couldn't be extracted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// What has been extracted, keyed by archive path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractManifest {
+    archives: BTreeMap<PathBuf, ExtractedArchive>,
+}
+
+impl ExtractManifest {
+    /// The manifest in `download_path`, empty if there is none yet.
+    pub fn load(download_path: &Path) -> Result<Self> {
+        let path = download_path.join(MANIFEST_FILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn save(&self, download_path: &Path) -> Result<()> {
+        let path = download_path.join(MANIFEST_FILE_NAME);
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Every extracted file, relative to the download folder.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.archives.values().flat_map(|a| a.files.iter().map(PathBuf::as_path))
+    }
+}
+
+/// Extract every archive of `rules` that is new or changed since it was
+/// last extracted or failed to. Returns the archives extracted; archives
+/// that don't exist yet are skipped.
+pub fn extract_pending(download_path: &Path, rules: &[ExtractRule]) -> Result<Vec<PathBuf>> {
+    let mut manifest = ExtractManifest::load(download_path)?;
+    let mut extracted = Vec::new();
+    let mut errors = Vec::new();
+    for rule in rules {
+        if !is_contained_relative_path(&rule.archive) || !is_contained_relative_path(&rule.target) {
+            errors.push(format!("{}: paths must stay inside the download folder", rule.archive.display()));
+            continue;
+        }
+        let archive_path = download_path.join(&rule.archive);
+        let Ok(metadata) = std::fs::metadata(&archive_path) else {
+            continue;
+        };
+        let modified_secs = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+        let previous = manifest.archives.get(&rule.archive);
+        if previous.is_some_and(|p| p.size == metadata.len() && p.modified_secs == modified_secs && p.target == rule.target) {
+            continue;
+        }
+        println!("Sync: Extracting {} into {}", rule.archive.display(), rule.target.display());
+        let record = |files, error| ExtractedArchive { size: metadata.len(), modified_secs, target: rule.target.clone(), files, error };
+        match extract_zip(&archive_path, download_path, &rule.target) {
+            Ok(files) => {
+                manifest.archives.insert(rule.archive.clone(), record(files, None));
+                extracted.push(rule.archive.clone());
+            }
+            Err(e) => {
+                let error = format!("{}: {:#}", rule.archive.display(), e);
+                // Files from an earlier version stay protected
+                let files = previous.map(|p| p.files.clone()).unwrap_or_default();
+                manifest.archives.insert(rule.archive.clone(), record(files, Some(error.clone())));
+                errors.push(error);
+            }
+        }
+    }
+    // Rules that were removed no longer protect their files
+    manifest.archives.retain(|archive, _| rules.iter().any(|r| &r.archive == archive));
+    manifest.save(download_path)?;
+    if errors.is_empty() {
+        Ok(extracted)
+    } else {
+        Err(anyhow!("Failed to extract {}", errors.join("; ")))
+    }
+}
+
+/// Run `extract_pending` for `config` off the async runtime, reporting
+/// what was extracted and any failure as events.
+pub async fn extract_and_report(config: &SyncConfig, ui_tx: &mpsc::UnboundedSender<SyncEvent>) {
+    let download_path = config.download_path.clone();
+    let rules = config.extract.clone();
+    let result = tokio::task::spawn_blocking(move || extract_pending(&download_path, &rules))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|r| r);
+    match result {
+        Ok(extracted) if extracted.is_empty() => {}
+        Ok(extracted) => {
+            let names: Vec<String> = extracted.iter().map(|a| a.display().to_string()).collect();
+            send_sync_event(ui_tx, SyncEvent::Notice(format!("Extracted {}", names.join(", "))));
+        }
+        Err(e) => {
+            eprintln!("Sync: {:#}", e);
+            send_sync_event(ui_tx, SyncEvent::Error(SyncError::caused_by(SyncError::Other, format!("{:#}", e), &*e)));
+        }
+    }
+}
+
+/// Unpack `archive` into `download_path/target`, returning the files
+/// written relative to `download_path`.
+fn extract_zip(archive: &Path, download_path: &Path, target: &Path) -> Result<Vec<PathBuf>> {
+    let file = std::fs::File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?;
+    let mut zip = zip::ZipArchive::new(file).context("Not a zip archive")?;
+    let mut files = Vec::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        // Entries with absolute paths or `..` could write outside the target
+        let name = entry
+            .enclosed_name()
+            .ok_or_else(|| anyhow!("Unsafe path in archive: {}", entry.name()))?;
+        let relative = target.join(name);
+        let out = download_path.join(&relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&out).with_context(|| format!("Failed to create {}", out.display()))?;
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut writer = std::fs::File::create(&out).with_context(|| format!("Failed to create {}", out.display()))?;
+        std::io::copy(&mut entry, &mut writer).with_context(|| format!("Failed to extract {}", out.display()))?;
+        files.push(relative);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_extract_pending_records_files_once() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(dir.path().join("optional.zip"))?);
+        zip.start_file("addons/extra.pbo", zip::write::SimpleFileOptions::default())?;
+        zip.write_all(b"pbo")?;
+        zip.finish()?;
+
+        let rules = [ExtractRule {
+            archive: PathBuf::from("optional.zip"),
+            target: PathBuf::from("@optional"),
+        }];
+        assert_eq!(extract_pending(dir.path(), &rules)?, [PathBuf::from("optional.zip")]);
+        assert_eq!(std::fs::read(dir.path().join("@optional/addons/extra.pbo"))?, b"pbo");
+        let manifest = ExtractManifest::load(dir.path())?;
+        assert_eq!(manifest.files().collect::<Vec<_>>(), [Path::new("@optional/addons/extra.pbo")]);
+
+        // Unchanged archives are not extracted again
+        assert!(extract_pending(dir.path(), &rules)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_archive_is_not_retried_until_it_changes() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("optional.zip"), b"not a zip")?;
+        let rules = [ExtractRule {
+            archive: PathBuf::from("optional.zip"),
+            target: PathBuf::from("@optional"),
+        }];
+        assert!(extract_pending(dir.path(), &rules).is_err());
+        assert!(extract_pending(dir.path(), &rules)?.is_empty());
+
+        std::fs::write(dir.path().join("optional.zip"), b"still not a zip")?;
+        assert!(extract_pending(dir.path(), &rules).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+// src/sync/filters.rs
+
+//! Rules for torrent files that are never downloaded.
+//!
+//! A `DownloadFilter` matches a glob against the file's path in the torrent,
+//! optionally only above a size, such as `*.wav` over 500 MiB or
+//! `/optional/**`. Matching files are deselected in librqbit when the
+//! torrent is added, or right away when the filters change, and the cleaner
+//! leaves their local copies alone instead of reporting them as extra.
+
+use anyhow::Result;
+use librqbit::api::TorrentDetailsResponse;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use tokio::sync::mpsc;
+
+use super::mapping::FolderMapping;
+use super::error::SyncError;
+use super::messages::SyncEvent;
+use super::snapshot::TorrentFile;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadFilter {
+    /// Glob over the file's path in the torrent. `*` and `?` match within a
+    /// folder name and `**` any number of folders; a pattern without a `/`
+    /// matches the file name in any folder.
+    pub pattern: String,
+    /// Only skip matching files larger than this many MiB.
+    #[serde(default)]
+    pub min_size_mb: Option<u64>,
+}
+
+impl DownloadFilter {
+    /// Whether the file at `path` of `length` bytes is skipped.
+    pub fn matches(&self, path: &Path, length: u64) -> bool {
+        let raw = self.pattern.trim();
+        if raw.is_empty() {
+            return false;
+        }
+        if let Some(min_size_mb) = self.min_size_mb
+            && length <= min_size_mb.saturating_mul(1024 * 1024)
+        {
+            return false;
+        }
+        let components: Vec<&str> = path
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect();
+        let pattern = raw.trim_start_matches('/');
+        if !raw.starts_with('/') && !pattern.contains('/') {
+            return components.last().is_some_and(|name| glob_match(pattern, name));
+        }
+        let segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+        match_segments(&segments, &components)
+    }
+}
+
+/// Match path `components` against pattern `segments`, where a `**` segment
+/// stands for any number of components.
+fn match_segments(segments: &[&str], components: &[&str]) -> bool {
+    match segments.split_first() {
+        None => components.is_empty(),
+        Some((&"**", rest)) => (0..=components.len()).any(|skip| match_segments(rest, &components[skip..])),
+        Some((segment, rest)) => match components.split_first() {
+            Some((name, tail)) => glob_match(segment, name) && match_segments(rest, tail),
+            None => false,
+        },
+    }
+}
+
+/// Match a single file or folder `name` against `pattern` with `*` and `?`.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether any of `filters` skips the file at `path` of `length` bytes.
+pub fn is_filtered(filters: &[DownloadFilter], path: &Path, length: u64) -> bool {
+    filters.iter().any(|filter| filter.matches(path, length))
+}
+
+/// Indices of the files in `torrent` to download, or `None` when the
+/// filters skip nothing and every file is downloaded.
+pub fn selected_files(filters: &[DownloadFilter], torrent: &[u8]) -> Result<Option<Vec<usize>>> {
+    if filters.is_empty() {
+        return Ok(None);
+    }
+    let meta = librqbit::torrent_from_bytes::<librqbit::ByteBuf>(torrent)?;
+    let mut files = Vec::new();
+    for details in meta.info.iter_file_details()? {
+        files.push((details.filename.to_pathbuf()?, details.len));
+    }
+    let selected: Vec<usize> = (0..files.len()).filter(|&i| !is_filtered(filters, &files[i].0, files[i].1)).collect();
+    Ok((selected.len() < files.len()).then_some(selected))
+}
+
+/// Paths of the files in `details` that are not downloaded, relative to the
+/// download folder after `mappings`. The cleaner leaves these alone.
+pub fn excluded_files(details: &TorrentDetailsResponse, mappings: &[FolderMapping]) -> Vec<PathBuf> {
+    TorrentFile::list_from_details(details, mappings)
+        .into_iter()
+        .filter(|file| !file.included)
+        .map(|file| file.path)
+        .collect()
+}
+
+/// Reselect the files of torrent `id` by `filters`, for when they change
+/// after the torrent was added.
+pub async fn apply(
+    filters: &[DownloadFilter],
+    mappings: &[FolderMapping],
+    api: &librqbit::api::Api,
+    id: usize,
+    ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+) {
+    let details = match api.api_torrent_details(id.into()) {
+        Ok(details) => details,
+        Err(e) => {
+            eprintln!("Sync: Failed to apply download filters, no torrent details: {}", e);
+            return;
+        }
+    };
+    let files = details.files.as_deref().unwrap_or_default();
+    let selected: HashSet<usize> = files
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| !is_filtered(filters, &file.components.iter().collect::<PathBuf>(), file.length))
+        .map(|(i, _)| i)
+        .collect();
+    println!("Sync: Download filters select {} of {} files", selected.len(), files.len());
+    if let Err(e) = api.api_torrent_action_update_only_files(id.into(), &selected).await {
+        let err_msg = format!("Failed to apply download filters: {:#}", e);
+        eprintln!("Sync: {}", err_msg);
+        let _ = ui_tx.send(SyncEvent::Error(SyncError::Engine(err_msg)));
+        return;
+    }
+    match api.api_torrent_details(id.into()) {
+        Ok(details) => {
+            let _ = ui_tx.send(SyncEvent::TorrentFiles(TorrentFile::list_from_details(&details, mappings)));
+        }
+        Err(e) => eprintln!("Sync: Failed to list files of torrent {}: {}", id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(pattern: &str, min_size_mb: Option<u64>) -> DownloadFilter {
+        DownloadFilter {
+            pattern: pattern.to_string(),
+            min_size_mb,
+        }
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        const MIB: u64 = 1024 * 1024;
+        let wav = filter("*.wav", Some(500));
+        assert!(wav.matches(Path::new("@music/sound/intro.wav"), 600 * MIB));
+        assert!(!wav.matches(Path::new("@music/sound/intro.wav"), 500 * MIB));
+        assert!(!wav.matches(Path::new("@music/sound/intro.ogg"), 600 * MIB));
+
+        let optional = filter("/optional/**", None);
+        assert!(optional.matches(Path::new("optional/@extras/addons/a.pbo"), 1));
+        assert!(!optional.matches(Path::new("@cba/optional/a.pbo"), 1));
+
+        let nested = filter("@*/addons/**/*_test.pbo", None);
+        assert!(nested.matches(Path::new("@ace/addons/ace_test.pbo"), 1));
+        assert!(nested.matches(Path::new("@ace/addons/sub/ace_test.pbo"), 1));
+        assert!(!nested.matches(Path::new("@ace/keys/ace_test.pbo"), 1));
+        assert!(!filter("  ", None).matches(Path::new("a"), 1));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a?c*", "abcdef"));
+        assert!(glob_match("*.p*o", "mod.pbo"));
+        assert!(!glob_match("*.pbo", "mod.pbo.bak"));
+    }
+}
@@ -0,0 +1,166 @@
+// src/sync/git.rs
+
+//! A git repository synced alongside the torrent.
+//!
+//! Missions and small configs change too often to reseed the whole torrent
+//! for. When a repository is configured it is cloned into a subfolder of the
+//! download path and pulled on every remote check, using the system's `git`
+//! so no git implementation has to be bundled. The subfolder is left alone
+//! by the cleaner.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::sync::mpsc;
+
+use super::messages::SyncEvent;
+use super::types::SyncConfig;
+use super::utils::{is_contained_relative_path, send_sync_event};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitSourceConfig {
+    /// Repository to clone; the source is off while this is empty.
+    pub url: String,
+    /// Branch to check out, the remote's default branch when empty.
+    pub branch: String,
+    /// Folder inside the download path the repository is cloned into.
+    pub folder: PathBuf,
+}
+
+impl Default for GitSourceConfig {
+    fn default() -> Self {
+        GitSourceConfig {
+            url: String::new(),
+            branch: String::new(),
+            folder: PathBuf::from("git"),
+        }
+    }
+}
+
+impl GitSourceConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.url.trim().is_empty()
+    }
+
+    /// The clone folder relative to the download path, refusing paths that
+    /// would leave it.
+    pub fn relative_folder(&self) -> Result<&Path> {
+        let folder = self.folder.as_path();
+        if is_contained_relative_path(folder) {
+            Ok(folder)
+        } else {
+            Err(anyhow!("Git folder must be a subfolder of the download path: {}", folder.display()))
+        }
+    }
+}
+
+/// Result of the latest sync of the git source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitSourceStatus {
+    /// RFC 3339 time of the sync.
+    pub checked_at: String,
+    /// Short hash of the checked out commit, when known.
+    pub commit: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Clone or pull the configured repository and report the outcome. Does
+/// nothing when no repository is configured.
+pub async fn refresh(config: &SyncConfig, ui_tx: &mpsc::UnboundedSender<SyncEvent>) {
+    if !config.git_source.is_enabled() {
+        return;
+    }
+    let result = sync_repository(&config.git_source, &config.download_path).await;
+    let status = match result {
+        Ok(commit) => {
+            println!("Sync: Git source at {}", commit);
+            GitSourceStatus {
+                checked_at: chrono::Utc::now().to_rfc3339(),
+                commit: Some(commit),
+                error: None,
+            }
+        }
+        Err(e) => {
+            eprintln!("Sync: Git source failed: {:#}", e);
+            let dir = config.git_source.relative_folder().map(|f| config.download_path.join(f));
+            GitSourceStatus {
+                checked_at: chrono::Utc::now().to_rfc3339(),
+                commit: match dir {
+                    Ok(dir) => head_commit(&dir).await.ok(),
+                    Err(_) => None,
+                },
+                error: Some(format!("{:#}", e)),
+            }
+        }
+    };
+    send_sync_event(ui_tx, SyncEvent::GitSourceStatus(status));
+}
+
+/// Bring the clone up to date, returning the short hash of its HEAD.
+async fn sync_repository(source: &GitSourceConfig, download_path: &Path) -> Result<String> {
+    let dir = download_path.join(source.relative_folder()?);
+    if dir.join(".git").exists() {
+        println!("Sync: Pulling git source into {}", dir.display());
+        run_git(&[OsStr::new("-C"), dir.as_os_str(), OsStr::new("pull"), OsStr::new("--ff-only")]).await?;
+    } else {
+        println!("Sync: Cloning git source {} into {}", source.url, dir.display());
+        if let Some(parent) = dir.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let url = source.url.trim();
+        let branch = source.branch.trim();
+        let mut args: Vec<&OsStr> = vec![OsStr::new("clone")];
+        if !branch.is_empty() {
+            args.extend([OsStr::new("--branch"), OsStr::new(branch)]);
+        }
+        args.extend([OsStr::new("--"), OsStr::new(url), dir.as_os_str()]);
+        run_git(&args).await?;
+    }
+    head_commit(&dir).await
+}
+
+async fn head_commit(dir: &Path) -> Result<String> {
+    let output = run_git(&[OsStr::new("-C"), dir.as_os_str(), OsStr::new("rev-parse"), OsStr::new("--short"), OsStr::new("HEAD")]).await?;
+    Ok(output.trim().to_string())
+}
+
+/// Run `git` non-interactively and return its stdout.
+async fn run_git(args: &[&OsStr]) -> Result<String> {
+    let output = tokio::process::Command::new("git")
+        .args(args)
+        // Fail instead of waiting for a password nobody can enter
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("Failed to start git; is it installed?")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git failed ({}): {}", output.status, stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_folder_stays_inside_download_path() {
+        let source = |folder: &str| GitSourceConfig {
+            folder: PathBuf::from(folder),
+            ..Default::default()
+        };
+        assert!(source("git").relative_folder().is_ok());
+        assert!(source("missions/main").relative_folder().is_ok());
+        assert!(source("").relative_folder().is_err());
+        assert!(source("../outside").relative_folder().is_err());
+        assert!(source("/abs").relative_folder().is_err());
+    }
+}
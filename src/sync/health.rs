@@ -0,0 +1,104 @@
+// src/sync/health.rs
+
+//! Health of the remote endpoints, from the outcome of recent downloads of
+//! the torrent from each URL.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Attempts remembered per URL.
+pub const ATTEMPTS_PER_URL: usize = 5;
+
+/// Successful downloads slower than this count as degraded.
+pub const SLOW_LATENCY_MS: u64 = 3000;
+
+/// Outcome of one attempt to download the torrent from `url`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteAttempt {
+    pub url: String,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    /// Never tried.
+    Unknown,
+    /// Recent attempts all succeeded quickly.
+    Good,
+    /// The last attempt succeeded, but recent ones failed or were slow.
+    Degraded,
+    /// The last attempt failed.
+    Down,
+}
+
+/// Recent attempts per URL.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EndpointHealth {
+    attempts: BTreeMap<String, VecDeque<RemoteAttempt>>,
+}
+
+impl EndpointHealth {
+    pub fn record(&mut self, attempt: RemoteAttempt) {
+        let attempts = self.attempts.entry(attempt.url.clone()).or_default();
+        attempts.push_back(attempt);
+        while attempts.len() > ATTEMPTS_PER_URL {
+            attempts.pop_front();
+        }
+    }
+
+    /// The most recent attempt for `url`.
+    pub fn last(&self, url: &str) -> Option<&RemoteAttempt> {
+        self.attempts.get(url).and_then(|a| a.back())
+    }
+
+    /// Most recent error for `url` among the remembered attempts.
+    pub fn last_error(&self, url: &str) -> Option<&str> {
+        self.attempts.get(url)?.iter().rev().find_map(|a| a.error.as_deref())
+    }
+
+    pub fn health(&self, url: &str) -> Health {
+        let Some(attempts) = self.attempts.get(url) else {
+            return Health::Unknown;
+        };
+        match attempts.back() {
+            None => Health::Unknown,
+            Some(last) if last.error.is_some() => Health::Down,
+            Some(_) if attempts.iter().all(|a| a.error.is_none() && a.latency_ms < SLOW_LATENCY_MS) => Health::Good,
+            Some(_) => Health::Degraded,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(latency_ms: u64, error: Option<&str>) -> RemoteAttempt {
+        RemoteAttempt {
+            url: "http://a".to_string(),
+            latency_ms,
+            error: error.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_health_follows_recent_attempts() {
+        let mut health = EndpointHealth::default();
+        assert_eq!(health.health("http://a"), Health::Unknown);
+        health.record(attempt(100, None));
+        assert_eq!(health.health("http://a"), Health::Good);
+        health.record(attempt(100, Some("timed out")));
+        assert_eq!(health.health("http://a"), Health::Down);
+        health.record(attempt(100, None));
+        assert_eq!(health.health("http://a"), Health::Degraded);
+        assert_eq!(health.last_error("http://a"), Some("timed out"));
+        // The failure ages out after enough good attempts
+        for _ in 0..ATTEMPTS_PER_URL {
+            health.record(attempt(100, None));
+        }
+        assert_eq!(health.health("http://a"), Health::Good);
+        health.record(attempt(SLOW_LATENCY_MS, None));
+        assert_eq!(health.health("http://a"), Health::Degraded);
+    }
+}
@@ -0,0 +1,203 @@
+// src/sync/history.rs
+
+//! Record of recent sync runs.
+//!
+//! The manager brackets every check, update, verification and repair with
+//! `RunStarted` / `RunFinished` events. `SyncHistory` folds the events in
+//! between into one `RunRecord` per run, so the daemon and the GUI can build
+//! the same history from the event stream.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::lifetime::LifetimeStats;
+use super::messages::{SyncCommand, SyncEvent};
+
+/// Number of finished runs kept.
+pub const HISTORY_LIMIT: usize = 50;
+
+/// What started a sync run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunTrigger {
+    /// Remote check requested by the user or a client.
+    Check,
+    /// Remote check started by the manager's timer.
+    Scheduled,
+    Update,
+    Verify,
+    Repair,
+}
+
+impl RunTrigger {
+    /// The run a command starts, if it starts one.
+    pub fn for_command(cmd: &SyncCommand) -> Option<Self> {
+        match cmd {
+            SyncCommand::DownloadAndCompare(_) => Some(Self::Check),
+            SyncCommand::ApplyUpdate(_) | SyncCommand::RollBack(_) => Some(Self::Update),
+            SyncCommand::VerifyFolder => Some(Self::Verify),
+            SyncCommand::FixMissingFiles | SyncCommand::ForceRecheck => Some(Self::Repair),
+            SyncCommand::UpdateConfig(_)
+            | SyncCommand::DeleteFiles(_)
+            | SyncCommand::SetPaused(_)
+            | SyncCommand::SetSpeedLimits { .. }
+            | SyncCommand::SetGamingMode(_)
+            | SyncCommand::ForgetTorrent
+            | SyncCommand::RemoveTorrentAndData => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Check => "check",
+            Self::Scheduled => "scheduled",
+            Self::Update => "update",
+            Self::Verify => "verify",
+            Self::Repair => "repair",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub trigger: RunTrigger,
+    /// RFC 3339 timestamps; `finished_at` is `None` while the run is going.
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub downloaded_bytes: u64,
+    pub uploaded_bytes: u64,
+    /// Files reported missing or extra during the run.
+    pub files_changed: usize,
+    /// First error reported during the run, `None` if it succeeded.
+    pub error: Option<String>,
+    /// Lifetime totals when the run started, to compute the bytes above.
+    #[serde(default)]
+    start_totals: LifetimeStats,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncHistory {
+    /// Finished runs, oldest first.
+    runs: VecDeque<RunRecord>,
+    current: Option<RunRecord>,
+}
+
+impl SyncHistory {
+    /// Finished runs, oldest first.
+    pub fn runs(&self) -> impl DoubleEndedIterator<Item = &RunRecord> {
+        self.runs.iter()
+    }
+
+    /// The run in progress, if any.
+    pub fn current(&self) -> Option<&RunRecord> {
+        self.current.as_ref()
+    }
+
+    /// Fold `event` into the history. `lifetime` are the transfer totals
+    /// after the event was counted.
+    pub fn observe(&mut self, event: &SyncEvent, lifetime: LifetimeStats) {
+        match event {
+            SyncEvent::RunStarted(trigger) => {
+                // A run that never finished (e.g. the manager stopped) is dropped
+                self.current = Some(RunRecord {
+                    trigger: *trigger,
+                    started_at: chrono::Utc::now().to_rfc3339(),
+                    finished_at: None,
+                    downloaded_bytes: 0,
+                    uploaded_bytes: 0,
+                    files_changed: 0,
+                    error: None,
+                    start_totals: lifetime,
+                });
+            }
+            SyncEvent::RunFinished => {
+                if let Some(mut run) = self.current.take() {
+                    run.finished_at = Some(chrono::Utc::now().to_rfc3339());
+                    self.runs.push_back(run);
+                    while self.runs.len() > HISTORY_LIMIT {
+                        self.runs.pop_front();
+                    }
+                }
+            }
+            _ => {}
+        }
+        let Some(run) = &mut self.current else {
+            return;
+        };
+        match event {
+            SyncEvent::ManagedTorrentUpdate(_) => {
+                run.downloaded_bytes = lifetime.downloaded_bytes.saturating_sub(run.start_totals.downloaded_bytes);
+                run.uploaded_bytes = lifetime.uploaded_bytes.saturating_sub(run.start_totals.uploaded_bytes);
+            }
+            SyncEvent::MissingFilesFound(files) => run.files_changed += files.len(),
+            SyncEvent::ExtraFilesFound(files) => run.files_changed += files.len(),
+            SyncEvent::Error(msg) => {
+                run.error.get_or_insert_with(|| msg.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    /// Finished runs as CSV with a header row, oldest first.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("trigger,started_at,finished_at,downloaded_bytes,uploaded_bytes,files_changed,result\n");
+        for run in &self.runs {
+            let fields = [
+                run.trigger.name().to_string(),
+                run.started_at.clone(),
+                run.finished_at.clone().unwrap_or_default(),
+                run.downloaded_bytes.to_string(),
+                run.uploaded_bytes.to_string(),
+                run.files_changed.to_string(),
+                run.error.clone().unwrap_or_else(|| "ok".to_string()),
+            ];
+            let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            csv.push_str(&line.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Quote `field` if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::SyncError;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_history_records_runs() {
+        let mut history = SyncHistory::default();
+        let totals = |downloaded_bytes| LifetimeStats {
+            downloaded_bytes,
+            uploaded_bytes: 0,
+        };
+        history.observe(&SyncEvent::RunStarted(RunTrigger::Verify), totals(100));
+        history.observe(&SyncEvent::ManagedTorrentUpdate(None), totals(160));
+        history.observe(&SyncEvent::ExtraFilesFound(vec![PathBuf::from("a"), PathBuf::from("b")]), totals(160));
+        history.observe(&SyncEvent::Error(SyncError::DiskFull("disk, full".to_string())), totals(160));
+        history.observe(&SyncEvent::RunFinished, totals(160));
+        // Events between runs are not attributed to any run
+        history.observe(&SyncEvent::Error(SyncError::Other("ignored".to_string())), totals(160));
+
+        let run = history.runs().next().unwrap();
+        assert_eq!(run.trigger, RunTrigger::Verify);
+        assert_eq!(run.downloaded_bytes, 60);
+        assert_eq!(run.files_changed, 2);
+        assert_eq!(run.error.as_deref(), Some("disk, full"));
+        assert!(history.current().is_none());
+
+        let csv = history.to_csv();
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.starts_with("verify,"));
+        assert!(row.ends_with(",60,0,2,\"disk, full\""));
+    }
+}
@@ -0,0 +1,143 @@
+// src/sync/hooks.rs
+
+//! Shell commands run around updates and verifications.
+//!
+//! `pre_update` runs before an update is applied, and a non-zero exit skips
+//! the update. `post_update` and `post_verify` run when the matching run
+//! finishes, with the run's outcome in `MODSYNC_*` environment variables.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::sync::{mpsc, watch};
+
+use super::history::{RunRecord, RunTrigger, SyncHistory};
+use super::lifetime::LifetimeCounter;
+use super::error::SyncError;
+use super::messages::SyncEvent;
+use super::types::SyncConfig;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Shell commands; empty ones are skipped.
+    pub pre_update: String,
+    pub post_update: String,
+    /// Runs after verifications and repairs.
+    pub post_verify: String,
+}
+
+/// Run `command` through the platform shell with `env` added, failing on a
+/// non-zero exit.
+pub async fn run_hook(name: &str, command: &str, download_path: &Path, env: &[(&str, String)]) -> Result<()> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Ok(());
+    }
+    println!("Sync: Running {} hook: {}", name, command);
+    let mut process = if cfg!(windows) {
+        let mut process = tokio::process::Command::new("cmd");
+        process.arg("/C").arg(command);
+        process
+    } else {
+        let mut process = tokio::process::Command::new("sh");
+        process.arg("-c").arg(command);
+        process
+    };
+    if download_path.is_dir() {
+        process.current_dir(download_path);
+    }
+    let output = process
+        .env("MODSYNC_HOOK", name)
+        .env("MODSYNC_DOWNLOAD_PATH", download_path)
+        .envs(env.iter().map(|(key, value)| (*key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .with_context(|| format!("Failed to start {} hook", name))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{} hook failed ({}): {}", name, output.status, stderr.trim()));
+    }
+    Ok(())
+}
+
+/// Environment describing a finished run.
+fn run_env(run: &RunRecord) -> Vec<(&'static str, String)> {
+    vec![
+        ("MODSYNC_RESULT", if run.error.is_some() { "failed" } else { "ok" }.to_string()),
+        ("MODSYNC_ERROR", run.error.clone().unwrap_or_default()),
+        ("MODSYNC_DOWNLOADED_BYTES", run.downloaded_bytes.to_string()),
+        ("MODSYNC_UPLOADED_BYTES", run.uploaded_bytes.to_string()),
+        ("MODSYNC_CHANGED_FILES", run.files_changed.to_string()),
+    ]
+}
+
+/// Wrap `ui_tx` so the post hooks of the latest `config` run when an
+/// update or verification run finishes. Events are forwarded unchanged and
+/// without waiting for the hooks.
+pub fn tap_events(
+    config: watch::Receiver<SyncConfig>,
+    ui_tx: mpsc::UnboundedSender<SyncEvent>,
+) -> mpsc::UnboundedSender<SyncEvent> {
+    let (tap_tx, mut tap_rx) = mpsc::unbounded_channel::<SyncEvent>();
+    tokio::spawn(async move {
+        let mut history = SyncHistory::default();
+        let mut lifetime = LifetimeCounter::default();
+        while let Some(event) = tap_rx.recv().await {
+            if let SyncEvent::ManagedTorrentUpdate(Some((id, stats))) = &event {
+                lifetime.observe(*id, stats);
+            }
+            let trigger = history.current().map(|run| run.trigger);
+            history.observe(&event, lifetime.totals());
+            if matches!(event, SyncEvent::RunFinished)
+                && let Some(run) = history.runs().next_back()
+            {
+                let (hooks, download_path) = {
+                    let config = config.borrow();
+                    (config.hooks.clone(), config.download_path.clone())
+                };
+                let hook = match trigger {
+                    Some(RunTrigger::Update) => Some(("post_update", hooks.post_update)),
+                    Some(RunTrigger::Verify | RunTrigger::Repair) => Some(("post_verify", hooks.post_verify)),
+                    _ => None,
+                };
+                if let Some((name, command)) = hook.filter(|(_, command)| !command.trim().is_empty()) {
+                    let env = run_env(run);
+                    let hook_tx = ui_tx.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = run_hook(name, &command, &download_path, &env).await {
+                            eprintln!("Sync: {:#}", e);
+                            let _ = hook_tx.send(SyncEvent::Error(SyncError::Other(format!("{:#}", e))));
+                        }
+                    });
+                }
+            }
+            if ui_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+    tap_tx
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hook_gets_environment_and_exit_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out.txt");
+        let command = format!("echo \"$MODSYNC_HOOK $MODSYNC_RESULT\" > '{}'", out.display());
+        run_hook("post_update", &command, dir.path(), &[("MODSYNC_RESULT", "ok".to_string())]).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap().trim(), "post_update ok");
+
+        assert!(run_hook("pre_update", "exit 3", dir.path(), &[]).await.is_err());
+        assert!(run_hook("pre_update", "  ", dir.path(), &[]).await.is_ok());
+    }
+}
@@ -1,7 +1,84 @@
 use anyhow::{Context, Result};
+use reqwest::redirect::{Attempt, Policy};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
 
-pub fn create_http_client() -> Result<reqwest::Client> {
+/// How the HTTP client follows redirects when fetching the torrent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedirectConfig {
+    /// Redirects followed before giving up; zero disables redirects.
+    pub max_redirects: usize,
+    /// Whether a redirect may lead to a different host, as short links do.
+    pub allow_cross_host: bool,
+}
+
+impl Default for RedirectConfig {
+    fn default() -> Self {
+        // reqwest's own default policy
+        Self {
+            max_redirects: 10,
+            allow_cross_host: true,
+        }
+    }
+}
+
+impl RedirectConfig {
+    /// Why the redirect to `next` after visiting `previous` is refused, if it is.
+    fn refusal(&self, previous: &[Url], next: &Url) -> Option<String> {
+        if previous.len() > self.max_redirects {
+            return Some(format!("too many redirects (limit {})", self.max_redirects));
+        }
+        let origin = previous.first().and_then(|url| url.host_str());
+        if !self.allow_cross_host && origin != next.host_str() {
+            return Some(format!(
+                "redirect to another host ({}) is not allowed",
+                next.host_str().unwrap_or_default()
+            ));
+        }
+        None
+    }
+
+    fn policy(&self) -> Policy {
+        let config = self.clone();
+        Policy::custom(move |attempt: Attempt| match config.refusal(attempt.previous(), attempt.url()) {
+            Some(reason) => attempt.error(reason),
+            None => attempt.follow(),
+        })
+    }
+}
+
+pub fn create_http_client(redirects: &RedirectConfig) -> Result<reqwest::Client> {
     reqwest::Client::builder()
+        .redirect(redirects.policy())
         .build()
         .context("Failed to build HTTP client")
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(list: &[&str]) -> Vec<Url> {
+        list.iter().map(|u| Url::parse(u).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_redirect_limits() {
+        let next = Url::parse("https://cdn.example.net/repo.torrent").unwrap();
+        let config = RedirectConfig {
+            max_redirects: 2,
+            allow_cross_host: true,
+        };
+        assert!(config.refusal(&urls(&["https://s.example.com/x"]), &next).is_none());
+        assert!(config.refusal(&urls(&["https://a", "https://b", "https://c"]), &next).is_some());
+
+        let same_host_only = RedirectConfig {
+            allow_cross_host: false,
+            ..config
+        };
+        assert!(same_host_only.refusal(&urls(&["https://s.example.com/x"]), &next).is_some());
+        let same_host = Url::parse("https://s.example.com/y").unwrap();
+        assert!(same_host_only.refusal(&urls(&["https://s.example.com/x"]), &same_host).is_none());
+    }
+}
@@ -0,0 +1,313 @@
+// src/sync/ipfs.rs
+
+//! Torrents published on IPFS.
+//!
+//! An `ipfs://<cid>` URL is fetched block by block from a gateway in raw
+//! mode, and every block is checked against the SHA-256 digest in its CID, so
+//! an untrusted public gateway cannot hand out a different torrent. Files
+//! are reassembled from UnixFS (dag-pb) nodes or taken directly from raw
+//! blocks. This is experimental: only SHA-256 CIDs are understood.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::utils::download_request;
+
+/// URL scheme selecting this source.
+pub const SCHEME: &str = "ipfs://";
+
+/// Upper bound on the blocks fetched for one file, about 1 GiB of default
+/// sized chunks, so a malicious DAG cannot keep the check running forever.
+const MAX_BLOCKS: usize = 4096;
+
+const CODEC_RAW: u64 = 0x55;
+const CODEC_DAG_PB: u64 = 0x70;
+const MULTIHASH_SHA2_256: u64 = 0x12;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IpfsConfig {
+    /// Gateway serving `/ipfs/<cid>`; it does not have to be trusted.
+    pub gateway: String,
+}
+
+impl Default for IpfsConfig {
+    fn default() -> Self {
+        IpfsConfig {
+            gateway: "https://ipfs.io".to_string(),
+        }
+    }
+}
+
+/// Content identifier of a SHA-256 hashed block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cid {
+    codec: u64,
+    digest: [u8; 32],
+}
+
+impl Cid {
+    /// Parse a CIDv0 (`Qm...`) or base32 CIDv1 (`b...`).
+    fn parse(text: &str) -> Result<Self> {
+        if text.len() == 46 && text.starts_with("Qm") {
+            return Self::from_multihash(CODEC_DAG_PB, &base58_decode(text)?);
+        }
+        match text.strip_prefix('b') {
+            Some(encoded) => Self::from_bytes(&base32_decode(encoded)?),
+            None => Err(anyhow!("Unsupported CID encoding: {}", text)),
+        }
+    }
+
+    /// Parse a binary CID as found in dag-pb links.
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.first() == Some(&(MULTIHASH_SHA2_256 as u8)) {
+            return Self::from_multihash(CODEC_DAG_PB, bytes);
+        }
+        let mut rest = bytes;
+        let version = read_varint(&mut rest)?;
+        if version != 1 {
+            return Err(anyhow!("Unsupported CID version {}", version));
+        }
+        let codec = read_varint(&mut rest)?;
+        Self::from_multihash(codec, rest)
+    }
+
+    fn from_multihash(codec: u64, mut bytes: &[u8]) -> Result<Self> {
+        let hash = read_varint(&mut bytes)?;
+        let len = read_varint(&mut bytes)?;
+        if hash != MULTIHASH_SHA2_256 || len != 32 {
+            return Err(anyhow!("Only SHA-256 CIDs are supported"));
+        }
+        let digest = bytes.try_into().map_err(|_| anyhow!("Truncated CID digest"))?;
+        Ok(Cid { codec, digest })
+    }
+
+    /// Base32 CIDv1 form, which every gateway accepts.
+    fn to_v1_string(&self) -> String {
+        let mut bytes = vec![1];
+        write_varint(&mut bytes, self.codec);
+        write_varint(&mut bytes, MULTIHASH_SHA2_256);
+        bytes.push(32);
+        bytes.extend_from_slice(&self.digest);
+        format!("b{}", base32_encode(&bytes))
+    }
+}
+
+/// Download and verify the file named by the `ipfs://` `url`.
+pub async fn download(url: &str, config: &IpfsConfig, client: &reqwest::Client) -> Result<Vec<u8>> {
+    let cid = url.strip_prefix(SCHEME).ok_or_else(|| anyhow!("Not an IPFS URL: {}", url))?;
+    let root = Cid::parse(cid.trim_end_matches('/'))?;
+    println!("Sync: Fetching {} through IPFS gateway {}", url, config.gateway);
+
+    let mut content = Vec::new();
+    // Depth-first, so each node's data comes before its children's in order
+    let mut pending = vec![root];
+    let mut fetched = 0;
+    while let Some(cid) = pending.pop() {
+        fetched += 1;
+        if fetched > MAX_BLOCKS {
+            return Err(anyhow!("{} has more than {} blocks", url, MAX_BLOCKS));
+        }
+        let block = fetch_block(&cid, config, client).await?;
+        match cid.codec {
+            CODEC_RAW => content.extend_from_slice(&block),
+            CODEC_DAG_PB => {
+                let (data, links) = parse_dag_pb(&block)?;
+                content.extend_from_slice(&data);
+                pending.extend(links.into_iter().rev());
+            }
+            codec => return Err(anyhow!("Unsupported IPLD codec 0x{:x}", codec)),
+        }
+    }
+    Ok(content)
+}
+
+async fn fetch_block(cid: &Cid, config: &IpfsConfig, client: &reqwest::Client) -> Result<Vec<u8>> {
+    let url = format!("{}/ipfs/{}?format=raw", config.gateway.trim().trim_end_matches('/'), cid.to_v1_string());
+    let request = client.get(&url).header(reqwest::header::ACCEPT, "application/vnd.ipld.raw");
+    let block = download_request(request, &url).await?;
+    if Sha256::digest(&block).as_slice() != cid.digest {
+        return Err(anyhow!("Gateway returned a block that does not match {}", cid.to_v1_string()));
+    }
+    Ok(block)
+}
+
+/// File data and child links of a UnixFS dag-pb node.
+fn parse_dag_pb(block: &[u8]) -> Result<(Vec<u8>, Vec<Cid>)> {
+    let mut data = Vec::new();
+    let mut links = Vec::new();
+    for (field, value) in protobuf_fields(block)? {
+        match field {
+            // PBNode.Data holds a UnixFS message whose field 2 is file data
+            1 => {
+                for (field, value) in protobuf_fields(value)? {
+                    if field == 2 {
+                        data.extend_from_slice(value);
+                    }
+                }
+            }
+            // PBNode.Links; PBLink.Hash is field 1
+            2 => {
+                let hash = protobuf_fields(value)?
+                    .into_iter()
+                    .find_map(|(field, value)| (field == 1).then_some(value))
+                    .ok_or_else(|| anyhow!("dag-pb link without a hash"))?;
+                links.push(Cid::from_bytes(hash)?);
+            }
+            _ => {}
+        }
+    }
+    Ok((data, links))
+}
+
+/// Length-delimited fields of a protobuf message; varint fields are skipped.
+fn protobuf_fields(mut bytes: &[u8]) -> Result<Vec<(u64, &[u8])>> {
+    let mut fields = Vec::new();
+    while !bytes.is_empty() {
+        let key = read_varint(&mut bytes)?;
+        match key & 7 {
+            0 => {
+                read_varint(&mut bytes)?;
+            }
+            2 => {
+                let len = read_varint(&mut bytes)? as usize;
+                if len > bytes.len() {
+                    return Err(anyhow!("Truncated protobuf field"));
+                }
+                let (value, rest) = bytes.split_at(len);
+                fields.push((key >> 3, value));
+                bytes = rest;
+            }
+            wire => return Err(anyhow!("Unexpected protobuf wire type {}", wire)),
+        }
+    }
+    Ok(fields)
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().context("Truncated varint")?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(anyhow!("Varint too long"))
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    encoded
+}
+
+fn base32_decode(text: &str) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in text.bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_lowercase())
+            .ok_or_else(|| anyhow!("Invalid base32 character '{}'", c as char))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(decoded)
+}
+
+fn base58_decode(text: &str) -> Result<Vec<u8>> {
+    // Big-endian base-256 digits, multiplied by 58 for every character
+    let mut decoded: Vec<u8> = Vec::new();
+    for c in text.bytes() {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or_else(|| anyhow!("Invalid base58 character '{}'", c as char))?;
+        for byte in decoded.iter_mut().rev() {
+            carry += usize::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            decoded.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+    let zeros = text.bytes().take_while(|&c| c == b'1').count();
+    let mut result = vec![0; zeros];
+    result.extend(decoded);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The empty UnixFS directory, a block every IPFS node knows
+    const EMPTY_DIR_V0: &str = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+    const EMPTY_DIR_V1: &str = "bafybeiczsscdsbs7ffqz55asqdf3smv6klcw3gofszvwlyarci47bgf354";
+    const EMPTY_DIR_BLOCK: [u8; 4] = [0x0a, 0x02, 0x08, 0x01];
+
+    #[test]
+    fn test_cid_parsing_and_encoding() {
+        let v0 = Cid::parse(EMPTY_DIR_V0).unwrap();
+        assert_eq!(v0.codec, CODEC_DAG_PB);
+        assert_eq!(v0.digest.as_slice(), Sha256::digest(EMPTY_DIR_BLOCK).as_slice());
+        assert_eq!(v0.to_v1_string(), EMPTY_DIR_V1);
+        assert_eq!(Cid::parse(EMPTY_DIR_V1).unwrap(), v0);
+        assert!(Cid::parse("zdj7WWeQ43G6JJvLWQWZpyHuAMq6uYWRjkBXFad11vE2LHhQ7").is_err());
+    }
+
+    #[test]
+    fn test_parse_dag_pb() {
+        let (data, links) = parse_dag_pb(&EMPTY_DIR_BLOCK).unwrap();
+        assert!(data.is_empty() && links.is_empty());
+
+        // A file node with inline data "hi" and one raw child
+        let child = Cid {
+            codec: CODEC_RAW,
+            digest: [7; 32],
+        };
+        let mut child_bytes = vec![1];
+        write_varint(&mut child_bytes, CODEC_RAW);
+        child_bytes.extend([0x12, 32]);
+        child_bytes.extend([7; 32]);
+        let mut link = vec![0x0a, child_bytes.len() as u8];
+        link.extend(&child_bytes);
+        let mut node = vec![0x12, link.len() as u8];
+        node.extend(&link);
+        node.extend([0x0a, 0x06, 0x08, 0x02, 0x12, 0x02, b'h', b'i']);
+        let (data, links) = parse_dag_pb(&node).unwrap();
+        assert_eq!(data, b"hi");
+        assert_eq!(links, [child]);
+    }
+}
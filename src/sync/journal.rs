@@ -0,0 +1,404 @@
+// src/sync/journal.rs
+
+//! Append-only journal of sync commands and events.
+//!
+//! Every `SyncCommand` received by the manager and every `SyncEvent` it emits
+//! is written as a single JSON line with a timestamp. Commands are stored in
+//! full so a journal can be replayed against a fresh manager; events are
+//! stored as a short description because they can carry large payloads
+//! (torrent bytes, per-file progress).
+//!
+//! Once the journal reaches its size limit it is moved aside to a single
+//! rotated file, replacing the previous one, and a new journal is started.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+use super::messages::{SyncCommand, SyncEvent};
+use super::types::SyncConfig;
+use super::utils::is_contained_relative_path;
+
+/// File name used for the journal inside the application data directory.
+pub const JOURNAL_FILE_NAME: &str = "modsync-journal.jsonl";
+
+/// Size the journal may grow to before it is rotated.
+pub const MAX_JOURNAL_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Where the journal at `path` is moved when it is rotated.
+pub fn rotated_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".1");
+    path.with_file_name(name)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum JournalRecord {
+    Command(SyncCommand),
+    Event(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// RFC 3339 timestamp of when the record was written.
+    pub timestamp: String,
+    pub record: JournalRecord,
+}
+
+/// Handle to an open journal file. Cheap to clone; all clones append to the
+/// same file.
+#[derive(Debug, Clone)]
+pub struct EventJournal {
+    path: PathBuf,
+    max_bytes: u64,
+    file: Arc<Mutex<JournalFile>>,
+}
+
+#[derive(Debug)]
+struct JournalFile {
+    file: File,
+    /// Bytes in the file so far.
+    len: u64,
+}
+
+impl JournalFile {
+    fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open journal file: {}", path.display()))?;
+        let len = file.metadata().map_or(0, |m| m.len());
+        Ok(Self { file, len })
+    }
+}
+
+impl EventJournal {
+    /// Open (or create) the journal at `path` for appending, rotated at
+    /// `MAX_JOURNAL_BYTES`.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        Self::open_with_limit(path, MAX_JOURNAL_BYTES)
+    }
+
+    /// Like `open`, rotating the journal once it would grow past `max_bytes`.
+    pub fn open_with_limit(path: impl Into<PathBuf>, max_bytes: u64) -> Result<Self> {
+        let path = path.into();
+        let file = JournalFile::open(&path)?;
+        Ok(Self {
+            path,
+            max_bytes,
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record_command(&self, cmd: &SyncCommand) {
+        self.append(JournalRecord::Command(cmd.clone()));
+    }
+
+    pub fn record_event(&self, event: &SyncEvent) {
+        self.append(JournalRecord::Event(describe_event(event)));
+    }
+
+    fn append(&self, record: JournalRecord) {
+        let entry = JournalEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            record,
+        };
+        let line = match serde_json::to_string(&entry) {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("Journal: Failed to serialize entry: {}", e);
+                return;
+            }
+        };
+        let mut file = match self.file.lock() {
+            Ok(file) => file,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let len = line.len() as u64 + 1;
+        if file.len > 0 && file.len + len > self.max_bytes && let Err(e) = self.rotate(&mut file) {
+            eprintln!("Journal: Failed to rotate {}: {:#}", self.path.display(), e);
+        }
+        if let Err(e) = writeln!(file.file, "{}", line) {
+            eprintln!("Journal: Failed to write to {}: {}", self.path.display(), e);
+            return;
+        }
+        file.len += len;
+    }
+
+    /// Move the journal to `rotated_path` and start a new one.
+    fn rotate(&self, file: &mut JournalFile) -> Result<()> {
+        let rotated = rotated_path(&self.path);
+        std::fs::rename(&self.path, &rotated)
+            .with_context(|| format!("Failed to move the journal to {}", rotated.display()))?;
+        *file = JournalFile::open(&self.path)?;
+        Ok(())
+    }
+
+    /// Read all entries from a journal file. Lines that fail to parse (for
+    /// example a partially written last line) are skipped with a warning.
+    pub fn read_entries(path: &Path) -> Result<Vec<JournalEntry>> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open journal file: {}", path.display()))?;
+        let mut entries = Vec::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read journal file: {}", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<JournalEntry>(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => eprintln!("Journal: Skipping unreadable line {}: {}", i + 1, e),
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Short, human-readable description of an event for the journal.
+pub fn describe_event(event: &SyncEvent) -> String {
+    match event {
+        SyncEvent::ManagedTorrentUpdate(Some((id, stats))) => format!(
+            "ManagedTorrentUpdate(id={}, state={:?}, {}/{} bytes)",
+            id, stats.state, stats.progress_bytes, stats.total_bytes
+        ),
+        SyncEvent::ManagedTorrentUpdate(None) => "ManagedTorrentUpdate(None)".to_string(),
+        SyncEvent::RemoteUpdateFound(bytes, summary) => format!(
+            "RemoteUpdateFound({} bytes, {:?}, {} files, {} bytes of content)",
+            bytes.len(),
+            summary.name,
+            summary.file_count,
+            summary.total_bytes
+        ),
+        SyncEvent::TorrentFiles(files) => format!("TorrentFiles({} files)", files.len()),
+        SyncEvent::Peers(peers) => format!("Peers({} peers)", peers.len()),
+        SyncEvent::ExtraFilesFound(files) => format!("ExtraFilesFound({:?})", files),
+        SyncEvent::MissingFilesFound(files) => format!("MissingFilesFound({:?})", files),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Describe a journal record in a single line, used when dumping a journal.
+pub fn describe_record(record: &JournalRecord) -> String {
+    match record {
+        JournalRecord::Command(SyncCommand::ApplyUpdate(bytes)) => {
+            format!("command ApplyUpdate({} bytes)", bytes.len())
+        }
+        JournalRecord::Command(SyncCommand::RollBack(bytes)) => {
+            format!("command RollBack({} bytes)", bytes.len())
+        }
+        JournalRecord::Command(cmd) => format!("command {:?}", cmd),
+        JournalRecord::Event(desc) => format!("event   {}", desc),
+    }
+}
+
+/// Wrap `ui_tx` so every event sent through the returned sender is recorded
+/// in `journal` before being forwarded unchanged.
+pub fn tap_events(
+    journal: EventJournal,
+    ui_tx: mpsc::UnboundedSender<SyncEvent>,
+) -> mpsc::UnboundedSender<SyncEvent> {
+    let (tap_tx, mut tap_rx) = mpsc::unbounded_channel::<SyncEvent>();
+    tokio::spawn(async move {
+        while let Some(event) = tap_rx.recv().await {
+            journal.record_event(&event);
+            if ui_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+    tap_tx
+}
+
+/// Re-send the recorded commands, in order, to a manager running with
+/// `config`. Nothing replayed may reach outside `config.download_path`:
+/// settings changes keep `config`'s folders, paths to delete are moved
+/// under it, and commands that download or delete elsewhere are dropped.
+/// Returns the number of commands sent.
+pub fn replay_commands(
+    entries: &[JournalEntry],
+    config: &SyncConfig,
+    cmd_tx: &mpsc::UnboundedSender<SyncCommand>,
+) -> Result<usize> {
+    let mut sent = 0;
+    // Download folder the journal was recorded against
+    let mut recorded_root: Option<PathBuf> = None;
+    for entry in entries {
+        let JournalRecord::Command(cmd) = &entry.record else {
+            continue;
+        };
+        let replayed = replayable(cmd, config, recorded_root.as_deref());
+        if let SyncCommand::UpdateConfig(recorded) = cmd {
+            recorded_root = Some(recorded.download_path.clone());
+        }
+        let Some(replayed) = replayed else {
+            println!("Journal: Not replaying {}", describe_record(&entry.record));
+            continue;
+        };
+        cmd_tx
+            .send(replayed)
+            .context("Sync manager stopped while replaying journal")?;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+/// `cmd` confined to `config.download_path`, `None` when it can't be.
+fn replayable(cmd: &SyncCommand, config: &SyncConfig, recorded_root: Option<&Path>) -> Option<SyncCommand> {
+    match cmd {
+        // Only settings that act inside the download folder carry over;
+        // folders, hooks and remote sources stay those of `config`
+        SyncCommand::UpdateConfig(recorded) => Some(SyncCommand::UpdateConfig(Box::new(SyncConfig {
+            max_upload_speed: recorded.max_upload_speed,
+            max_download_speed: recorded.max_download_speed,
+            should_seed: recorded.should_seed,
+            ignored_paths: recorded.ignored_paths.clone(),
+            download_filters: recorded.download_filters.clone(),
+            read_only: recorded.read_only,
+            strict_mirror: recorded.strict_mirror,
+            ..config.clone()
+        }))),
+        SyncCommand::DeleteFiles(files) => {
+            let files: Vec<PathBuf> = files
+                .iter()
+                .filter_map(|file| {
+                    let relative = match recorded_root {
+                        Some(root) if file.is_absolute() => file.strip_prefix(root).ok()?,
+                        _ => file.as_path(),
+                    };
+                    is_contained_relative_path(relative).then(|| config.download_path.join(relative))
+                })
+                .collect();
+            (!files.is_empty()).then_some(SyncCommand::DeleteFiles(files))
+        }
+        SyncCommand::DownloadAndCompare(_) | SyncCommand::RemoveTorrentAndData => None,
+        other => Some(other.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::status::SyncStatus;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_journal_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join(JOURNAL_FILE_NAME);
+
+        let journal = EventJournal::open(&path)?;
+        journal.record_command(&SyncCommand::DeleteFiles(vec![PathBuf::from("a/b.pbo")]));
+        journal.record_event(&SyncEvent::StatusUpdate(SyncStatus::CheckingLocal));
+        journal.record_command(&SyncCommand::ApplyUpdate(vec![1, 2, 3]));
+
+        let entries = EventJournal::read_entries(&path)?;
+        assert_eq!(entries.len(), 3);
+        assert!(matches!(
+            &entries[0].record,
+            JournalRecord::Command(SyncCommand::DeleteFiles(files)) if files == &vec![PathBuf::from("a/b.pbo")]
+        ));
+        assert!(matches!(
+            &entries[1].record,
+            JournalRecord::Event(desc) if desc == "StatusUpdate(CheckingLocal)"
+        ));
+
+        let (cmd_tx, mut cmd_rx) = mpsc::unbounded_channel();
+        let config = SyncConfig { download_path: dir.path().join("output"), ..Default::default() };
+        assert_eq!(replay_commands(&entries, &config, &cmd_tx)?, 2);
+        assert!(matches!(cmd_rx.try_recv(), Ok(SyncCommand::DeleteFiles(files)) if files == vec![dir.path().join("output/a/b.pbo")]));
+        assert!(matches!(cmd_rx.try_recv(), Ok(SyncCommand::ApplyUpdate(bytes)) if bytes == vec![1, 2, 3]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_entries_skips_truncated_line() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join(JOURNAL_FILE_NAME);
+
+        let journal = EventJournal::open(&path)?;
+        journal.record_command(&SyncCommand::VerifyFolder);
+        drop(journal);
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)?
+            .write_all(b"{\"timestamp\":\"2025-")?;
+
+        let entries = EventJournal::read_entries(&path)?;
+        assert_eq!(entries.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_journal_rotates_at_its_limit() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join(JOURNAL_FILE_NAME);
+
+        let journal = EventJournal::open_with_limit(&path, 1024)?;
+        for _ in 0..100 {
+            journal.record_command(&SyncCommand::VerifyFolder);
+        }
+        journal.record_command(&SyncCommand::DeleteFiles(vec![PathBuf::from("last.pbo")]));
+
+        // Only the journal and one rotated file are kept, neither past the limit
+        assert!(std::fs::metadata(&path)?.len() <= 1024);
+        assert!(std::fs::metadata(rotated_path(&path))?.len() <= 1024);
+        assert_eq!(std::fs::read_dir(dir.path())?.count(), 2);
+        let entries = EventJournal::read_entries(&path)?;
+        assert!(matches!(entries.last().map(|e| &e.record), Some(JournalRecord::Command(SyncCommand::DeleteFiles(_)))));
+        assert!(!EventJournal::read_entries(&rotated_path(&path))?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replay_stays_inside_the_output() -> Result<()> {
+        let dir = tempdir()?;
+        let real = dir.path().join("mods");
+        let output = dir.path().join("output");
+        for folder in [&real, &output] {
+            std::fs::create_dir_all(folder)?;
+            std::fs::write(folder.join("a.pbo"), "pbo")?;
+        }
+        std::fs::write(dir.path().join("elsewhere.pbo"), "pbo")?;
+
+        let journal = EventJournal::open(dir.path().join(JOURNAL_FILE_NAME))?;
+        journal.record_command(&SyncCommand::UpdateConfig(Box::new(SyncConfig { download_path: real.clone(), ..Default::default() })));
+        journal.record_command(&SyncCommand::DeleteFiles(vec![real.join("a.pbo"), dir.path().join("elsewhere.pbo")]));
+        journal.record_command(&SyncCommand::RemoveTorrentAndData);
+        let entries = EventJournal::read_entries(journal.path())?;
+
+        let config = SyncConfig { download_path: output.clone(), ..Default::default() };
+        let session = librqbit::Session::new_with_opts(output.clone(), librqbit::SessionOptions { disable_dht: true, ..Default::default() }).await?;
+        let (ui_tx, _ui_rx) = mpsc::unbounded_channel();
+        let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+        let manager = tokio::spawn(crate::sync::run_sync_manager(
+            librqbit::Api::new(session, None),
+            config.clone(),
+            ui_tx,
+            cmd_rx,
+            None,
+            std::sync::Arc::new(crate::sync::Backends::default()),
+        ));
+        assert_eq!(replay_commands(&entries, &config, &cmd_tx)?, 2);
+
+        // The copy under the output goes; the real folder is never touched
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while output.join("a.pbo").exists() {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+        })
+        .await?;
+        manager.abort();
+        assert!(real.join("a.pbo").exists());
+        assert!(dir.path().join("elsewhere.pbo").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+// src/sync/keys.rs
+
+//! Collection of `.bikey` signature keys into a server's keys folder.
+//!
+//! After the torrent has finished, every `*.bikey` in the download folder
+//! is copied into the configured keys folder. The names of the keys copied
+//! are remembered in a manifest there, so keys of mods that were removed
+//! are deleted again while keys put there by hand are left alone.
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use walkdir::WalkDir;
+
+use super::error::SyncError;
+use super::messages::SyncEvent;
+use super::types::SyncConfig;
+use super::utils::send_sync_event;
+
+/// Keys copied by ModSync, inside the keys folder.
+pub const KEYS_MANIFEST_FILE_NAME: &str = ".modsync-keys.json";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct KeysReport {
+    /// File names of keys copied because they were new or changed.
+    pub copied: Vec<String>,
+    /// File names of previously copied keys no longer in any mod.
+    pub removed: Vec<String>,
+}
+
+/// Copy the keys found in `download_path` into `keys_dir` and delete stale
+/// ones copied earlier.
+pub fn collect_keys(download_path: &Path, keys_dir: &Path) -> Result<KeysReport> {
+    std::fs::create_dir_all(keys_dir).with_context(|| format!("Failed to create {}", keys_dir.display()))?;
+
+    // Key file name to its source; the first one found wins on clashes
+    let mut found: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for entry in WalkDir::new(download_path).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let is_key = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bikey"));
+        if is_key && entry.file_type().is_file() && !path.starts_with(keys_dir) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            found.entry(name).or_insert_with(|| path.to_path_buf());
+        }
+    }
+
+    let mut report = KeysReport::default();
+    for (name, source) in &found {
+        let dest = keys_dir.join(name);
+        let content = std::fs::read(source).with_context(|| format!("Failed to read {}", source.display()))?;
+        if std::fs::read(&dest).ok().as_deref() != Some(content.as_slice()) {
+            std::fs::write(&dest, &content).with_context(|| format!("Failed to write {}", dest.display()))?;
+            report.copied.push(name.clone());
+        }
+    }
+
+    let manifest_path = keys_dir.join(KEYS_MANIFEST_FILE_NAME);
+    let previous: BTreeSet<String> = match std::fs::read_to_string(&manifest_path) {
+        Ok(content) => serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", manifest_path.display()))?,
+        Err(_) => BTreeSet::new(),
+    };
+    for name in previous.iter().filter(|name| !found.contains_key(*name)) {
+        let stale = keys_dir.join(name);
+        match std::fs::remove_file(&stale) {
+            Ok(()) => report.removed.push(name.clone()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).with_context(|| format!("Failed to remove {}", stale.display())),
+        }
+    }
+    let current: BTreeSet<&String> = found.keys().collect();
+    std::fs::write(&manifest_path, serde_json::to_string_pretty(&current)?)
+        .with_context(|| format!("Failed to write {}", manifest_path.display()))?;
+    Ok(report)
+}
+
+/// Run `collect_keys` for `config` off the async runtime, reporting changes
+/// and any failure as events.
+pub async fn collect_and_report(config: &SyncConfig, ui_tx: &mpsc::UnboundedSender<SyncEvent>) {
+    let Some(keys_dir) = config.keys_dir.clone() else {
+        return;
+    };
+    let download_path = config.download_path.clone();
+    let result = tokio::task::spawn_blocking(move || collect_keys(&download_path, &keys_dir))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|r| r);
+    match result {
+        Ok(report) if report == KeysReport::default() => {}
+        Ok(report) => {
+            let message = format!("Keys updated: {} copied, {} removed", report.copied.len(), report.removed.len());
+            println!("Sync: {}", message);
+            send_sync_event(ui_tx, SyncEvent::Notice(message));
+        }
+        Err(e) => {
+            eprintln!("Sync: {:#}", e);
+            send_sync_event(ui_tx, SyncEvent::Error(SyncError::caused_by(SyncError::Other, format!("Failed to collect keys: {:#}", e), &*e)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_keys_removes_only_stale_copies() -> Result<()> {
+        let mods = tempfile::tempdir()?;
+        let keys = tempfile::tempdir()?;
+        for key in ["@cba/keys/cba_3.bikey", "@ace/Keys/ace.BIKEY"] {
+            let path = mods.path().join(key);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, key)?;
+        }
+        std::fs::write(keys.path().join("a3.bikey"), "vanilla")?;
+
+        let report = collect_keys(mods.path(), keys.path())?;
+        assert_eq!(report.copied, ["ace.BIKEY", "cba_3.bikey"]);
+        assert!(collect_keys(mods.path(), keys.path())? == KeysReport::default());
+
+        std::fs::remove_dir_all(mods.path().join("@ace"))?;
+        let report = collect_keys(mods.path(), keys.path())?;
+        assert_eq!(report.removed, ["ace.BIKEY"]);
+        // Keys that were not copied by ModSync stay
+        assert!(keys.path().join("a3.bikey").exists());
+        assert!(keys.path().join("cba_3.bikey").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+// src/sync/lifetime.rs
+
+//! Download and upload totals kept across sessions.
+//!
+//! librqbit only counts bytes for the current session, and resets those
+//! counters whenever a torrent is re-added or resumed. `LifetimeCounter`
+//! turns the per-session figures from successive snapshots into running
+//! totals that are persisted as JSON next to the settings.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::snapshot::TorrentSnapshot;
+
+/// File name used for the lifetime totals inside the application data directory.
+pub const LIFETIME_STATS_FILE_NAME: &str = "modsync-stats.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LifetimeStats {
+    pub downloaded_bytes: u64,
+    pub uploaded_bytes: u64,
+}
+
+impl LifetimeStats {
+    /// Uploaded divided by downloaded bytes, zero before anything was downloaded.
+    pub fn ratio(&self) -> f64 {
+        if self.downloaded_bytes == 0 {
+            0.0
+        } else {
+            self.uploaded_bytes as f64 / self.downloaded_bytes as f64
+        }
+    }
+
+    /// Load totals from `path`, starting from zero if the file doesn't exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let s = fs::read_to_string(path).with_context(|| format!("Failed to read stats file: {}", path.display()))?;
+        serde_json::from_str(&s).context("Failed to parse stats JSON")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize stats to JSON")?;
+        fs::write(path, json).with_context(|| format!("Failed to write stats file: {}", path.display()))
+    }
+}
+
+/// Accumulates session counters from successive snapshots into lifetime totals.
+#[derive(Debug, Clone, Default)]
+pub struct LifetimeCounter {
+    totals: LifetimeStats,
+    /// Torrent id and session counters (fetched, uploaded) of the last snapshot.
+    last: Option<(usize, u64, u64)>,
+    /// Take the next snapshot as the baseline without counting it, for
+    /// totals that already include the current session.
+    resume: bool,
+}
+
+impl LifetimeCounter {
+    /// Count on top of `totals`, including everything the first snapshot reports.
+    pub fn new(totals: LifetimeStats) -> Self {
+        Self {
+            totals,
+            ..Self::default()
+        }
+    }
+
+    /// Count on top of `totals`, which already include the session counters
+    /// of the next snapshot.
+    pub fn resume(totals: LifetimeStats) -> Self {
+        Self {
+            totals,
+            last: None,
+            resume: true,
+        }
+    }
+
+    pub fn totals(&self) -> LifetimeStats {
+        self.totals
+    }
+
+    /// Add what torrent `id` transferred since the previous snapshot. Returns
+    /// whether the totals changed.
+    pub fn observe(&mut self, id: usize, stats: &TorrentSnapshot) -> bool {
+        let (fetched, uploaded) = (stats.fetched_bytes, stats.uploaded_bytes);
+        if std::mem::take(&mut self.resume) {
+            self.last = Some((id, fetched, uploaded));
+            return false;
+        }
+        // A different torrent or a counter that went backwards means a new
+        // session, whose counters started from zero
+        let (last_fetched, last_uploaded) = match self.last {
+            Some((last_id, f, u)) if last_id == id && fetched >= f && uploaded >= u => (f, u),
+            _ => (0, 0),
+        };
+        let downloaded = fetched - last_fetched;
+        let uploaded_delta = uploaded - last_uploaded;
+        self.last = Some((id, fetched, uploaded));
+        self.totals.downloaded_bytes += downloaded;
+        self.totals.uploaded_bytes += uploaded_delta;
+        downloaded > 0 || uploaded_delta > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(fetched_bytes: u64, uploaded_bytes: u64) -> TorrentSnapshot {
+        TorrentSnapshot {
+            fetched_bytes,
+            uploaded_bytes,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_counter_survives_session_resets() {
+        let mut counter = LifetimeCounter::new(LifetimeStats {
+            downloaded_bytes: 100,
+            uploaded_bytes: 10,
+        });
+        assert!(counter.observe(1, &snapshot(50, 5)));
+        assert!(counter.observe(1, &snapshot(80, 5)));
+        assert!(!counter.observe(1, &snapshot(80, 5)));
+        // Resumed: counters restart from zero
+        assert!(counter.observe(1, &snapshot(20, 7)));
+        // Re-added under a new id
+        assert!(counter.observe(2, &snapshot(30, 30)));
+        assert_eq!(
+            counter.totals(),
+            LifetimeStats {
+                downloaded_bytes: 100 + 80 + 20 + 30,
+                uploaded_bytes: 10 + 5 + 7 + 30,
+            }
+        );
+    }
+
+    #[test]
+    fn test_resumed_counter_skips_first_snapshot() {
+        let totals = LifetimeStats {
+            downloaded_bytes: 500,
+            uploaded_bytes: 250,
+        };
+        let mut counter = LifetimeCounter::resume(totals);
+        assert!(!counter.observe(1, &snapshot(400, 200)));
+        assert!(counter.observe(1, &snapshot(450, 200)));
+        assert_eq!(counter.totals().downloaded_bytes, 550);
+        assert_eq!(counter.totals().ratio(), 250.0 / 550.0);
+    }
+}
@@ -4,12 +4,16 @@ use crate::sync::status::SyncStatus;
 use super::types::SyncConfig;
 use librqbit::TorrentStatsState;
 use std::path::PathBuf;
-use std::sync::Arc;
 use tokio::sync::mpsc;
 
 use super::cleaner::{find_extra_files, find_missing_files, get_expected_files_from_details};
+use super::backend::Backends;
+use super::error::SyncError;
 use super::messages::SyncEvent;
-use super::types::{LocalTorrentState, SyncState};
+use super::snapshot::TorrentSnapshot;
+use super::cache::TorrentCache;
+use super::quarantine::{self, Quarantine};
+use super::types::{LocalTorrentState, RemoteTorrentState, SyncState};
 use super::utils::send_sync_status_event;
 use super::torrent::manage_torrent_task;
 // cached_torrent_path is now supplied via SyncConfig.cached_torrent_path
@@ -25,7 +29,7 @@ pub async fn verify_folder_contents(
         if config.download_path.as_os_str().is_empty() {
             let err_msg = "Download path not configured".to_string();
             eprintln!("Sync: {}", err_msg);
-            let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+            let _ = ui_tx.send(SyncEvent::Error(SyncError::Config(err_msg.clone())));
             send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
             return;
         }
@@ -39,7 +43,7 @@ pub async fn verify_folder_contents(
         match api.api_torrent_details(id.into()) {
             Ok(details) => {
                 // Get the expected files list from torrent
-                let expected_files = get_expected_files_from_details(&details);
+                let expected_files = get_expected_files_from_details(&details, &config.folder_mappings);
 
                 // Will be set based on missing files check below. Do not initialize
                 // here to avoid the “value assigned is never read” warning since
@@ -70,14 +74,14 @@ pub async fn verify_folder_contents(
                     Err(e) => {
                         let err_msg = format!("Failed to check for missing files: {}", e);
                         eprintln!("Sync: {}", err_msg);
-                        let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+                        let _ = ui_tx.send(SyncEvent::Error(SyncError::caused_by(SyncError::Other, err_msg.clone(), &*e)));
                         send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
                         return;
                     }
                 }
 
                 // Proceed with checking for extra files
-                match find_extra_files(&config.download_path, &expected_files) {
+                match find_extra_files(&config.download_path, &expected_files, &config.cleaner_ignored_paths_for(&details)) {
                     Ok(extra_files) => {
                         println!("Sync: Found {} extra files in directory", extra_files.len());
                         
@@ -105,7 +109,7 @@ pub async fn verify_folder_contents(
                     Err(e) => {
                         let err_msg = format!("Failed to find extra files: {}", e);
                         eprintln!("Sync: {}", err_msg);
-                        let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+                        let _ = ui_tx.send(SyncEvent::Error(SyncError::caused_by(SyncError::Other, err_msg.clone(), &*e)));
                         send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
                     }
                 }
@@ -113,14 +117,14 @@ pub async fn verify_folder_contents(
             Err(e) => {
                 let err_msg = format!("Failed to get torrent details: {}", e);
                 eprintln!("Sync: {}", err_msg);
-                let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+                let _ = ui_tx.send(SyncEvent::Error(SyncError::Engine(err_msg.clone())));
                 send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
             }
         }
     } else {
         let err_msg = "No active torrent to verify against".to_string();
         eprintln!("Sync: {}", err_msg);
-        let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+        let _ = ui_tx.send(SyncEvent::Error(SyncError::Other(err_msg.clone())));
         send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
     }
 }
@@ -130,10 +134,37 @@ pub async fn fix_missing_files(
     state: &mut SyncState,
     api: &librqbit::Api,
     ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    backends: &Backends,
+) {
+    println!("Sync: Attempting to fix missing files by restarting the torrent");
+    restart_torrent(config, state, api, ui_tx, backends).await;
+}
+
+/// Hash every piece of the active torrent again, ignoring its fastresume
+/// state. Forgetting the torrent drops that state, so restarting it from the
+/// cache does a full check.
+pub async fn force_recheck(
+    config: &SyncConfig,
+    state: &mut SyncState,
+    api: &librqbit::Api,
+    ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    backends: &Backends,
+) {
+    println!("Sync: Forcing a full re-check by restarting the torrent");
+    restart_torrent(config, state, api, ui_tx, backends).await;
+}
+
+/// Forget the active torrent and add it again from the cached torrent file.
+async fn restart_torrent(
+    config: &SyncConfig,
+    state: &mut SyncState,
+    api: &librqbit::Api,
+    ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    backends: &Backends,
 ) {
     // Only proceed if we have an active torrent
     if let LocalTorrentState::Active { id } = state.local {
-        println!("Sync: Attempting to fix missing files by restarting torrent ID {}", id);
+        println!("Sync: Restarting torrent ID {}", id);
         send_sync_status_event(ui_tx, SyncStatus::UpdatingTorrent);
         
         // Get cached torrent file for restarting (supplied by the client)
@@ -146,13 +177,14 @@ pub async fn fix_missing_files(
                             config,
                             api,
                             ui_tx,
+                            backends,
                             Some(id), // Current ID to forget
                             torrent_content,
                         ).await;
 
                         match restart_result {
                             Ok(new_id) => {
-                                println!("Sync: Torrent restarted successfully to download missing files. New ID: {:?}", new_id);
+                                println!("Sync: Torrent restarted successfully. New ID: {:?}", new_id);
 
                                 // Update the state with the new torrent ID
                                 state.local = match new_id {
@@ -169,20 +201,23 @@ pub async fn fix_missing_files(
                                 };
                             },
                             Err(e) => {
-                                let err_msg = format!("Failed to restart torrent to download missing files: {}", e);
+                                let err_msg = format!("Failed to restart torrent: {}", e);
                                 eprintln!("Sync: {}", err_msg);
-                                let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+                                let _ = ui_tx.send(SyncEvent::Error(SyncError::caused_by(SyncError::Engine, err_msg.clone(), &*e)));
                                 send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
 
-                                // The old torrent was removed but we failed to add a new one
-                                state.local = LocalTorrentState::NotLoaded;
+                                // Unless it was refused, the old torrent was removed but
+                                // the new one couldn't be added
+                                if api.api_torrent_details(id.into()).is_err() {
+                                    state.local = LocalTorrentState::NotLoaded;
+                                }
                             }
                         }
                     },
                     Err(e) => {
                         let err_msg = format!("Failed to read cached torrent file: {}", e);
                         eprintln!("Sync: {}", err_msg);
-                        let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+                        let _ = ui_tx.send(SyncEvent::Error(SyncError::caused_by(SyncError::Other, err_msg.clone(), &e)));
                         send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
                     }
                 }
@@ -190,36 +225,105 @@ pub async fn fix_missing_files(
             None => {
                 let err_msg = "No cached torrent path supplied; cannot restart torrent".to_string();
                 eprintln!("Sync: {}", err_msg);
-                let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+                let _ = ui_tx.send(SyncEvent::Error(SyncError::Config(err_msg.clone())));
                 send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
             }
         }
     } else {
-        let err_msg = "No active torrent to fix missing files".to_string();
+        let err_msg = "No active torrent to restart".to_string();
         eprintln!("Sync: {}", err_msg);
-        let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+        let _ = ui_tx.send(SyncEvent::Error(SyncError::Other(err_msg.clone())));
         send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
     }
 }
 
-pub async fn delete_files(files_to_delete: &[PathBuf], ui_tx: &mpsc::UnboundedSender<SyncEvent>) {
-    println!("Sync: Deleting {} files", files_to_delete.len());
+/// Stop managing the active torrent. The cached torrent is removed too, so
+/// it isn't added again on the next start. With `delete_data` the torrent's
+/// downloaded files and every cached torrent version are deleted as well;
+/// otherwise the files stay where they are.
+pub async fn remove_torrent(
+    config: &SyncConfig,
+    state: &mut SyncState,
+    api: &librqbit::Api,
+    ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    delete_data: bool,
+) {
+    let LocalTorrentState::Active { id } = state.local else {
+        let _ = ui_tx.send(SyncEvent::Error(SyncError::Other("No active torrent to remove".to_string())));
+        return;
+    };
+    send_sync_status_event(ui_tx, SyncStatus::UpdatingTorrent);
+    let result = if delete_data {
+        api.api_torrent_action_delete(id.into()).await
+    } else {
+        api.api_torrent_action_forget(id.into()).await
+    };
+    if let Err(e) = result {
+        let err_msg = format!("Failed to remove torrent {}: {}", id, e);
+        eprintln!("Sync: {}", err_msg);
+        let _ = ui_tx.send(SyncEvent::Error(SyncError::Engine(err_msg.clone())));
+        send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
+        return;
+    }
+    println!("Sync: Removed torrent {}{}", id, if delete_data { " and its files" } else { ", keeping its files" });
+    state.local = LocalTorrentState::NotLoaded;
+    state.remote = RemoteTorrentState::Unknown;
+
+    if let Some(cached) = &config.cached_torrent_path
+        && let Err(e) = tokio::fs::remove_file(cached).await
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        eprintln!("Sync: Failed to remove cached torrent {}: {}", cached.display(), e);
+    }
+    if delete_data && let Some(dir) = &config.cache_dir {
+        match TorrentCache::new(dir).clear() {
+            Ok(count) => println!("Sync: Cleared {} cached torrent versions", count),
+            Err(e) => eprintln!("Sync: Failed to clear torrent cache: {:#}", e),
+        }
+    }
+
+    let _ = ui_tx.send(SyncEvent::ManagedTorrentUpdate(None));
+    let _ = ui_tx.send(SyncEvent::TorrentFiles(Vec::new()));
+    let _ = ui_tx.send(SyncEvent::ConsistencyCheck(Vec::new()));
+    send_sync_status_event(ui_tx, SyncStatus::Idle);
+}
+
+/// Delete `files_to_delete`, or move them into the quarantine when it is on.
+pub async fn delete_files(config: &SyncConfig, files_to_delete: &[PathBuf], ui_tx: &mpsc::UnboundedSender<SyncEvent>) {
     send_sync_status_event(ui_tx, SyncStatus::CheckingLocal); // Re-use the CheckingLocal status
 
     let mut errors = Vec::new();
+    let mut first_cause = None;
 
-    for file_path in files_to_delete {
-        println!("Sync: Deleting file: {}", file_path.display());
-        if let Err(e) = tokio::fs::remove_file(file_path).await {
-            let err_msg = format!("Failed to delete {}: {}", file_path.display(), e);
+    if let Some(quarantine) = Quarantine::for_config(config) {
+        println!("Sync: Quarantining {} files", files_to_delete.len());
+        let (batch, quarantine_errors) = quarantine.quarantine(&config.download_path, files_to_delete, chrono::Utc::now());
+        println!("Sync: Moved files to {}", batch.display());
+        for err_msg in quarantine_errors {
             eprintln!("Sync: {}", err_msg);
             errors.push(err_msg);
         }
+        quarantine::purge_expired(config);
+    } else {
+        println!("Sync: Deleting {} files", files_to_delete.len());
+        for file_path in files_to_delete {
+            println!("Sync: Deleting file: {}", file_path.display());
+            if let Err(e) = tokio::fs::remove_file(file_path).await {
+                let err_msg = format!("Failed to delete {}: {}", file_path.display(), e);
+                eprintln!("Sync: {}", err_msg);
+                errors.push(err_msg);
+                first_cause.get_or_insert(e);
+            }
+        }
     }
 
     if !errors.is_empty() {
         let err_msg = format!("Errors during file deletion: {}", errors.join(", "));
-        let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+        let error = match &first_cause {
+            Some(cause) => SyncError::caused_by(SyncError::Other, err_msg.clone(), cause),
+            None => SyncError::Other(err_msg.clone()),
+        };
+        let _ = ui_tx.send(SyncEvent::Error(error));
         send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
     } else {
         println!("Sync: All files deleted successfully");
@@ -233,6 +337,19 @@ pub async fn delete_files(files_to_delete: &[PathBuf], ui_tx: &mpsc::UnboundedSe
     }
 }
 
+/// Whether torrent `id` is private. librqbit skips DHT and peer exchange
+/// for private torrents by itself.
+pub fn is_private(api: &librqbit::Api, id: usize) -> bool {
+    api.mgr_handle(id.into())
+        .is_ok_and(|handle| handle.with_metadata(|meta| meta.info.private).unwrap_or(false))
+}
+
+/// The metainfo torrent `id` was added with.
+pub fn torrent_bytes(api: &librqbit::Api, id: usize) -> Option<Vec<u8>> {
+    let handle = api.mgr_handle(id.into()).ok()?;
+    handle.with_metadata(|meta| meta.torrent_bytes.to_vec()).ok()
+}
+
 pub fn refresh_managed_torrent_status_event(
     api: &librqbit::Api,
     tx: &mpsc::UnboundedSender<SyncEvent>,
@@ -241,8 +358,9 @@ pub fn refresh_managed_torrent_status_event(
     println!("Sync: Fetching stats for torrent ID {}", managed_id);
     match api.api_stats_v1(managed_id.into()) {
         Ok(stats) => {
-            // Send the torrent stats update - wrap in Arc
-            if let Err(e) = tx.send(SyncEvent::ManagedTorrentUpdate(Some((managed_id, Arc::new(stats))))) {
+            // Send the torrent stats update as a serializable snapshot
+            let snapshot = TorrentSnapshot { private: is_private(api, managed_id), ..TorrentSnapshot::from(&stats) };
+            if let Err(e) = tx.send(SyncEvent::ManagedTorrentUpdate(Some((managed_id, snapshot)))) {
                 eprintln!(
                     "Sync: Failed to send managed torrent stats update to UI (ID {}): {}",
                     managed_id, e
@@ -273,7 +391,7 @@ pub fn refresh_managed_torrent_status_event(
                             .error
                             .unwrap_or_else(|| "Unknown error".to_string());
                         send_sync_status_event(tx, SyncStatus::Error(err_msg.clone()));
-                        let _ = tx.send(SyncEvent::Error(err_msg));
+                        let _ = tx.send(SyncEvent::Error(SyncError::from_engine(err_msg)));
                     }
                 }
             }
@@ -287,7 +405,31 @@ pub fn refresh_managed_torrent_status_event(
 
             let err_msg = format!("Failed to get torrent stats: {}", e);
             send_sync_status_event(tx, SyncStatus::Error(err_msg.clone()));
-            let _ = tx.send(SyncEvent::Error(err_msg));
+            let _ = tx.send(SyncEvent::Error(SyncError::Engine(err_msg)));
         }
     }
-}
\ No newline at end of file
+}
+/// Pause or resume the managed torrent, then report its new state.
+pub async fn set_torrent_paused(
+    state: &SyncState,
+    api: &librqbit::Api,
+    ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    paused: bool,
+) {
+    let LocalTorrentState::Active { id } = state.local else {
+        let _ = ui_tx.send(SyncEvent::Error(SyncError::Other("No active torrent to pause or resume".to_string())));
+        return;
+    };
+
+    let result = if paused {
+        api.api_torrent_action_pause(id.into()).await
+    } else {
+        api.api_torrent_action_start(id.into()).await
+    };
+    if let Err(e) = result {
+        let err_msg = format!("Failed to {} torrent: {}", if paused { "pause" } else { "resume" }, e);
+        eprintln!("Sync: {}", err_msg);
+        let _ = ui_tx.send(SyncEvent::Error(SyncError::Engine(err_msg)));
+    }
+    refresh_managed_torrent_status_event(api, ui_tx, id);
+}
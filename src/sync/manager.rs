@@ -3,38 +3,129 @@
 //! Main manager for the synchronization process
 
 use anyhow::{Context, Result};
-use std::time::Instant;
-use tokio::sync::mpsc;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::time::{Instant, MissedTickBehavior};
 
 use crate::sync::status::SyncStatus;
 use super::types::SyncConfig;
 
+use super::cache;
+use super::coalesce;
+use super::consistency;
+use super::discord::{self, Presence};
+use super::telemetry;
+use super::schedule::CheckSchedule;
+use super::extract;
+use super::filters;
+use super::git;
+use super::keys;
+use super::peers;
+use super::power::{self, PowerAction, PowerRestriction};
+use super::preflight;
+use super::quarantine;
+use super::stall::{self, StallChange, StallDetector};
+use super::store;
+use super::swarm;
+use super::usage;
+use super::backend::Backends;
+use super::history::RunTrigger;
+use super::hooks;
+use super::http::create_http_client;
 use super::cleaner::{find_extra_files, get_expected_files_from_details};
-use super::local::{delete_files, refresh_managed_torrent_status_event, verify_folder_contents, fix_missing_files};
+use super::journal::{tap_events, EventJournal};
+use super::diff::diff_torrents;
+use super::local::{delete_files, refresh_managed_torrent_status_event, set_torrent_paused, torrent_bytes, verify_folder_contents, fix_missing_files, force_recheck, remove_torrent};
+use super::mapping::map_path;
+use super::error::SyncError;
 use super::messages::{SyncCommand, SyncEvent};
 use super::remote::{apply_remote_update, direct_download_and_compare};
+use super::torrent::{apply_speed_limits, manage_torrent_task};
 use super::types::{LocalTorrentState, RemoteTorrentState, SyncState};
-use super::utils::send_sync_status_event;
+use super::utils::{send_sync_event, send_sync_status_event};
+
+/// How often the torrent status is refreshed and the timed work below is
+/// looked at.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often the download folder's disk usage is re-scanned, besides once
+/// the torrent finishes.
+const USAGE_SCAN_INTERVAL: Duration = Duration::from_secs(900);
+
+/// Free space on the download folder's drive below which a downloading
+/// torrent is paused, before its writes start failing.
+const LOW_SPACE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// How often the connection and power source are checked while a power
+/// action is configured.
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the trackers are scraped for the size of the swarm.
+const SCRAPE_INTERVAL: Duration = Duration::from_secs(900);
 
 pub async fn run_sync_manager(
     api: librqbit::Api,
+    mut config: SyncConfig,
     ui_tx: mpsc::UnboundedSender<SyncEvent>,
     mut sync_cmd_rx: mpsc::UnboundedReceiver<SyncCommand>,
-    initial_torrent_id: Option<usize>, // Accept initial ID
+    journal: Option<EventJournal>,
+    backends: Arc<Backends>,
 ) -> Result<()> {
+    // Post hooks watch the outgoing events, with the config as last updated
+    let (hooks_config_tx, hooks_config_rx) = watch::channel(config.clone());
+    let ui_tx = hooks::tap_events(hooks_config_rx, ui_tx);
+
+    // Route outgoing events through the journal when one is supplied
+    let ui_tx = match &journal {
+        Some(journal) => tap_events(journal.clone(), ui_tx),
+        None => ui_tx,
+    };
+    // Repeated stats are dropped before the journal and the hooks see them
+    let ui_tx = coalesce::tap_events(ui_tx);
+
+    // The cached torrent is added here rather than before the manager is
+    // spawned, so clients are served while a large torrent is restored
+    let initial_torrent_id = restore_session(&config, &api, &ui_tx, &backends).await;
+    send_sync_event(&ui_tx, SyncEvent::SessionRestored);
+
     let mut state = SyncState {
         local: match initial_torrent_id {
             Some(id) => LocalTorrentState::Active { id },
             None => LocalTorrentState::NotLoaded,
         },
         remote: RemoteTorrentState::Unknown,
+        last_good_url: None,
+        retry_after: None,
     };
 
+    let mut gaming_mode = false;
+    let mut power: Option<PowerRestriction> = None;
+    // Torrent paused for the power restriction, resumed when it ends
+    let mut power_paused: Option<usize> = None;
+    let mut last_power_check: Option<Instant> = None;
+    // Finished torrent paused outside the seeding window
+    let mut window_paused: Option<usize> = None;
+    apply_config_limits(&api, &config, gaming_mode, power);
+
     // Create HTTP client once
-    let http_client = super::http::create_http_client().context("Failed to create HTTP client")?;
+    let mut http_client = create_http_client(&config.redirects).context("Failed to create HTTP client")?;
     
-    // Track the last time we checked for updates
-    let mut last_update_check: Option<std::time::Instant> = None;
+    // When the next periodic update check is due
+    let mut check_schedule = CheckSchedule::new(&config, Instant::now());
+    // Whether the finished download has been post-processed, so keys are
+    // collected once per download rather than on every tick
+    let mut post_processed = false;
+    let mut last_usage_scan: Option<Instant> = None;
+    // Torrent last scraped and when, so a new torrent is scraped right away
+    let mut last_scrape: Option<(usize, Instant)> = None;
+    let mut stall_detector = StallDetector::default();
+    let mut presence = Presence::default();
+    // Torrent being downloaded and when it started, timing the completion
+    // report
+    let mut download_started: Option<(usize, Instant)> = None;
 
     // Send initial status based on whether a cached torrent was loaded
     if let LocalTorrentState::Active { id } = state.local {
@@ -44,126 +135,587 @@ pub async fn run_sync_manager(
             id
         );
         refresh_managed_torrent_status_event(&api, &ui_tx, id);
+        check_consistency(&config, &state, &api, &ui_tx).await;
         // Set overall sync status to Idle, actual torrent status comes from refresh
         send_sync_status_event(&ui_tx, SyncStatus::Idle);
     } else {
         send_sync_status_event(&ui_tx, SyncStatus::Idle);
     }
 
+    quarantine::purge_expired(&config);
+    preflight::check_and_report(&config.download_path, &ui_tx);
+
     println!("Sync: Manager started. Initial State: {:?}", state);
 
+    // An interval rather than a sleep in the loop, so a stream of commands
+    // doesn't keep pushing the refresh back
+    let mut refresh = tokio::time::interval_at(Instant::now() + REFRESH_INTERVAL, REFRESH_INTERVAL);
+    refresh.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
     loop {
         tokio::select! {
             // Handle command messages from the UI
             Some(cmd_message) = sync_cmd_rx.recv() => {
+                if let Some(journal) = &journal {
+                    journal.record_command(&cmd_message);
+                }
+                let trigger = RunTrigger::for_command(&cmd_message);
+                if let Some(trigger) = trigger {
+                    send_sync_event(&ui_tx, SyncEvent::RunStarted(trigger));
+                }
                 match cmd_message {
-                    SyncCommand::UpdateConfig(_new_config) => {
-                            // Configuration updates are now handled via the external
-                            // configuration API. The manager no longer stores or
-                            // mutates a local config copy. For now just acknowledge
-                            // receipt and notify the UI; the real apply will come
-                            // from the configuration API when implemented.
-                            println!("Sync: Received configuration update (forwarded to config API)");
-                            let _ = ui_tx.send(SyncEvent::Error("Configuration update received; it will be applied via the config API.".to_string()));
+                    SyncCommand::UpdateConfig(new_config) => {
+                        // The new config applies to subsequent commands; an already
+                        // added torrent keeps its options until it is re-added, apart
+                        // from the download filters which reselect its files.
+                        println!("Sync: Configuration updated");
+                        if new_config.redirects != config.redirects {
+                            match create_http_client(&new_config.redirects) {
+                                Ok(client) => http_client = client,
+                                Err(e) => eprintln!("Sync: Keeping the previous HTTP client: {:#}", e),
+                            }
+                        }
+                        let moved = new_config.download_path != config.download_path;
+                        let recheck = moved
+                            || new_config.cached_torrent_path != config.cached_torrent_path;
+                        let refilter = new_config.download_filters != config.download_filters;
+                        let relimit = new_config.max_upload_speed != config.max_upload_speed
+                            || new_config.max_download_speed != config.max_download_speed
+                            || new_config.power != config.power;
+                        let unpinned = config.pinned && !new_config.pinned;
+                        if new_config.banned_peers != config.banned_peers {
+                            println!("Sync: Banned peers changed, refused from the next session start");
+                        }
+                        if new_config.power != config.power {
+                            last_power_check = None;
+                        }
+                        config = *new_config;
+                        post_processed = false;
+                        hooks_config_tx.send_replace(config.clone());
+                        if moved {
+                            preflight::check_and_report(&config.download_path, &ui_tx);
+                        }
+                        if recheck {
+                            check_consistency(&config, &state, &api, &ui_tx).await;
+                        }
+                        if relimit {
+                            apply_config_limits(&api, &config, gaming_mode, power);
+                        }
+                        if refilter && let LocalTorrentState::Active { id } = state.local {
+                            filters::apply(&config.download_filters, &config.folder_mappings, &api, id, &ui_tx).await;
+                        }
+                        // Updates ignored while pinned are announced right away
+                        if unpinned {
+                            println!("Sync: Version unpinned, checking for updates");
+                            direct_download_and_compare(&config, &mut state, &api, &ui_tx, &backends, &http_client).await;
+                        }
                     }
                     SyncCommand::VerifyFolder => {
                         println!("Sync: Folder verification requested");
-                            // Use a placeholder config; the real config will be
-                            // supplied by the configuration API in future.
-                            let cfg = SyncConfig::default();
-                            verify_folder_contents(&cfg, &mut state, &api, &ui_tx).await;
+                        verify_folder_contents(&config, &mut state, &api, &ui_tx).await;
                     },
                     SyncCommand::FixMissingFiles => {
                         println!("Sync: Fix missing files requested");
-                            let cfg = SyncConfig::default();
-                            fix_missing_files(&cfg, &mut state, &api, &ui_tx).await;
+                        fix_missing_files(&config, &mut state, &api, &ui_tx, &backends).await;
+                        // Re-adding from the cache is also the repair for inconsistencies
+                        check_consistency(&config, &state, &api, &ui_tx).await;
+                    },
+                    SyncCommand::ForceRecheck => {
+                        println!("Sync: Full re-check requested");
+                        force_recheck(&config, &mut state, &api, &ui_tx, &backends).await;
+                        check_consistency(&config, &state, &api, &ui_tx).await;
+                    },
+                    SyncCommand::ForgetTorrent => {
+                        println!("Sync: Forget torrent requested");
+                        remove_torrent(&config, &mut state, &api, &ui_tx, false).await;
+                        send_next_check_event(&ui_tx, &state, &check_schedule);
+                    },
+                    SyncCommand::RemoveTorrentAndData => {
+                        println!("Sync: Remove torrent and data requested");
+                        if config.read_only {
+                            refuse_deletion(&ui_tx, "the torrent's files");
+                        } else {
+                            remove_torrent(&config, &mut state, &api, &ui_tx, true).await;
+                            send_next_check_event(&ui_tx, &state, &check_schedule);
+                        }
+                    },
+                    SyncCommand::SetPaused(paused) => {
+                        println!("Sync: {} requested", if paused { "Pause" } else { "Resume" });
+                        set_torrent_paused(&state, &api, &ui_tx, paused).await;
+                    },
+                    SyncCommand::SetSpeedLimits { max_upload_speed, max_download_speed } => {
+                        println!(
+                            "Sync: Speed limits changed - Upload: {:?} KB/s, Download: {:?} KB/s",
+                            max_upload_speed, max_download_speed
+                        );
+                        config.max_upload_speed = max_upload_speed;
+                        config.max_download_speed = max_download_speed;
+                        hooks_config_tx.send_replace(config.clone());
+                        apply_config_limits(&api, &config, gaming_mode, power);
+                    },
+                    SyncCommand::SetGamingMode(enabled) => {
+                        println!("Sync: Gaming mode {}", if enabled { "on" } else { "off" });
+                        gaming_mode = enabled;
+                        apply_config_limits(&api, &config, gaming_mode, power);
+                        send_sync_event(&ui_tx, SyncEvent::GamingMode(enabled));
                     },
                     SyncCommand::DeleteFiles(files_to_delete) => {
                         println!("Sync: Deletion requested for {} files", files_to_delete.len());
-                        delete_files(&files_to_delete, &ui_tx).await;
+                        if config.read_only {
+                            refuse_deletion(&ui_tx, &format!("{} files", files_to_delete.len()));
+                        } else {
+                            delete_files(&config, &files_to_delete, &ui_tx).await;
+                        }
                     },
                     SyncCommand::ApplyUpdate(torrent_content) => {
                         println!("Sync: Apply remote update requested ({} bytes)", torrent_content.len());
-                            let cfg = SyncConfig::default();
-
-                            match apply_remote_update(&cfg, &mut state, &api, &ui_tx, torrent_content).await {
-                            true => {
-                                state.remote = RemoteTorrentState::Checked; // Update state on success
-                                
-                                // Verification logic after successful update
-                                if let LocalTorrentState::Active { id } = state.local {
-                                    println!("Sync: Checking for extra files after update");
-                                    send_sync_status_event(&ui_tx, SyncStatus::CheckingLocal);
-                                    match api.api_torrent_details(id.into()) {
-                                        Ok(details) => {
-                                            let expected_files = get_expected_files_from_details(&details);
-                                            match find_extra_files(&cfg.download_path, &expected_files) {
-                                                Ok(extra_files) => {
-                                                    println!("Sync: Found {} extra files after update", extra_files.len());
-                                                    if let Err(e) = ui_tx.send(SyncEvent::ExtraFilesFound(extra_files)) {
-                                                        eprintln!("Sync: Failed to send extra files list to UI: {}", e);
-                                                    }
-                                                    send_sync_status_event(&ui_tx, SyncStatus::Idle);
-                                                },
-                                                Err(e) => {
-                                                    let err_msg = format!("Failed to find extra files after update: {}", e);
-                                                    eprintln!("Sync: {}", err_msg);
-                                                    let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
-                                                    send_sync_status_event(&ui_tx, SyncStatus::Error(err_msg));
-                                                }
-                                            }
-                                        },
-                                        Err(e) => {
-                                            let err_msg = format!("Failed to get torrent details after update: {}", e);
-                                            eprintln!("Sync: {}", err_msg);
-                                            let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
-                                            send_sync_status_event(&ui_tx, SyncStatus::Error(err_msg));
-                                        }
-                                    }
-                                } else {
-                                     send_sync_status_event(&ui_tx, SyncStatus::Idle); // No active torrent to verify against
-                                }
-                            },
-                            false => {
-                                // If update failed, set remote state back to Unknown
-                                // Maybe also set local state to NotLoaded if appropriate?
-                                state.remote = RemoteTorrentState::Unknown;
-                                // Error status is sent by apply_remote_update itself
-                            }
-                        }
+                        apply_update(&config, &mut state, &api, &ui_tx, &backends, torrent_content, None).await;
+                    },
+                    SyncCommand::RollBack(torrent_content) => {
+                        println!("Sync: Roll back requested ({} bytes)", torrent_content.len());
+                        let newer = match state.local {
+                            LocalTorrentState::Active { id } => torrent_bytes(&api, id),
+                            LocalTorrentState::NotLoaded => None,
+                        };
+                        apply_update(&config, &mut state, &api, &ui_tx, &backends, torrent_content, newer.as_deref()).await;
                     },
                     SyncCommand::DownloadAndCompare(url) => {
                         println!("Sync: Force download and compare requested for URL: {}", url);
-                        let mut cfg = SyncConfig::default();
-                        cfg.torrent_url = url.clone();
-                        direct_download_and_compare(&cfg, &mut state, &api, &ui_tx, &http_client).await;
+                        let cfg = SyncConfig {
+                            torrent_url: url.clone(),
+                            ..config.clone()
+                        };
+                        direct_download_and_compare(&cfg, &mut state, &api, &ui_tx, &backends, &http_client).await;
+                        git::refresh(&cfg, &ui_tx).await;
+                        // A manual check postpones the next periodic one
+                        check_schedule.checked(&config, Instant::now());
+                        if let Some(after) = state.retry_after {
+                            check_schedule.retry_after(after, Instant::now());
+                        }
+                        send_next_check_event(&ui_tx, &state, &check_schedule);
                     },
                     // No need for a catch-all since all variants are explicitly handled
                 }
+                if trigger.is_some() {
+                    send_sync_event(&ui_tx, SyncEvent::RunFinished);
+                }
             },
-            // Define a timeout to periodically refresh the status
-            _ = tokio::time::sleep(std::time::Duration::from_secs(10)) => {
+            // Periodically refresh the status
+            _ = refresh.tick() => {
+                if (config.power.is_active() || power.is_some())
+                    && last_power_check.is_none_or(|t| t.elapsed() >= POWER_CHECK_INTERVAL)
+                {
+                    last_power_check = Some(Instant::now());
+                    let restriction = if config.power.is_active() { config.power.restriction(power::detect().await) } else { None };
+                    if restriction != power {
+                        println!("Sync: Power restriction changed to {:?}", restriction);
+                        power = restriction;
+                        apply_config_limits(&api, &config, gaming_mode, power);
+                        send_sync_event(&ui_tx, SyncEvent::PowerRestriction(power));
+                    }
+                    pause_for_power(&state, &api, &ui_tx, power, &mut power_paused).await;
+                }
+                if power.is_none_or(|power| power.action != PowerAction::Pause) {
+                    hold_seeding(&config, &state, &api, &ui_tx, &mut window_paused).await;
+                }
+                let activity = match state.local {
+                    LocalTorrentState::Active { id } => discord::Activity::of(&api, id),
+                    _ => None,
+                };
+                presence.update(&config.discord, activity).await;
                 // Refresh the torrent status periodically
                 if let LocalTorrentState::Active { id } = state.local {
                     refresh_managed_torrent_status_event(&api, &ui_tx, id);
+                    send_sync_event(&ui_tx, SyncEvent::Peers(peers::snapshot(&api, id)));
+                    pause_when_disk_full(&config, &api, &ui_tx, id).await;
+                    let stats = api.api_stats_v1(id.into()).ok();
+                    let finished = stats.as_ref().is_some_and(|stats| stats.finished);
+                    if let Some(after) = stall::stall_after(&config) {
+                        let downloading = stats.as_ref().is_some_and(|stats| matches!(stats.state, librqbit::TorrentStatsState::Live) && !stats.finished);
+                        let progress = stats.as_ref().map_or(0, |stats| stats.progress_bytes);
+                        match stall_detector.update(downloading, progress, Instant::now(), after) {
+                            Some(StallChange::Stalled(stalled_for)) => {
+                                stall::spawn_diagnosis(&config, &api, id, stalled_for, http_client.clone(), ui_tx.clone());
+                            }
+                            Some(StallChange::Recovered) => {
+                                println!("Sync: Download is moving again");
+                                send_sync_event(&ui_tx, SyncEvent::Stalled(None));
+                            }
+                            None => {}
+                        }
+                    }
+                    // Extracting writes into the download folder
+                    if finished && !config.extract.is_empty() && !config.read_only {
+                        extract::extract_and_report(&config, &ui_tx).await;
+                    }
+                    if !finished && download_started.is_none_or(|(started, _)| started != id) {
+                        download_started = Some((id, Instant::now()));
+                    }
+                    if finished && !post_processed {
+                        keys::collect_and_report(&config, &ui_tx).await;
+                        store::publish_finished(&config).await;
+                        if let Some((started, t)) = download_started.take()
+                            && started == id
+                        {
+                            telemetry::spawn_report(&config, &api, id, t.elapsed(), http_client.clone());
+                        }
+                    }
+                    if (finished && !post_processed) || last_usage_scan.is_none_or(|t| t.elapsed() >= USAGE_SCAN_INTERVAL) {
+                        last_usage_scan = Some(Instant::now());
+                        usage::scan_and_report(&config, &api, id, &ui_tx).await;
+                    }
+                    post_processed = finished;
+                    if last_scrape.is_none_or(|(scraped, t)| scraped != id || t.elapsed() >= SCRAPE_INTERVAL) {
+                        last_scrape = Some((id, Instant::now()));
+                        swarm::spawn_scrape(&api, id, http_client.clone(), ui_tx.clone());
+                    }
 
-                    // Every 10 minutes, also check for remote updates
-                    let now = Instant::now();
-                    let should_check = match last_update_check {
-                        Some(last) => now.duration_since(last).as_secs() >= 600, // 10 minutes
-                        None => true
-                    };
-
-                    if should_check {
-                        last_update_check = Some(now);
+                    // About every 10 minutes, also check for remote updates
+                    if check_schedule.is_due(Instant::now()) {
+                        check_schedule.checked(&config, Instant::now());
                         println!("Sync: Periodic remote check triggered");
-                        // No local config is stored in the manager; use default
-                        // placeholder until the external config API is available.
-                        let periodic_cfg = SyncConfig::default();
-                        direct_download_and_compare(&periodic_cfg, &mut state, &api, &ui_tx, &http_client).await;
+                        send_sync_event(&ui_tx, SyncEvent::RunStarted(RunTrigger::Scheduled));
+                        direct_download_and_compare(&config, &mut state, &api, &ui_tx, &backends, &http_client).await;
+                        if let Some(after) = state.retry_after {
+                            println!("Sync: Remote asked to retry in {} s, holding off checks", after.as_secs());
+                            check_schedule.retry_after(after, Instant::now());
+                        }
+                        git::refresh(&config, &ui_tx).await;
+                        send_sync_event(&ui_tx, SyncEvent::RunFinished);
                     }
                 }
+                send_next_check_event(&ui_tx, &state, &check_schedule);
+            }
+        }
+    }
+}
+
+/// Apply `torrent_content` as the new torrent after the pre-update hook
+/// and look for files it doesn't have. They are deleted in strict mirror
+/// mode. A rollback from `newer`, the torrent it replaces, also deletes the
+/// files only `newer` has; other extra files are left for the user to
+/// confirm. Once added, the torrent is cached and becomes what restarts and
+/// remote checks go by. Returns whether the torrent was added.
+async fn apply_update(
+    config: &SyncConfig,
+    state: &mut SyncState,
+    api: &librqbit::Api,
+    ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    backends: &Backends,
+    torrent_content: Vec<u8>,
+    newer: Option<&[u8]>,
+) -> bool {
+    let newer_only = newer.map(|newer| newer_only_files(config, newer, &torrent_content)).unwrap_or_default();
+    let pre_update = hooks::run_hook(
+        "pre_update",
+        &config.hooks.pre_update,
+        &config.download_path,
+        &[("MODSYNC_TORRENT_BYTES", torrent_content.len().to_string())],
+    )
+    .await;
+    let applied = match pre_update {
+        Ok(()) => apply_remote_update(config, state, api, ui_tx, backends, torrent_content.clone()).await,
+        Err(e) => {
+            let err_msg = format!("Update skipped: {:#}", e);
+            eprintln!("Sync: {}", err_msg);
+            let _ = ui_tx.send(SyncEvent::Error(SyncError::caused_by(SyncError::Other, err_msg.clone(), &*e)));
+            send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
+            false
+        }
+    };
+
+    if !applied {
+        // Error status is sent by apply_remote_update itself
+        state.remote = RemoteTorrentState::Unknown;
+        return false;
+    }
+    state.remote = RemoteTorrentState::Checked;
+    if matches!(state.local, LocalTorrentState::Active { .. }) {
+        cache_applied(config, &torrent_content).await;
+    }
+    check_consistency(config, state, api, ui_tx).await;
+
+    let LocalTorrentState::Active { id } = state.local else {
+        // The torrent was refused, so there is nothing to verify against
+        send_sync_status_event(ui_tx, SyncStatus::Idle);
+        return false;
+    };
+    println!("Sync: Checking for extra files after update");
+    send_sync_status_event(ui_tx, SyncStatus::CheckingLocal);
+    let details = match api.api_torrent_details(id.into()) {
+        Ok(details) => details,
+        Err(e) => {
+            let err_msg = format!("Failed to get torrent details after update: {}", e);
+            eprintln!("Sync: {}", err_msg);
+            let _ = ui_tx.send(SyncEvent::Error(SyncError::Engine(err_msg.clone())));
+            send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
+            return true;
+        }
+    };
+    let expected_files = get_expected_files_from_details(&details, &config.folder_mappings);
+    match find_extra_files(&config.download_path, &expected_files, &config.cleaner_ignored_paths_for(&details)) {
+        Ok(extra_files) => {
+            println!("Sync: Found {} extra files after update", extra_files.len());
+            let to_delete: Vec<PathBuf> = if config.read_only {
+                Vec::new()
+            } else if config.strict_mirror {
+                extra_files.clone()
+            } else {
+                extra_files
+                    .iter()
+                    .filter(|file| file.strip_prefix(&config.download_path).is_ok_and(|path| newer_only.contains(path)))
+                    .cloned()
+                    .collect()
+            };
+            if let Err(e) = ui_tx.send(SyncEvent::ExtraFilesFound(extra_files.clone())) {
+                eprintln!("Sync: Failed to send extra files list to UI: {}", e);
+            }
+            if to_delete.is_empty() {
+                send_sync_status_event(ui_tx, SyncStatus::Idle);
+                return true;
+            }
+            if config.strict_mirror {
+                println!("Sync: Strict mirror mode, deleting the extra files");
+            } else {
+                println!("Sync: Rolled back, deleting the {} files of the newer version", to_delete.len());
+            }
+            delete_files(config, &to_delete, ui_tx).await;
+            let remaining: Vec<PathBuf> = extra_files.into_iter().filter(|file| !to_delete.contains(file)).collect();
+            if !remaining.is_empty() {
+                send_sync_event(ui_tx, SyncEvent::ExtraFilesFound(remaining));
+            }
+        }
+        Err(e) => {
+            let err_msg = format!("Failed to find extra files after update: {}", e);
+            eprintln!("Sync: {}", err_msg);
+            let _ = ui_tx.send(SyncEvent::Error(SyncError::caused_by(SyncError::Other, err_msg.clone(), &*e)));
+            send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
+        }
+    }
+    true
+}
+
+/// Make `torrent` the cached torrent of `config` and keep it among the
+/// cached versions.
+async fn cache_applied(config: &SyncConfig, torrent: &[u8]) {
+    if let Some(cache_path) = &config.cached_torrent_path {
+        println!("Sync: Writing applied torrent to cache: {}", cache_path.display());
+        if let Err(e) = tokio::fs::write(cache_path, torrent).await {
+            eprintln!("Sync: Failed to write cached torrent file: {}", e);
+        }
+    }
+    cache::remember(config, torrent);
+}
+
+/// Paths in the download folder of the files `newer` has and `older`
+/// doesn't. Empty when either torrent can't be read, so nothing is deleted.
+fn newer_only_files(config: &SyncConfig, newer: &[u8], older: &[u8]) -> HashSet<PathBuf> {
+    match diff_torrents(newer, older) {
+        Ok(diff) => diff.removed.into_iter().map(|(path, _)| map_path(&config.folder_mappings, &path)).collect(),
+        Err(e) => {
+            eprintln!("Sync: Not deleting the files of the newer version, the versions can't be compared: {:#}", e);
+            HashSet::new()
+        }
+    }
+}
+
+/// Apply the speed limits of `config` to the session, held to the gaming
+/// caps while `gaming_mode` is on and to the low-power caps while `power`
+/// asks for them.
+fn apply_config_limits(api: &librqbit::Api, config: &SyncConfig, gaming_mode: bool, power: Option<PowerRestriction>) {
+    let mut limits = config.speed_limits(gaming_mode);
+    if power.is_some_and(|power| power.action == PowerAction::LowPower) {
+        limits = config.power.capped(limits);
+    }
+    let (upload, download) = limits;
+    println!("Sync: Applying speed limits - Upload: {:?} KB/s, Download: {:?} KB/s", upload, download);
+    apply_speed_limits(api, upload, download);
+}
+
+/// Check the active torrent against the cache and config, pausing it while
+/// there are problems so nothing is synced to the wrong place.
+async fn check_consistency(config: &SyncConfig, state: &SyncState, api: &librqbit::Api, ui_tx: &mpsc::UnboundedSender<SyncEvent>) {
+    let LocalTorrentState::Active { id } = state.local else {
+        return;
+    };
+    let problems = consistency::check(config, api, id).await;
+    if !problems.is_empty() {
+        eprintln!("Sync: Torrent {} doesn't match the cache or config, pausing it: {:?}", id, problems);
+        if let Err(e) = api.api_torrent_action_pause(id.into()).await {
+            eprintln!("Sync: Failed to pause torrent {}: {}", id, e);
+        }
+        refresh_managed_torrent_status_event(api, ui_tx, id);
+    }
+    send_sync_event(ui_tx, SyncEvent::ConsistencyCheck(problems));
+}
+
+/// Add the cached torrent of `config`, if there is one, returning its id.
+async fn restore_session(
+    config: &SyncConfig,
+    api: &librqbit::Api,
+    ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    backends: &Backends,
+) -> Option<usize> {
+    let cached = config.cached_torrent_path.as_ref().filter(|path| path.exists())?;
+    println!("Sync: Restoring session from {}", cached.display());
+    send_sync_status_event(ui_tx, SyncStatus::RestoringSession);
+    let content = match tokio::fs::read(cached).await {
+        Ok(content) => content,
+        Err(e) => {
+            let err_msg = format!("Failed to read cached torrent file {}: {}", cached.display(), e);
+            eprintln!("Sync: {}", err_msg);
+            send_sync_event(ui_tx, SyncEvent::Error(SyncError::caused_by(SyncError::Other, err_msg, &e)));
+            return None;
+        }
+    };
+    match manage_torrent_task(config, api, ui_tx, backends, None, content).await {
+        Ok(id) => id,
+        Err(e) => {
+            let err_msg = format!("Failed to restore the cached torrent: {:#}", e);
+            eprintln!("Sync: {}", err_msg);
+            send_sync_event(ui_tx, SyncEvent::Error(SyncError::caused_by(SyncError::Engine, err_msg, &*e)));
+            None
+        }
+    }
+}
+
+/// Pause the torrent `id` while it is downloading and its drive is about
+/// full, and report it as a full disk. A torrent whose writes already failed
+/// is stopped by librqbit and reported by the status refresh.
+async fn pause_when_disk_full(config: &SyncConfig, api: &librqbit::Api, ui_tx: &mpsc::UnboundedSender<SyncEvent>, id: usize) {
+    let Ok(stats) = api.api_stats_v1(id.into()) else {
+        return;
+    };
+    if stats.finished || !matches!(stats.state, librqbit::TorrentStatsState::Live) {
+        return;
+    }
+    let Some(free) = usage::available_space(&config.download_path) else {
+        return;
+    };
+    if free >= LOW_SPACE_BYTES {
+        return;
+    }
+    let err_msg = format!("Only {} bytes are free on the drive of the download folder, pausing the download", free);
+    eprintln!("Sync: {}", err_msg);
+    if let Err(e) = api.api_torrent_action_pause(id.into()).await {
+        eprintln!("Sync: Failed to pause torrent {}: {}", id, e);
+    }
+    send_sync_event(ui_tx, SyncEvent::Error(SyncError::DiskFull(err_msg)));
+    refresh_managed_torrent_status_event(api, ui_tx, id);
+}
+
+/// Pause the running torrent while `power` asks for it, and resume the
+/// torrent paused that way once it doesn't. A torrent the user paused or
+/// resumed meanwhile is left as it is.
+async fn pause_for_power(
+    state: &SyncState,
+    api: &librqbit::Api,
+    ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    power: Option<PowerRestriction>,
+    power_paused: &mut Option<usize>,
+) {
+    let active = match state.local {
+        LocalTorrentState::Active { id } => Some(id),
+        LocalTorrentState::NotLoaded => None,
+    };
+    if power.is_some_and(|power| power.action == PowerAction::Pause) {
+        if let Some(id) = active
+            && *power_paused != Some(id)
+            && api.api_stats_v1(id.into()).is_ok_and(|stats| matches!(stats.state, librqbit::TorrentStatsState::Live))
+        {
+            println!("Sync: Pausing torrent {} for {:?}", id, power);
+            set_torrent_paused(state, api, ui_tx, true).await;
+            *power_paused = Some(id);
+        }
+    } else if let Some(id) = power_paused.take()
+        && active == Some(id)
+    {
+        println!("Sync: Resuming torrent {} paused for the power restriction", id);
+        set_torrent_paused(state, api, ui_tx, false).await;
+    }
+}
+
+/// Pause the finished torrent outside the seeding window and resume it once
+/// the window opens. A torrent still downloading is left running.
+async fn hold_seeding(
+    config: &SyncConfig,
+    state: &SyncState,
+    api: &librqbit::Api,
+    ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    window_paused: &mut Option<usize>,
+) {
+    let LocalTorrentState::Active { id } = state.local else {
+        *window_paused = None;
+        return;
+    };
+    let open = config.seed_window.is_none_or(|window| window.is_open());
+    if *window_paused == Some(id) {
+        if open {
+            println!("Sync: Seeding window open, resuming torrent {}", id);
+            set_torrent_paused(state, api, ui_tx, false).await;
+            *window_paused = None;
+        }
+        return;
+    }
+    if open || !config.should_seed {
+        return;
+    }
+    let Ok(stats) = api.api_stats_v1(id.into()) else {
+        return;
+    };
+    if stats.finished && matches!(stats.state, librqbit::TorrentStatsState::Live) {
+        println!("Sync: Outside the seeding window, pausing torrent {}", id);
+        set_torrent_paused(state, api, ui_tx, true).await;
+        *window_paused = Some(id);
+    }
+}
+
+/// Report that read-only mode kept `what` from being deleted.
+fn refuse_deletion(ui_tx: &mpsc::UnboundedSender<SyncEvent>, what: &str) {
+    let err_msg = format!("Read-only mode is on, not deleting {}", what);
+    eprintln!("Sync: {}", err_msg);
+    send_sync_event(ui_tx, SyncEvent::Error(SyncError::Config(err_msg)));
+}
+
+/// Tell the UI when the next periodic remote check is due. Checks only run
+/// while a torrent is loaded, so nothing is scheduled otherwise.
+fn send_next_check_event(ui_tx: &mpsc::UnboundedSender<SyncEvent>, state: &SyncState, schedule: &CheckSchedule) {
+    let next = match state.local {
+        LocalTorrentState::Active { .. } => {
+            let remaining = schedule.remaining(Instant::now());
+            chrono::Duration::from_std(remaining).ok().map(|d| (chrono::Utc::now() + d).to_rfc3339())
+        }
+        LocalTorrentState::NotLoaded => None,
+    };
+    send_sync_event(ui_tx, SyncEvent::NextCheckAt(next));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_refresh_keeps_its_pace_while_commands_arrive() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SyncConfig { download_path: dir.path().to_path_buf(), ..Default::default() };
+        let session = librqbit::Session::new_with_opts(dir.path().to_path_buf(), librqbit::SessionOptions { disable_dht: true, ..Default::default() })
+            .await
+            .unwrap();
+        let (ui_tx, mut ui_rx) = mpsc::unbounded_channel();
+        let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_sync_manager(librqbit::Api::new(session, None), config, ui_tx, cmd_rx, None, Arc::new(Backends::default())));
+
+        // Every refresh tells the UI when the next check is due. A command
+        // every 3 s must not hold them back.
+        let mut refreshes = 0;
+        for _ in 0..19 {
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            cmd_tx.send(SyncCommand::SetGamingMode(false)).unwrap();
+            while let Ok(event) = ui_rx.try_recv() {
+                refreshes += matches!(event, SyncEvent::NextCheckAt(_)) as usize;
             }
         }
+        // 57 s in, refreshed at 10 s through 50 s
+        assert_eq!(refreshes, 5);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,162 @@
+// src/sync/mapping.rs
+
+//! Routing of the torrent's files into subfolders of the download folder.
+//!
+//! A `FolderMapping` moves a top-level folder of the torrent, or the whole
+//! torrent when its `folder` is empty, into `target`. librqbit has no
+//! per-file output paths, so the mapped paths are applied by wrapping its
+//! filesystem storage; the cleaner and file list map the torrent's paths
+//! the same way.
+
+use anyhow::Result;
+use librqbit::storage::filesystem::FilesystemStorageFactory;
+use librqbit::storage::{BoxStorageFactory, StorageFactory, StorageFactoryExt, TorrentStorage};
+use librqbit::{FileInfos, ManagedTorrentShared, TorrentMetadata};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+use super::utils::is_contained_relative_path;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FolderMapping {
+    /// Top-level folder of the torrent; empty maps the whole torrent.
+    pub folder: String,
+    /// Subfolder of the download folder it is stored in.
+    pub target: PathBuf,
+}
+
+/// Where the torrent file at `path` is stored, relative to the download
+/// folder. A mapping for the file's top-level folder wins over one for the
+/// whole torrent; mappings with an unsafe target are ignored.
+pub fn map_path(mappings: &[FolderMapping], path: &Path) -> PathBuf {
+    let top = match path.components().next() {
+        Some(Component::Normal(top)) if path.components().nth(1).is_some() => top.to_str(),
+        _ => None,
+    };
+    let usable = |m: &&FolderMapping| is_contained_relative_path(&m.target);
+    let folder_mapping = mappings.iter().filter(usable).find(|m| !m.folder.is_empty() && top == Some(m.folder.as_str()));
+    match folder_mapping {
+        Some(mapping) => mapping.target.join(path.strip_prefix(&mapping.folder).unwrap_or(path)),
+        None => match mappings.iter().filter(usable).find(|m| m.folder.is_empty()) {
+            Some(mapping) => mapping.target.join(path),
+            None => path.to_path_buf(),
+        },
+    }
+}
+
+/// Storage placing files at their mapped paths, or `None` when nothing is
+/// mapped and librqbit's default storage does.
+pub fn storage_factory(mappings: &[FolderMapping]) -> Option<BoxStorageFactory> {
+    let mappings: Vec<FolderMapping> = mappings.iter().filter(|m| is_contained_relative_path(&m.target)).cloned().collect();
+    (!mappings.is_empty()).then(|| MappedStorageFactory { mappings }.boxed())
+}
+
+#[derive(Clone)]
+struct MappedStorageFactory {
+    mappings: Vec<FolderMapping>,
+}
+
+impl MappedStorageFactory {
+    fn map_metadata(&self, metadata: &TorrentMetadata) -> TorrentMetadata {
+        let file_infos: FileInfos = metadata
+            .file_infos
+            .iter()
+            .map(|file| {
+                let mut file = file.clone();
+                file.relative_filename = map_path(&self.mappings, &file.relative_filename);
+                file
+            })
+            .collect();
+        TorrentMetadata {
+            info: metadata.info.clone(),
+            torrent_bytes: metadata.torrent_bytes.clone(),
+            info_bytes: metadata.info_bytes.clone(),
+            lengths: metadata.lengths,
+            file_infos,
+            name: metadata.name.clone(),
+        }
+    }
+}
+
+impl StorageFactory for MappedStorageFactory {
+    type Storage = MappedStorage;
+
+    fn create(&self, shared: &ManagedTorrentShared, metadata: &TorrentMetadata) -> Result<MappedStorage> {
+        let inner = FilesystemStorageFactory::default().create(shared, &self.map_metadata(metadata))?;
+        Ok(MappedStorage {
+            inner: Box::new(inner),
+            factory: self.clone(),
+        })
+    }
+
+    fn clone_box(&self) -> BoxStorageFactory {
+        self.clone().boxed()
+    }
+}
+
+struct MappedStorage {
+    inner: Box<dyn TorrentStorage>,
+    factory: MappedStorageFactory,
+}
+
+impl TorrentStorage for MappedStorage {
+    fn init(&mut self, shared: &ManagedTorrentShared, metadata: &TorrentMetadata) -> Result<()> {
+        self.inner.init(shared, &self.factory.map_metadata(metadata))
+    }
+
+    fn pread_exact(&self, file_id: usize, offset: u64, buf: &mut [u8]) -> Result<()> {
+        self.inner.pread_exact(file_id, offset, buf)
+    }
+
+    fn pwrite_all(&self, file_id: usize, offset: u64, buf: &[u8]) -> Result<()> {
+        self.inner.pwrite_all(file_id, offset, buf)
+    }
+
+    fn remove_file(&self, file_id: usize, filename: &Path) -> Result<()> {
+        self.inner.remove_file(file_id, &map_path(&self.factory.mappings, filename))
+    }
+
+    fn remove_directory_if_empty(&self, path: &Path) -> Result<()> {
+        self.inner.remove_directory_if_empty(&map_path(&self.factory.mappings, path))
+    }
+
+    fn ensure_file_length(&self, file_id: usize, length: u64) -> Result<()> {
+        self.inner.ensure_file_length(file_id, length)
+    }
+
+    fn take(&self) -> Result<Box<dyn TorrentStorage>> {
+        Ok(Box::new(MappedStorage {
+            inner: self.inner.take()?,
+            factory: self.factory.clone(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_path() {
+        let mappings = [
+            FolderMapping {
+                folder: "maps".to_string(),
+                target: PathBuf::from("@maps"),
+            },
+            FolderMapping {
+                folder: String::new(),
+                target: PathBuf::from("mods"),
+            },
+            FolderMapping {
+                folder: "escape".to_string(),
+                target: PathBuf::from("../outside"),
+            },
+        ];
+        assert_eq!(map_path(&mappings, Path::new("maps/altis/map.pbo")), PathBuf::from("@maps/altis/map.pbo"));
+        assert_eq!(map_path(&mappings, Path::new("@cba/addons/cba.pbo")), PathBuf::from("mods/@cba/addons/cba.pbo"));
+        // A file named like a mapped folder isn't inside it
+        assert_eq!(map_path(&mappings, Path::new("maps")), PathBuf::from("mods/maps"));
+        assert_eq!(map_path(&mappings, Path::new("escape/file")), PathBuf::from("mods/escape/file"));
+        assert_eq!(map_path(&[], Path::new("a/b")), PathBuf::from("a/b"));
+    }
+}
@@ -0,0 +1,122 @@
+// src/sync/memory.rs
+
+//! librqbit tunables trading memory and CPU for speed.
+//!
+//! By default writes go straight to disk and every downloaded piece is read
+//! back to verify its hash. On a NAS with little RAM these stay off; on a
+//! desktop a write buffer smooths out slow disks and a piece cache keeps
+//! recently written pieces in memory so verifying them needs no read. The
+//! write buffer and the number of concurrent hash checks are session-wide
+//! and only change when the session is created.
+//!
+//! With fastresume the session keeps which pieces were verified in
+//! `fastresume_dir` and restores its torrents on start, so a restart skips
+//! the full check. Restored torrents get the storage of `torrent` as the
+//! session default, since librqbit doesn't persist the storage.
+//!
+//! librqbit hashes pieces on the runtime's worker threads, checking each
+//! torrent on one of them, so `runtime` sizes the runtime for the hashing
+//! threads setting. By default it leaves half the cores free and checks one
+//! torrent at a time, so a check of a large repo stays in the background.
+
+use anyhow::Result;
+use librqbit::storage::filesystem::FilesystemStorageFactory;
+use librqbit::storage::middleware::write_through_cache::WriteThroughCacheStorageFactory;
+use librqbit::storage::{BoxStorageFactory, StorageFactory, StorageFactoryExt, TorrentStorage};
+use librqbit::{ManagedTorrentShared, SessionOptions, SessionPersistenceConfig, TorrentMetadata};
+use std::sync::Arc;
+
+use super::peers;
+use super::torrent;
+use super::types::SyncConfig;
+
+/// Torrents hash checked at once when not configured.
+pub const DEFAULT_CONCURRENT_CHECKS: usize = 1;
+
+/// Session options for `config`'s write buffer, hash check concurrency,
+/// fastresume and banned peers.
+pub fn session_options(config: &SyncConfig) -> SessionOptions {
+    SessionOptions {
+        // A zero sized buffer would be a zero capacity channel in librqbit
+        defer_writes_up_to: config.write_buffer_mb.filter(|&mb| mb > 0),
+        concurrent_init_limit: Some(config.concurrent_checks.filter(|&n| n > 0).unwrap_or(DEFAULT_CONCURRENT_CHECKS)),
+        fastresume: config.fastresume,
+        persistence: config.fastresume.then(|| SessionPersistenceConfig::Json {
+            folder: config.fastresume_dir.clone(),
+        }),
+        default_storage_factory: config.fastresume.then(|| torrent::storage_factory(config)).flatten(),
+        blocklist_url: peers::blocklist_url(config),
+        ..Default::default()
+    }
+}
+
+/// Threads hashing pieces: `hashing_threads` if set, otherwise half the
+/// cores.
+pub fn worker_threads(hashing_threads: Option<usize>) -> usize {
+    hashing_threads.filter(|&n| n > 0).unwrap_or_else(|| {
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+        (cores / 2).max(1)
+    })
+}
+
+/// Build the runtime a session runs on, with `worker_threads` threads.
+pub fn runtime(hashing_threads: Option<usize>) -> std::io::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(worker_threads(hashing_threads))
+        .enable_all()
+        .build()
+}
+
+/// Wrap `inner`, or the default filesystem storage when `None`, in an
+/// in-memory cache of up to `piece_cache_mb` MiB of written pieces. Returns
+/// `inner` unchanged when the cache is off.
+pub fn storage_factory(inner: Option<BoxStorageFactory>, piece_cache_mb: Option<u64>) -> Option<BoxStorageFactory> {
+    let Some(mb) = piece_cache_mb.filter(|&mb| mb > 0) else {
+        return inner;
+    };
+    let inner = inner.unwrap_or_else(|| FilesystemStorageFactory::default().boxed());
+    Some(WriteThroughCacheStorageFactory::new(mb * 1024 * 1024, SharedStorageFactory(Arc::new(inner))).boxed())
+}
+
+/// A boxed factory that can be cloned, as librqbit's cache requires.
+#[derive(Clone)]
+struct SharedStorageFactory(Arc<BoxStorageFactory>);
+
+impl StorageFactory for SharedStorageFactory {
+    type Storage = Box<dyn TorrentStorage>;
+
+    fn create(&self, shared: &ManagedTorrentShared, metadata: &TorrentMetadata) -> Result<Self::Storage> {
+        self.0.create(shared, metadata)
+    }
+
+    fn clone_box(&self) -> BoxStorageFactory {
+        self.clone().boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_means_off() {
+        let config = SyncConfig {
+            write_buffer_mb: Some(0),
+            concurrent_checks: Some(0),
+            ..Default::default()
+        };
+        let opts = session_options(&config);
+        assert_eq!(opts.defer_writes_up_to, None);
+        assert_eq!(opts.concurrent_init_limit, Some(DEFAULT_CONCURRENT_CHECKS));
+        assert!(storage_factory(None, Some(0)).is_none());
+        assert!(storage_factory(None, Some(64)).is_some());
+        assert!(opts.persistence.is_none());
+    }
+
+    #[test]
+    fn test_worker_threads() {
+        assert_eq!(worker_threads(Some(3)), 3);
+        assert!(worker_threads(Some(0)) >= 1);
+        assert!(worker_threads(None) >= 1);
+    }
+}
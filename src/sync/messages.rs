@@ -1,26 +1,135 @@
 use super::types::SyncConfig;
+use crate::sync::git::GitSourceStatus;
+use crate::sync::usage::DiskUsage;
+use crate::sync::consistency::Inconsistency;
+use crate::sync::error::SyncError;
+use crate::sync::health::RemoteAttempt;
+use crate::sync::history::RunTrigger;
+use crate::sync::peers::PeerInfo;
+use crate::sync::power::PowerRestriction;
+use crate::sync::stall::StallDiagnosis;
+use crate::sync::swarm::SwarmHealth;
+use crate::sync::trackers::TrackerInfo;
+use crate::sync::snapshot::{TorrentFile, TorrentSnapshot, TorrentSummary};
 use crate::sync::status::SyncStatus;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::sync::Arc;
 use std::collections::HashSet;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SyncCommand {
-    UpdateConfig(SyncConfig),
+    UpdateConfig(Box<SyncConfig>),
     VerifyFolder,
     DeleteFiles(Vec<PathBuf>),
     ApplyUpdate(Vec<u8>),
+    /// Go back to an older torrent version: make it the cached torrent,
+    /// apply it like an update and delete the files it doesn't have, unless
+    /// `read_only` is set.
+    RollBack(Vec<u8>),
     DownloadAndCompare(String),
     FixMissingFiles,
+    /// Pause (`true`) or resume (`false`) the managed torrent.
+    SetPaused(bool),
+    /// Change the speed limits in KB/s without re-adding the torrent;
+    /// `None` is unlimited.
+    SetSpeedLimits {
+        max_upload_speed: Option<u32>,
+        max_download_speed: Option<u32>,
+    },
+    /// Turn gaming mode on or off: while on, transfers are held to the
+    /// gaming caps of `SyncConfig::speed_limits`.
+    SetGamingMode(bool),
+    /// Re-add the active torrent without its fastresume state, so every
+    /// piece is hashed again.
+    ForceRecheck,
+    /// Stop managing the active torrent, keeping its downloaded files.
+    ForgetTorrent,
+    /// Stop managing the active torrent and delete its downloaded files and
+    /// the cached torrent versions.
+    RemoveTorrentAndData,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SyncEvent {
-    ManagedTorrentUpdate(Option<(usize, Arc<librqbit::TorrentStats>)>),
+    ManagedTorrentUpdate(Option<(usize, TorrentSnapshot)>),
     TorrentAdded(usize),
-    Error(String),
+    /// Files of the torrent that was just added.
+    TorrentFiles(Vec<TorrentFile>),
+    /// Trackers of the torrent that was just added.
+    Trackers(Vec<TrackerInfo>),
+    /// Live peers of the managed torrent. Sent on every status refresh.
+    Peers(Vec<PeerInfo>),
+    /// Swarm of the managed torrent from the latest tracker scrape, `None`
+    /// when no tracker answered.
+    Swarm(Option<SwarmHealth>),
+    /// The download stopped making progress, with what might be the cause;
+    /// `None` once it moves again.
+    Stalled(Option<StallDiagnosis>),
+    Error(SyncError),
     StatusUpdate(SyncStatus),
     ExtraFilesFound(Vec<PathBuf>),
-    RemoteUpdateFound(Vec<u8>),
+    /// Bytes of the changed remote torrent and what it contains.
+    RemoteUpdateFound(Vec<u8>, TorrentSummary),
     MissingFilesFound(HashSet<PathBuf>),
-}
\ No newline at end of file
+    /// The manager started a sync run; events up to the matching
+    /// `RunFinished` belong to it.
+    RunStarted(RunTrigger),
+    RunFinished,
+    /// RFC 3339 time of the next periodic remote check, `None` while no
+    /// checks are scheduled. Sent on every status refresh.
+    NextCheckAt(Option<String>),
+    /// URL that served the remote torrent in the latest check, which may be
+    /// a mirror of the configured one.
+    RemoteSource(String),
+    /// Outcome of one download of the torrent from a remote URL.
+    RemoteAttempt(RemoteAttempt),
+    /// Outcome of the latest pull of the git source.
+    GitSourceStatus(GitSourceStatus),
+    /// Message for the user that is not an error, e.g. from a script.
+    Notice(String),
+    /// Latest breakdown of the download folder's size.
+    DiskUsage(DiskUsage),
+    /// Problems found between the loaded torrent, the cached one and the
+    /// config; empty once they are resolved.
+    ConsistencyCheck(Vec<Inconsistency>),
+    /// Gaming mode was turned on (`true`) or off.
+    GamingMode(bool),
+    /// Transfers are paused or slowed for a metered connection or battery
+    /// power; `None` once that no longer applies.
+    PowerRestriction(Option<PowerRestriction>),
+    /// The manager has added the cached torrent of the last session, if
+    /// there was one, and handles commands from now on.
+    SessionRestored,
+}
+
+impl SyncEvent {
+    /// Variant name, for logs and external interfaces.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SyncEvent::ManagedTorrentUpdate(_) => "ManagedTorrentUpdate",
+            SyncEvent::TorrentAdded(_) => "TorrentAdded",
+            SyncEvent::TorrentFiles(_) => "TorrentFiles",
+            SyncEvent::Error(_) => "Error",
+            SyncEvent::StatusUpdate(_) => "StatusUpdate",
+            SyncEvent::ExtraFilesFound(_) => "ExtraFilesFound",
+            SyncEvent::RemoteUpdateFound(..) => "RemoteUpdateFound",
+            SyncEvent::MissingFilesFound(_) => "MissingFilesFound",
+            SyncEvent::RunStarted(_) => "RunStarted",
+            SyncEvent::RunFinished => "RunFinished",
+            SyncEvent::NextCheckAt(_) => "NextCheckAt",
+            SyncEvent::RemoteSource(_) => "RemoteSource",
+            SyncEvent::RemoteAttempt(_) => "RemoteAttempt",
+            SyncEvent::GitSourceStatus(_) => "GitSourceStatus",
+            SyncEvent::Notice(_) => "Notice",
+            SyncEvent::DiskUsage(_) => "DiskUsage",
+            SyncEvent::ConsistencyCheck(_) => "ConsistencyCheck",
+            SyncEvent::GamingMode(_) => "GamingMode",
+            SyncEvent::PowerRestriction(_) => "PowerRestriction",
+            SyncEvent::Peers(_) => "Peers",
+            SyncEvent::Swarm(_) => "Swarm",
+            SyncEvent::Stalled(_) => "Stalled",
+            SyncEvent::Trackers(_) => "Trackers",
+            SyncEvent::SessionRestored => "SessionRestored",
+        }
+    }
+}
@@ -1,14 +1,68 @@
 pub mod http;
 pub mod cleaner;
 pub mod torrent;
+pub mod trackers;
 pub mod status;
 pub mod messages;
+pub mod error;
 pub mod types;
 pub mod utils;
 pub mod local;
 pub mod remote;
 pub mod manager;
+pub mod journal;
+pub mod coalesce;
+pub mod snapshot;
+pub mod checking;
+pub mod lifetime;
+pub mod history;
+pub mod health;
+pub mod s3;
+pub mod seed_window;
+pub mod stall;
+pub mod swarm;
+pub mod sftp;
+pub mod webdav;
+pub mod credentials;
+pub mod git;
+pub mod rsync;
+pub mod ipfs;
+pub mod backend;
+pub mod hooks;
+pub mod extract;
+pub mod keys;
+pub mod mapping;
+pub mod filters;
+pub mod throttle;
+pub mod memory;
+pub mod usage;
+pub mod cache;
+pub mod quarantine;
+pub mod peers;
+pub mod power;
+pub mod preflight;
+pub mod version;
+pub mod recovery;
+pub mod store;
+pub mod consistency;
+pub mod create;
+pub mod diff;
+pub mod discord;
+pub mod telemetry;
+pub mod schedule;
+#[cfg(feature = "scripting")]
+pub mod scripting;
 
 pub use messages::{SyncCommand, SyncEvent};
+pub use error::SyncError;
+pub use snapshot::{FileStats, SyncStats, TorrentFile, TorrentSnapshot, TorrentSummary};
+pub use lifetime::{LifetimeCounter, LifetimeStats};
+pub use history::{RunRecord, RunTrigger, SyncHistory};
+pub use backend::{Backends, SourceContext, SyncSource, Transport};
+pub use git::GitSourceStatus;
+pub use usage::DiskUsage;
+pub use consistency::Inconsistency;
+pub use health::{EndpointHealth, Health, RemoteAttempt};
+pub use types::SyncConfig;
 pub use manager::run_sync_manager;
 pub use torrent::manage_torrent_task;
\ No newline at end of file
@@ -0,0 +1,104 @@
+// src/sync/peers.rs
+
+//! Peers of the managed torrent and banned peer addresses.
+//!
+//! librqbit only checks its blocklist, loaded when the session is created,
+//! so banned addresses are written to a blocklist file in the cache folder
+//! that the next session loads. librqbit 8 counts what each peer sent us
+//! but not what we uploaded to it.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+
+use super::types::SyncConfig;
+
+/// Blocklist file inside the cache folder.
+pub const BLOCKLIST_FILE_NAME: &str = "banned_peers.txt";
+
+/// A connected peer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerInfo {
+    /// `ip:port` as librqbit reports it.
+    pub addr: String,
+    pub downloaded: u64,
+    pub connections: u32,
+    pub errors: u32,
+}
+
+impl PeerInfo {
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.addr.parse::<SocketAddr>().ok().map(|addr| addr.ip())
+    }
+}
+
+/// Live peers of torrent `id`, those that sent the most first.
+pub fn snapshot(api: &librqbit::Api, id: usize) -> Vec<PeerInfo> {
+    let Ok(stats) = api.api_peer_stats(id.into(), Default::default()) else {
+        return Vec::new();
+    };
+    let mut peers: Vec<PeerInfo> = stats
+        .peers
+        .into_iter()
+        .map(|(addr, peer)| PeerInfo {
+            addr,
+            downloaded: peer.counters.fetched_bytes,
+            connections: peer.counters.connections + peer.counters.incoming_connections,
+            errors: peer.counters.errors,
+        })
+        .collect();
+    peers.sort_by(|a, b| b.downloaded.cmp(&a.downloaded).then_with(|| a.addr.cmp(&b.addr)));
+    peers
+}
+
+/// `ip` as a single-address range in librqbit's blocklist format.
+fn blocklist_line(ip: IpAddr) -> String {
+    format!("modsync-ban:{}-{}", ip, ip)
+}
+
+fn write_blocklist(path: &Path, banned: &[IpAddr]) -> Result<()> {
+    let mut text = String::from("# Peers banned in ModSync, rewritten on every start\n");
+    for ip in banned {
+        text.push_str(&blocklist_line(*ip));
+        text.push('\n');
+    }
+    std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Write the banned peers of `config` to the blocklist file and return its
+/// URL for the session, `None` when nobody is banned or there is no cache
+/// folder to put it in.
+pub fn blocklist_url(config: &SyncConfig) -> Option<String> {
+    let dir = config.cache_dir.as_ref().filter(|_| !config.banned_peers.is_empty())?;
+    let path = dir.join(BLOCKLIST_FILE_NAME);
+    if let Err(e) = std::fs::create_dir_all(dir).map_err(anyhow::Error::from).and_then(|()| write_blocklist(&path, &config.banned_peers)) {
+        eprintln!("Sync: Failed to write the peer blocklist: {:#}", e);
+        return None;
+    }
+    let path = std::path::absolute(&path).unwrap_or(path);
+    reqwest::Url::from_file_path(&path).ok().map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocklist_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut config = SyncConfig { cache_dir: Some(dir.path().to_path_buf()), ..SyncConfig::default() };
+        assert_eq!(blocklist_url(&config), None);
+
+        config.banned_peers = vec!["203.0.113.7".parse()?, "2001:db8::1".parse()?];
+        let url = blocklist_url(&config).unwrap();
+        assert!(url.starts_with("file://") && url.ends_with(BLOCKLIST_FILE_NAME));
+        let text = std::fs::read_to_string(dir.path().join(BLOCKLIST_FILE_NAME))?;
+        assert!(text.contains("modsync-ban:203.0.113.7-203.0.113.7\n"));
+        assert!(text.contains("modsync-ban:2001:db8::1-2001:db8::1\n"));
+
+        let peer = PeerInfo { addr: "[2001:db8::1]:6881".to_string(), downloaded: 0, connections: 1, errors: 0 };
+        assert_eq!(peer.ip(), Some("2001:db8::1".parse()?));
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+// src/sync/power.rs
+
+//! Metered connections and battery power.
+//!
+//! Each can be set to pause the torrent or hold it to low-power speed
+//! limits while it lasts. The manager polls the state of the computer and
+//! reports the restriction in force, so the UI can say why syncing is
+//! paused or slow.
+
+use serde::{Deserialize, Serialize};
+
+/// Upload cap in KB/s in low-power mode, when none is configured.
+pub const LOW_POWER_UPLOAD_SPEED: u32 = 100;
+/// Download cap in KB/s in low-power mode, when none is configured.
+pub const LOW_POWER_DOWNLOAD_SPEED: u32 = 500;
+
+/// What to do while a condition holds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PowerAction {
+    #[default]
+    Ignore,
+    /// Hold transfers to the low-power speed limits.
+    LowPower,
+    /// Pause the torrent.
+    Pause,
+}
+
+/// Why transfers are restricted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerReason {
+    Metered,
+    Battery,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerRestriction {
+    pub action: PowerAction,
+    pub reason: PowerReason,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerConfig {
+    pub on_metered: PowerAction,
+    pub on_battery: PowerAction,
+    /// Low-power upload cap in KB/s, `LOW_POWER_UPLOAD_SPEED` when unset.
+    pub upload_speed: Option<u32>,
+    /// Low-power download cap in KB/s, `LOW_POWER_DOWNLOAD_SPEED` when unset.
+    pub download_speed: Option<u32>,
+}
+
+/// Conditions of the computer; each is `false` when it can't be told.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerState {
+    pub metered: bool,
+    pub on_battery: bool,
+}
+
+impl PowerConfig {
+    /// Whether anything is configured, so the state has to be polled.
+    pub fn is_active(&self) -> bool {
+        self.on_metered != PowerAction::Ignore || self.on_battery != PowerAction::Ignore
+    }
+
+    /// The strictest restriction for `state`, if any.
+    pub fn restriction(&self, state: PowerState) -> Option<PowerRestriction> {
+        [(state.metered, self.on_metered, PowerReason::Metered), (state.on_battery, self.on_battery, PowerReason::Battery)]
+            .into_iter()
+            .filter(|(holds, action, _)| *holds && *action != PowerAction::Ignore)
+            .map(|(_, action, reason)| PowerRestriction { action, reason })
+            .max_by_key(|restriction| restriction.action)
+    }
+
+    /// `limits` held to the low-power caps.
+    pub fn capped(&self, (upload, download): (Option<u32>, Option<u32>)) -> (Option<u32>, Option<u32>) {
+        let capped = |limit: Option<u32>, cap: u32| Some(limit.map_or(cap, |l| l.min(cap)));
+        (
+            capped(upload, self.upload_speed.unwrap_or(LOW_POWER_UPLOAD_SPEED)),
+            capped(download, self.download_speed.unwrap_or(LOW_POWER_DOWNLOAD_SPEED)),
+        )
+    }
+}
+
+/// Read the state of the computer. What the platform can't tell reads as
+/// `false`.
+pub async fn detect() -> PowerState {
+    PowerState {
+        metered: platform::metered().await.unwrap_or(false),
+        on_battery: platform::on_battery().await.unwrap_or(false),
+    }
+}
+
+/// Output of `program` with `args`, `None` if it can't be run or fails.
+async fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = tokio::process::Command::new(program);
+    command.args(args).stdin(std::process::Stdio::null()).kill_on_drop(true);
+    #[cfg(windows)]
+    {
+        // CREATE_NO_WINDOW, so polling doesn't flash a console
+        command.creation_flags(0x0800_0000);
+    }
+    let output = command.output().await.ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `NetworkCostType` printed by the PowerShell query on Windows: `Fixed` and
+/// `Variable` plans are metered.
+pub fn parse_network_cost(output: &str) -> Option<bool> {
+    match output.trim() {
+        "Unrestricted" => Some(false),
+        "Fixed" | "Variable" => Some(true),
+        _ => None,
+    }
+}
+
+/// `pmset -g batt` output on macOS, whose first line names the power source.
+pub fn parse_pmset(output: &str) -> Option<bool> {
+    let first = output.lines().next()?;
+    if first.contains("'Battery Power'") {
+        Some(true)
+    } else if first.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// NetworkManager's `Metered` property as printed by `busctl get-property`:
+/// `u 1` (yes) and `u 3` (guessed yes) are metered.
+pub fn parse_nm_metered(output: &str) -> Option<bool> {
+    match output.trim().strip_prefix("u ")? {
+        "1" | "3" => Some(true),
+        "2" | "4" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    //! `GetSystemPowerStatus` for the power source and the WinRT connection
+    //! profile, through PowerShell, for the network cost.
+
+    use super::{command_output, parse_network_cost};
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+
+    const COST_QUERY: &str = "[void][Windows.Networking.Connectivity.NetworkInformation, Windows.Networking.Connectivity, ContentType = WindowsRuntime]; \
+        $p = [Windows.Networking.Connectivity.NetworkInformation]::GetInternetConnectionProfile(); \
+        if ($p) { $p.GetConnectionCost().NetworkCostType }";
+
+    pub async fn on_battery() -> Option<bool> {
+        let mut status = SystemPowerStatus::default();
+        // Safety: the call only writes the struct it is handed
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            return None;
+        }
+        // 0 is offline, 1 online, 255 unknown
+        match status.ac_line_status {
+            0 => Some(true),
+            1 => Some(false),
+            _ => None,
+        }
+    }
+
+    pub async fn metered() -> Option<bool> {
+        let output = command_output("powershell", &["-NoProfile", "-NonInteractive", "-Command", COST_QUERY]).await?;
+        parse_network_cost(&output)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    //! `pmset` for the power source. macOS only tells apps about expensive
+    //! networks through `NWPathMonitor`, so connections never read as
+    //! metered.
+
+    use super::{command_output, parse_pmset};
+
+    pub async fn on_battery() -> Option<bool> {
+        parse_pmset(&command_output("pmset", &["-g", "batt"]).await?)
+    }
+
+    pub async fn metered() -> Option<bool> {
+        None
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    //! The kernel's power supplies for the power source and NetworkManager
+    //! over D-Bus for the connection.
+
+    use super::{command_output, parse_nm_metered};
+
+    pub async fn on_battery() -> Option<bool> {
+        let mut on_battery = None;
+        for entry in std::fs::read_dir("/sys/class/power_supply").ok()?.filter_map(|e| e.ok()) {
+            let read = |name: &str| std::fs::read_to_string(entry.path().join(name)).map(|s| s.trim().to_string()).unwrap_or_default();
+            match read("type").as_str() {
+                "Mains" if read("online") == "1" => return Some(false),
+                "Battery" => on_battery = Some(on_battery.unwrap_or(false) || read("status") == "Discharging"),
+                _ => {}
+            }
+        }
+        on_battery
+    }
+
+    pub async fn metered() -> Option<bool> {
+        let output = command_output(
+            "busctl",
+            &[
+                "get-property",
+                "org.freedesktop.NetworkManager",
+                "/org/freedesktop/NetworkManager",
+                "org.freedesktop.NetworkManager",
+                "Metered",
+            ],
+        )
+        .await?;
+        parse_nm_metered(&output)
+    }
+}
+
+#[cfg(not(any(windows, unix)))]
+mod platform {
+    pub async fn on_battery() -> Option<bool> {
+        None
+    }
+
+    pub async fn metered() -> Option<bool> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strictest_restriction_wins() {
+        let config = PowerConfig {
+            on_metered: PowerAction::LowPower,
+            on_battery: PowerAction::Pause,
+            ..PowerConfig::default()
+        };
+        let both = PowerState { metered: true, on_battery: true };
+        assert_eq!(config.restriction(both), Some(PowerRestriction { action: PowerAction::Pause, reason: PowerReason::Battery }));
+        let metered = PowerState { metered: true, on_battery: false };
+        assert_eq!(config.restriction(metered).map(|r| r.reason), Some(PowerReason::Metered));
+        assert_eq!(config.restriction(PowerState::default()), None);
+        assert_eq!(PowerConfig::default().restriction(both), None);
+
+        assert_eq!(config.capped((None, Some(50))), (Some(LOW_POWER_UPLOAD_SPEED), Some(50)));
+    }
+
+    #[test]
+    fn test_parse_platform_output() {
+        assert_eq!(parse_pmset("Now drawing from 'Battery Power'\n -InternalBattery-0 80%"), Some(true));
+        assert_eq!(parse_pmset("Now drawing from 'AC Power'"), Some(false));
+        assert_eq!(parse_nm_metered("u 3\n"), Some(true));
+        assert_eq!(parse_nm_metered("u 0"), None);
+        assert_eq!(parse_network_cost("Variable\r\n"), Some(true));
+        assert_eq!(parse_network_cost(""), None);
+    }
+}
@@ -0,0 +1,104 @@
+// src/sync/preflight.rs
+
+//! Checks of the download folder before anything is synced into it.
+//!
+//! The manager runs them on startup and whenever the download path changes,
+//! so a folder the user can't write to, such as one under Program Files, is
+//! reported right away instead of failing the sync halfway through.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+
+use super::error::SyncError;
+use super::messages::SyncEvent;
+use super::utils::send_sync_event;
+
+/// Folders that hold the OS or installed programs; syncing into them
+/// would let the cleaner loose on system files.
+#[cfg(windows)]
+const SYSTEM_FOLDERS: &[&str] = &["C:\\Windows", "C:\\Program Files", "C:\\Program Files (x86)", "C:\\ProgramData"];
+#[cfg(not(windows))]
+const SYSTEM_FOLDERS: &[&str] = &[
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib64", "/proc", "/sbin", "/sys", "/usr", "/var", "/System", "/Library",
+];
+
+/// Whether `path` is a drive root or one of `SYSTEM_FOLDERS`.
+pub fn is_system_folder(path: &Path) -> bool {
+    if path.parent().is_none() {
+        return true;
+    }
+    SYSTEM_FOLDERS.iter().any(|folder| {
+        #[cfg(windows)]
+        let matches = path.to_string_lossy().to_lowercase().starts_with(&folder.to_lowercase());
+        #[cfg(not(windows))]
+        let matches = path.starts_with(folder);
+        matches
+    })
+}
+
+/// Create, write and delete a file in `dir`, which has to exist.
+pub fn probe_write(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".modsync-write-test-{}", std::process::id()));
+    let written = std::fs::File::create(&probe).and_then(|mut file| file.write_all(b"modsync"));
+    let removed = std::fs::remove_file(&probe);
+    written.and(removed)
+}
+
+/// Check that files can be written to and deleted from `download_path`,
+/// or from its closest existing parent if it hasn't been created yet.
+pub fn check_download_path(download_path: &Path) -> Result<(), SyncError> {
+    let path = std::path::absolute(download_path).unwrap_or_else(|_| PathBuf::from(download_path));
+    if is_system_folder(&path) {
+        return Err(SyncError::Config(format!("{} is a system folder, choose another download folder", path.display())));
+    }
+    let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+        return Err(SyncError::Config(format!("{} can't be created", path.display())));
+    };
+    if !existing.is_dir() {
+        return Err(SyncError::Config(format!("{} is not a folder", existing.display())));
+    }
+    probe_write(existing).map_err(|e| {
+        let message = format!("Can't write to {}: {}", existing.display(), e);
+        SyncError::caused_by(SyncError::PermissionDenied, message, &e)
+    })
+}
+
+/// Check the download folder and report a problem to the UI. Nothing is
+/// checked until a download path is configured.
+pub fn check_and_report(download_path: &Path, ui_tx: &mpsc::UnboundedSender<SyncEvent>) {
+    if download_path.as_os_str().is_empty() {
+        return;
+    }
+    match check_download_path(download_path) {
+        Ok(()) => println!("Sync: Download folder {} is writable", download_path.display()),
+        Err(e) => {
+            eprintln!("Sync: {}", e);
+            send_sync_event(ui_tx, SyncEvent::Error(e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_download_path() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(check_download_path(&dir.path().join("new/mods")), Ok(()));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"").unwrap();
+        assert!(matches!(check_download_path(&file.join("mods")), Err(SyncError::Config(_))));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_system_folders() {
+        assert!(matches!(check_download_path(Path::new("/usr/share/mods")), Err(SyncError::Config(_))));
+        assert!(is_system_folder(Path::new("/")));
+        assert!(!is_system_folder(Path::new("/home/user/mods")));
+    }
+}
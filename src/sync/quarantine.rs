@@ -0,0 +1,215 @@
+// src/sync/quarantine.rs
+
+//! Quarantine for deleted extra files.
+//!
+//! With quarantine on, deleting extras moves them into a folder named after
+//! the time of the deletion inside `quarantine` in the cache folder, keeping
+//! their paths relative to the download folder so a batch can be restored
+//! as it was. Batches older than the configured number of days are purged
+//! whenever files are quarantined.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::types::SyncConfig;
+
+/// Folder inside the cache folder holding the batches.
+pub const QUARANTINE_DIR_NAME: &str = "quarantine";
+
+/// Days a batch is kept when not configured.
+pub const DEFAULT_QUARANTINE_DAYS: u32 = 14;
+
+/// Batch folder names, in UTC.
+const BATCH_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Files moved out of the download folder at one time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuarantineBatch {
+    pub dir: PathBuf,
+    pub quarantined_at: DateTime<Utc>,
+    /// Paths relative to the download folder.
+    pub files: Vec<PathBuf>,
+    pub size: u64,
+}
+
+pub struct Quarantine {
+    dir: PathBuf,
+}
+
+impl Quarantine {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The quarantine of `config`, when it is on and there is a cache folder.
+    pub fn for_config(config: &SyncConfig) -> Option<Self> {
+        let cache_dir = config.cache_dir.as_ref().filter(|_| config.quarantine)?;
+        Some(Self::new(cache_dir.join(QUARANTINE_DIR_NAME)))
+    }
+
+    /// Move `files`, full paths inside `download_path`, into a new batch.
+    /// Returns the batch folder and an error per file that couldn't be moved.
+    pub fn quarantine(&self, download_path: &Path, files: &[PathBuf], now: DateTime<Utc>) -> (PathBuf, Vec<String>) {
+        let batch = self.dir.join(now.format(BATCH_FORMAT).to_string());
+        let mut errors = Vec::new();
+        for file in files {
+            let result = file
+                .strip_prefix(download_path)
+                .map_err(|_| anyhow!("{} is outside the download folder", file.display()))
+                .and_then(|relative| move_file(file, &batch.join(relative)));
+            if let Err(e) = result {
+                errors.push(format!("{:#}", e));
+            }
+        }
+        (batch, errors)
+    }
+
+    /// Batches, newest first.
+    pub fn batches(&self) -> Result<Vec<QuarantineBatch>> {
+        let read_dir = match std::fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to list {}", self.dir.display())),
+        };
+        let mut batches = Vec::new();
+        for entry in read_dir.filter_map(|e| e.ok()) {
+            let dir = entry.path();
+            let Some(quarantined_at) = dir
+                .file_name()
+                .and_then(|name| NaiveDateTime::parse_from_str(&name.to_string_lossy(), BATCH_FORMAT).ok())
+            else {
+                continue;
+            };
+            let mut batch = QuarantineBatch {
+                quarantined_at: quarantined_at.and_utc(),
+                files: Vec::new(),
+                size: 0,
+                dir: dir.clone(),
+            };
+            for file in WalkDir::new(&dir).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+                if let Ok(relative) = file.path().strip_prefix(&dir) {
+                    batch.files.push(relative.to_path_buf());
+                    batch.size += file.metadata().map_or(0, |m| m.len());
+                }
+            }
+            batches.push(batch);
+        }
+        batches.sort_by_key(|b| std::cmp::Reverse(b.quarantined_at));
+        Ok(batches)
+    }
+
+    /// Move the files of `batch` back into `download_path` and remove the
+    /// batch. Files that exist there again are left in the batch.
+    pub fn restore(&self, batch: &QuarantineBatch, download_path: &Path) -> Result<usize> {
+        let mut restored = 0;
+        for relative in &batch.files {
+            let dest = download_path.join(relative);
+            if dest.exists() {
+                println!("Sync: Not restoring {}, it exists again", dest.display());
+                continue;
+            }
+            move_file(&batch.dir.join(relative), &dest)?;
+            restored += 1;
+        }
+        if restored == batch.files.len() {
+            std::fs::remove_dir_all(&batch.dir).with_context(|| format!("Failed to remove {}", batch.dir.display()))?;
+        }
+        Ok(restored)
+    }
+
+    /// Delete `batch` for good.
+    pub fn discard(&self, batch: &QuarantineBatch) -> Result<()> {
+        std::fs::remove_dir_all(&batch.dir).with_context(|| format!("Failed to remove {}", batch.dir.display()))
+    }
+
+    /// Delete batches quarantined more than `days` days before `now`.
+    pub fn purge(&self, days: u32, now: DateTime<Utc>) -> Result<Vec<PathBuf>> {
+        let cutoff = now - chrono::Duration::days(i64::from(days));
+        let mut purged = Vec::new();
+        for batch in self.batches()? {
+            if batch.quarantined_at < cutoff {
+                self.discard(&batch)?;
+                purged.push(batch.dir);
+            }
+        }
+        Ok(purged)
+    }
+}
+
+/// Purge the expired batches of `config`'s quarantine. Failures are logged,
+/// as a leftover batch only costs disk space.
+pub fn purge_expired(config: &SyncConfig) {
+    let Some(quarantine) = Quarantine::for_config(config) else {
+        return;
+    };
+    match quarantine.purge(config.quarantine_days.unwrap_or(DEFAULT_QUARANTINE_DAYS), Utc::now()) {
+        Ok(purged) => {
+            for dir in purged {
+                println!("Sync: Purged quarantined files {}", dir.display());
+            }
+        }
+        Err(e) => eprintln!("Sync: Failed to purge the quarantine: {:#}", e),
+    }
+}
+
+/// Move `from` to `to`, creating `to`'s folder. Falls back to copying when
+/// the two are on different volumes.
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    if std::fs::rename(from, to).is_err() {
+        std::fs::copy(from, to).with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))?;
+        std::fs::remove_file(from).with_context(|| format!("Failed to remove {}", from.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quarantine_and_restore() -> Result<()> {
+        let downloads = tempfile::tempdir()?;
+        let cache = tempfile::tempdir()?;
+        let extra = downloads.path().join("@mod/addons/old.pbo");
+        std::fs::create_dir_all(extra.parent().unwrap())?;
+        std::fs::write(&extra, b"old")?;
+
+        let quarantine = Quarantine::new(cache.path().join(QUARANTINE_DIR_NAME));
+        let (_, errors) = quarantine.quarantine(downloads.path(), std::slice::from_ref(&extra), Utc::now());
+        assert!(errors.is_empty());
+        assert!(!extra.exists());
+
+        let batches = quarantine.batches()?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].files, [PathBuf::from("@mod/addons/old.pbo")]);
+        assert_eq!(batches[0].size, 3);
+
+        assert_eq!(quarantine.restore(&batches[0], downloads.path())?, 1);
+        assert_eq!(std::fs::read(&extra)?, b"old");
+        assert!(quarantine.batches()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge_only_old_batches() -> Result<()> {
+        let downloads = tempfile::tempdir()?;
+        let cache = tempfile::tempdir()?;
+        let quarantine = Quarantine::new(cache.path());
+        let now = Utc::now();
+        for (name, age) in [("a", 20), ("b", 1)] {
+            let file = downloads.path().join(name);
+            std::fs::write(&file, name)?;
+            quarantine.quarantine(downloads.path(), &[file], now - chrono::Duration::days(age));
+        }
+        assert_eq!(quarantine.purge(14, now)?.len(), 1);
+        let batches = quarantine.batches()?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].files, [PathBuf::from("b")]);
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+// src/sync/recovery.rs
+
+//! Recovery from a session that didn't shut down cleanly.
+//!
+//! A running client holds a lock on a file in the cache folder and writes
+//! what it is into it, emptying the file when it shuts down. Finding the
+//! file unlocked but not empty on start means the previous session crashed
+//! or was killed mid-write, so its fastresume state can't be trusted: it is
+//! discarded so the cached torrent is checked in full, and the folder is
+//! verified once the torrent is back.
+
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use super::types::SyncConfig;
+
+/// Lock file inside the cache folder.
+pub const LOCK_FILE_NAME: &str = "session.lock";
+
+/// The lock of the running session, held until the process exits.
+#[derive(Debug)]
+pub struct SessionLock {
+    file: File,
+    path: PathBuf,
+    /// What the previous session left in the lock when it didn't shut down
+    /// cleanly.
+    pub previous: Option<String>,
+}
+
+impl SessionLock {
+    /// Lock the session in `dir`. Returns `None` when another session holds
+    /// the lock.
+    pub fn acquire(dir: &Path) -> Result<Option<Self>> {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(LOCK_FILE_NAME);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Error(e)) => return Err(e).with_context(|| format!("Failed to lock {}", path.display())),
+        }
+        let mut previous = String::new();
+        file.read_to_string(&mut previous)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(file, "pid {} started {}", std::process::id(), chrono::Utc::now().to_rfc3339())
+            .and_then(|()| file.sync_all())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Some(Self {
+            file,
+            path,
+            previous: Some(previous).filter(|previous| !previous.trim().is_empty()),
+        }))
+    }
+
+    /// Mark the session as shut down cleanly. The lock itself is released
+    /// when the process exits.
+    pub fn release(&self) {
+        if let Err(e) = self.file.set_len(0) {
+            eprintln!("Sync: Failed to clear {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Delete the fastresume state of `config`, returning whether there was
+/// any, so the session starts without it.
+pub fn discard_fastresume(config: &SyncConfig) -> Result<bool> {
+    let Some(dir) = config.fastresume_dir.as_ref().filter(|dir| config.fastresume && dir.exists()) else {
+        return Ok(false);
+    };
+    std::fs::remove_dir_all(dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unclean_shutdown_is_detected() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let first = SessionLock::acquire(dir.path())?.unwrap();
+        assert_eq!(first.previous, None);
+        assert!(SessionLock::acquire(dir.path())?.is_none());
+
+        // Dropped without releasing, like a crashed process
+        drop(first);
+        let second = SessionLock::acquire(dir.path())?.unwrap();
+        assert!(second.previous.as_deref().is_some_and(|p| p.starts_with("pid ")));
+
+        second.release();
+        drop(second);
+        assert_eq!(SessionLock::acquire(dir.path())?.unwrap().previous, None);
+        Ok(())
+    }
+}
@@ -1,22 +1,33 @@
 //! Operations related to the remote torrent state
 
 use super::types::SyncConfig;
+use anyhow::{anyhow, Result};
 use reqwest;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 use crate::sync::status::SyncStatus;
 
+use super::cache;
+use super::health::RemoteAttempt;
+use super::backend::{Backends, SourceContext};
 use super::local::refresh_managed_torrent_status_event;
+use super::error::SyncError;
+use super::schedule::RetryAfterError;
 use super::messages::SyncEvent;
+use super::snapshot::TorrentSummary;
 use super::types::{LocalTorrentState, RemoteTorrentState, SyncState};
-use super::utils::{download_torrent, calculate_torrent_hash, get_local_torrent_hash, send_sync_status_event};
+use super::utils::{calculate_torrent_hash, get_local_torrent_hash, send_sync_event, send_sync_status_event};
 use super::manage_torrent_task;
+use super::torrent::validate_torrent;
+use super::version;
 
 pub async fn apply_remote_update(
     config: &SyncConfig,
     state: &mut SyncState,
     api: &librqbit::Api,
     ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    backends: &Backends,
     torrent_content: Vec<u8>,
 ) -> bool {
     send_sync_status_event(ui_tx, SyncStatus::UpdatingTorrent);
@@ -30,6 +41,7 @@ pub async fn apply_remote_update(
         config,
         api,
         ui_tx,
+        backends,
         current_id_to_forget,
         torrent_content,
     )
@@ -54,8 +66,12 @@ pub async fn apply_remote_update(
         Err(e) => {
             let err_msg = format!("Sync error managing torrent: {}", e);
             eprintln!("Sync: {}", err_msg);
-            let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+            let _ = ui_tx.send(SyncEvent::Error(SyncError::caused_by(SyncError::Engine, err_msg.clone(), &*e)));
             send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
+            // A refused torrent leaves the current one running
+            if current_id_to_forget.is_some_and(|id| api.api_torrent_details(id.into()).is_err()) {
+                state.local = LocalTorrentState::NotLoaded;
+            }
             false
         }
     }
@@ -66,22 +82,23 @@ pub async fn direct_download_and_compare(
     state: &mut SyncState,
     _api: &librqbit::Api,
     ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    backends: &Backends,
     http_client: &reqwest::Client,
 ) {
-    if config.torrent_url.is_empty() {
+    let urls = order_by_last_good(config.torrent_urls(), state.last_good_url.as_deref());
+    if urls.is_empty() {
         println!("Sync: No remote URL configured, skipping direct download.");
         send_sync_status_event(ui_tx, SyncStatus::Idle);
         return;
     }
 
-    println!(
-        "Sync: Directly downloading torrent from {}...",
-        config.torrent_url
-    );
     send_sync_status_event(ui_tx, SyncStatus::CheckingRemote);
 
-    match download_torrent(&config.torrent_url, http_client).await {
-        Ok(remote_torrent) => {
+    state.retry_after = None;
+    match download_from_any(&urls, config, backends, http_client, ui_tx, &mut state.retry_after).await {
+        Ok((url, remote_torrent)) => {
+            state.last_good_url = Some(url.to_string());
+            send_sync_event(ui_tx, SyncEvent::RemoteSource(url.to_string()));
             println!(
                 "Sync: Downloaded remote torrent successfully ({} bytes)",
                 remote_torrent.len()
@@ -90,71 +107,151 @@ pub async fn direct_download_and_compare(
             let remote_hash = calculate_torrent_hash(&remote_torrent);
             println!("Sync: Remote torrent hash: {}", remote_hash);
 
-            let local_hash_result = get_local_torrent_hash(config.cached_torrent_path.clone()).await;
-
-            match local_hash_result {
-                Ok(Some(local_hash)) => {
-                    println!("Sync: Local torrent hash: {}", local_hash);
-
-                    if remote_hash != local_hash {
-                        println!(
-                            "Sync: Torrent has changed! Remote hash different from local hash."
-                        );
-
-                        if let Some(cache_path) = &config.cached_torrent_path {
-                            println!("Sync: Writing downloaded torrent to cache: {}", cache_path.display());
-                            if let Err(e) = tokio::fs::write(&cache_path, &remote_torrent).await {
-                                eprintln!("Sync: Failed to write cached torrent file: {}", e);
-                            }
-                        }
-
-                        state.remote = RemoteTorrentState::UpdateAvailable;
-
-                        if let Err(e) = ui_tx.send(SyncEvent::RemoteUpdateFound(remote_torrent)) {
-                            let err_msg = format!("Failed to send update notification to UI: {}", e);
-                            eprintln!("Sync: {}", err_msg);
-                            send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
-                        } else {
-                            send_sync_status_event(ui_tx, SyncStatus::RemoteChanged);
-                        }
-                    } else {
-                        println!("Sync: Torrent is unchanged. Local and remote hashes match.");
-                        send_sync_status_event(ui_tx, SyncStatus::Idle);
-                    }
-                }
-                Ok(None) => {
-                    println!("Sync: No local torrent found. This is a new torrent.");
-
-                    if let Some(cache_path) = &config.cached_torrent_path {
-                        println!("Sync: Writing downloaded torrent to cache: {}", cache_path.display());
-                        if let Err(e) = tokio::fs::write(&cache_path, &remote_torrent).await {
-                            eprintln!("Sync: Failed to write cached torrent file: {}", e);
-                        }
-                    }
-
-                    state.remote = RemoteTorrentState::UpdateAvailable;
-
-                    if let Err(e) = ui_tx.send(SyncEvent::RemoteUpdateFound(remote_torrent)) {
-                        let err_msg = format!("Failed to send update notification to UI: {}", e);
-                        eprintln!("Sync: {}", err_msg);
-                        send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
-                    } else {
-                        send_sync_status_event(ui_tx, SyncStatus::RemoteChanged);
-                    }
-                }
+            let local_hash = match get_local_torrent_hash(config.cached_torrent_path.clone()).await {
+                Ok(local_hash) => local_hash,
                 Err(e) => {
                     let err_msg = format!("Failed to get local torrent hash: {}", e);
                     eprintln!("Sync: {}", err_msg);
-                    let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+                    let _ = ui_tx.send(SyncEvent::Error(SyncError::caused_by(SyncError::Other, err_msg.clone(), &*e)));
                     send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
+                    return;
                 }
+            };
+            match &local_hash {
+                Some(local_hash) => println!("Sync: Local torrent hash: {}", local_hash),
+                None => println!("Sync: No local torrent found. This is a new torrent."),
+            }
+
+            // The source that served the torrent decides what counts as an update
+            let is_update = backends
+                .source_for(url)
+                .map(|source| source.is_update(&remote_torrent, local_hash.as_deref()))
+                .unwrap_or(true);
+
+            if is_update && config.pinned {
+                println!("Sync: Torrent has changed, but the version is pinned. Ignoring the update.");
+                state.remote = RemoteTorrentState::Checked;
+                send_sync_status_event(ui_tx, SyncStatus::Idle);
+            } else if is_update {
+                println!("Sync: Torrent has changed! Remote hash different from local hash.");
+
+                // The cached torrent stays the applied one until the user
+                // applies this, so a restart or recheck can't slip it in
+                cache::remember(config, &remote_torrent);
+
+                state.remote = RemoteTorrentState::UpdateAvailable;
+
+                let summary = TorrentSummary::from_torrent(&remote_torrent).unwrap_or_default();
+                println!(
+                    "Sync: Update is {:?}: {} bytes in {} files, infohash {}",
+                    summary.name, summary.total_bytes, summary.file_count, summary.info_hash
+                );
+                if let Err(e) = ui_tx.send(SyncEvent::RemoteUpdateFound(remote_torrent, summary)) {
+                    let err_msg = format!("Failed to send update notification to UI: {}", e);
+                    eprintln!("Sync: {}", err_msg);
+                    send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
+                } else {
+                    send_sync_status_event(ui_tx, SyncStatus::RemoteChanged);
+                }
+            } else {
+                println!("Sync: Torrent is unchanged. Local and remote hashes match.");
+                send_sync_status_event(ui_tx, SyncStatus::Idle);
             }
         }
         Err(e) => {
             let err_msg = format!("Failed to download remote torrent: {}", e);
             eprintln!("Sync: {}", err_msg);
-            let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+            let _ = ui_tx.send(SyncEvent::Error(SyncError::caused_by(SyncError::Network, err_msg.clone(), &*e)));
             send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
         }
     }
-}
\ No newline at end of file
+}
+
+/// `urls` with `last_good` moved to the front, so a mirror that worked is
+/// preferred over a primary that was down last time.
+fn order_by_last_good<'a>(mut urls: Vec<&'a str>, last_good: Option<&str>) -> Vec<&'a str> {
+    if let Some(pos) = last_good.and_then(|good| urls.iter().position(|url| *url == good)) {
+        let url = urls.remove(pos);
+        urls.insert(0, url);
+    }
+    urls
+}
+
+/// Download the torrent from the first of `urls` that serves it, returning
+/// that URL with the content. Every attempt is reported as a `RemoteAttempt`.
+/// The version for URLs with `{latest}` is fetched once, before the first
+/// of them is tried. The longest `Retry-After` of the failed URLs is kept in
+/// `retry_after`.
+async fn download_from_any<'a>(
+    urls: &[&'a str],
+    config: &SyncConfig,
+    backends: &Backends,
+    http_client: &reqwest::Client,
+    ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    retry_after: &mut Option<Duration>,
+) -> Result<(&'a str, Vec<u8>)> {
+    let mut errors = Vec::new();
+    let mut latest: Option<Result<String, String>> = None;
+    for url in urls {
+        let started = Instant::now();
+        let version = match &latest {
+            _ if !version::is_templated(url) => None,
+            Some(fetched) => Some(fetched.clone()),
+            None => {
+                let fetched = version::fetch_latest(http_client, &config.version_url)
+                    .await
+                    .map_err(|e| format!("{:#}", e));
+                if let Ok(version) = &fetched {
+                    println!("Sync: Latest version is {}", version);
+                }
+                latest = Some(fetched.clone());
+                Some(fetched)
+            }
+        };
+        let result = match version {
+            Some(Err(e)) => Err(anyhow!(e)),
+            Some(Ok(version)) => fetch_torrent(&version::resolve(url, &version), config, backends, http_client).await,
+            None => fetch_torrent(url, config, backends, http_client).await,
+        };
+        // Attempts are reported under the configured URL, which is what the
+        // health of a source is kept for
+        send_sync_event(ui_tx, SyncEvent::RemoteAttempt(RemoteAttempt {
+            url: url.to_string(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+        }));
+        match result {
+            Ok(content) => return Ok((url, content)),
+            Err(e) => {
+                eprintln!("Sync: Failed to download torrent from {}: {:#}", url, e);
+                if let Some(error) = e.downcast_ref::<RetryAfterError>() {
+                    *retry_after = (*retry_after).max(Some(error.after));
+                }
+                errors.push(format!("{}: {:#}", url, e));
+            }
+        }
+    }
+    Err(anyhow!(errors.join("; ")))
+}
+
+async fn fetch_torrent(url: &str, config: &SyncConfig, backends: &Backends, http_client: &reqwest::Client) -> Result<Vec<u8>> {
+    println!("Sync: Directly downloading torrent from {}...", url);
+    let content = backends.source_for(url)?.fetch(url, SourceContext { config, http_client }).await?;
+    // An error page served in place of the torrent fails this URL too
+    validate_torrent(&content)?;
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_good_url_is_tried_first() {
+        let urls = vec!["http://a", "http://b", "http://c"];
+        assert_eq!(order_by_last_good(urls.clone(), Some("http://c")), ["http://c", "http://a", "http://b"]);
+        // A last-good URL that is no longer configured is ignored
+        assert_eq!(order_by_last_good(urls.clone(), Some("http://gone")), urls);
+        assert_eq!(order_by_last_good(urls.clone(), None), urls);
+    }
+}
@@ -0,0 +1,112 @@
+// src/sync/rsync.rs
+
+//! rsync-over-SSH transfer of the payload.
+//!
+//! With the rsync engine selected the torrent still describes the repo, but
+//! its files are copied from an rsync server before the torrent is added.
+//! Adding the torrent then hash-checks what rsync wrote, so verification and
+//! the cleaner work exactly as with the swarm, which only has to fill in
+//! whatever rsync could not deliver.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Stdio;
+
+use super::sftp::SftpConfig;
+
+/// How the payload files reach the download folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferEngine {
+    #[default]
+    Torrent,
+    Rsync,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RsyncConfig {
+    /// Remote folder holding the repo, as `[user@]host:/path`.
+    pub source: String,
+}
+
+/// Arguments for `rsync` copying `source` into `dest`. The SSH login uses
+/// the same key as SFTP URLs.
+fn command_args(config: &RsyncConfig, ssh: &SftpConfig, dest: &Path) -> Result<Vec<OsString>> {
+    let source = config.source.trim();
+    if !source.contains(':') {
+        return Err(anyhow!("rsync source must look like [user@]host:/path: {}", source));
+    }
+    // BatchMode fails instead of prompting for a password nobody can enter
+    let mut shell = "ssh -o BatchMode=yes".to_string();
+    if let Some(key) = &ssh.identity_file {
+        shell.push_str(&format!(" -i '{}'", key.display().to_string().replace('\'', r"'\''")));
+    }
+    // Trailing slashes copy the folder's contents rather than the folder.
+    // Extra local files are left for the cleaner, so no --delete.
+    let mut dest = dest.as_os_str().to_os_string();
+    dest.push("/");
+    Ok(vec![
+        "--recursive".into(),
+        "--times".into(),
+        "--partial".into(),
+        "-e".into(),
+        shell.into(),
+        format!("{}/", source.trim_end_matches('/')).into(),
+        dest,
+    ])
+}
+
+/// Copy the repo from the rsync server into `dest`.
+pub async fn transfer(config: &RsyncConfig, ssh: &SftpConfig, dest: &Path) -> Result<()> {
+    let args = command_args(config, ssh, dest)?;
+    println!("Sync: Transferring {} to {} with rsync", config.source.trim(), dest.display());
+    let output = tokio::process::Command::new("rsync")
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("Failed to start rsync; is it installed?")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("rsync failed ({}): {}", output.status, stderr.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_command_args() {
+        let config = RsyncConfig {
+            source: "mods@example.com:/srv/repo/".to_string(),
+        };
+        let ssh = SftpConfig {
+            identity_file: Some(PathBuf::from("/keys/id_ed25519")),
+        };
+        let args = command_args(&config, &ssh, Path::new("/games/mods")).unwrap();
+        assert_eq!(
+            args,
+            [
+                "--recursive",
+                "--times",
+                "--partial",
+                "-e",
+                "ssh -o BatchMode=yes -i '/keys/id_ed25519'",
+                "mods@example.com:/srv/repo/",
+                "/games/mods/",
+            ]
+        );
+        let local = RsyncConfig {
+            source: "/srv/repo".to_string(),
+        };
+        assert!(command_args(&local, &ssh, Path::new("/games/mods")).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+// src/sync/schedule.rs
+
+//! When the periodic remote check runs.
+//!
+//! Every client checks the torrent URL about every ten minutes, so members
+//! who started together would otherwise hit the web host in the same second
+//! once an update is published. Each check is pushed back by a random
+//! jitter, the first one after starting by a random share of the splay
+//! window, and a host that answers with `Retry-After` is left alone for as
+//! long as it asks.
+
+use chrono::{DateTime, Utc};
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+use tokio::time::Instant;
+
+use super::types::SyncConfig;
+
+/// How often the remote torrent is checked while a torrent is loaded.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Most seconds each check is pushed back when not configured.
+pub const DEFAULT_JITTER_SECS: u64 = 60;
+
+/// Longest `Retry-After` honoured, so a misconfigured host can't stop checks
+/// for days.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// A failed request whose response asked to retry later.
+#[derive(Debug)]
+pub struct RetryAfterError {
+    pub status: reqwest::StatusCode,
+    pub after: Duration,
+}
+
+impl std::fmt::Display for RetryAfterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP error: {}, retry after {} s", self.status, self.after.as_secs())
+    }
+}
+
+impl std::error::Error for RetryAfterError {}
+
+/// Wait asked for by a `Retry-After` header, either seconds or an HTTP date.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// A random duration below `max`.
+fn random_below(max: Duration) -> Duration {
+    let millis = max.as_millis() as u64;
+    if millis == 0 {
+        return Duration::ZERO;
+    }
+    let random = std::collections::hash_map::RandomState::new().build_hasher().finish();
+    Duration::from_millis(random % millis)
+}
+
+fn jitter(config: &SyncConfig) -> Duration {
+    Duration::from_secs(config.check_jitter_secs.unwrap_or(DEFAULT_JITTER_SECS))
+}
+
+fn splay(config: &SyncConfig) -> Duration {
+    Duration::from_secs(config.check_splay_secs.unwrap_or(0))
+}
+
+/// Time of the next periodic remote check.
+#[derive(Debug, Clone, Copy)]
+pub struct CheckSchedule {
+    next: Instant,
+}
+
+impl CheckSchedule {
+    /// First check somewhere in the splay window from `now`.
+    pub fn new(config: &SyncConfig, now: Instant) -> Self {
+        CheckSchedule { next: now + random_below(splay(config)) }
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.next
+    }
+
+    /// Schedule the check after one that ran at `now`.
+    pub fn checked(&mut self, config: &SyncConfig, now: Instant) {
+        self.next = now + CHECK_INTERVAL + random_below(jitter(config));
+    }
+
+    /// Hold the next check back until `after` from `now`.
+    pub fn retry_after(&mut self, after: Duration, now: Instant) {
+        self.next = self.next.max(now + after.min(MAX_RETRY_AFTER));
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.next.saturating_duration_since(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_and_retry_after() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Sun, 01 Mar 2026 12:05:00 GMT", now), Some(Duration::from_secs(300)));
+        assert_eq!(parse_retry_after("Sun, 01 Mar 2026 11:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+
+        let config = SyncConfig { check_jitter_secs: Some(30), check_splay_secs: Some(0), ..Default::default() };
+        let start = Instant::now();
+        let mut schedule = CheckSchedule::new(&config, start);
+        assert!(schedule.is_due(start));
+        schedule.checked(&config, start);
+        let wait = schedule.remaining(start);
+        assert!(wait >= CHECK_INTERVAL && wait < CHECK_INTERVAL + Duration::from_secs(30));
+        schedule.retry_after(Duration::from_secs(3600), start);
+        assert_eq!(schedule.remaining(start), Duration::from_secs(3600));
+        // A shorter wait never brings the check forward
+        schedule.retry_after(Duration::from_secs(5), start);
+        assert_eq!(schedule.remaining(start), Duration::from_secs(3600));
+    }
+}
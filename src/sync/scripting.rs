@@ -0,0 +1,249 @@
+// src/sync/scripting.rs
+
+//! User automation scripts, built with the `scripting` feature.
+//!
+//! The script named by `SyncConfig::script_path` is loaded when the client
+//! starts. Its `on_event(event)` function, if defined, is called for every
+//! sync event with a map holding `kind` and the event's details, and can
+//! drive the client through a small API:
+//!
+//! - `check()`, `verify()`, `pause()`, `resume()`
+//! - `apply_update()` applies the latest update found; `apply_update_at(hour)`
+//!   waits until that local hour first
+//! - `notify(message)` shows a message in the UI, `log(message)` prints one
+//! - `launch(path)` starts a program
+//! - `hour()` is the current local hour
+//!
+//! ```rhai
+//! fn on_event(event) {
+//!     if event.kind == "RemoteUpdateFound" {
+//!         if event.update_bytes > 5 * 1024 * 1024 * 1024 {
+//!             notify("Large update, applying it at 2 AM");
+//!             apply_update_at(2);
+//!         } else {
+//!             apply_update();
+//!         }
+//!     }
+//! }
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Timelike;
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+
+use super::messages::{SyncCommand, SyncEvent};
+
+/// Upper bound on the operations of one call, so a runaway loop can't hang
+/// the script host.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// How often a scheduled update checks the clock.
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// State shared between the host loop and the functions scripts call.
+struct ScriptApi {
+    cmd_tx: mpsc::UnboundedSender<SyncCommand>,
+    events_tx: broadcast::Sender<SyncEvent>,
+    torrent_url: String,
+    pending_update: Mutex<Option<Vec<u8>>>,
+    apply_at_hour: Mutex<Option<u32>>,
+}
+
+impl ScriptApi {
+    fn send(&self, cmd: SyncCommand) {
+        if self.cmd_tx.send(cmd).is_err() {
+            eprintln!("Script: Sync manager is no longer running");
+        }
+    }
+
+    fn apply_update(&self) {
+        match self.pending_update.lock().unwrap().take() {
+            Some(torrent) => self.send(SyncCommand::ApplyUpdate(torrent)),
+            None => println!("Script: No update to apply"),
+        }
+    }
+}
+
+/// Load the script at `path` and run it against `events` until they end.
+pub fn spawn(
+    path: &Path,
+    events: broadcast::Receiver<SyncEvent>,
+    events_tx: broadcast::Sender<SyncEvent>,
+    cmd_tx: mpsc::UnboundedSender<SyncCommand>,
+    torrent_url: String,
+) -> Result<JoinHandle<()>> {
+    let api = Arc::new(ScriptApi {
+        cmd_tx,
+        events_tx,
+        torrent_url,
+        pending_update: Mutex::new(None),
+        apply_at_hour: Mutex::new(None),
+    });
+    let engine = create_engine(&api);
+    let ast = engine
+        .compile_file(PathBuf::from(path))
+        .map_err(|e| anyhow!("{}", e))
+        .with_context(|| format!("Failed to load script {}", path.display()))?;
+    println!("Script: Loaded {}", path.display());
+    Ok(tokio::spawn(run(engine, ast, api, events)))
+}
+
+fn create_engine(api: &Arc<ScriptApi>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(|message| println!("Script: {}", message));
+
+    let a = Arc::clone(api);
+    engine.register_fn("check", move || a.send(SyncCommand::DownloadAndCompare(a.torrent_url.clone())));
+    let a = Arc::clone(api);
+    engine.register_fn("verify", move || a.send(SyncCommand::VerifyFolder));
+    let a = Arc::clone(api);
+    engine.register_fn("pause", move || a.send(SyncCommand::SetPaused(true)));
+    let a = Arc::clone(api);
+    engine.register_fn("resume", move || a.send(SyncCommand::SetPaused(false)));
+    let a = Arc::clone(api);
+    engine.register_fn("apply_update", move || a.apply_update());
+    let a = Arc::clone(api);
+    engine.register_fn("apply_update_at", move |hour: i64| {
+        *a.apply_at_hour.lock().unwrap() = u32::try_from(hour.rem_euclid(24)).ok();
+    });
+    let a = Arc::clone(api);
+    engine.register_fn("notify", move |message: &str| {
+        println!("Script: {}", message);
+        let _ = a.events_tx.send(SyncEvent::Notice(message.to_string()));
+    });
+    engine.register_fn("log", |message: &str| println!("Script: {}", message));
+    engine.register_fn("launch", |path: &str| {
+        let path = Path::new(path);
+        let mut command = std::process::Command::new(path);
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            command.current_dir(dir);
+        }
+        if let Err(e) = command.spawn() {
+            eprintln!("Script: Failed to launch {}: {}", path.display(), e);
+        }
+    });
+    engine.register_fn("hour", || i64::from(chrono::Local::now().hour()));
+    engine
+}
+
+async fn run(engine: Engine, ast: AST, api: Arc<ScriptApi>, mut events: broadcast::Receiver<SyncEvent>) {
+    let mut scope = Scope::new();
+    if let Err(e) = engine.run_ast_with_scope(&mut scope, &ast) {
+        eprintln!("Script: {}", e);
+    }
+    let has_handler = ast.iter_functions().any(|f| f.name == "on_event" && f.params.len() == 1);
+    let mut schedule = tokio::time::interval(SCHEDULE_INTERVAL);
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        eprintln!("Script: Skipped {} events", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                match &event {
+                    SyncEvent::RemoteUpdateFound(torrent, _) => *api.pending_update.lock().unwrap() = Some(torrent.clone()),
+                    // Applied, by the script or anyone else
+                    SyncEvent::TorrentAdded(_) => {
+                        *api.pending_update.lock().unwrap() = None;
+                        *api.apply_at_hour.lock().unwrap() = None;
+                    }
+                    _ => {}
+                }
+                if has_handler
+                    && let Err(e) = engine.call_fn::<Dynamic>(&mut scope, &ast, "on_event", (event_map(&event),))
+                {
+                    eprintln!("Script: on_event failed: {}", e);
+                }
+            }
+            _ = schedule.tick() => {
+                let due = *api.apply_at_hour.lock().unwrap() == Some(chrono::Local::now().hour());
+                if due {
+                    println!("Script: Applying scheduled update");
+                    *api.apply_at_hour.lock().unwrap() = None;
+                    api.apply_update();
+                }
+            }
+        }
+    }
+}
+
+/// `event` as the map passed to `on_event`.
+fn event_map(event: &SyncEvent) -> Map {
+    let mut map = Map::new();
+    map.insert("kind".into(), event.kind().into());
+    let mut set = |key: &str, value: Dynamic| {
+        map.insert(key.into(), value);
+    };
+    match event {
+        SyncEvent::StatusUpdate(status) => set("status", format!("{:?}", status).into()),
+        SyncEvent::Error(error) => {
+            set("message", error.message().into());
+            set("error_kind", error.kind().into());
+        }
+        SyncEvent::Notice(message) => set("message", message.clone().into()),
+        SyncEvent::RemoteUpdateFound(torrent, summary) => {
+            set("torrent_bytes", (torrent.len() as i64).into());
+            set("update_bytes", (summary.total_bytes as i64).into());
+            set("name", summary.name.clone().unwrap_or_default().into());
+            set("file_count", (summary.file_count as i64).into());
+            set("info_hash", summary.info_hash.clone().into());
+        }
+        SyncEvent::MissingFilesFound(files) => set("count", (files.len() as i64).into()),
+        SyncEvent::ExtraFilesFound(files) => set("count", (files.len() as i64).into()),
+        SyncEvent::ManagedTorrentUpdate(Some((_, stats))) => {
+            set("state", stats.state.clone().into());
+            set("progress_bytes", (stats.progress_bytes as i64).into());
+            set("total_bytes", (stats.total_bytes as i64).into());
+            set("download_speed", stats.download_speed.into());
+            set("upload_speed", stats.upload_speed.into());
+        }
+        SyncEvent::RemoteSource(url) => set("url", url.clone().into()),
+        SyncEvent::GamingMode(enabled) => set("enabled", (*enabled).into()),
+        _ => {}
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::SyncError;
+
+    #[tokio::test]
+    async fn test_script_reacts_to_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("auto.rhai");
+        std::fs::write(
+            &script,
+            r#"
+            fn on_event(event) {
+                if event.kind == "RemoteUpdateFound" && event.torrent_bytes < 10 {
+                    apply_update();
+                }
+                if event.kind == "Error" && event.error_kind == "DiskFull" { pause(); }
+            }
+            "#,
+        )
+        .unwrap();
+        let (events_tx, events_rx) = broadcast::channel(16);
+        let (cmd_tx, mut cmd_rx) = mpsc::unbounded_channel();
+        let task = spawn(&script, events_rx, events_tx.clone(), cmd_tx, String::new()).unwrap();
+
+        events_tx.send(SyncEvent::RemoteUpdateFound(b"small".to_vec(), Default::default())).unwrap();
+        events_tx.send(SyncEvent::Error(SyncError::DiskFull("disk full".to_string()))).unwrap();
+        assert!(matches!(cmd_rx.recv().await, Some(SyncCommand::ApplyUpdate(t)) if t == b"small"));
+        assert!(matches!(cmd_rx.recv().await, Some(SyncCommand::SetPaused(true))));
+        task.abort();
+    }
+}
@@ -0,0 +1,87 @@
+// src/sync/seed_window.rs
+
+//! Hours of the day in which the finished torrent seeds.
+//!
+//! Outside the window the manager pauses a torrent that has nothing left to
+//! download and resumes it once the window opens. Downloads are never held
+//! back, so an update still arrives at any time of day.
+
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Seeding hours, as minutes after local midnight. A window whose end is
+/// before its start runs past midnight; one that starts where it ends is
+/// open all day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeedWindow {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// `HH:MM` as minutes after midnight.
+fn parse_time(text: &str) -> Option<u32> {
+    let (hour, minute) = text.trim().split_once(':')?;
+    let (hour, minute) = (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?);
+    (hour < 24 && minute < 60).then_some(hour * 60 + minute)
+}
+
+impl SeedWindow {
+    /// Parse a window written like `22:00-08:00`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (start, end) = text.split_once('-')?;
+        Some(Self { start: parse_time(start)?, end: parse_time(end)? })
+    }
+
+    pub fn contains(&self, minute: u32) -> bool {
+        let minute = minute % MINUTES_PER_DAY;
+        if self.start <= self.end {
+            self.start == self.end || (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        let now = chrono::Local::now();
+        self.contains(now.hour() * 60 + now.minute())
+    }
+
+    /// Opening time as `HH:MM`.
+    pub fn start_text(&self) -> String {
+        format!("{:02}:{:02}", self.start / 60, self.start % 60)
+    }
+}
+
+impl fmt::Display for SeedWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{:02}:{:02}", self.start_text(), self.end / 60, self.end % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_window() {
+        let night = SeedWindow::parse("22:00-08:00").unwrap();
+        assert_eq!(night, SeedWindow { start: 22 * 60, end: 8 * 60 });
+        assert!(night.contains(23 * 60));
+        assert!(night.contains(0));
+        assert!(!night.contains(8 * 60));
+        assert!(!night.contains(12 * 60));
+        assert_eq!(night.to_string(), "22:00-08:00");
+
+        let day = SeedWindow::parse(" 9:30 - 17:00 ").unwrap();
+        assert!(day.contains(9 * 60 + 30));
+        assert!(!day.contains(17 * 60));
+        assert!(SeedWindow::parse("00:00-00:00").unwrap().contains(12 * 60));
+
+        assert_eq!(SeedWindow::parse("22:00"), None);
+        assert_eq!(SeedWindow::parse("24:00-08:00"), None);
+        assert_eq!(SeedWindow::parse("night"), None);
+    }
+}
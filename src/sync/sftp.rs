@@ -0,0 +1,142 @@
+// src/sync/sftp.rs
+
+//! Torrents served from an SSH box over SFTP.
+//!
+//! `sftp://[user@]host[:port]/path` URLs are fetched with the system's
+//! OpenSSH `sftp` client in batch mode, authenticating with a key file, so
+//! no SSH implementation has to be bundled.
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+
+/// URL scheme selecting this source.
+pub const SCHEME: &str = "sftp://";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SftpConfig {
+    /// Private key used to log in. The SSH agent and `~/.ssh/config` are
+    /// used when unset.
+    pub identity_file: Option<PathBuf>,
+}
+
+/// Arguments for `sftp` and the remote path to fetch for `url`.
+fn command_args(url: &str, config: &SftpConfig) -> Result<(Vec<String>, String)> {
+    let parsed = Url::parse(url).with_context(|| format!("Invalid SFTP URL: {}", url))?;
+    let host = parsed.host_str().filter(|h| !h.is_empty()).ok_or_else(|| anyhow!("SFTP URL has no host: {}", url))?;
+    let path = percent_decode(parsed.path());
+    if path.is_empty() || path.ends_with('/') {
+        return Err(anyhow!("SFTP URL must name a file: {}", url));
+    }
+
+    // BatchMode fails instead of prompting for a password nobody can enter
+    let mut args = vec!["-q".to_string(), "-b".to_string(), "-".to_string(), "-o".to_string(), "BatchMode=yes".to_string()];
+    if let Some(key) = &config.identity_file {
+        args.push("-i".to_string());
+        args.push(key.display().to_string());
+    }
+    if let Some(port) = parsed.port() {
+        args.push("-P".to_string());
+        args.push(port.to_string());
+    }
+    args.push(match parsed.username() {
+        "" => host.to_string(),
+        user => format!("{}@{}", percent_decode(user), host),
+    });
+    Ok((args, path))
+}
+
+/// Download the file named by the `sftp://` `url`.
+pub async fn download(url: &str, config: &SftpConfig) -> Result<Vec<u8>> {
+    let (args, remote_path) = command_args(url, config)?;
+    let local_path = std::env::temp_dir().join(format!(
+        "modsync-sftp-{}-{}.torrent",
+        std::process::id(),
+        chrono::Utc::now().timestamp_millis()
+    ));
+    println!("Sync: Fetching {} over SFTP", url);
+
+    let result = run_sftp(&args, &remote_path, &local_path).await;
+    let content = match result {
+        Ok(()) => tokio::fs::read(&local_path)
+            .await
+            .with_context(|| format!("Failed to read downloaded file: {}", local_path.display())),
+        Err(e) => Err(e),
+    };
+    let _ = tokio::fs::remove_file(&local_path).await;
+    content
+}
+
+async fn run_sftp(args: &[String], remote_path: &str, local_path: &Path) -> Result<()> {
+    let mut child = tokio::process::Command::new("sftp")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start sftp; is the OpenSSH client installed?")?;
+
+    let batch = format!("get {} {}\n", quote(remote_path), quote(&local_path.display().to_string()));
+    let mut stdin = child.stdin.take().ok_or_else(|| anyhow!("Failed to open sftp stdin"))?;
+    stdin.write_all(batch.as_bytes()).await.context("Failed to send sftp commands")?;
+    drop(stdin);
+
+    let output = child.wait_with_output().await.context("Failed to wait for sftp")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("sftp failed ({}): {}", output.status, stderr.trim()));
+    }
+    Ok(())
+}
+
+/// Quote a path for an sftp batch file.
+fn quote(path: &str) -> String {
+    format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args() {
+        let config = SftpConfig {
+            identity_file: Some(PathBuf::from("/keys/id_ed25519")),
+        };
+        let (args, path) = command_args("sftp://mods@example.com:2222/srv/repo%20v2/repo.torrent", &config).unwrap();
+        assert_eq!(path, "/srv/repo v2/repo.torrent");
+        assert_eq!(
+            args,
+            ["-q", "-b", "-", "-o", "BatchMode=yes", "-i", "/keys/id_ed25519", "-P", "2222", "mods@example.com"]
+        );
+
+        let (args, _) = command_args("sftp://example.com/repo.torrent", &SftpConfig::default()).unwrap();
+        assert_eq!(args.last().map(String::as_str), Some("example.com"));
+        assert!(command_args("sftp://example.com/", &SftpConfig::default()).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+// src/sync/snapshot.rs
+
+//! Serializable copy of the librqbit torrent stats the UI and external
+//! interfaces care about. `librqbit::TorrentStats` can't be deserialized, so
+//! events crossing a process boundary carry this instead.
+
+use librqbit::api::TorrentDetailsResponse;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::mapping::{map_path, FolderMapping};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TorrentSnapshot {
+    /// librqbit state name: "initializing", "live", "paused" or "error".
+    pub state: String,
+    pub error: Option<String>,
+    /// Bytes downloaded per file, in torrent file order.
+    pub file_progress: Vec<u64>,
+    pub progress_bytes: u64,
+    /// Bytes fetched from and sent to peers since the torrent last went
+    /// live. Both restart from zero when the torrent is resumed or re-added.
+    #[serde(default)]
+    pub fetched_bytes: u64,
+    pub uploaded_bytes: u64,
+    pub total_bytes: u64,
+    pub finished: bool,
+    /// Current speeds in MiB/s; zero when the torrent isn't live.
+    pub download_speed: f64,
+    pub upload_speed: f64,
+    /// Connected peers; zero when the torrent isn't live.
+    pub live_peers: usize,
+    /// The metainfo has the private flag, so peers only come from the
+    /// tracker.
+    #[serde(default)]
+    pub private: bool,
+}
+
+impl From<&librqbit::TorrentStats> for TorrentSnapshot {
+    fn from(stats: &librqbit::TorrentStats) -> Self {
+        let live = stats.live.as_ref();
+        Self {
+            state: stats.state.to_string(),
+            error: stats.error.clone(),
+            file_progress: stats.file_progress.clone(),
+            progress_bytes: stats.progress_bytes,
+            fetched_bytes: live.map(|l| l.snapshot.fetched_bytes).unwrap_or(0),
+            uploaded_bytes: stats.uploaded_bytes,
+            total_bytes: stats.total_bytes,
+            finished: stats.finished,
+            download_speed: live.map(|l| l.download_speed.mbps).unwrap_or(0.0),
+            upload_speed: live.map(|l| l.upload_speed.mbps).unwrap_or(0.0),
+            live_peers: live.map(|l| l.snapshot.peer_stats.live).unwrap_or(0),
+            private: false,
+        }
+    }
+}
+
+/// Progress of the managed torrent for library consumers, combining the
+/// stats with the file list so no librqbit types are needed to read it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncStats {
+    /// librqbit state name: "initializing", "live", "paused" or "error".
+    pub state: String,
+    pub error: Option<String>,
+    pub finished: bool,
+    pub progress_bytes: u64,
+    pub total_bytes: u64,
+    /// `progress_bytes` of `total_bytes`, from 0.0 to 1.0.
+    pub progress: f64,
+    /// Bytes fetched from and sent to peers since the torrent last went live.
+    pub fetched_bytes: u64,
+    pub uploaded_bytes: u64,
+    /// Current speeds in MiB/s.
+    pub download_speed: f64,
+    pub upload_speed: f64,
+    pub live_peers: usize,
+    /// Every file of the torrent, in torrent order.
+    pub files: Vec<FileStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStats {
+    /// Path relative to the download folder.
+    pub path: PathBuf,
+    pub length: u64,
+    pub downloaded_bytes: u64,
+    /// Whether the file is selected for download.
+    pub included: bool,
+}
+
+impl SyncStats {
+    /// Stats of `snapshot` with the progress of each of `files`, which are
+    /// in the same torrent order as its per-file progress.
+    pub fn new(snapshot: &TorrentSnapshot, files: &[TorrentFile]) -> Self {
+        Self {
+            state: snapshot.state.clone(),
+            error: snapshot.error.clone(),
+            finished: snapshot.finished,
+            progress_bytes: snapshot.progress_bytes,
+            total_bytes: snapshot.total_bytes,
+            progress: if snapshot.total_bytes == 0 { 0.0 } else { snapshot.progress_bytes as f64 / snapshot.total_bytes as f64 },
+            fetched_bytes: snapshot.fetched_bytes,
+            uploaded_bytes: snapshot.uploaded_bytes,
+            download_speed: snapshot.download_speed,
+            upload_speed: snapshot.upload_speed,
+            live_peers: snapshot.live_peers,
+            files: files
+                .iter()
+                .enumerate()
+                .map(|(i, file)| FileStats {
+                    path: file.path.clone(),
+                    length: file.length,
+                    downloaded_bytes: snapshot.file_progress.get(i).copied().unwrap_or(0),
+                    included: file.included,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// One file of the managed torrent, in torrent file order so it lines up with
+/// `TorrentSnapshot::file_progress`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TorrentFile {
+    /// Path relative to the download folder.
+    pub path: PathBuf,
+    pub length: u64,
+    /// Whether the file is selected for download.
+    pub included: bool,
+}
+
+impl TorrentFile {
+    /// Every file listed in `details`, in torrent order, at its path after
+    /// `mappings`.
+    pub fn list_from_details(details: &TorrentDetailsResponse, mappings: &[FolderMapping]) -> Vec<Self> {
+        details
+            .files
+            .iter()
+            .flatten()
+            .map(|file| Self {
+                path: map_path(mappings, &file.components.iter().collect::<PathBuf>()),
+                length: file.length,
+                included: file.included,
+            })
+            .collect()
+    }
+}
+
+/// What a torrent contains, for showing an update before it is applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TorrentSummary {
+    pub name: Option<String>,
+    pub total_bytes: u64,
+    pub file_count: usize,
+    /// Hex infohash.
+    pub info_hash: String,
+}
+
+impl TorrentSummary {
+    pub fn from_torrent(torrent: &[u8]) -> anyhow::Result<Self> {
+        let meta = librqbit::torrent_from_bytes::<librqbit::ByteBuf>(torrent)?;
+        let lengths: Vec<u64> = meta.info.iter_file_lengths()?.collect();
+        Ok(Self {
+            name: meta.info.name.as_ref().map(|name| String::from_utf8_lossy(name.as_ref()).into_owned()),
+            total_bytes: lengths.iter().fold(0u64, |total, &length| total.saturating_add(length)),
+            file_count: lengths.len(),
+            info_hash: meta.info_hash.as_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_stats_pairs_files_with_progress() {
+        let snapshot = TorrentSnapshot {
+            file_progress: vec![10, 0],
+            progress_bytes: 10,
+            total_bytes: 40,
+            ..Default::default()
+        };
+        let file = |path: &str, included| TorrentFile { path: PathBuf::from(path), length: 20, included };
+        let stats = SyncStats::new(&snapshot, &[file("a.pbo", true), file("b.pbo", false)]);
+        assert_eq!(stats.progress, 0.25);
+        assert_eq!(stats.files[0].downloaded_bytes, 10);
+        assert!(!stats.files[1].included);
+        assert_eq!(SyncStats::new(&TorrentSnapshot::default(), &[]).progress, 0.0);
+    }
+}
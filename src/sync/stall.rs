@@ -0,0 +1,176 @@
+// src/sync/stall.rs
+
+//! Noticing a download that stopped moving, and why it might have.
+//!
+//! A live, unfinished torrent whose progress hasn't changed for the stall
+//! time is stalled. The trackers are scraped again right then, so the
+//! diagnosis says whether they answer now rather than 15 minutes ago.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio::sync::mpsc;
+
+use super::messages::SyncEvent;
+use super::rsync::TransferEngine;
+use super::swarm::{self, SwarmHealth};
+use super::types::SyncConfig;
+use super::utils::send_sync_event;
+
+/// Minutes without progress before a download counts as stalled, when not
+/// configured.
+pub const DEFAULT_STALL_MINUTES: u64 = 10;
+
+/// Time without progress before a download counts as stalled, `None` when
+/// stall detection is off.
+pub fn stall_after(config: &SyncConfig) -> Option<Duration> {
+    let minutes = config.stall_minutes.unwrap_or(DEFAULT_STALL_MINUTES);
+    (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+}
+
+/// Something the user can do about a stalled download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StallSuggestion {
+    /// Nobody has the whole torrent; it continues once someone does.
+    WaitForSeeders,
+    /// No tracker answered; the network or the trackers are down.
+    CheckTrackers,
+    /// No peer is connected; a firewall may block BitTorrent.
+    CheckFirewall,
+    /// An rsync server is configured to download from instead.
+    UseRsync,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StallDiagnosis {
+    /// Minutes since the download last made progress.
+    pub minutes: u64,
+    /// Peers connected when the stall was noticed.
+    pub peers: usize,
+    /// The torrent has trackers to ask.
+    pub has_trackers: bool,
+    /// Scrape taken when the stall was noticed, `None` when no tracker
+    /// answered.
+    pub swarm: Option<SwarmHealth>,
+    /// Port the session accepts peers on, `None` when it only connects out.
+    pub listen_port: Option<u16>,
+    pub suggestions: Vec<StallSuggestion>,
+}
+
+pub fn diagnose(
+    config: &SyncConfig,
+    stalled_for: Duration,
+    peers: usize,
+    has_trackers: bool,
+    swarm: Option<SwarmHealth>,
+    listen_port: Option<u16>,
+) -> StallDiagnosis {
+    let mut suggestions = Vec::new();
+    if swarm.is_some_and(|swarm| swarm.is_empty()) {
+        suggestions.push(StallSuggestion::WaitForSeeders);
+    }
+    if has_trackers && swarm.is_none() {
+        suggestions.push(StallSuggestion::CheckTrackers);
+    }
+    if peers == 0 {
+        suggestions.push(StallSuggestion::CheckFirewall);
+    }
+    if config.engine == TransferEngine::Torrent && !config.rsync.source.trim().is_empty() {
+        suggestions.push(StallSuggestion::UseRsync);
+    }
+    StallDiagnosis { minutes: stalled_for.as_secs() / 60, peers, has_trackers, swarm, listen_port, suggestions }
+}
+
+/// A change in whether the download is stalled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StallChange {
+    /// No progress for this long.
+    Stalled(Duration),
+    Recovered,
+}
+
+/// Follows the progress of the managed torrent across status refreshes.
+#[derive(Debug, Default)]
+pub struct StallDetector {
+    progress: u64,
+    /// When the progress last changed, `None` while not downloading.
+    since: Option<Instant>,
+    stalled: bool,
+}
+
+impl StallDetector {
+    /// Take one refresh of a torrent at `progress` bytes, `downloading` when
+    /// it is live and unfinished.
+    pub fn update(&mut self, downloading: bool, progress: u64, now: Instant, after: Duration) -> Option<StallChange> {
+        let moved = !downloading || self.since.is_none() || progress != self.progress;
+        if moved {
+            self.progress = progress;
+            self.since = downloading.then_some(now);
+            return std::mem::take(&mut self.stalled).then_some(StallChange::Recovered);
+        }
+        let stalled_for = self.since.map_or(Duration::ZERO, |since| now.duration_since(since));
+        if !self.stalled && stalled_for >= after {
+            self.stalled = true;
+            return Some(StallChange::Stalled(stalled_for));
+        }
+        None
+    }
+}
+
+/// Scrape the trackers of torrent `id` and report why the download may have
+/// stalled.
+pub fn spawn_diagnosis(
+    config: &SyncConfig,
+    api: &librqbit::Api,
+    id: usize,
+    stalled_for: Duration,
+    http_client: reqwest::Client,
+    ui_tx: mpsc::UnboundedSender<SyncEvent>,
+) {
+    let config = config.clone();
+    let target = swarm::scrape_target(api, id);
+    let peers = api.api_stats_v1(id.into()).ok().and_then(|stats| stats.live).map_or(0, |live| live.snapshot.peer_stats.live);
+    let listen_port = api.session().tcp_listen_port();
+    tokio::spawn(async move {
+        let swarm = match &target {
+            Some((trackers, info_hash)) => {
+                let health = swarm::scrape(&http_client, trackers, info_hash).await;
+                send_sync_event(&ui_tx, SyncEvent::Swarm(health));
+                health
+            }
+            None => None,
+        };
+        let diagnosis = diagnose(&config, stalled_for, peers, target.is_some(), swarm, listen_port);
+        println!("Sync: Download stalled: {:?}", diagnosis);
+        send_sync_event(&ui_tx, SyncEvent::Stalled(Some(diagnosis)));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stall_detection() {
+        let after = Duration::from_secs(600);
+        let start = Instant::now();
+        let mut detector = StallDetector::default();
+        assert_eq!(detector.update(true, 100, start, after), None);
+        assert_eq!(detector.update(true, 100, start + Duration::from_secs(599), after), None);
+        assert_eq!(detector.update(true, 100, start + after, after), Some(StallChange::Stalled(after)));
+        assert_eq!(detector.update(true, 100, start + after * 2, after), None);
+        assert_eq!(detector.update(true, 200, start + after * 2, after), Some(StallChange::Recovered));
+        // Pausing isn't stalling
+        assert_eq!(detector.update(false, 200, start + after * 4, after), None);
+        assert_eq!(detector.update(true, 200, start + after * 4, after), None);
+
+        let config = SyncConfig::default();
+        let empty = SwarmHealth { seeders: 0, leechers: 3, downloaded: 0 };
+        let diagnosis = diagnose(&config, after, 2, true, Some(empty), None);
+        assert_eq!(diagnosis.minutes, 10);
+        assert_eq!(diagnosis.suggestions, vec![StallSuggestion::WaitForSeeders]);
+        let diagnosis = diagnose(&config, after, 0, true, None, None);
+        assert_eq!(diagnosis.suggestions, vec![StallSuggestion::CheckTrackers, StallSuggestion::CheckFirewall]);
+        assert_eq!(stall_after(&SyncConfig { stall_minutes: Some(0), ..config }), None);
+    }
+}
@@ -2,9 +2,15 @@
 // Shared SyncStatus enum used by the sync subsystem. This was previously part of the UI
 // module; the enum has been moved here so sync logic doesn't depend on UI code.
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::i18n::{tr, tr_args};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum SyncStatus {
+    #[default]
     Idle,
+    /// Adding the cached torrent from the last session.
+    RestoringSession,
     CheckingRemote,
     UpdatingTorrent,
     CheckingLocal,
@@ -13,8 +19,18 @@ pub enum SyncStatus {
     Error(String),
 }
 
-impl Default for SyncStatus {
-    fn default() -> Self {
-        SyncStatus::Idle
+impl SyncStatus {
+    /// Translated text shown to the user for this status.
+    pub fn display_text(&self) -> String {
+        match self {
+            SyncStatus::Idle => tr("status-idle"),
+            SyncStatus::RestoringSession => tr("status-restoring-session"),
+            SyncStatus::CheckingRemote => tr("status-checking-remote"),
+            SyncStatus::UpdatingTorrent => tr("status-updating-torrent"),
+            SyncStatus::CheckingLocal => tr("status-checking-local"),
+            SyncStatus::LocalActive => tr("status-local-active"),
+            SyncStatus::RemoteChanged => tr("status-remote-changed"),
+            SyncStatus::Error(msg) => tr_args("status-error", &[("error", msg.as_str().into())]),
+        }
     }
 }
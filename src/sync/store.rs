@@ -0,0 +1,310 @@
+// src/sync/store.rs
+
+//! Download store shared by several profiles on one computer.
+//!
+//! Profiles, or OS users, syncing the same repo can point `shared_store` at
+//! one folder that holds every finished file once. A file is stored under a
+//! key taken from the torrent's metainfo: its length, where it starts in
+//! its first piece and the hashes of the pieces it spans. Equal keys mean
+//! equal content without reading the file; a file whose neighbour changed
+//! gets a new key and is stored again, but two different files never share
+//! one.
+//!
+//! Before a torrent is added, stored files are hard linked into the
+//! download folder so the hash check finds them complete. Once the download
+//! finishes, its files are linked into the store for the other profiles.
+//! Links share their data, so a linked file an update is about to change is
+//! first replaced with a private copy, keeping librqbit's writes out of the
+//! store. Stored files no profile links to anymore are pruned after
+//! publishing. Hard links only work within a volume, so the store has to be
+//! on the same drive as the download folders. Both steps need the link
+//! count of a file, so the store is only used on unix and Windows.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use super::mapping::{map_path, FolderMapping};
+use super::types::SyncConfig;
+
+/// A torrent file as it is kept in the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreFile {
+    /// Path relative to the download folder, after the folder mappings.
+    pub path: PathBuf,
+    pub key: String,
+    pub length: u64,
+}
+
+/// What linking a download folder to the store did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkReport {
+    /// Files linked from the store.
+    pub linked: usize,
+    /// Shared files replaced with a private copy before an update.
+    pub unshared: usize,
+}
+
+/// The files of `torrent` with their store keys. Empty and padding files
+/// are left out.
+pub fn store_files(torrent: &[u8], mappings: &[FolderMapping]) -> Result<Vec<StoreFile>> {
+    let meta = librqbit::torrent_from_bytes::<librqbit::ByteBuf>(torrent)?;
+    let info = &meta.info;
+    let piece_length = u64::from(info.piece_length);
+    let mut files = Vec::new();
+    let mut offset = 0u64;
+    for details in info.iter_file_details()? {
+        let (start, length) = (offset, details.len);
+        offset += length;
+        if length == 0 || details.attrs().padding {
+            continue;
+        }
+        let first = start / piece_length;
+        let last = (start + length - 1) / piece_length;
+        let Some(hashes) = info.pieces.as_ref().get(first as usize * 20..(last as usize + 1) * 20) else {
+            continue;
+        };
+        let mut hasher = Sha256::new();
+        hasher.update(length.to_le_bytes());
+        hasher.update((start % piece_length).to_le_bytes());
+        hasher.update(piece_length.to_le_bytes());
+        hasher.update(hashes);
+        files.push(StoreFile {
+            path: map_path(mappings, &details.filename.to_pathbuf()?),
+            key: format!("{:x}", hasher.finalize()),
+            length,
+        });
+    }
+    Ok(files)
+}
+
+/// Link the stored `files` into `download_path`, replacing what is there,
+/// and give files that aren't stored yet but are linked elsewhere a private
+/// copy.
+pub fn link_into(store: &Path, download_path: &Path, files: &[StoreFile]) -> Result<LinkReport> {
+    let mut report = LinkReport::default();
+    for file in files {
+        let local = download_path.join(&file.path);
+        let stored = store.join(&file.key);
+        let local_meta = std::fs::metadata(&local).ok();
+        if stored.is_file() {
+            if local_meta.is_some() && same_file::is_same_file(&local, &stored).unwrap_or(false) {
+                continue;
+            }
+            if let Some(parent) = local.parent() {
+                std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            if local_meta.is_some() {
+                std::fs::remove_file(&local).with_context(|| format!("Failed to remove {}", local.display()))?;
+            }
+            std::fs::hard_link(&stored, &local).with_context(|| format!("Failed to link {} from the store", local.display()))?;
+            report.linked += 1;
+        } else if local_meta.is_some() && is_shared(&local) {
+            unshare(&local)?;
+            report.unshared += 1;
+        }
+    }
+    Ok(report)
+}
+
+/// Link the finished `files` in `download_path` into the store, returning
+/// how many were new there. Only call this once they are verified.
+pub fn publish(store: &Path, download_path: &Path, files: &[StoreFile]) -> Result<usize> {
+    std::fs::create_dir_all(store).with_context(|| format!("Failed to create {}", store.display()))?;
+    let mut published = 0;
+    for file in files {
+        let local = download_path.join(&file.path);
+        let stored = store.join(&file.key);
+        if stored.exists() || std::fs::metadata(&local).map_or(true, |meta| meta.len() != file.length) {
+            continue;
+        }
+        std::fs::hard_link(&local, &stored).with_context(|| format!("Failed to link {} into the store", local.display()))?;
+        published += 1;
+    }
+    Ok(published)
+}
+
+/// Remove stored files no download folder links to anymore.
+pub fn prune(store: &Path) -> Result<usize> {
+    let mut pruned = 0;
+    for entry in std::fs::read_dir(store).with_context(|| format!("Failed to list {}", store.display()))? {
+        let entry = entry?;
+        if let Ok(meta) = entry.metadata()
+            && meta.is_file()
+            && is_unlinked(&entry.path())
+        {
+            std::fs::remove_file(entry.path()).with_context(|| format!("Failed to remove {}", entry.path().display()))?;
+            pruned += 1;
+        }
+    }
+    Ok(pruned)
+}
+
+/// The shared store of `config`, `None` when it has none or link counts
+/// can't be read here.
+fn store_of(config: &SyncConfig) -> Option<PathBuf> {
+    let store = config.shared_store.clone()?;
+    if !cfg!(any(unix, windows)) {
+        eprintln!("Sync: The shared store isn't supported on this platform, ignoring it");
+        return None;
+    }
+    Some(store)
+}
+
+/// Link the stored files of `torrent` into the download folder of `config`
+/// ahead of adding it. Failures are logged; the torrent then downloads
+/// what it can't find.
+pub async fn link_from_store(config: &SyncConfig, torrent: &[u8]) {
+    let Some(store) = store_of(config) else {
+        return;
+    };
+    let download_path = config.download_path.clone();
+    let files = match store_files(torrent, &config.folder_mappings) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Sync: Shared store skipped: {:#}", e);
+            return;
+        }
+    };
+    let result = tokio::task::spawn_blocking(move || link_into(&store, &download_path, &files))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|r| r);
+    match result {
+        Ok(report) => println!("Sync: Linked {} files from the shared store, copied {} shared files", report.linked, report.unshared),
+        Err(e) => eprintln!("Sync: Failed to link files from the shared store: {:#}", e),
+    }
+}
+
+/// Publish the finished download of `config`'s cached torrent to the
+/// shared store and prune files nobody uses anymore.
+pub async fn publish_finished(config: &SyncConfig) {
+    let (Some(store), Some(cached)) = (store_of(config), config.cached_torrent_path.clone()) else {
+        return;
+    };
+    let download_path = config.download_path.clone();
+    let mappings = config.folder_mappings.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let torrent = std::fs::read(&cached).with_context(|| format!("Failed to read {}", cached.display()))?;
+        let files = store_files(&torrent, &mappings)?;
+        let published = publish(&store, &download_path, &files)?;
+        Ok::<_, anyhow::Error>((published, prune(&store)?))
+    })
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|r| r);
+    match result {
+        Ok((published, pruned)) => println!("Sync: Published {} files to the shared store, pruned {}", published, pruned),
+        Err(e) => eprintln!("Sync: Failed to publish to the shared store: {:#}", e),
+    }
+}
+
+/// Replace `path` with a copy of itself, breaking its hard links.
+fn unshare(path: &Path) -> Result<()> {
+    let mut copy = path.as_os_str().to_owned();
+    copy.push(".modsync-copy");
+    let copy = PathBuf::from(copy);
+    std::fs::copy(path, &copy).with_context(|| format!("Failed to copy {}", path.display()))?;
+    std::fs::rename(&copy, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+/// Whether the file has other hard links. Where the link count can't be
+/// read, it counts as shared, which costs a copy but never the store's
+/// data.
+fn is_shared(path: &Path) -> bool {
+    link_count(path).is_none_or(|links| links > 1)
+}
+
+/// Whether a stored file has no links besides the store's. Where the link
+/// count can't be read, it is kept.
+fn is_unlinked(path: &Path) -> bool {
+    link_count(path) == Some(1)
+}
+
+/// Number of hard links to the file at `path`.
+#[cfg(unix)]
+fn link_count(path: &Path) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|meta| std::os::unix::fs::MetadataExt::nlink(&meta))
+}
+
+#[cfg(windows)]
+fn link_count(path: &Path) -> Option<u64> {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::Storage::FileSystem::{GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION};
+
+    let file = std::fs::File::open(path).ok()?;
+    let mut info = BY_HANDLE_FILE_INFORMATION::default();
+    // Safety: the handle stays open for the call and `info` is writable
+    unsafe { GetFileInformationByHandle(HANDLE(file.as_raw_handle()), &mut info) }.ok()?;
+    Some(u64::from(info.nNumberOfLinks))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn link_count(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two-file torrent of `a.pbo` and `b.pbo` with one piece per file.
+    fn torrent(b_hash: u8) -> Vec<u8> {
+        let mut t = b"d4:infod5:filesld6:lengthi16384e4:pathl5:a.pboeed6:lengthi16384e4:pathl5:b.pboeee4:name3:mod12:piece lengthi16384e6:pieces40:".to_vec();
+        t.extend([1u8; 20]);
+        t.extend([b_hash; 20]);
+        t.extend(b"ee");
+        t
+    }
+
+    #[test]
+    fn test_keys_follow_content() -> Result<()> {
+        let old = store_files(&torrent(2), &[])?;
+        let new = store_files(&torrent(3), &[])?;
+        assert_eq!(old[0].path, PathBuf::from("a.pbo"));
+        assert_eq!(old[0].key, new[0].key);
+        assert_ne!(old[1].key, new[1].key);
+        assert_ne!(old[0].key, old[1].key);
+        Ok(())
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn test_link_counts() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let stored = dir.path().join("stored");
+        std::fs::write(&stored, "pbo")?;
+        assert!(is_unlinked(&stored));
+        assert!(!is_shared(&stored));
+        std::fs::hard_link(&stored, dir.path().join("linked"))?;
+        assert!(!is_unlinked(&stored));
+        assert!(is_shared(&stored));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_profiles_share_files() -> Result<()> {
+        let store = tempfile::tempdir()?;
+        let first = tempfile::tempdir()?;
+        let second = tempfile::tempdir()?;
+        let files = store_files(&torrent(2), &[])?;
+        for file in &files {
+            std::fs::write(first.path().join(&file.path), vec![7u8; 16384])?;
+        }
+        assert_eq!(publish(store.path(), first.path(), &files)?, 2);
+        assert_eq!(link_into(store.path(), second.path(), &files)?, LinkReport { linked: 2, unshared: 0 });
+        assert!(same_file::is_same_file(first.path().join("a.pbo"), second.path().join("a.pbo"))?);
+
+        // b.pbo changes in the update, so the second profile gets its own copy
+        let updated = store_files(&torrent(3), &[])?;
+        assert_eq!(link_into(store.path(), second.path(), &updated)?, LinkReport { linked: 0, unshared: 1 });
+        assert!(!same_file::is_same_file(first.path().join("b.pbo"), second.path().join("b.pbo"))?);
+
+        assert_eq!(prune(store.path())?, 0);
+        std::fs::remove_file(first.path().join("b.pbo"))?;
+        assert_eq!(prune(store.path())?, 1);
+        Ok(())
+    }
+}
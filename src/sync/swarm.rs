@@ -0,0 +1,227 @@
+// src/sync/swarm.rs
+
+//! Swarm health from tracker scrapes.
+//!
+//! Trackers report how many seeders and leechers a torrent has. HTTP
+//! trackers answer at their scrape URL, the announce URL with `announce` in
+//! the last path segment swapped for `scrape`; UDP trackers answer the
+//! scrape action of BEP 15. The busiest answer counts, since trackers of
+//! one torrent each see part of the swarm.
+
+use anyhow::{anyhow, bail, Context, Result};
+use bencode::BencodeValue;
+use librqbit::ByteBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::messages::SyncEvent;
+use super::utils::send_sync_event;
+
+/// How long a tracker gets to answer.
+const SCRAPE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// BEP 15 connect magic.
+const UDP_PROTOCOL_ID: u64 = 0x41727101980;
+const UDP_ACTION_CONNECT: u32 = 0;
+const UDP_ACTION_SCRAPE: u32 = 2;
+const UDP_ACTION_ERROR: u32 = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwarmHealth {
+    pub seeders: u32,
+    pub leechers: u32,
+    /// Completed downloads the tracker has counted.
+    pub downloaded: u32,
+}
+
+impl SwarmHealth {
+    /// Nobody has the whole torrent, so a download may never finish.
+    pub fn is_empty(&self) -> bool {
+        self.seeders == 0
+    }
+}
+
+/// Scrape URL of an HTTP announce URL, `None` if the tracker doesn't
+/// follow the convention.
+pub fn scrape_url(announce: &str) -> Option<String> {
+    let (path, query) = announce.split_once('?').map_or((announce, None), |(path, query)| (path, Some(query)));
+    let slash = path.rfind('/')?;
+    let last = &path[slash + 1..];
+    if !last.starts_with("announce") {
+        return None;
+    }
+    let mut url = format!("{}/scrape{}", &path[..slash], &last["announce".len()..]);
+    if let Some(query) = query {
+        url.push('?');
+        url.push_str(query);
+    }
+    Some(url)
+}
+
+fn percent_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("%{:02X}", b)).collect()
+}
+
+type Dict<'a> = HashMap<ByteBuf<'a>, BencodeValue<ByteBuf<'a>>>;
+
+fn get<'d, 'a>(dict: &'d Dict<'a>, key: &[u8]) -> Option<&'d BencodeValue<ByteBuf<'a>>> {
+    dict.iter().find(|(k, _)| k.as_ref() == key).map(|(_, v)| v)
+}
+
+/// Counts for `info_hash` in an HTTP scrape response.
+pub fn parse_http_scrape(body: &[u8], info_hash: &[u8; 20]) -> Result<SwarmHealth> {
+    let value = bencode::dyn_from_bytes::<ByteBuf>(body).context("Invalid scrape response")?;
+    let BencodeValue::Dict(root) = value else {
+        bail!("Scrape response is not a dictionary");
+    };
+    if let Some(BencodeValue::Bytes(reason)) = get(&root, b"failure reason") {
+        bail!("Tracker refused the scrape: {}", String::from_utf8_lossy(reason.as_ref()));
+    }
+    let Some(BencodeValue::Dict(files)) = get(&root, b"files") else {
+        bail!("Scrape response has no files");
+    };
+    let Some(BencodeValue::Dict(stats)) = get(files, info_hash) else {
+        bail!("Tracker doesn't know the torrent");
+    };
+    let count = |key: &[u8]| match get(stats, key) {
+        Some(BencodeValue::Integer(n)) => u32::try_from(*n).unwrap_or(0),
+        _ => 0,
+    };
+    Ok(SwarmHealth { seeders: count(b"complete"), leechers: count(b"incomplete"), downloaded: count(b"downloaded") })
+}
+
+async fn scrape_http(http_client: &reqwest::Client, announce: &str, info_hash: &[u8; 20]) -> Result<SwarmHealth> {
+    let url = scrape_url(announce).ok_or_else(|| anyhow!("{} has no scrape URL", announce))?;
+    let separator = if url.contains('?') { '&' } else { '?' };
+    let body = http_client
+        .get(format!("{}{}info_hash={}", url, separator, percent_encode(info_hash)))
+        .timeout(SCRAPE_TIMEOUT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())?
+        .bytes()
+        .await?;
+    parse_http_scrape(&body, info_hash)
+}
+
+/// Counts in a UDP scrape response to transaction `transaction`.
+pub fn parse_udp_scrape(response: &[u8], transaction: u32) -> Result<SwarmHealth> {
+    let word = |at: usize| response.get(at..at + 4).map(|b| u32::from_be_bytes(b.try_into().unwrap()));
+    let (action, id) = word(0).zip(word(4)).ok_or_else(|| anyhow!("Short UDP scrape response"))?;
+    if id != transaction {
+        bail!("UDP scrape response to another request");
+    }
+    if action == UDP_ACTION_ERROR {
+        bail!("Tracker refused the scrape: {}", String::from_utf8_lossy(&response[8..]));
+    }
+    if action != UDP_ACTION_SCRAPE {
+        bail!("Unexpected UDP action {}", action);
+    }
+    let (seeders, downloaded, leechers) = word(8)
+        .zip(word(12))
+        .zip(word(16))
+        .map(|((s, d), l)| (s, d, l))
+        .ok_or_else(|| anyhow!("Short UDP scrape response"))?;
+    Ok(SwarmHealth { seeders, leechers, downloaded })
+}
+
+async fn scrape_udp(announce: &str, info_hash: &[u8; 20]) -> Result<SwarmHealth> {
+    let host = announce
+        .strip_prefix("udp://")
+        .and_then(|rest| rest.split('/').next())
+        .ok_or_else(|| anyhow!("Invalid UDP tracker URL {}", announce))?;
+    let socket = tokio::net::UdpSocket::bind("0.0.0.0:0").await?;
+    socket.connect(host).await.with_context(|| format!("Failed to resolve {}", host))?;
+    let transaction = std::process::id() ^ chrono::Utc::now().timestamp_subsec_nanos();
+    let exchange = async {
+        let mut request = UDP_PROTOCOL_ID.to_be_bytes().to_vec();
+        request.extend(UDP_ACTION_CONNECT.to_be_bytes());
+        request.extend(transaction.to_be_bytes());
+        socket.send(&request).await?;
+        let mut response = [0u8; 512];
+        let len = socket.recv(&mut response).await?;
+        if len < 16 || response[..4] != UDP_ACTION_CONNECT.to_be_bytes() || response[4..8] != transaction.to_be_bytes() {
+            bail!("Unexpected UDP connect response");
+        }
+        let mut request = response[8..16].to_vec();
+        request.extend(UDP_ACTION_SCRAPE.to_be_bytes());
+        request.extend(transaction.to_be_bytes());
+        request.extend(info_hash);
+        socket.send(&request).await?;
+        let len = socket.recv(&mut response).await?;
+        parse_udp_scrape(&response[..len], transaction)
+    };
+    tokio::time::timeout(SCRAPE_TIMEOUT, exchange).await.map_err(|_| anyhow!("{} didn't answer", announce))?
+}
+
+/// Scrape each tracker for `info_hash` and return the busiest answer,
+/// `None` when no tracker answered.
+pub async fn scrape(http_client: &reqwest::Client, trackers: &[String], info_hash: &[u8; 20]) -> Option<SwarmHealth> {
+    let mut best: Option<SwarmHealth> = None;
+    for tracker in trackers {
+        let result = if tracker.starts_with("udp://") {
+            scrape_udp(tracker, info_hash).await
+        } else {
+            scrape_http(http_client, tracker, info_hash).await
+        };
+        match result {
+            Ok(health) => {
+                println!("Sync: {} reports {} seeders, {} leechers", tracker, health.seeders, health.leechers);
+                if best.is_none_or(|best| (health.seeders, health.leechers) > (best.seeders, best.leechers)) {
+                    best = Some(health);
+                }
+            }
+            Err(e) => eprintln!("Sync: Failed to scrape {}: {:#}", tracker, e),
+        }
+    }
+    best
+}
+
+/// Trackers and info hash of torrent `id`, `None` when it has no trackers.
+pub fn scrape_target(api: &librqbit::Api, id: usize) -> Option<(Vec<String>, [u8; 20])> {
+    let handle = api.mgr_handle(id.into()).ok()?;
+    let trackers: Vec<String> = handle.shared().trackers.iter().map(|url| url.as_str().to_string()).collect();
+    (!trackers.is_empty()).then(|| (trackers, handle.info_hash().0))
+}
+
+/// Scrape the trackers of torrent `id` in the background and report the
+/// swarm it finds.
+pub fn spawn_scrape(api: &librqbit::Api, id: usize, http_client: reqwest::Client, ui_tx: mpsc::UnboundedSender<SyncEvent>) {
+    let Some((trackers, info_hash)) = scrape_target(api, id) else {
+        return;
+    };
+    tokio::spawn(async move {
+        let health = scrape(&http_client, &trackers, &info_hash).await;
+        send_sync_event(&ui_tx, SyncEvent::Swarm(health));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scrapes() -> Result<()> {
+        assert_eq!(scrape_url("http://t.example.com/announce").as_deref(), Some("http://t.example.com/scrape"));
+        assert_eq!(scrape_url("https://t.example.com/x/announce.php?pk=1").as_deref(), Some("https://t.example.com/x/scrape.php?pk=1"));
+        assert_eq!(scrape_url("http://t.example.com/a"), None);
+
+        let hash = [7u8; 20];
+        let mut body = b"d5:filesd20:".to_vec();
+        body.extend(hash);
+        body.extend(b"d8:completei3e10:downloadedi40e10:incompletei5eeee");
+        assert_eq!(parse_http_scrape(&body, &hash)?, SwarmHealth { seeders: 3, leechers: 5, downloaded: 40 });
+        assert!(parse_http_scrape(b"d14:failure reason6:bannede", &hash).is_err());
+        assert!(parse_http_scrape(&body, &[0u8; 20]).is_err());
+
+        let mut udp = Vec::new();
+        for word in [UDP_ACTION_SCRAPE, 9, 0, 12, 2] {
+            udp.extend(word.to_be_bytes());
+        }
+        assert_eq!(parse_udp_scrape(&udp, 9)?, SwarmHealth { seeders: 0, leechers: 2, downloaded: 12 });
+        assert!(parse_udp_scrape(&udp, 8).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+// src/sync/telemetry.rs
+
+//! Opt-in completion reports for repository admins.
+//!
+//! When a download finishes, a client that opted in posts which torrent it
+//! reached, how long it took and how much it transferred to the admin's
+//! endpoint. Reports carry a random id kept in the cache folder so an admin
+//! can count members, and nothing else about the computer: no paths, user
+//! names or settings. The endpoint still sees the address a report comes
+//! from.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::hash::{BuildHasher, Hasher};
+use std::path::Path;
+use std::time::Duration;
+
+use super::types::SyncConfig;
+
+/// Random client id inside the cache folder.
+const CLIENT_ID_FILE_NAME: &str = "telemetry_id";
+
+const REPORT_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// URL the admin collects reports at.
+    pub endpoint: String,
+}
+
+impl TelemetryConfig {
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.endpoint.trim().is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletionReport {
+    /// Random id of this install, the same in every report.
+    pub client_id: String,
+    /// Info hash of the torrent the download reached.
+    pub info_hash: String,
+    pub name: Option<String>,
+    pub duration_secs: u64,
+    pub downloaded_bytes: u64,
+    pub uploaded_bytes: u64,
+    pub total_bytes: u64,
+    pub modsync_version: String,
+}
+
+/// 128 random bits as hex, from the standard library's randomly keyed
+/// hasher.
+fn random_id() -> String {
+    (0..2)
+        .map(|_| format!("{:016x}", std::collections::hash_map::RandomState::new().build_hasher().finish()))
+        .collect()
+}
+
+/// The id kept in `dir`, created on first use.
+fn client_id(dir: &Path) -> Result<String> {
+    let path = dir.join(CLIENT_ID_FILE_NAME);
+    if let Ok(id) = std::fs::read_to_string(&path)
+        && !id.trim().is_empty()
+    {
+        return Ok(id.trim().to_string());
+    }
+    let id = random_id();
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(&path, &id).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(id)
+}
+
+/// Report of the finished torrent `id`, which took `duration`.
+pub fn completion_report(config: &SyncConfig, api: &librqbit::Api, id: usize, duration: Duration) -> Result<CompletionReport> {
+    let dir = config.cache_dir.as_ref().context("No cache folder to keep the client id in")?;
+    let handle = api.mgr_handle(id.into())?;
+    let stats = handle.stats();
+    Ok(CompletionReport {
+        client_id: client_id(dir)?,
+        info_hash: handle.info_hash().as_string(),
+        name: handle.name(),
+        duration_secs: duration.as_secs(),
+        downloaded_bytes: stats.live.as_ref().map_or(0, |live| live.snapshot.fetched_bytes),
+        uploaded_bytes: stats.uploaded_bytes,
+        total_bytes: stats.total_bytes,
+        modsync_version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+/// Post a completion report in the background when the user opted in.
+/// Failures are logged; a missed report is not retried.
+pub fn spawn_report(config: &SyncConfig, api: &librqbit::Api, id: usize, duration: Duration, http_client: reqwest::Client) {
+    if !config.telemetry.is_active() {
+        return;
+    }
+    let report = match completion_report(config, api, id, duration) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Sync: Failed to prepare the completion report: {:#}", e);
+            return;
+        }
+    };
+    let endpoint = config.telemetry.endpoint.trim().to_string();
+    tokio::spawn(async move {
+        let sent = http_client.post(&endpoint).json(&report).timeout(REPORT_TIMEOUT).send().await.and_then(|response| response.error_for_status());
+        match sent {
+            Ok(_) => println!("Sync: Sent the completion report"),
+            Err(e) => eprintln!("Sync: Failed to send the completion report: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_id_is_kept() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let id = client_id(dir.path())?;
+        assert_eq!(id.len(), 32);
+        assert_eq!(client_id(dir.path())?, id);
+        assert_ne!(random_id(), id);
+        assert!(!TelemetryConfig { enabled: true, endpoint: " ".to_string() }.is_active());
+        Ok(())
+    }
+}
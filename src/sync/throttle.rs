@@ -0,0 +1,223 @@
+// src/sync/throttle.rs
+
+//! Gentler hash checks of the download folder.
+//!
+//! librqbit reads every piece of the torrent when it is added, which can
+//! make a game on the same disk stutter. `storage_factory` wraps the storage
+//! so reads during that check are capped in MB/s and, where the platform
+//! has I/O priorities, issued at idle priority. librqbit hands the storage
+//! on with `take` once the check is done, which returns the unthrottled
+//! storage, so downloading and seeding run at full speed.
+
+use anyhow::Result;
+use librqbit::storage::filesystem::FilesystemStorageFactory;
+use librqbit::storage::{BoxStorageFactory, StorageFactory, StorageFactoryExt, TorrentStorage};
+use librqbit::{ManagedTorrentShared, TorrentMetadata};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How a hash check reads the download folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CheckLimits {
+    /// Read cap in MB/s, unlimited when unset.
+    pub max_mb_per_sec: Option<u32>,
+    /// Read at idle I/O priority where the platform supports it.
+    pub low_io_priority: bool,
+}
+
+impl CheckLimits {
+    fn is_unlimited(&self) -> bool {
+        self.max_mb_per_sec.is_none_or(|mb| mb == 0) && !self.low_io_priority
+    }
+}
+
+/// Wrap `inner`, or the default filesystem storage when `None`, so hash
+/// checks honour `limits`. Returns `inner` unchanged when nothing is limited.
+pub fn storage_factory(inner: Option<BoxStorageFactory>, limits: CheckLimits) -> Option<BoxStorageFactory> {
+    if limits.is_unlimited() {
+        return inner;
+    }
+    let inner = inner.unwrap_or_else(|| FilesystemStorageFactory::default().boxed());
+    Some(CheckStorageFactory { inner: Arc::new(inner), limits }.boxed())
+}
+
+#[derive(Clone)]
+struct CheckStorageFactory {
+    inner: Arc<BoxStorageFactory>,
+    limits: CheckLimits,
+}
+
+impl StorageFactory for CheckStorageFactory {
+    type Storage = CheckStorage;
+
+    fn create(&self, shared: &ManagedTorrentShared, metadata: &TorrentMetadata) -> Result<CheckStorage> {
+        Ok(CheckStorage {
+            inner: self.inner.create(shared, metadata)?,
+            limiter: self.limits.max_mb_per_sec.filter(|&mb| mb > 0).map(|mb| ReadLimiter::new(u64::from(mb) * 1024 * 1024)),
+            low_io_priority: self.limits.low_io_priority,
+        })
+    }
+
+    fn clone_box(&self) -> BoxStorageFactory {
+        self.clone().boxed()
+    }
+}
+
+struct CheckStorage {
+    inner: Box<dyn TorrentStorage>,
+    limiter: Option<ReadLimiter>,
+    low_io_priority: bool,
+}
+
+impl TorrentStorage for CheckStorage {
+    fn init(&mut self, shared: &ManagedTorrentShared, metadata: &TorrentMetadata) -> Result<()> {
+        self.inner.init(shared, metadata)
+    }
+
+    fn pread_exact(&self, file_id: usize, offset: u64, buf: &mut [u8]) -> Result<()> {
+        if let Some(limiter) = &self.limiter {
+            limiter.wait(buf.len() as u64);
+        }
+        let _priority = self.low_io_priority.then(io_priority::IdleGuard::enter);
+        self.inner.pread_exact(file_id, offset, buf)
+    }
+
+    fn pwrite_all(&self, file_id: usize, offset: u64, buf: &[u8]) -> Result<()> {
+        self.inner.pwrite_all(file_id, offset, buf)
+    }
+
+    fn remove_file(&self, file_id: usize, filename: &Path) -> Result<()> {
+        self.inner.remove_file(file_id, filename)
+    }
+
+    fn remove_directory_if_empty(&self, path: &Path) -> Result<()> {
+        self.inner.remove_directory_if_empty(path)
+    }
+
+    fn ensure_file_length(&self, file_id: usize, length: u64) -> Result<()> {
+        self.inner.ensure_file_length(file_id, length)
+    }
+
+    /// Called when the check is done; the torrent goes on with the plain
+    /// storage.
+    fn take(&self) -> Result<Box<dyn TorrentStorage>> {
+        self.inner.take()
+    }
+}
+
+/// Paces reads to a rate by sleeping the reading thread, which is one of
+/// librqbit's blocking workers during a check.
+struct ReadLimiter {
+    bytes_per_sec: u64,
+    /// Start of the current window and the bytes read in it.
+    window: Mutex<(Instant, u64)>,
+}
+
+impl ReadLimiter {
+    fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec,
+            window: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    /// Account for `bytes` about to be read and sleep until reading them
+    /// keeps within the rate.
+    fn wait(&self, bytes: u64) {
+        let delay = {
+            let mut window = self.window.lock().unwrap();
+            // Start over after idling so a pause doesn't bank a burst
+            if window.0.elapsed() > Duration::from_secs(1) && window.1 <= self.bytes_per_sec {
+                *window = (Instant::now(), 0);
+            }
+            window.1 += bytes;
+            let due = Duration::from_secs_f64(window.1 as f64 / self.bytes_per_sec as f64);
+            due.saturating_sub(window.0.elapsed())
+        };
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod io_priority {
+    //! Per-thread I/O priority through `ioprio_set(2)`.
+
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+
+    /// Runs the calling thread at idle I/O priority until dropped.
+    pub struct IdleGuard {
+        previous: Option<libc::c_long>,
+    }
+
+    impl IdleGuard {
+        pub fn enter() -> Self {
+            // Safety: plain syscalls on the calling thread (`who` 0)
+            let previous = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, 0) };
+            let idle = IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT;
+            let set = unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, idle) };
+            Self {
+                previous: (previous >= 0 && set == 0).then_some(previous),
+            }
+        }
+    }
+
+    impl Drop for IdleGuard {
+        fn drop(&mut self) {
+            if let Some(previous) = self.previous {
+                // Safety: as above
+                unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, previous) };
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod io_priority {
+    //! No I/O priority hint on this platform; only the MB/s cap applies.
+
+    pub struct IdleGuard;
+
+    impl IdleGuard {
+        pub fn enter() -> Self {
+            static WARNED: std::sync::Once = std::sync::Once::new();
+            WARNED.call_once(|| println!("Sync: Low I/O priority isn't supported on this platform"));
+            Self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_limiter_paces_reads() {
+        let limiter = ReadLimiter::new(1000);
+        let start = Instant::now();
+        limiter.wait(100);
+        limiter.wait(100);
+        // 200 bytes at 1000 B/s take 0.2 s
+        assert!(start.elapsed() >= Duration::from_millis(190));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_unlimited_keeps_factory() {
+        assert!(storage_factory(None, CheckLimits::default()).is_none());
+        let limits = CheckLimits {
+            max_mb_per_sec: Some(0),
+            low_io_priority: false,
+        };
+        assert!(storage_factory(None, limits).is_none());
+        let limits = CheckLimits {
+            max_mb_per_sec: Some(50),
+            low_io_priority: false,
+        };
+        assert!(storage_factory(None, limits).is_some());
+    }
+}
@@ -2,19 +2,89 @@
 
 use super::types::SyncConfig;
 use crate::sync::status::SyncStatus;
+use crate::sync::error::SyncError;
 use crate::sync::messages::SyncEvent;
-use anyhow::{Context, Result};
+use crate::sync::snapshot::TorrentFile;
+use anyhow::{anyhow, bail, Context, Result};
+use librqbit::storage::BoxStorageFactory;
 use librqbit::{AddTorrent, AddTorrentOptions};
 use tokio::sync::mpsc;
-use librqbit::limits::LimitsConfig;
 use std::num::NonZeroU32;
 
+use super::backend::Backends;
+use super::filters;
+use super::mapping;
+use super::memory;
+use super::store;
+use super::throttle::{self, CheckLimits};
+use super::trackers;
 use super::utils::send_sync_status_event;
 
+/// Smallest and largest piece length accepted, 16 KiB to 64 MiB.
+const PIECE_LENGTH_RANGE: std::ops::RangeInclusive<u32> = (16 * 1024)..=(64 * 1024 * 1024);
+
+/// Check that `content` is a torrent worth caching or adding: it must
+/// parse, list at least one file and have a sane piece length and a piece
+/// hash for every piece. Catches error pages served in place of a torrent.
+pub fn validate_torrent(content: &[u8]) -> Result<()> {
+    if content.is_empty() {
+        bail!("Torrent file is empty");
+    }
+    if content[0] != b'd' {
+        let start = String::from_utf8_lossy(&content[..content.len().min(256)]).to_ascii_lowercase();
+        if start.trim_start().starts_with('<') || start.contains("<html") {
+            bail!("Got an HTML page instead of a torrent file");
+        }
+        bail!("Not a torrent file: it is not a bencoded dictionary");
+    }
+    let meta = librqbit::torrent_from_bytes::<librqbit::ByteBuf>(content).map_err(|e| anyhow!("Not a valid torrent file: {:#}", e))?;
+    let info = &meta.info;
+    if !PIECE_LENGTH_RANGE.contains(&info.piece_length) || !info.piece_length.is_power_of_two() {
+        bail!("Torrent has an invalid piece length of {} bytes", info.piece_length);
+    }
+    let lengths: Vec<u64> = info.iter_file_lengths().context("Torrent lists no files")?.collect();
+    let total = lengths.iter().try_fold(0u64, |total, &length| total.checked_add(length)).context("Torrent files add up to more than 16 EiB")?;
+    if lengths.is_empty() || total == 0 {
+        bail!("Torrent lists no files");
+    }
+    let expected_pieces = total.div_ceil(u64::from(info.piece_length));
+    if info.pieces.len() as u64 != expected_pieces * 20 {
+        bail!(
+            "Torrent has {} bytes of piece hashes, expected {} for {} pieces",
+            info.pieces.len(),
+            expected_pieces * 20,
+            expected_pieces
+        );
+    }
+    Ok(())
+}
+
+/// Set the session's speed limits in KB/s, `None` for unlimited. Takes
+/// effect right away for every torrent of the session.
+pub fn apply_speed_limits(api: &librqbit::api::Api, max_upload_speed: Option<u32>, max_download_speed: Option<u32>) {
+    let bps = |kbps: Option<u32>| kbps.and_then(|s| NonZeroU32::new(s.saturating_mul(1024)));
+    let limits = &api.session().ratelimits;
+    limits.set_upload_bps(bps(max_upload_speed));
+    limits.set_download_bps(bps(max_download_speed));
+}
+
+/// Storage for torrents of `config`: its folder mappings, piece cache and
+/// hash check limits, or `None` for librqbit's plain filesystem storage.
+pub fn storage_factory(config: &SyncConfig) -> Option<BoxStorageFactory> {
+    throttle::storage_factory(
+        memory::storage_factory(mapping::storage_factory(&config.folder_mappings), config.piece_cache_mb),
+        CheckLimits {
+            max_mb_per_sec: config.check_max_mb_per_sec,
+            low_io_priority: config.check_low_io_priority,
+        },
+    )
+}
+
 pub async fn manage_torrent_task(
     app_config: &SyncConfig,
     api: &librqbit::api::Api,
     ui_tx: &mpsc::UnboundedSender<SyncEvent>,
+    backends: &Backends,
     current_id_to_forget: Option<usize>,
     torrent_content: Vec<u8>,
 ) -> Result<Option<usize>> {
@@ -25,6 +95,18 @@ pub async fn manage_torrent_task(
         current_id_to_forget
     );
 
+    // A refused torrent leaves the current one running
+    if let Err(e) = validate_torrent(&torrent_content) {
+        let err_msg = format!("Refusing to add torrent: {:#}", e);
+        eprintln!("Sync: {}", err_msg);
+        return Err(SyncError::TorrentParse(err_msg).into());
+    }
+
+    if app_config.download_path.as_os_str().is_empty() {
+        println!("Sync: Download path is empty, cannot add torrent.");
+        return Err(SyncError::Config("Download path not configured".to_string()).into());
+    }
+
     if let Some(id_to_forget) = current_id_to_forget {
         println!("Sync: Forgetting previous torrent ID: {}", id_to_forget);
         send_sync_status_event(ui_tx, SyncStatus::UpdatingTorrent);
@@ -40,7 +122,7 @@ pub async fn manage_torrent_task(
                     id_to_forget,
                     e
                 );
-                 let _ = ui_tx.send(SyncEvent::Error(format!("Error forgetting old torrent {}: {}", id_to_forget, e)));
+                 let _ = ui_tx.send(SyncEvent::Error(SyncError::Engine(format!("Error forgetting old torrent {}: {}", id_to_forget, e))));
             }
         }
     }
@@ -51,34 +133,49 @@ pub async fn manage_torrent_task(
         app_config.download_path.display()
     );
 
-    if app_config.download_path.as_os_str().is_empty() {
-        println!("Sync: Download path is empty, cannot add torrent.");
-        let err_msg = "Download path not configured".to_string();
-        let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
-        send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
-        return Ok(None);
+    send_sync_status_event(ui_tx, SyncStatus::UpdatingTorrent);
+
+    let transfer = match backends.transport_for(app_config.engine) {
+        Ok(transport) => transport.fetch_payload(app_config).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = transfer {
+        // Adding the torrent still shows what is missing, and the swarm
+        // may be able to provide it
+        let err_msg = format!("{:?} transfer failed: {:#}", app_config.engine, e);
+        eprintln!("Sync: {}", err_msg);
+        let _ = ui_tx.send(SyncEvent::Error(SyncError::caused_by(SyncError::Network, err_msg, &*e)));
     }
 
-    send_sync_status_event(ui_tx, SyncStatus::UpdatingTorrent);
+    store::link_from_store(app_config, &torrent_content).await;
 
-    let add_request = AddTorrent::from_bytes(torrent_content);
-    
-    let ratelimits = LimitsConfig {
-        download_bps: app_config.max_download_speed.and_then(|s| {
-            let value = (s * 1024) as u32;
-            NonZeroU32::new(value)
-        }),
-        upload_bps: app_config.max_upload_speed.and_then(|s| {
-            let value = (s * 1024) as u32;
-            NonZeroU32::new(value)
-        }),
+    let only_files = match filters::selected_files(&app_config.download_filters, &torrent_content) {
+        Ok(only_files) => only_files,
+        Err(e) => {
+            eprintln!("Sync: Download filters not applied: {:#}", e);
+            None
+        }
     };
-    
+    if let Some(files) = &only_files {
+        println!("Sync: Download filters select {} files", files.len());
+    }
+
+    let trackers = trackers::apply(app_config, torrent_content)?;
+    if let Some(extra) = &trackers.extra {
+        println!("Sync: {} trackers: {:?}", if app_config.replace_trackers { "Replacing the" } else { "Adding" }, extra);
+    }
+    let add_request = AddTorrent::from_bytes(trackers.torrent);
+
+    // Speed limits are kept on the session by the manager rather than on
+    // the torrent, so they can be changed while it runs
     let options = AddTorrentOptions {
     output_folder: Some(app_config.download_path.to_string_lossy().into_owned()),
         overwrite: true,
         paused: !app_config.should_seed,
-        ratelimits,
+        storage_factory: storage_factory(app_config),
+        only_files,
+        trackers: trackers.extra,
+        initial_peers: Some(app_config.peers.clone()).filter(|peers| !peers.is_empty()),
         ..Default::default()
     };
 
@@ -96,14 +193,50 @@ pub async fn manage_torrent_task(
 
     if let Some(id) = response.id {
         println!("Sync: Torrent added successfully with ID: {}", id);
+        if super::local::is_private(api, id) {
+            println!("Sync: Torrent {} is private, peers only come from its tracker", id);
+        }
         let _ = ui_tx.send(SyncEvent::TorrentAdded(id));
+        let _ = ui_tx.send(SyncEvent::Trackers(trackers.list));
+        match api.api_torrent_details(id.into()) {
+            Ok(details) => {
+                let _ = ui_tx.send(SyncEvent::TorrentFiles(TorrentFile::list_from_details(&details, &app_config.folder_mappings)));
+            }
+            Err(e) => eprintln!("Sync: Failed to list files of torrent {}: {}", id, e),
+        }
         send_sync_status_event(ui_tx, SyncStatus::Idle);
         Ok(Some(id))
     } else {
         println!("Sync: Torrent added but no ID returned by API.");
         let err_msg = "Torrent added but API returned no ID".to_string();
-        let _ = ui_tx.send(SyncEvent::Error(err_msg.clone()));
+        let _ = ui_tx.send(SyncEvent::Error(SyncError::Engine(err_msg.clone())));
         send_sync_status_event(ui_tx, SyncStatus::Error(err_msg));
         Ok(None)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Single-file torrent with `pieces` bytes of piece hashes.
+    fn torrent(length: u64, piece_length: u32, pieces: usize) -> Vec<u8> {
+        let mut t = format!("d4:infod6:lengthi{}e4:name5:a.pbo12:piece lengthi{}e6:pieces{}:", length, piece_length, pieces).into_bytes();
+        t.extend(std::iter::repeat_n(0u8, pieces));
+        t.extend(b"ee");
+        t
+    }
+
+    #[test]
+    fn test_validate_torrent() {
+        assert!(validate_torrent(&torrent(40_000, 16384, 60)).is_ok());
+
+        let html = validate_torrent(b"<!DOCTYPE html><html>Not Found</html>").unwrap_err();
+        assert!(html.to_string().contains("HTML"));
+        assert!(validate_torrent(b"").is_err());
+        assert!(validate_torrent(b"d4:infoe").is_err());
+        assert!(validate_torrent(&torrent(40_000, 1000, 60)).is_err());
+        assert!(validate_torrent(&torrent(40_000, 16384, 40)).is_err());
+        assert!(validate_torrent(&torrent(0, 16384, 0)).is_err());
+    }
+}
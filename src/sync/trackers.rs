@@ -0,0 +1,165 @@
+// src/sync/trackers.rs
+
+//! Trackers configured on top of the torrent's own announce URLs.
+//!
+//! Communities move trackers without re-issuing their torrents, so the
+//! config can add announce URLs or replace the torrent's. Replacing strips
+//! `announce` and `announce-list` from the torrent handed to librqbit; the
+//! info dictionary, and so the info hash, is left byte for byte as it was.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::types::SyncConfig;
+
+/// Where a tracker of the loaded torrent comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrackerSource {
+    /// Announce URL in the torrent.
+    Torrent,
+    /// Added by the config.
+    Config,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackerInfo {
+    pub url: String,
+    pub source: TrackerSource,
+    /// `false` for a torrent tracker the config replaced.
+    pub active: bool,
+}
+
+/// Announce URLs of `torrent`, each once.
+pub fn announce_urls(torrent: &[u8]) -> Result<Vec<String>> {
+    let meta = librqbit::torrent_from_bytes::<librqbit::ByteBuf>(torrent)?;
+    let mut urls: Vec<String> = Vec::new();
+    for url in meta.iter_announce() {
+        let url = String::from_utf8_lossy(url.as_ref()).into_owned();
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    Ok(urls)
+}
+
+/// Configured tracker URLs, trimmed and without blank lines.
+fn configured(config: &SyncConfig) -> Vec<String> {
+    config.extra_trackers.iter().map(|url| url.trim().to_string()).filter(|url| !url.is_empty()).collect()
+}
+
+/// A torrent with the configured trackers applied.
+pub struct Applied {
+    /// The torrent to hand to librqbit.
+    pub torrent: Vec<u8>,
+    /// Trackers for librqbit to add to the torrent's.
+    pub extra: Option<Vec<String>>,
+    /// Trackers to show.
+    pub list: Vec<TrackerInfo>,
+}
+
+pub fn apply(config: &SyncConfig, torrent: Vec<u8>) -> Result<Applied> {
+    let own = announce_urls(&torrent)?;
+    let extra = configured(config);
+    let replace = config.replace_trackers && !extra.is_empty();
+    let mut list: Vec<TrackerInfo> = own
+        .iter()
+        .map(|url| TrackerInfo { url: url.clone(), source: TrackerSource::Torrent, active: !replace })
+        .collect();
+    list.extend(extra.iter().map(|url| TrackerInfo { url: url.clone(), source: TrackerSource::Config, active: true }));
+    let torrent = if replace && !own.is_empty() { strip_announce(&torrent)? } else { torrent };
+    Ok(Applied { torrent, extra: Some(extra).filter(|extra| !extra.is_empty()), list })
+}
+
+/// Deepest list or dictionary nesting `value_len` walks into, so a crafted
+/// torrent can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Length of the bencoded value at the start of `data`.
+fn value_len(data: &[u8], depth: usize) -> Result<usize> {
+    let truncated = || anyhow!("Truncated bencode");
+    match data.first().ok_or_else(truncated)? {
+        b'i' => Ok(data.iter().position(|&b| b == b'e').ok_or_else(truncated)? + 1),
+        b'l' | b'd' => {
+            if depth >= MAX_DEPTH {
+                bail!("Bencode nested too deeply");
+            }
+            let mut at = 1;
+            while *data.get(at).ok_or_else(truncated)? != b'e' {
+                at += value_len(&data[at..], depth + 1)?;
+            }
+            Ok(at + 1)
+        }
+        b'0'..=b'9' => {
+            let colon = data.iter().position(|&b| b == b':').ok_or_else(truncated)?;
+            let len: usize = std::str::from_utf8(&data[..colon])?.parse().context("Invalid bencode string length")?;
+            match (colon + 1).checked_add(len) {
+                Some(end) if end <= data.len() => Ok(end),
+                _ => Err(truncated()),
+            }
+        }
+        other => bail!("Unexpected byte {:?} in bencode", *other as char),
+    }
+}
+
+/// `torrent` without its top-level `announce` and `announce-list` keys.
+pub fn strip_announce(torrent: &[u8]) -> Result<Vec<u8>> {
+    if torrent.first() != Some(&b'd') {
+        bail!("Torrent is not a bencoded dictionary");
+    }
+    let mut stripped = vec![b'd'];
+    let mut at = 1;
+    while *torrent.get(at).ok_or_else(|| anyhow!("Truncated bencode"))? != b'e' {
+        let key_len = value_len(&torrent[at..], 1)?;
+        let value_len = value_len(&torrent[at + key_len..], 1)?;
+        let key = &torrent[at..at + key_len];
+        if key != b"8:announce" && key != b"13:announce-list" {
+            stripped.extend_from_slice(&torrent[at..at + key_len + value_len]);
+        }
+        at += key_len + value_len;
+    }
+    stripped.push(b'e');
+    Ok(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACKER: &str = "https://old.example.com/announce";
+
+    fn torrent() -> Vec<u8> {
+        let mut t = format!("d8:announce{}:{}13:announce-listll{}:{}ee4:info", TRACKER.len(), TRACKER, TRACKER.len(), TRACKER).into_bytes();
+        t.extend(b"d6:lengthi5e4:name5:a.pbo12:piece lengthi16384e6:pieces20:");
+        t.extend([0u8; 20]);
+        t.extend(b"6:source3:modee");
+        t
+    }
+
+    #[test]
+    fn test_replace_trackers_keeps_info_hash() -> Result<()> {
+        let original = torrent();
+        let hash = librqbit::torrent_from_bytes::<librqbit::ByteBuf>(&original)?.info_hash;
+        let mut config = SyncConfig { extra_trackers: vec![" https://new.example.com/announce ".to_string(), String::new()], ..SyncConfig::default() };
+
+        let appended = apply(&config, original.clone())?;
+        assert_eq!(appended.torrent, original);
+        assert_eq!(appended.extra, Some(vec!["https://new.example.com/announce".to_string()]));
+        assert!(appended.list.iter().all(|t| t.active));
+
+        config.replace_trackers = true;
+        let replaced = apply(&config, original.clone())?;
+        assert!(announce_urls(&replaced.torrent)?.is_empty());
+        assert_eq!(librqbit::torrent_from_bytes::<librqbit::ByteBuf>(&replaced.torrent)?.info_hash, hash);
+        assert_eq!(replaced.list[0], TrackerInfo { url: TRACKER.to_string(), source: TrackerSource::Torrent, active: false });
+        assert!(strip_announce(b"d8:announce").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_strip_announce_rejects_hostile_bencode() {
+        let mut deep = b"d4:info".to_vec();
+        deep.extend(std::iter::repeat_n(b'l', 100_000));
+        assert!(strip_announce(&deep).is_err());
+        assert!(strip_announce(b"d18446744073709551615:xe").is_err());
+    }
+}
@@ -19,6 +19,11 @@ pub enum RemoteTorrentState {
 pub struct SyncState {
     pub local: LocalTorrentState,
     pub remote: RemoteTorrentState,
+    /// URL that served the remote torrent last time; tried first next time.
+    pub last_good_url: Option<String>,
+    /// Wait the remote asked for with `Retry-After` when the last check
+    /// failed.
+    pub retry_after: Option<Duration>,
 }
 
 impl Default for SyncState {
@@ -26,18 +31,47 @@ impl Default for SyncState {
         SyncState {
             local: LocalTorrentState::NotLoaded,
             remote: RemoteTorrentState::Unknown,
+            last_good_url: None,
+            retry_after: None,
         }
     }
 }
 
+use librqbit::api::TorrentDetailsResponse;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
+use std::time::Duration;
+
+use super::credentials::CredentialStore;
+use super::git::GitSourceConfig;
+use super::extract::{ExtractManifest, ExtractRule, MANIFEST_FILE_NAME};
+use super::filters::{excluded_files, DownloadFilter};
+use super::mapping::FolderMapping;
+use super::power::PowerConfig;
+use super::seed_window::SeedWindow;
+use super::discord::DiscordConfig;
+use super::telemetry::TelemetryConfig;
+use super::hooks::HooksConfig;
+use super::http::RedirectConfig;
+use super::ipfs::IpfsConfig;
+use super::rsync::{RsyncConfig, TransferEngine};
+use super::s3::S3Config;
+use super::sftp::SftpConfig;
 
 /// Minimal config used by the sync subsystem. This replaces the previous
 /// dependency on the top-level `AppConfig` and keeps the sync crate
 /// independent from the old system.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncConfig {
     pub torrent_url: String,
+    /// Mirrors of `torrent_url`, tried in order when it can't be fetched.
+    #[serde(default)]
+    pub torrent_mirrors: Vec<String>,
+    /// Endpoint returning the current version, put in place of `{latest}`
+    /// in the torrent URLs.
+    #[serde(default)]
+    pub version_url: String,
     pub download_path: PathBuf,
     pub max_upload_speed: Option<u32>,
     pub max_download_speed: Option<u32>,
@@ -46,17 +80,286 @@ pub struct SyncConfig {
     /// sync subsystem will not try to discover this itself; it must be
     /// supplied by the client if desired.
     pub cached_torrent_path: Option<PathBuf>,
+    /// Paths relative to the download folder that the cleaner leaves alone:
+    /// files at or below them are never reported as extra.
+    #[serde(default)]
+    pub ignored_paths: Vec<PathBuf>,
+    /// Optional file the client persists lifetime transfer totals to.
+    /// Totals are only kept for the session when unset.
+    #[serde(default)]
+    pub lifetime_stats_path: Option<PathBuf>,
+    #[serde(default)]
+    pub redirects: RedirectConfig,
+    /// Storage used for `s3://bucket/key` torrent URLs.
+    #[serde(default)]
+    pub s3: S3Config,
+    /// Login used for `sftp://` torrent URLs.
+    #[serde(default)]
+    pub sftp: SftpConfig,
+    /// Logins for remote sources that need one, such as WebDAV shares.
+    #[serde(default)]
+    pub credentials: CredentialStore,
+    /// Gateway used for `ipfs://` torrent URLs.
+    #[serde(default)]
+    pub ipfs: IpfsConfig,
+    /// Git repository pulled into a subfolder on every remote check.
+    #[serde(default)]
+    pub git_source: GitSourceConfig,
+    /// How the payload files are transferred.
+    #[serde(default)]
+    pub engine: TransferEngine,
+    /// Server used by the rsync engine.
+    #[serde(default)]
+    pub rsync: RsyncConfig,
+    /// Shell commands run around updates and verifications.
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Automation script loaded when the client starts; needs the
+    /// `scripting` feature.
+    #[serde(default)]
+    pub script_path: Option<PathBuf>,
+    /// Archives unpacked once the torrent has finished downloading.
+    #[serde(default)]
+    pub extract: Vec<ExtractRule>,
+    /// Folder the mods' `.bikey` files are collected into, such as a
+    /// server's `keys` folder.
+    #[serde(default)]
+    pub keys_dir: Option<PathBuf>,
+    /// Subfolders the torrent or its top-level folders are stored in.
+    #[serde(default)]
+    pub folder_mappings: Vec<FolderMapping>,
+    /// Torrent files that are never downloaded, see `sync::filters`.
+    #[serde(default)]
+    pub download_filters: Vec<DownloadFilter>,
+    /// Folder keeping every torrent version seen, see `sync::cache`.
+    #[serde(default)]
+    pub cache_dir: Option<PathBuf>,
+    /// Size cap of `cache_dir` in MiB; unlimited when unset.
+    #[serde(default)]
+    pub cache_max_mb: Option<u64>,
+    /// Torrent versions kept in `cache_dir` to roll back to; unlimited
+    /// when unset.
+    #[serde(default)]
+    pub cache_versions: Option<usize>,
+    /// What to do on a metered connection or battery power.
+    #[serde(default)]
+    pub power: PowerConfig,
+    /// Hours in which the finished torrent seeds; all day when unset.
+    #[serde(default)]
+    pub seed_window: Option<SeedWindow>,
+    /// Peer addresses refused by the session, see `peers`.
+    #[serde(default)]
+    pub banned_peers: Vec<IpAddr>,
+    /// Peers connected to directly when the torrent is added, such as a
+    /// seeder on the LAN, on top of those from trackers and DHT.
+    #[serde(default)]
+    pub peers: Vec<SocketAddr>,
+    /// Announce URLs added to the torrent's, see `trackers`.
+    #[serde(default)]
+    pub extra_trackers: Vec<String>,
+    /// Use `extra_trackers` instead of the torrent's announce URLs.
+    #[serde(default)]
+    pub replace_trackers: bool,
+    /// Minutes without progress before a download counts as stalled,
+    /// `stall::DEFAULT_STALL_MINUTES` when unset and off at zero.
+    #[serde(default)]
+    pub stall_minutes: Option<u64>,
+    /// Most seconds each periodic remote check is pushed back at random,
+    /// `schedule::DEFAULT_JITTER_SECS` when unset.
+    #[serde(default)]
+    pub check_jitter_secs: Option<u64>,
+    /// Window in seconds the first check after starting is spread over.
+    #[serde(default)]
+    pub check_splay_secs: Option<u64>,
+    /// Rich Presence in the Discord client, see `discord`.
+    #[serde(default)]
+    pub discord: DiscordConfig,
+    /// Opt-in completion reports to the admin, see `telemetry`.
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    /// Upload cap in KB/s while gaming mode is on, `GAMING_UPLOAD_SPEED`
+    /// when unset.
+    #[serde(default)]
+    pub gaming_upload_speed: Option<u32>,
+    /// Read cap in MB/s while the torrent's files are hash checked;
+    /// unlimited when unset.
+    #[serde(default)]
+    pub check_max_mb_per_sec: Option<u32>,
+    /// Hash check at idle I/O priority where the platform supports it.
+    #[serde(default)]
+    pub check_low_io_priority: bool,
+    /// Memory in MiB buffering disk writes; writes go straight to disk when
+    /// unset. Applies when the session starts.
+    #[serde(default)]
+    pub write_buffer_mb: Option<usize>,
+    /// Memory in MiB caching written pieces so verifying them needs no read.
+    #[serde(default)]
+    pub piece_cache_mb: Option<u64>,
+    /// Torrents hash checked at once, `memory::DEFAULT_CONCURRENT_CHECKS`
+    /// when unset. Applies when the session starts.
+    #[serde(default)]
+    pub concurrent_checks: Option<usize>,
+    /// Threads hashing pieces, half the cores when unset. Sizes the runtime,
+    /// so only the daemon and `blocking::sync_once` honour it.
+    #[serde(default)]
+    pub hashing_threads: Option<usize>,
+    /// Remember which pieces were verified so a restart skips the full
+    /// check. Applies when the session starts.
+    #[serde(default)]
+    pub fastresume: bool,
+    /// Folder of the fastresume state, librqbit's default when unset.
+    #[serde(default)]
+    pub fastresume_dir: Option<PathBuf>,
+    /// Never delete anything in the download folder. Missing files are
+    /// still downloaded and repaired, and extra files are still reported.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Delete extra files right after each update, leaving the protected
+    /// paths alone. `read_only` takes precedence.
+    #[serde(default)]
+    pub strict_mirror: bool,
+    /// Stay on the current version: remote checks still run, but a changed
+    /// torrent is neither cached nor announced as an update.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Move deleted files into `quarantine` in `cache_dir` instead of
+    /// removing them.
+    #[serde(default)]
+    pub quarantine: bool,
+    /// Days quarantined files are kept, `DEFAULT_QUARANTINE_DAYS` when
+    /// unset.
+    #[serde(default)]
+    pub quarantine_days: Option<u32>,
+    /// Folder of the download store shared with other profiles on this
+    /// computer, see `store`. Off when unset.
+    #[serde(default)]
+    pub shared_store: Option<PathBuf>,
 }
 
+/// Default upload cap in KB/s while gaming mode is on.
+pub const GAMING_UPLOAD_SPEED: u32 = 32;
+
+/// Download cap in KB/s while gaming mode is on. librqbit can't stop
+/// downloading a torrent while it keeps seeding, so downloads are held at
+/// the lowest limit instead.
+pub const GAMING_DOWNLOAD_SPEED: u32 = 1;
+
 impl Default for SyncConfig {
     fn default() -> Self {
         SyncConfig {
             torrent_url: String::new(),
+            torrent_mirrors: Vec::new(),
+            version_url: String::new(),
             download_path: PathBuf::new(),
             max_upload_speed: None,
             max_download_speed: None,
             should_seed: false,
             cached_torrent_path: None,
+            ignored_paths: Vec::new(),
+            lifetime_stats_path: None,
+            redirects: RedirectConfig::default(),
+            s3: S3Config::default(),
+            sftp: SftpConfig::default(),
+            credentials: CredentialStore::default(),
+            ipfs: IpfsConfig::default(),
+            git_source: GitSourceConfig::default(),
+            engine: TransferEngine::default(),
+            rsync: RsyncConfig::default(),
+            hooks: HooksConfig::default(),
+            script_path: None,
+            extract: Vec::new(),
+            keys_dir: None,
+            folder_mappings: Vec::new(),
+            download_filters: Vec::new(),
+            cache_dir: None,
+            cache_max_mb: None,
+            cache_versions: None,
+            power: PowerConfig::default(),
+            seed_window: None,
+            banned_peers: Vec::new(),
+            peers: Vec::new(),
+            extra_trackers: Vec::new(),
+            replace_trackers: false,
+            stall_minutes: None,
+            check_jitter_secs: None,
+            check_splay_secs: None,
+            discord: DiscordConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            gaming_upload_speed: None,
+            check_max_mb_per_sec: None,
+            check_low_io_priority: false,
+            write_buffer_mb: None,
+            piece_cache_mb: None,
+            concurrent_checks: None,
+            hashing_threads: None,
+            fastresume: false,
+            fastresume_dir: None,
+            read_only: false,
+            strict_mirror: false,
+            pinned: false,
+            quarantine: false,
+            quarantine_days: None,
+            shared_store: None,
+        }
+    }
+}
+
+impl SyncConfig {
+    /// Paths the cleaner leaves alone: `ignored_paths`, the git source's
+    /// folder and the files extracted from archives.
+    pub fn cleaner_ignored_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.ignored_paths.clone();
+        if !self.extract.is_empty() {
+            paths.push(PathBuf::from(MANIFEST_FILE_NAME));
+            match ExtractManifest::load(&self.download_path) {
+                Ok(manifest) => paths.extend(manifest.files().map(PathBuf::from)),
+                Err(e) => eprintln!("Sync: Extracted files may be reported as extra: {:#}", e),
+            }
+        }
+        // Keys collected into a folder inside the repo aren't extra either
+        if let Some(keys_dir) = &self.keys_dir
+            && let Ok(folder) = keys_dir.strip_prefix(&self.download_path)
+            && !folder.as_os_str().is_empty()
+        {
+            paths.push(folder.to_path_buf());
+        }
+        if self.git_source.is_enabled()
+            && let Ok(folder) = self.git_source.relative_folder()
+        {
+            paths.push(folder.to_path_buf());
         }
+        paths
     }
-}
\ No newline at end of file
+
+    /// `cleaner_ignored_paths` plus the files of the torrent in `details`
+    /// that aren't downloaded, such as those skipped by `download_filters`.
+    pub fn cleaner_ignored_paths_for(&self, details: &TorrentDetailsResponse) -> Vec<PathBuf> {
+        let mut paths = self.cleaner_ignored_paths();
+        paths.extend(excluded_files(details, &self.folder_mappings));
+        paths
+    }
+
+    /// Upload and download limits in KB/s to apply, the gaming mode caps
+    /// while `gaming_mode` is on.
+    pub fn speed_limits(&self, gaming_mode: bool) -> (Option<u32>, Option<u32>) {
+        if gaming_mode {
+            let upload = self.gaming_upload_speed.unwrap_or(GAMING_UPLOAD_SPEED);
+            let capped = |limit: Option<u32>, cap: u32| Some(limit.map_or(cap, |l| l.min(cap)));
+            (capped(self.max_upload_speed, upload), capped(self.max_download_speed, GAMING_DOWNLOAD_SPEED))
+        } else {
+            (self.max_upload_speed, self.max_download_speed)
+        }
+    }
+
+    /// `torrent_url` followed by its mirrors, without blanks or duplicates.
+    pub fn torrent_urls(&self) -> Vec<&str> {
+        let mut urls: Vec<&str> = Vec::new();
+        for url in std::iter::once(&self.torrent_url).chain(&self.torrent_mirrors) {
+            let url = url.trim();
+            if !url.is_empty() && !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+}
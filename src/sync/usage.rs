@@ -0,0 +1,106 @@
+// src/sync/usage.rs
+
+//! Disk usage breakdown of the download folder.
+//!
+//! Files are sorted into the synced repo, protected files the cleaner
+//! leaves alone, and extras, by the same rules the cleaner uses. Scanning
+//! walks the whole folder, so the manager runs it in the background now and
+//! then and the latest result is kept with the rest of the client state.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use walkdir::WalkDir;
+
+use super::cleaner::{get_expected_files_from_details, is_ignored};
+use super::messages::SyncEvent;
+use super::types::SyncConfig;
+use super::utils::send_sync_event;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskUsage {
+    /// RFC 3339 time of the scan.
+    pub scanned_at: String,
+    /// Files of the torrent.
+    pub repo_bytes: u64,
+    /// Files the cleaner would report as extra.
+    pub extra_bytes: u64,
+    /// Protected paths, extracted archives and other files the cleaner skips.
+    pub protected_bytes: u64,
+    /// Free space on the volume, when it could be read.
+    pub free_bytes: Option<u64>,
+}
+
+/// Free space for `path`, measured on its closest existing ancestor since
+/// the download folder may not have been created yet.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| !p.as_os_str().is_empty() && p.exists()).unwrap_or(Path::new("."));
+    fs4::available_space(existing).ok()
+}
+
+/// Sum up the files under `download_path`.
+pub fn scan(download_path: &Path, expected_files: &HashSet<PathBuf>, ignored_paths: &[PathBuf]) -> DiskUsage {
+    let mut usage = DiskUsage {
+        scanned_at: chrono::Utc::now().to_rfc3339(),
+        free_bytes: available_space(download_path),
+        ..DiskUsage::default()
+    };
+    for entry in WalkDir::new(download_path).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let (Ok(relative_path), Ok(metadata)) = (entry.path().strip_prefix(download_path), entry.metadata()) else {
+            continue;
+        };
+        let bucket = if expected_files.contains(relative_path) {
+            &mut usage.repo_bytes
+        } else if is_ignored(relative_path, ignored_paths) {
+            &mut usage.protected_bytes
+        } else {
+            &mut usage.extra_bytes
+        };
+        *bucket += metadata.len();
+    }
+    usage
+}
+
+/// Scan the download folder against torrent `id` off the async runtime and
+/// send the result.
+pub async fn scan_and_report(config: &SyncConfig, api: &librqbit::api::Api, id: usize, ui_tx: &mpsc::UnboundedSender<SyncEvent>) {
+    let details = match api.api_torrent_details(id.into()) {
+        Ok(details) => details,
+        Err(e) => {
+            eprintln!("Sync: Skipping disk usage scan, failed to get torrent details: {}", e);
+            return;
+        }
+    };
+    let expected_files = get_expected_files_from_details(&details, &config.folder_mappings);
+    let download_path = config.download_path.clone();
+    let ignored_paths = config.cleaner_ignored_paths_for(&details);
+    match tokio::task::spawn_blocking(move || scan(&download_path, &expected_files, &ignored_paths)).await {
+        Ok(usage) => send_sync_event(ui_tx, SyncEvent::DiskUsage(usage)),
+        Err(e) => eprintln!("Sync: Disk usage scan failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_scan_sorts_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        for (path, size) in [("@cba/cba.pbo", 100), ("userconfig/cba.hpp", 20), ("stray.log", 3)] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, vec![0u8; size])?;
+        }
+        let expected = HashSet::from([PathBuf::from("@cba/cba.pbo"), PathBuf::from("@cba/missing.pbo")]);
+        let usage = scan(dir.path(), &expected, &[PathBuf::from("userconfig")]);
+        assert_eq!((usage.repo_bytes, usage.protected_bytes, usage.extra_bytes), (100, 20, 3));
+        assert!(usage.free_bytes.is_some());
+        Ok(())
+    }
+}
@@ -4,7 +4,8 @@ use tokio::sync::mpsc;
 
 use crate::sync::status::SyncStatus;
 use super::messages::SyncEvent;
-use std::path::PathBuf;
+use super::schedule::{parse_retry_after, RetryAfterError};
+use std::path::{Component, Path, PathBuf};
 
 pub fn send_sync_event(tx: &mpsc::UnboundedSender<SyncEvent>, event: SyncEvent) {
     if let Err(e) = tx.send(event) {
@@ -18,14 +19,26 @@ pub fn send_sync_status_event(tx: &mpsc::UnboundedSender<SyncEvent>, status: Syn
 
 pub async fn download_torrent(url: &str, client: &reqwest::Client) -> Result<Vec<u8>> {
     println!("Sync: Downloading torrent from: {}", url);
+    download_request(client.get(url), url).await
+}
 
-    let response = client
-        .get(url)
+/// Send `request` and return the body, failing on non-success statuses.
+/// `url` is only used in error messages.
+pub async fn download_request(request: reqwest::RequestBuilder, url: &str) -> Result<Vec<u8>> {
+    let response = request
         .send()
         .await
         .with_context(|| format!("Failed to send request to {}", url))?;
 
     if !response.status().is_success() {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, chrono::Utc::now()));
+        if let Some(after) = retry_after {
+            return Err(RetryAfterError { status: response.status(), after }.into());
+        }
         return Err(anyhow!("HTTP error: {}", response.status()));
     }
 
@@ -74,4 +87,10 @@ pub async fn get_local_torrent_hash(cached_path: Option<PathBuf>) -> Result<Opti
     let hash = calculate_torrent_hash(&data);
 
     Ok(Some(hash))
-}
\ No newline at end of file
+}
+/// Whether `path` is a non-empty relative path that stays inside the folder
+/// it is joined to, i.e. has no root, prefix or `..` components.
+pub fn is_contained_relative_path(path: &Path) -> bool {
+    path.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        && path.components().any(|c| matches!(c, Component::Normal(_)))
+}
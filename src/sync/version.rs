@@ -0,0 +1,91 @@
+// src/sync/version.rs
+
+//! Torrent URLs with a version placeholder.
+//!
+//! Repositories that keep every release under its own path, such as
+//! `https://repo.example.com/releases/{latest}/mods.torrent`, publish the
+//! current version at a small endpoint. The remote check fetches it first
+//! and puts it in place of `{latest}` before fetching the torrent.
+
+use anyhow::{anyhow, bail, Context, Result};
+
+/// Placeholder replaced by the version the version endpoint returns.
+pub const LATEST_PLACEHOLDER: &str = "{latest}";
+
+/// Longest version accepted, so an error page isn't taken for one.
+const MAX_VERSION_LEN: usize = 64;
+
+pub fn is_templated(url: &str) -> bool {
+    url.contains(LATEST_PLACEHOLDER)
+}
+
+pub fn resolve(url: &str, version: &str) -> String {
+    url.replace(LATEST_PLACEHOLDER, version)
+}
+
+/// The version in a version endpoint's response: either plain text, of
+/// which the first line counts, or a JSON object with a `latest` or
+/// `version` field.
+pub fn parse_version(body: &str) -> Result<String> {
+    let body = body.trim();
+    let version = if body.starts_with('{') {
+        let value: serde_json::Value = serde_json::from_str(body).context("Version endpoint returned invalid JSON")?;
+        ["latest", "version"]
+            .iter()
+            .find_map(|key| value.get(key).and_then(|v| v.as_str()))
+            .ok_or_else(|| anyhow!("Version endpoint returned no \"latest\" or \"version\" field"))?
+            .trim()
+            .to_string()
+    } else {
+        body.lines().next().unwrap_or_default().trim().to_string()
+    };
+    if version.is_empty() {
+        bail!("Version endpoint returned no version");
+    }
+    // The version becomes part of a path, so only plain version characters go
+    if version.len() > MAX_VERSION_LEN
+        || version.starts_with('.')
+        || !version.chars().all(|c| c.is_ascii_alphanumeric() || "._-+".contains(c))
+    {
+        bail!("Version endpoint returned {:?}, which is not a version", version);
+    }
+    Ok(version)
+}
+
+/// Fetch the current version from `version_url`.
+pub async fn fetch_latest(http_client: &reqwest::Client, version_url: &str) -> Result<String> {
+    if version_url.trim().is_empty() {
+        bail!("The torrent URL contains {} but no version URL is configured", LATEST_PLACEHOLDER);
+    }
+    let body = http_client
+        .get(version_url.trim())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to fetch the version from {}", version_url))?
+        .text()
+        .await
+        .with_context(|| format!("Failed to read the version from {}", version_url))?;
+    parse_version(&body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.4.2\n").unwrap(), "1.4.2");
+        assert_eq!(parse_version("v2025-03\nreleased today").unwrap(), "v2025-03");
+        assert_eq!(parse_version(r#"{"latest": "7", "notes": "x"}"#).unwrap(), "7");
+        assert_eq!(parse_version(r#"{"version": "1.0+build5"}"#).unwrap(), "1.0+build5");
+        assert!(parse_version("").is_err());
+        assert!(parse_version("../etc").is_err());
+        assert!(parse_version("<html><body>Not found</body></html>").is_err());
+        assert!(parse_version(r#"{"name": "mods"}"#).is_err());
+
+        let url = "https://repo.example.com/releases/{latest}/mods.torrent";
+        assert!(is_templated(url));
+        assert_eq!(resolve(url, "1.4.2"), "https://repo.example.com/releases/1.4.2/mods.torrent");
+    }
+}
@@ -0,0 +1,55 @@
+// src/sync/webdav.rs
+
+//! Torrents hosted on WebDAV shares such as Nextcloud.
+//!
+//! `dav://` and `davs://` URLs are plain HTTP(S) GETs of the file, with the
+//! login for the host taken from the credentials store. Payload files still
+//! come from the swarm; only the torrent itself is fetched this way.
+
+use anyhow::{anyhow, Result};
+
+use super::credentials::CredentialStore;
+use super::utils::download_request;
+
+/// URL schemes selecting this source, with the HTTP scheme each maps to.
+pub const SCHEMES: [(&str, &str); 2] = [("dav://", "http://"), ("davs://", "https://")];
+
+pub fn is_webdav_url(url: &str) -> bool {
+    SCHEMES.iter().any(|(dav, _)| url.starts_with(dav))
+}
+
+/// The HTTP(S) URL for a `dav://` or `davs://` URL.
+pub fn to_http_url(url: &str) -> Result<String> {
+    SCHEMES
+        .iter()
+        .find_map(|(dav, http)| url.strip_prefix(dav).map(|rest| format!("{}{}", http, rest)))
+        .ok_or_else(|| anyhow!("Not a WebDAV URL: {}", url))
+}
+
+/// Download the file named by the WebDAV `url`.
+pub async fn download(url: &str, credentials: &CredentialStore, client: &reqwest::Client) -> Result<Vec<u8>> {
+    let http_url = to_http_url(url)?;
+    let host = reqwest::Url::parse(&http_url)?.host_str().unwrap_or_default().to_string();
+    println!("Sync: Downloading torrent over WebDAV from: {}", url);
+    let mut request = client.get(&http_url);
+    match credentials.for_host(&host) {
+        Some(login) => request = request.basic_auth(&login.username, Some(&login.password)),
+        None => println!("Sync: No credentials stored for {}, trying without login", host),
+    }
+    download_request(request, url).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_http_url() {
+        assert_eq!(
+            to_http_url("davs://cloud.example.com/remote.php/dav/files/mods/repo.torrent").unwrap(),
+            "https://cloud.example.com/remote.php/dav/files/mods/repo.torrent"
+        );
+        assert_eq!(to_http_url("dav://nas:8080/repo.torrent").unwrap(), "http://nas:8080/repo.torrent");
+        assert!(to_http_url("https://example.com/repo.torrent").is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use eframe::egui;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+
+/// Requests raised by the file views and carried out by the app, which owns
+/// the settings and the daemon connection. Paths are relative to the
+/// download folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UiAction {
+    /// Open the folder containing the path, or the path itself for folders.
+    OpenLocation(PathBuf),
+    CopyPath(PathBuf),
+    /// Add the path to the cleaner's ignore list.
+    IgnorePath(PathBuf),
+    /// Switch to the graph and highlight the path's node.
+    RevealInGraph(PathBuf),
+    /// Refuse the peer address from the next session on.
+    BanPeer(IpAddr),
+}
+
+/// Context menu entries for `path`. `reveal` adds the "Reveal in graph" entry
+/// for views other than the graph itself.
+pub fn path_menu(ui: &mut egui::Ui, path: &Path, reveal: bool, actions: &mut Vec<UiAction>) {
+    if ui.button(tr("menu-open-location")).clicked() {
+        actions.push(UiAction::OpenLocation(path.to_path_buf()));
+        ui.close();
+    }
+    if ui.button(tr("menu-copy-path")).clicked() {
+        actions.push(UiAction::CopyPath(path.to_path_buf()));
+        ui.close();
+    }
+    if ui.button(tr("menu-ignore")).on_hover_text(tr("menu-ignore-hover")).clicked() {
+        actions.push(UiAction::IgnorePath(path.to_path_buf()));
+        ui.close();
+    }
+    if reveal && ui.button(tr("menu-reveal-in-graph")).clicked() {
+        actions.push(UiAction::RevealInGraph(path.to_path_buf()));
+        ui.close();
+    }
+}
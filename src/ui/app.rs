@@ -1,30 +1,177 @@
 use eframe::{egui, App, Frame};
-use egui::{Color32, RichText, Vec2};
+use egui::{RichText, Vec2};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::path::PathBuf;
 use std::time::Instant;
+use tokio::sync::mpsc;
 
+use crate::crash;
+use crate::diagnostics;
+use crate::i18n::{self, tr, tr_args};
+use crate::ipc::{ConnectionUpdate, DaemonMessage, DaemonState};
+use crate::settings::{AppSettings, BandwidthPreset, DisplaySettings, NotificationSettings, ThemeSettings};
+use crate::sync::checking::CheckMonitor;
+use crate::sync::power::{PowerAction, PowerReason, PowerRestriction};
+use crate::sync::seed_window::SeedWindow;
+use crate::sync::status::SyncStatus;
+use crate::sync::{Inconsistency, LifetimeCounter, SyncCommand, SyncError, SyncEvent, TorrentSnapshot, TorrentSummary};
+use crate::ui::actions::UiAction;
+use crate::ui::file_graph::{FileGraph, GraphInput};
+use crate::ui::files_view::FilesView;
 use crate::ui::header::Header;
+use crate::ui::health_badge;
+use crate::ui::history_view::HistoryView;
+use crate::ui::layout::WindowLayout;
+use crate::ui::log_view::LogView;
+use crate::ui::palette::CommandPalette;
+use crate::ui::peers_view;
+use crate::ui::popout;
+use crate::ui::repaint;
+use crate::ui::sound::{self, Sound};
+use crate::ui::stall_banner;
+use crate::ui::taskbar::{Taskbar, TaskbarProgress};
+use crate::ui::toast::{ToastAction, Toasts};
+use crate::ui::trackers_view;
+use crate::ui::version_diff::VersionDiffView;
+use crate::ui::settings_panel::SettingsPanel;
+use crate::ui::shortcuts::{self, Action, SHORTCUTS};
+use crate::ui::state::{parse_timestamp, UiState};
+use crate::ui::statistics;
+use crate::ui::storage_view;
+use crate::ui::restore_panel::{self, RestoreState};
+use crate::ui::status_bar::StatusBar;
+use crate::ui::theme::Theme;
+use crate::ui::format;
 use rfd::FileDialog;
 
 // Layout constants
 const PROGRESS_PANEL_HEIGHT: f32 = 140.0;
 const MIN_INPUT_WIDTH: f32 = 80.0;
+const HEALTH_BADGE_WIDTH: f32 = 12.0;
 const ACTION_BUTTON_HEIGHT: f32 = 36.0;
 
-// UI-local state
-struct UiState {
-    url: String,
-    folder: String,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Tab {
+    #[default]
+    Overview,
+    Files,
+    Graph,
+    History,
+    Peers,
+    Trackers,
+    Log,
+}
+
+impl Tab {
+    const ALL: [Tab; 7] = [Tab::Overview, Tab::Files, Tab::Graph, Tab::History, Tab::Peers, Tab::Trackers, Tab::Log];
+
+    fn label_id(self) -> &'static str {
+        match self {
+            Tab::Overview => "app-tab-overview",
+            Tab::Files => "app-tab-files",
+            Tab::Graph => "app-tab-graph",
+            Tab::History => "app-tab-history",
+            Tab::Peers => "app-tab-peers",
+            Tab::Trackers => "app-tab-trackers",
+            Tab::Log => "app-tab-log",
+        }
+    }
+}
+
+/// What a command palette entry does.
+#[derive(Debug, Clone)]
+enum PaletteCommand {
+    Action(Action),
+    Tab(Tab),
+    Preset(BandwidthPreset),
+    CopyDiagnostics,
+}
+
+/// Channels to the background daemon, see `crate::ipc::connect`.
+struct DaemonLink {
+    cmd_tx: mpsc::UnboundedSender<SyncCommand>,
+    updates: mpsc::UnboundedReceiver<ConnectionUpdate>,
+    connected: bool,
 }
 
 pub struct ModApp {
     last_update: Instant,
+    daemon: Option<DaemonLink>,
+    // Latest state reported by the daemon
+    status: SyncStatus,
+    pending_update: Option<Vec<u8>>,
+    /// What `pending_update` contains, when it could be read.
+    pending_summary: Option<TorrentSummary>,
+    /// Latest stats of the managed torrent, for diagnostics.
+    stats: Option<TorrentSnapshot>,
+    /// Speed of the initial file check, while one runs.
+    check_monitor: CheckMonitor,
+    /// Progress of the daemon restoring the previous session.
+    restore: RestoreState,
+    last_error: Option<String>,
+    /// The last error reported by the sync manager, for its hint while
+    /// it is still the one shown.
+    sync_error: Option<SyncError>,
+    notice: Option<String>,
+    paused: bool,
+    show_shortcuts: bool,
+    palette: CommandPalette,
+    /// Message of the full-disk dialog while it is open.
+    disk_full: Option<String>,
+    /// Full-disk message the user dismissed, so the status refresh
+    /// repeating it doesn't open the dialog again.
+    disk_full_dismissed: Option<String>,
+    /// Asking whether to delete the torrent's files.
+    confirm_remove: bool,
+    /// Crash reports not shown yet, oldest first.
+    crash_reports: Vec<PathBuf>,
+    focus_initialized: bool,
     header: Header,
+    settings_panel: SettingsPanel,
+    #[cfg(feature = "grpc")]
+    fleet: crate::ui::fleet_panel::FleetPanel,
+    theme_settings: ThemeSettings,
+    applied_theme: Option<Theme>,
+    display: DisplaySettings,
+    applied_display: Option<DisplaySettings>,
     ui_state: UiState,
+    tab: Tab,
+    /// Window geometry and open panels, saved when the window closes.
+    layout: WindowLayout,
+    files_view: FilesView,
+    file_graph: FileGraph,
+    history_view: HistoryView,
+    log_view: LogView,
+    /// Statistics and log popped out into windows of their own.
+    stats_detached: bool,
+    log_detached: bool,
+    version_diff: VersionDiffView,
     torrent_progress: crate::ui::torrent_progress::TorrentProgress,
+    status_bar: StatusBar,
     // Inline settings (moved from the side panel)
     should_seed: bool,
+    /// Never delete files; extras are only reported.
+    read_only: bool,
+    /// Updates are ignored while the version is pinned.
+    pinned: bool,
+    seed_window: Option<SeedWindow>,
+    banned_peers: Vec<IpAddr>,
     upload_str: String,
     download_str: String,
+    bandwidth_presets: Vec<BandwidthPreset>,
+    gaming_mode: bool,
+    gaming_shortcut: Option<egui::KeyboardShortcut>,
+    /// Gaming mode as last shown in the window title.
+    titled_gaming_mode: Option<bool>,
+    /// Why transfers are paused or slowed, if they are.
+    power: Option<PowerRestriction>,
+    notifications: NotificationSettings,
+    toasts: Toasts,
+    taskbar: Taskbar,
+    /// Info hash of the update last announced, so each update is only
+    /// announced once.
+    announced_update: Option<String>,
     // Demo
     demo_mode: bool,
     demo_percent: f64,
@@ -34,41 +181,131 @@ impl Default for ModApp {
     fn default() -> Self {
         Self {
             last_update: Instant::now(),
+            daemon: None,
+            status: SyncStatus::Idle,
+            pending_update: None,
+            pending_summary: None,
+            stats: None,
+            check_monitor: CheckMonitor::default(),
+            restore: RestoreState::default(),
+            last_error: None,
+            sync_error: None,
+            notice: None,
+            paused: false,
+            show_shortcuts: false,
+            palette: CommandPalette::default(),
+            disk_full: None,
+            disk_full_dismissed: None,
+            confirm_remove: false,
+            crash_reports: Vec::new(),
+            focus_initialized: false,
             header: Header::default(),
-            ui_state: UiState { url: String::new(), folder: String::from("downloads") },
+            settings_panel: SettingsPanel::default(),
+            #[cfg(feature = "grpc")]
+            fleet: crate::ui::fleet_panel::FleetPanel::default(),
+            theme_settings: ThemeSettings::default(),
+            applied_theme: None,
+            display: DisplaySettings::default(),
+            applied_display: None,
+            ui_state: UiState::new(String::new(), String::from("downloads")),
+            tab: Tab::Overview,
+            layout: WindowLayout::default(),
+            files_view: FilesView::default(),
+            file_graph: FileGraph::default(),
+            history_view: HistoryView::default(),
+            log_view: LogView::default(),
+            stats_detached: false,
+            log_detached: false,
+            version_diff: VersionDiffView::default(),
             torrent_progress: crate::ui::torrent_progress::TorrentProgress::new(),
+            status_bar: StatusBar::default(),
             should_seed: false,
+            read_only: false,
+            pinned: false,
+            seed_window: None,
+            banned_peers: Vec::new(),
             upload_str: String::new(),
             download_str: String::new(),
+            bandwidth_presets: Vec::new(),
+            gaming_mode: false,
+            gaming_shortcut: None,
+            titled_gaming_mode: None,
+            power: None,
+            notifications: NotificationSettings::default(),
+            toasts: Toasts::default(),
+            taskbar: Taskbar::default(),
+            announced_update: None,
             demo_mode: false,
             demo_percent: 0.0,
         }
     }
 }
 
-fn init_style(ctx: &egui::Context) {
-    let mut style = (*ctx.style()).clone();
-    style.visuals.widgets.inactive.bg_fill = Color32::from_rgb(24, 24, 26);
-    style.visuals.window_fill = Color32::from_rgb(10, 10, 12);
-    style.visuals.override_text_color = Some(Color32::from_rgb(235, 235, 235));
-    style.spacing.item_spacing = egui::vec2(10.0, 6.0);
-    style.spacing.button_padding = egui::vec2(12.0, 8.0);
-    style.text_styles.get_mut(&egui::TextStyle::Heading).map(|ts| ts.size = 30.0);
-    style.text_styles.get_mut(&egui::TextStyle::Body).map(|ts| ts.size = 15.0);
-    ctx.set_style(style);
-}
-
 impl App for ModApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
         // timing + style
         self.last_update = Instant::now();
-        init_style(ctx);
+        let theme = Theme::from_settings(&self.theme_settings, ctx.system_theme());
+        if self.applied_theme != Some(theme) {
+            theme.apply(ctx);
+            self.applied_theme = Some(theme);
+        }
+        if self.applied_display.as_ref() != Some(&self.display) {
+            crate::ui::display::apply(ctx, &self.display);
+            self.applied_display = Some(self.display.clone());
+        }
+        self.poll_daemon();
+        self.announce_update(ctx);
+        self.taskbar.show(frame, TaskbarProgress::of(self.stats.as_ref()));
+        self.handle_keyboard(ctx);
+        self.layout.capture(ctx);
+        if ctx.input(|i| i.viewport().close_requested()) {
+            self.save_layout();
+        }
+        // Without a tray icon the window title shows that gaming mode is on
+        if self.titled_gaming_mode != Some(self.gaming_mode) {
+            let title = if self.gaming_mode { format!("ModSync — {}", tr("app-gaming-mode")) } else { "ModSync".to_string() };
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title));
+            self.titled_gaming_mode = Some(self.gaming_mode);
+        }
 
         // Top controls: header + inputs + actions + inline settings
         egui::TopBottomPanel::top("controls_panel").show(ctx, |ui| {
             // Header
             ui.horizontal(|ui| {
                 self.header.ui(ui);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                    if ui.button(tr("app-settings")).on_hover_text(shortcut_hint(ui.ctx(), Action::OpenSettings)).clicked() {
+                        self.run_action(Action::OpenSettings);
+                    }
+                    ui.menu_button(tr("app-help"), |ui| {
+                        if ui.button(tr("app-shortcuts")).on_hover_text(shortcut_hint(ui.ctx(), Action::ShowShortcuts)).clicked() {
+                            self.run_action(Action::ShowShortcuts);
+                            ui.close();
+                        }
+                        if ui.button(tr("app-copy-diagnostics")).on_hover_text(tr("app-copy-diagnostics-hover")).clicked() {
+                            self.copy_diagnostics(ui.ctx());
+                            ui.close();
+                        }
+                        if ui.button(tr("app-save-diagnostics")).on_hover_text(tr("app-save-diagnostics-hover")).clicked() {
+                            self.save_diagnostic_bundle();
+                            ui.close();
+                        }
+                    });
+                    #[cfg(feature = "grpc")]
+                    if ui.button(tr("app-fleet")).on_hover_text(tr("app-fleet-hover")).clicked() {
+                        self.fleet.open = !self.fleet.open;
+                    }
+                    self.preset_picker(ui);
+                    let hint = self.gaming_shortcut.map(|s| ui.ctx().format_shortcut(&s)).unwrap_or_default();
+                    if ui
+                        .selectable_label(self.gaming_mode, tr("app-gaming-mode"))
+                        .on_hover_text(tr_args("app-gaming-mode-hover", &[("shortcut", hint.into())]))
+                        .clicked()
+                    {
+                        self.run_action(Action::ToggleGamingMode);
+                    }
+                });
             });
 
             ui.add_space(6.0);
@@ -77,9 +314,17 @@ impl App for ModApp {
             ui.horizontal(|ui| {
                 let avail = ui.available_width();
                 let btn_w = 110.0_f32.min(avail * 0.18);
-                let input_w = (avail - btn_w - ui.spacing().item_spacing.x).max(MIN_INPUT_WIDTH);
-                ui.add_sized(egui::vec2(input_w, 28.0), egui::widgets::TextEdit::singleline(&mut self.ui_state.url).hint_text("Torrent URL"));
-                if ui.add_sized(egui::vec2(btn_w, 28.0), egui::widgets::Button::new("Load").fill(Color32::from_rgb(70,130,180))).clicked() {}
+                let input_w = (avail - btn_w - HEALTH_BADGE_WIDTH - ui.spacing().item_spacing.x * 2.0).max(MIN_INPUT_WIDTH);
+                health_badge::badge(ui, &self.ui_state.health, self.ui_state.url.trim());
+                let url_input = ui.add_sized(egui::vec2(input_w, 28.0), egui::widgets::TextEdit::singleline(&mut self.ui_state.url).hint_text(tr("app-url-hint")));
+                // Start keyboard users in the first field
+                if !self.focus_initialized {
+                    url_input.request_focus();
+                    self.focus_initialized = true;
+                }
+                if ui.add_sized(egui::vec2(btn_w, 28.0), egui::widgets::Button::new(tr("app-load")).fill(theme.accent)).on_hover_text(tr("app-load-hover")).clicked() {
+                    self.load_settings_into_daemon();
+                }
             });
 
             ui.add_space(6.0);
@@ -89,11 +334,11 @@ impl App for ModApp {
                 let avail = ui.available_width();
                 let btn_w = 110.0_f32.min(avail * 0.18);
                 let input_w = (avail - btn_w - ui.spacing().item_spacing.x).max(MIN_INPUT_WIDTH);
-                ui.add_sized(egui::vec2(input_w, 28.0), egui::widgets::TextEdit::singleline(&mut self.ui_state.folder).hint_text("Download folder"));
-                if ui.add_sized(egui::vec2(btn_w, 28.0), egui::widgets::Button::new("Browse").fill(Color32::from_rgb(100,160,100))).clicked() {
-                    if let Some(folder) = FileDialog::new().pick_folder() {
-                        self.ui_state.folder = folder.display().to_string();
-                    }
+                ui.add_sized(egui::vec2(input_w, 28.0), egui::widgets::TextEdit::singleline(&mut self.ui_state.folder).hint_text(tr("app-folder-hint")));
+                if ui.add_sized(egui::vec2(btn_w, 28.0), egui::widgets::Button::new(tr("app-browse")).fill(theme.secondary)).on_hover_text(tr("app-browse-hover")).clicked()
+                    && let Some(folder) = FileDialog::new().pick_folder()
+                {
+                    self.ui_state.folder = folder.display().to_string();
                 }
             });
 
@@ -104,35 +349,71 @@ impl App for ModApp {
                 let avail = ui.available_width();
                 let spacing = ui.spacing().item_spacing.x;
                 let btn_w = (avail - spacing * 3.0) / 4.0;
-                ui.add_sized(egui::vec2(btn_w, ACTION_BUTTON_HEIGHT), egui::widgets::Button::new(RichText::new("Check for updates").strong()).fill(Color32::from_rgb(75,135,185)));
-                ui.add_sized(egui::vec2(btn_w, ACTION_BUTTON_HEIGHT), egui::widgets::Button::new(RichText::new("Check").strong()).fill(Color32::from_rgb(190,120,90)));
-                ui.add_sized(egui::vec2(btn_w, ACTION_BUTTON_HEIGHT), egui::widgets::Button::new(RichText::new("Launch").strong()).fill(Color32::from_rgb(120,200,140)));
-                ui.add_sized(egui::vec2(btn_w, ACTION_BUTTON_HEIGHT), egui::widgets::Button::new(RichText::new("Join").strong()).fill(Color32::from_rgb(200,160,80)));
+                if ui.add_sized(egui::vec2(btn_w, ACTION_BUTTON_HEIGHT), egui::widgets::Button::new(RichText::new(tr("app-check-updates")).strong()).fill(theme.accent)).on_hover_text(shortcut_hint(ui.ctx(), Action::CheckForUpdates)).clicked() {
+                    self.run_action(Action::CheckForUpdates);
+                }
+                let verify = ui.add_sized(egui::vec2(btn_w, ACTION_BUTTON_HEIGHT), egui::widgets::Button::new(RichText::new(tr("app-verify")).strong()).fill(theme.caution)).on_hover_text(shortcut_hint(ui.ctx(), Action::Verify));
+                if verify.clicked() {
+                    self.run_action(Action::Verify);
+                }
+                verify.context_menu(|ui| {
+                    if ui.button(tr("app-force-recheck")).on_hover_text(tr("app-force-recheck-hover")).clicked() {
+                        self.send(SyncCommand::ForceRecheck);
+                        ui.close();
+                    }
+                    ui.separator();
+                    if ui.button(tr("app-forget-torrent")).on_hover_text(tr("app-forget-torrent-hover")).clicked() {
+                        self.send(SyncCommand::ForgetTorrent);
+                        ui.close();
+                    }
+                    if ui
+                        .add_enabled(!self.read_only, egui::Button::new(RichText::new(tr("app-remove-torrent")).color(theme.error)))
+                        .on_disabled_hover_text(tr("app-read-only-hover"))
+                        .clicked()
+                    {
+                        self.confirm_remove = true;
+                        ui.close();
+                    }
+                });
+                // Not wired up yet; disabled so they don't look like working controls
+                ui.add_enabled(false, egui::widgets::Button::new(RichText::new(tr("app-launch")).strong()).fill(theme.success).min_size(egui::vec2(btn_w, ACTION_BUTTON_HEIGHT)))
+                    .on_disabled_hover_text(tr("app-not-available"));
+                ui.add_enabled(false, egui::widgets::Button::new(RichText::new(tr("app-join")).strong()).fill(theme.warning).min_size(egui::vec2(btn_w, ACTION_BUTTON_HEIGHT)))
+                    .on_disabled_hover_text(tr("app-not-available"));
             });
 
             ui.add_space(6.0);
 
             // Inline settings (clean, single row)
             ui.horizontal(|ui| {
-                ui.checkbox(&mut self.should_seed, "Enable seeding");
+                ui.checkbox(&mut self.should_seed, tr("app-enable-seeding"));
                 ui.add_space(12.0);
-                ui.label("Max upload (KB/s):");
-                ui.add(egui::widgets::TextEdit::singleline(&mut self.upload_str).desired_width(80.0));
+                let label = ui.label(tr("app-max-upload"));
+                ui.add(egui::widgets::TextEdit::singleline(&mut self.upload_str).desired_width(80.0)).labelled_by(label.id);
                 ui.add_space(8.0);
-                ui.label("Max download (KB/s):");
-                ui.add(egui::widgets::TextEdit::singleline(&mut self.download_str).desired_width(80.0));
+                let label = ui.label(tr("app-max-download"));
+                ui.add(egui::widgets::TextEdit::singleline(&mut self.download_str).desired_width(80.0)).labelled_by(label.id);
+                ui.add_space(12.0);
+                let language_label = ui.label(tr("app-language"));
+                let current = i18n::current_language();
+                let current_name = i18n::available_languages()
+                    .find(|(code, _)| *code == current)
+                    .map_or(current, |(_, name)| name);
+                egui::ComboBox::from_id_salt("language").selected_text(current_name).show_ui(ui, |ui| {
+                    for (code, name) in i18n::available_languages() {
+                        if ui.selectable_label(code == current, name).clicked() {
+                            self.change_language(code);
+                        }
+                    }
+                }).response.labelled_by(language_label.id);
             });
         });
 
-        // Central content (simple and uncluttered)
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.vertical_centered(|ui| {
-                ui.add_space(8.0);
-                ui.heading("Main content area");
-                ui.add_space(6.0);
-                ui.label("Flexible content. Torrent progress is anchored to bottom.");
-                ui.add_space(200.0);
-            });
+        // Status bar along the very bottom, below the progress panel
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            if self.status_bar.ui(ui, &self.status, &self.ui_state) {
+                self.check_now();
+            }
         });
 
         // Bottom fixed torrent progress panel (clean)
@@ -143,7 +424,7 @@ impl App for ModApp {
 
             // demo simulation if enabled
             if self.demo_mode {
-                let base = vec![1u64 << 20, 5u64 << 20, 20u64 << 20];
+                let base = [1u64 << 20, 5u64 << 20, 20u64 << 20];
                 let total: u64 = base.iter().sum();
                 let progress_total = ((total as f64) * self.demo_percent) as u64;
                 let mut remaining = progress_total;
@@ -162,18 +443,942 @@ impl App for ModApp {
             });
         });
 
-        // keep updating
-        ctx.request_repaint();
+        // Central content (simple and uncluttered)
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for tab in Tab::ALL {
+                    ui.selectable_value(&mut self.tab, tab, tr(tab.label_id()));
+                }
+            });
+            ui.separator();
+            let actions = match self.tab {
+                Tab::Overview => Vec::new(),
+                Tab::Files => self.files_view.ui(ui, &self.ui_state.files, &self.ui_state.file_progress),
+                Tab::Graph => {
+                    let input = GraphInput {
+                        files: &self.ui_state.files,
+                        file_progress: &self.ui_state.file_progress,
+                        missing_files: self.ui_state.missing_files.as_ref(),
+                        extra_files: self.ui_state.extra_files.as_deref().unwrap_or_default(),
+                    };
+                    self.file_graph.ui(ui, self.ui_state.files_revision, self.ui_state.revision, &input)
+                }
+                Tab::History => {
+                    self.history_view.ui(ui, &self.ui_state.history);
+                    Vec::new()
+                }
+                Tab::Peers => peers_view::ui(ui, &self.ui_state.peers, &self.banned_peers),
+                Tab::Trackers => {
+                    trackers_view::ui(ui, &self.ui_state.trackers);
+                    Vec::new()
+                }
+                Tab::Log if self.log_detached => {
+                    ui.label(RichText::new(tr("log-detached")).weak());
+                    if ui.button(tr("popout-attach")).clicked() {
+                        self.log_detached = false;
+                    }
+                    Vec::new()
+                }
+                Tab::Log => {
+                    if popout::button(ui) {
+                        self.log_detached = true;
+                    }
+                    self.log_view.ui(ui);
+                    Vec::new()
+                }
+            };
+            for action in actions {
+                self.handle_ui_action(ui.ctx(), action);
+            }
+            if self.tab != Tab::Overview {
+                return;
+            }
+            ui.vertical_centered(|ui| {
+                ui.add_space(8.0);
+                let (daemon_text, daemon_color) = match &self.daemon {
+                    Some(link) if link.connected => (tr("daemon-connected"), theme.success),
+                    Some(_) => (tr("daemon-connecting"), theme.warning),
+                    None => (tr("daemon-none"), theme.text_muted),
+                };
+                ui.label(RichText::new(daemon_text).color(daemon_color));
+                ui.add_space(6.0);
+                if self.restore.is_active() {
+                    restore_panel::ui(ui, self.stats.as_ref(), self.check_monitor.progress().as_ref());
+                } else {
+                    ui.heading(tr_args("app-status", &[("status", self.status.display_text().into())]));
+                    if let Some(check) = self.check_monitor.progress() {
+                        ui.label(restore_panel::check_text(&check));
+                        if check.slow {
+                            ui.label(RichText::new(tr("check-slow")).color(theme.warning));
+                        }
+                    }
+                }
+                if let Some(power) = self.power {
+                    let id = match (power.action, power.reason) {
+                        (PowerAction::Pause, PowerReason::Metered) => "app-power-paused-metered",
+                        (PowerAction::Pause, PowerReason::Battery) => "app-power-paused-battery",
+                        (_, PowerReason::Metered) => "app-power-limited-metered",
+                        (_, PowerReason::Battery) => "app-power-limited-battery",
+                    };
+                    ui.label(RichText::new(tr(id)).color(theme.warning));
+                }
+                if let Some(window) = self.seed_window.filter(|w| self.should_seed && !w.is_open())
+                    && self.stats.as_ref().is_some_and(|s| s.finished)
+                {
+                    ui.label(RichText::new(tr_args("app-seed-window-closed", &[("start", window.start_text().into())])).weak());
+                }
+                if let Some(stalled) = &self.ui_state.stalled {
+                    if stall_banner::ui(ui, stalled) {
+                        self.ui_state.stalled = None;
+                    }
+                } else if self.ui_state.swarm.is_some_and(|swarm| swarm.is_empty()) && self.stats.as_ref().is_some_and(|s| !s.finished) {
+                    ui.label(RichText::new(tr("app-swarm-empty")).color(theme.error).strong());
+                    ui.label(RichText::new(tr("app-swarm-empty-hint")).color(theme.text_muted));
+                }
+                if let Some(err) = &self.last_error {
+                    ui.label(RichText::new(err).color(theme.error));
+                    if let Some(hint) = self.sync_error.as_ref().filter(|e| e.message() == err).and_then(|e| e.hint_id()) {
+                        ui.label(RichText::new(tr(hint)).color(theme.text_muted));
+                    }
+                }
+                if let Some(notice) = &self.notice {
+                    ui.label(RichText::new(notice).color(theme.notice));
+                }
+                if !self.ui_state.inconsistencies.is_empty() {
+                    for problem in &self.ui_state.inconsistencies {
+                        let text = match problem {
+                            Inconsistency::InfoHash { cached, loaded } => tr_args(
+                                "app-inconsistent-torrent",
+                                &[("cached", cached.as_str().into()), ("loaded", loaded.as_str().into())],
+                            ),
+                            Inconsistency::OutputFolder { configured, loaded } => tr_args(
+                                "app-inconsistent-folder",
+                                &[
+                                    ("configured", configured.display().to_string().into()),
+                                    ("loaded", loaded.display().to_string().into()),
+                                ],
+                            ),
+                        };
+                        ui.label(RichText::new(text).color(theme.warning));
+                    }
+                    if ui.button(tr("app-repair-torrent")).on_hover_text(tr("app-repair-torrent-hover")).clicked() {
+                        self.send(SyncCommand::FixMissingFiles);
+                    }
+                }
+                if let (Some(missing), Some(extra)) = (&self.ui_state.missing_files, &self.ui_state.extra_files) {
+                    ui.label(tr_args("app-file-summary", &[("missing", missing.len().into()), ("extra", extra.len().into())]));
+                    if self.read_only && !extra.is_empty() {
+                        ui.label(RichText::new(tr("app-read-only-extras")).weak());
+                    }
+                }
+                ui.add_space(6.0);
+                if !self.stats_detached {
+                    statistics::ui(ui, &self.ui_state.lifetime.totals());
+                    if let Some(usage) = &self.ui_state.disk_usage {
+                        storage_view::ui(ui, usage);
+                    }
+                    if popout::button(ui) {
+                        self.stats_detached = true;
+                    }
+                }
+                if self.pinned {
+                    ui.add_space(6.0);
+                    ui.label(RichText::new(tr("app-pinned")).weak());
+                } else if self.pending_update.is_some() {
+                    ui.add_space(6.0);
+                    ui.label(tr("app-update-available"));
+                    if let Some(summary) = &self.pending_summary {
+                        let name = summary.name.clone().unwrap_or_else(|| tr("app-update-unnamed"));
+                        ui.label(
+                            RichText::new(tr_args(
+                                "app-update-summary",
+                                &[
+                                    ("name", name.into()),
+                                    ("size", format::size(summary.total_bytes).into()),
+                                    ("files", summary.file_count.into()),
+                                ],
+                            ))
+                            .strong(),
+                        )
+                        .on_hover_text(tr_args("app-update-info-hash", &[("hash", summary.info_hash.as_str().into())]));
+                    }
+                    if ui.button(RichText::new(tr("app-apply-update")).strong()).clicked()
+                        && let Some(torrent) = self.pending_update.take()
+                    {
+                        self.send(SyncCommand::ApplyUpdate(torrent));
+                    }
+                }
+            });
+        });
+
+        let stats = self.ui_state.lifetime.totals();
+        let usage = self.ui_state.disk_usage.as_ref();
+        popout::show(ctx, "stats_window", &tr("stats-title"), &mut self.stats_detached, |ui| {
+            statistics::ui(ui, &stats);
+            if let Some(usage) = usage {
+                storage_view::ui(ui, usage);
+            }
+        });
+        let log_view = &mut self.log_view;
+        popout::show(ctx, "log_window", &tr("app-tab-log"), &mut self.log_detached, |ui| log_view.ui(ui));
+
+        if self.settings_panel.open {
+            let mut open = true;
+            egui::Window::new(tr("settings-title"))
+                .id(egui::Id::new("settings_window"))
+                .open(&mut open)
+                .show(ctx, |ui| self.settings_panel.ui(ui, &self.ui_state.health, self.stats.as_ref()));
+            self.settings_panel.open &= open;
+            if self.settings_panel.loaded() {
+                if self.settings_panel.theme != self.theme_settings {
+                    self.theme_settings = self.settings_panel.theme.clone();
+                }
+                if self.settings_panel.display != self.display {
+                    self.display = self.settings_panel.display.clone();
+                }
+                if self.settings_panel.bandwidth_presets != self.bandwidth_presets {
+                    self.bandwidth_presets = self.settings_panel.bandwidth_presets.clone();
+                }
+                self.gaming_shortcut = shortcuts::parse_shortcut(&self.settings_panel.gaming_shortcut_str);
+                self.read_only = self.settings_panel.read_only;
+                self.pinned = self.settings_panel.pinned;
+                self.seed_window = SeedWindow::parse(&self.settings_panel.seed_window_str);
+                self.banned_peers = self.settings_panel.banned_peers.clone();
+                self.notifications = self.settings_panel.notifications();
+                if std::mem::take(&mut self.settings_panel.compare_versions) {
+                    self.version_diff.show_versions();
+                }
+                if let Some(path) = self.settings_panel.roll_back.take() {
+                    match std::fs::read(&path) {
+                        Ok(torrent) => self.send(SyncCommand::RollBack(torrent)),
+                        Err(e) => self.last_error = Some(format!("Failed to read {}: {}", path.display(), e)),
+                    }
+                }
+            }
+        }
+
+        self.version_diff.show(ctx);
+        #[cfg(feature = "grpc")]
+        self.fleet.show(ctx);
+
+        if let Some(report) = self.crash_reports.last().cloned() {
+            egui::Window::new(tr("app-crash-title"))
+                .id(egui::Id::new("crash_window"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(tr_args("app-crash-found", &[("count", self.crash_reports.len().into())]));
+                    ui.label(RichText::new(report.display().to_string()).weak());
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("app-crash-open")).clicked()
+                            && let Some(seen) = self.dismiss_crash_reports()
+                            && let Err(e) = opener::open(&seen)
+                        {
+                            eprintln!("UI: Failed to open crash report: {}", e);
+                        }
+                        if ui.button(tr("app-crash-open-folder")).clicked()
+                            && let Some(dir) = report.parent()
+                            && let Err(e) = opener::open(dir)
+                        {
+                            eprintln!("UI: Failed to open crash folder: {}", e);
+                        }
+                        if ui.button(tr("app-crash-dismiss")).clicked() {
+                            let _ = self.dismiss_crash_reports();
+                        }
+                    });
+                });
+        }
+
+        if self.confirm_remove {
+            egui::Window::new(tr("app-remove-torrent-title"))
+                .id(egui::Id::new("remove_torrent_window"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(RichText::new(tr_args("app-remove-torrent-warning", &[("folder", self.ui_state.folder.trim().into())])).color(theme.warning));
+                    ui.horizontal(|ui| {
+                        if ui.button(RichText::new(tr("app-remove-torrent-confirm")).color(theme.error)).clicked() {
+                            self.send(SyncCommand::RemoveTorrentAndData);
+                            self.confirm_remove = false;
+                        }
+                        if ui.button(tr("app-cancel")).clicked() {
+                            self.confirm_remove = false;
+                        }
+                    });
+                });
+        }
+
+        if let Some(message) = self.disk_full.clone() {
+            egui::Window::new(tr("disk-full-title"))
+                .id(egui::Id::new("disk_full_window"))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let folder = self.ui_state.folder.trim().to_string();
+                    ui.label(RichText::new(tr_args("disk-full-message", &[("folder", folder.clone().into())])).color(theme.error));
+                    ui.label(RichText::new(message).color(theme.text_muted));
+                    let free = match crate::sync::usage::available_space(std::path::Path::new(&folder)) {
+                        Some(free) => format::size(free),
+                        None => tr("disk-full-free-unknown"),
+                    };
+                    ui.label(tr_args("disk-full-free", &[("free", free.into())]));
+                    ui.label(tr("disk-full-advice"));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("disk-full-open-folder")).clicked() {
+                            self.run_action(Action::OpenFolder);
+                        }
+                        if ui.button(tr("disk-full-change-folder")).clicked()
+                            && let Some(folder) = FileDialog::new().pick_folder()
+                        {
+                            self.ui_state.folder = folder.display().to_string();
+                            self.load_settings_into_daemon();
+                            self.disk_full = None;
+                        }
+                        if ui.button(tr("disk-full-resume")).clicked() {
+                            self.send(SyncCommand::SetPaused(false));
+                            self.disk_full = None;
+                            self.disk_full_dismissed = None;
+                        }
+                        if ui.button(tr("disk-full-dismiss")).clicked() {
+                            self.disk_full_dismissed = self.disk_full.take();
+                        }
+                    });
+                });
+        }
+
+        if self.show_shortcuts {
+            let mut open = true;
+            egui::Window::new(tr("shortcuts-title"))
+                .id(egui::Id::new("shortcuts_window"))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    egui::Grid::new("shortcuts_table").striped(true).show(ui, |ui| {
+                        for (action, shortcut) in SHORTCUTS {
+                            ui.label(RichText::new(ctx.format_shortcut(shortcut)).monospace());
+                            ui.label(tr(action.label_id()));
+                            ui.end_row();
+                        }
+                        if let Some(shortcut) = &self.gaming_shortcut {
+                            ui.label(RichText::new(ctx.format_shortcut(shortcut)).monospace());
+                            ui.label(tr(Action::ToggleGamingMode.label_id()));
+                            ui.end_row();
+                        }
+                        ui.label(RichText::new("Esc").monospace());
+                        ui.label(tr("shortcut-close-dialog"));
+                        ui.end_row();
+                        ui.label(RichText::new("Enter").monospace());
+                        ui.label(tr("shortcut-confirm-dialog"));
+                        ui.end_row();
+                    });
+                });
+            self.show_shortcuts &= open;
+        }
+
+        if self.palette.open {
+            let entries = self.palette_entries(ctx);
+            match self.palette.ui(ctx, &entries) {
+                Some(PaletteCommand::Action(action)) => self.run_action(action),
+                Some(PaletteCommand::Tab(tab)) => self.tab = tab,
+                Some(PaletteCommand::Preset(preset)) => self.apply_preset(&preset),
+                Some(PaletteCommand::CopyDiagnostics) => self.copy_diagnostics(ctx),
+                None => {}
+            }
+        }
+
+        // Daemon updates and input wake the window; otherwise only the heartbeat
+        ctx.request_repaint_after(repaint::HEARTBEAT);
     }
 }
 
 impl ModApp {
+    /// App attached to the daemon through the channels from `crate::ipc::connect`,
+    /// with the inputs filled from the saved settings.
+    pub fn with_daemon(
+        cmd_tx: mpsc::UnboundedSender<SyncCommand>,
+        updates: mpsc::UnboundedReceiver<ConnectionUpdate>,
+    ) -> Self {
+        let settings = AppSettings::load().unwrap_or_else(|e| {
+            eprintln!("UI: Failed to load settings, using defaults: {:#}", e);
+            AppSettings::default()
+        });
+        if let Err(e) = i18n::set_language(&settings.language) {
+            eprintln!("UI: {:#}, using {}", e, i18n::DEFAULT_LANGUAGE);
+        }
+        Self {
+            daemon: Some(DaemonLink { cmd_tx, updates, connected: false }),
+            theme_settings: settings.theme.clone(),
+            display: settings.display.clone(),
+            ui_state: UiState::new(settings.torrent_url.clone(), settings.download_path.display().to_string()),
+            should_seed: settings.should_seed,
+            read_only: settings.read_only,
+            pinned: settings.pinned,
+            seed_window: settings.seed_window,
+            banned_peers: settings.banned_peers.clone(),
+            notifications: settings.notifications.clone(),
+            upload_str: settings.max_upload_speed.map(|v| v.to_string()).unwrap_or_default(),
+            download_str: settings.max_download_speed.map(|v| v.to_string()).unwrap_or_default(),
+            bandwidth_presets: settings.bandwidth_presets.clone(),
+            gaming_shortcut: shortcuts::parse_shortcut(&settings.gaming_mode_shortcut),
+            crash_reports: crash::crash_dir().map(|dir| crash::pending_reports(&dir)).unwrap_or_default(),
+            ..Self::default()
+        }
+    }
+
+    /// Repaint `ctx` as soon as the daemon connection reports something,
+    /// rather than on the next heartbeat.
+    pub fn wake_on_updates(&mut self, ctx: &egui::Context) {
+        if let Some(link) = &mut self.daemon {
+            let updates = std::mem::replace(&mut link.updates, mpsc::unbounded_channel().1);
+            link.updates = repaint::wake_on_receive(ctx, updates);
+        }
+    }
+
     /// Accept managed torrent updates from the sync layer.
-    pub fn on_managed_torrent_update(&mut self, stats_opt: Option<(usize, std::sync::Arc<librqbit::TorrentStats>)>) {
+    pub fn on_managed_torrent_update(&mut self, stats_opt: Option<(usize, TorrentSnapshot)>) {
+        let was_downloading = self.stats.as_ref().is_some_and(|stats| !stats.finished);
+        self.stats = stats_opt.as_ref().map(|(_, stats)| stats.clone());
+        if was_downloading
+            && self.stats.as_ref().is_some_and(|stats| stats.finished)
+            && self.notifications.sound
+            && !self.notifications.is_quiet()
+        {
+            sound::play(Sound::Completed);
+        }
         if let Some((_id, stats)) = stats_opt {
+            self.paused = stats.state == "paused";
+            self.check_monitor.observe(&stats, Instant::now());
             self.torrent_progress.update_from_stats(&stats);
+            self.ui_state.update_stats(Some(&stats));
         } else {
+            self.paused = false;
+            self.check_monitor = CheckMonitor::default();
+            self.ui_state.update_stats(None);
             self.torrent_progress = crate::ui::torrent_progress::TorrentProgress::new();
         }
     }
-}
\ No newline at end of file
+
+    /// Dialog keys first (Escape closes and Enter confirms the front-most
+    /// dialog), then the global shortcuts.
+    fn handle_keyboard(&mut self, ctx: &egui::Context) {
+        // The palette reads its own keys while open; other shortcuts are
+        // swallowed so they don't run behind it
+        if self.palette.open {
+            if shortcuts::pressed(ctx).contains(&Action::CommandPalette) {
+                self.palette.toggle();
+            }
+            return;
+        }
+        if self.disk_full.is_some() {
+            if shortcuts::escape_pressed(ctx) {
+                self.disk_full_dismissed = self.disk_full.take();
+            }
+        } else if self.confirm_remove {
+            // Enter doesn't confirm here, deleting files takes a click
+            if shortcuts::escape_pressed(ctx) {
+                self.confirm_remove = false;
+            }
+        } else if self.show_shortcuts {
+            if shortcuts::escape_pressed(ctx) || shortcuts::enter_pressed(ctx) {
+                self.show_shortcuts = false;
+            }
+        } else if self.version_diff.open {
+            if shortcuts::escape_pressed(ctx) {
+                self.version_diff.open = false;
+            }
+        } else if self.settings_panel.open {
+            if shortcuts::escape_pressed(ctx) {
+                self.settings_panel.open = false;
+            } else if shortcuts::enter_pressed(ctx) {
+                self.settings_panel.save();
+            }
+        } else {
+            #[cfg(feature = "grpc")]
+            if self.fleet.open && shortcuts::escape_pressed(ctx) {
+                self.fleet.open = false;
+            }
+        }
+
+        for action in shortcuts::pressed(ctx) {
+            self.run_action(action);
+        }
+        if let Some(shortcut) = self.gaming_shortcut
+            && shortcuts::shortcut_pressed(ctx, &shortcut)
+        {
+            self.run_action(Action::ToggleGamingMode);
+        }
+    }
+
+    /// Everything the command palette offers, with its label.
+    fn palette_entries(&self, ctx: &egui::Context) -> Vec<(PaletteCommand, String)> {
+        let mut entries: Vec<(PaletteCommand, String)> = SHORTCUTS
+            .iter()
+            .map(|(action, _)| *action)
+            .filter(|action| *action != Action::CommandPalette)
+            .chain(std::iter::once(Action::ToggleGamingMode))
+            .map(|action| {
+                let hint = shortcut_hint(ctx, action);
+                let label = if hint.is_empty() { tr(action.label_id()) } else { format!("{}  ({})", tr(action.label_id()), hint) };
+                (PaletteCommand::Action(action), label)
+            })
+            .collect();
+        entries.extend(Tab::ALL.map(|tab| (PaletteCommand::Tab(tab), tr_args("palette-go-to", &[("tab", tr(tab.label_id()).into())]))));
+        entries.extend(
+            self.bandwidth_presets
+                .iter()
+                .map(|preset| (PaletteCommand::Preset(preset.clone()), tr_args("palette-preset", &[("name", preset.name.clone().into())]))),
+        );
+        entries.push((PaletteCommand::CopyDiagnostics, tr("app-copy-diagnostics")));
+        entries
+    }
+
+    fn run_action(&mut self, action: Action) {
+        match action {
+            Action::CheckForUpdates => self.send(SyncCommand::DownloadAndCompare(self.ui_state.url.clone())),
+            Action::Verify => self.send(SyncCommand::VerifyFolder),
+            Action::TogglePause => self.send(SyncCommand::SetPaused(!self.paused)),
+            Action::OpenFolder => {
+                if let Err(e) = opener::open(self.ui_state.folder.trim()) {
+                    self.last_error = Some(tr_args("app-open-folder-failed", &[("error", e.to_string().into())]));
+                }
+            }
+            Action::OpenSettings => self.settings_panel.open = !self.settings_panel.open,
+            Action::ShowShortcuts => self.show_shortcuts = !self.show_shortcuts,
+            Action::CommandPalette => self.palette.toggle(),
+            Action::ToggleGamingMode => self.send(SyncCommand::SetGamingMode(!self.gaming_mode)),
+        }
+    }
+
+    /// Mark every pending crash report as seen, returning where the latest
+    /// one is now.
+    fn dismiss_crash_reports(&mut self) -> Option<PathBuf> {
+        let mut latest = None;
+        for report in self.crash_reports.drain(..) {
+            match crash::acknowledge(&report) {
+                Ok(seen) => latest = Some(seen),
+                Err(e) => eprintln!("UI: {:#}", e),
+            }
+        }
+        latest
+    }
+
+    /// Put a diagnostic report on the clipboard for a support request.
+    fn copy_diagnostics(&mut self, ctx: &egui::Context) {
+        let settings = AppSettings::load().unwrap_or_else(|e| {
+            eprintln!("UI: Failed to load settings for diagnostics: {:#}", e);
+            AppSettings::default()
+        });
+        ctx.copy_text(diagnostics::report(&settings, &self.status, self.stats.as_ref()));
+        self.notice = Some(tr("app-diagnostics-copied"));
+    }
+
+    /// Ask where to save a diagnostic bundle and write it there.
+    fn save_diagnostic_bundle(&mut self) {
+        let name = format!("modsync-diagnostics-{}.zip", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        let Some(path) = FileDialog::new().set_file_name(name).add_filter("zip", &["zip"]).save_file() else {
+            return;
+        };
+        let settings = AppSettings::load().unwrap_or_else(|e| {
+            eprintln!("UI: Failed to load settings for diagnostics: {:#}", e);
+            AppSettings::default()
+        });
+        match diagnostics::write_bundle(&path, &settings, &self.status, self.stats.as_ref()) {
+            Ok(()) => self.notice = Some(tr_args("app-diagnostics-saved", &[("path", path.display().to_string().into())])),
+            Err(e) => self.last_error = Some(tr_args("app-diagnostics-save-failed", &[("error", format!("{:#}", e).into())])),
+        }
+    }
+
+    /// Check the saved torrent URL right away, regardless of the schedule.
+    fn check_now(&mut self) {
+        let url = match AppSettings::load() {
+            Ok(settings) => settings.torrent_url,
+            Err(e) => {
+                eprintln!("UI: Failed to load settings, checking the entered URL: {:#}", e);
+                self.ui_state.url.trim().to_string()
+            }
+        };
+        self.send(SyncCommand::DownloadAndCompare(url));
+    }
+
+    fn handle_ui_action(&mut self, ctx: &egui::Context, action: UiAction) {
+        match action {
+            UiAction::OpenLocation(path) => {
+                let full = PathBuf::from(self.ui_state.folder.trim()).join(&path);
+                let target = if full.is_dir() { full.as_path() } else { full.parent().unwrap_or(&full) };
+                if let Err(e) = opener::open(target) {
+                    self.last_error = Some(tr_args("app-open-folder-failed", &[("error", e.to_string().into())]));
+                }
+            }
+            UiAction::CopyPath(path) => ctx.copy_text(path.display().to_string()),
+            UiAction::IgnorePath(path) => self.ignore_path(path),
+            UiAction::RevealInGraph(path) => {
+                self.file_graph.reveal(&path);
+                self.tab = Tab::Graph;
+            }
+            UiAction::BanPeer(ip) => self.ban_peer(ip),
+        }
+    }
+
+    /// Add `ip` to the banned peers and hand the new config to the daemon.
+    /// librqbit only refuses it once the session is next created.
+    fn ban_peer(&mut self, ip: IpAddr) {
+        let mut settings = AppSettings::load().unwrap_or_default();
+        if !settings.banned_peers.contains(&ip) {
+            settings.banned_peers.push(ip);
+        }
+        if let Err(e) = settings.save() {
+            self.last_error = Some(tr_args("settings-save-failed", &[("error", format!("{:#}", e).into())]));
+            return;
+        }
+        self.settings_panel.banned_peers = settings.banned_peers.clone();
+        self.banned_peers = settings.banned_peers.clone();
+        match settings.to_sync_config() {
+            Ok(config) => self.send(SyncCommand::UpdateConfig(Box::new(config))),
+            Err(e) => self.last_error = Some(format!("{:#}", e)),
+        }
+    }
+
+    /// Add `path` to the ignore list, hand the new config to the daemon and
+    /// stop showing files below it as extra.
+    fn ignore_path(&mut self, path: PathBuf) {
+        let mut settings = AppSettings::load().unwrap_or_default();
+        if !settings.ignored_paths.contains(&path) {
+            settings.ignored_paths.push(path.clone());
+        }
+        if let Err(e) = settings.save() {
+            self.last_error = Some(tr_args("settings-save-failed", &[("error", format!("{:#}", e).into())]));
+            return;
+        }
+        self.settings_panel.ignored_paths = settings.ignored_paths.clone();
+        match settings.to_sync_config() {
+            Ok(config) => self.send(SyncCommand::UpdateConfig(Box::new(config))),
+            Err(e) => self.last_error = Some(format!("{:#}", e)),
+        }
+        if let Some(extra) = &mut self.ui_state.extra_files {
+            extra.retain(|extra| !extra.starts_with(&path));
+            self.ui_state.revision += 1;
+            self.ui_state.files_revision += 1;
+        }
+    }
+
+    /// Announce a newly found update with a sound and, while the window is
+    /// in the background, a toast, unless it is quiet hours. Carries out the
+    /// buttons clicked on earlier toasts.
+    fn announce_update(&mut self, ctx: &egui::Context) {
+        for action in self.toasts.actions() {
+            match action {
+                ToastAction::ApplyUpdate => {
+                    if let Some(torrent) = self.pending_update.take() {
+                        println!("UI: Applying the update from the notification");
+                        self.send(SyncCommand::ApplyUpdate(torrent));
+                    }
+                }
+                ToastAction::Later => {}
+            }
+        }
+        let Some(summary) = self.pending_summary.as_ref().filter(|_| !self.pinned && self.pending_update.is_some()) else {
+            return;
+        };
+        if self.announced_update.as_ref() == Some(&summary.info_hash) {
+            return;
+        }
+        self.announced_update = Some(summary.info_hash.clone());
+        if self.notifications.is_quiet() {
+            return;
+        }
+        if self.notifications.sound {
+            sound::play(Sound::UpdateFound);
+        }
+        if !Toasts::supported() || ctx.input(|i| i.focused) {
+            return;
+        }
+        let name = summary.name.clone().unwrap_or_else(|| tr("app-update-unnamed"));
+        let body = tr_args(
+            "app-update-summary",
+            &[("name", name.into()), ("size", format::size(summary.total_bytes).into()), ("files", summary.file_count.into())],
+        );
+        let buttons = [(ToastAction::ApplyUpdate, tr("app-apply-update")), (ToastAction::Later, tr("toast-later"))];
+        self.toasts.show(ctx, &tr("app-update-available"), &body, &buttons);
+    }
+
+    /// Drain everything the daemon connection has reported since the last frame.
+    fn poll_daemon(&mut self) {
+        let mut updates = Vec::new();
+        if let Some(link) = &mut self.daemon {
+            while let Ok(update) = link.updates.try_recv() {
+                match &update {
+                    ConnectionUpdate::Connected => link.connected = true,
+                    ConnectionUpdate::Disconnected => link.connected = false,
+                    ConnectionUpdate::Message(_) => {}
+                }
+                updates.push(update);
+            }
+        }
+        for update in updates {
+            match update {
+                ConnectionUpdate::Connected => self.last_error = None,
+                ConnectionUpdate::Disconnected => {
+                    self.last_error = Some(tr("daemon-lost"));
+                }
+                ConnectionUpdate::Message(DaemonMessage::State(state)) => {
+                    let DaemonState { status, stats, files, trackers, pending_update, last_remote_check, next_check_at, lifetime, history, health, git_source, disk_usage, inconsistencies, gaming_mode, recovery, power, swarm, stalled } = *state;
+                    self.gaming_mode = gaming_mode;
+                    self.power = power;
+                    if recovery.is_some() {
+                        self.notice = recovery;
+                    }
+                    self.restore = if status == SyncStatus::RestoringSession { RestoreState::Adding } else { RestoreState::Off };
+                    self.status = status;
+                    self.ui_state.last_remote_check = last_remote_check.as_deref().and_then(parse_timestamp);
+                    self.ui_state.next_check_at = next_check_at.as_deref().and_then(parse_timestamp);
+                    self.ui_state.files = files;
+                    self.ui_state.trackers = trackers;
+                    self.ui_state.swarm = swarm;
+                    self.ui_state.stalled = stalled;
+                    self.ui_state.revision += 1;
+                    self.ui_state.files_revision += 1;
+                    self.pending_summary = pending_update.as_deref().and_then(|t| TorrentSummary::from_torrent(t).ok());
+                    self.pending_update = pending_update;
+                    self.ui_state.history = history;
+                    self.ui_state.health = health;
+                    self.ui_state.git_source = git_source;
+                    self.ui_state.disk_usage = disk_usage;
+                    self.ui_state.inconsistencies = inconsistencies;
+                    // The daemon's totals already include the counters of a
+                    // running session, so the next update only sets the baseline
+                    self.ui_state.lifetime = if stats.is_some() {
+                        LifetimeCounter::resume(lifetime)
+                    } else {
+                        LifetimeCounter::new(lifetime)
+                    };
+                    self.on_managed_torrent_update(stats.map(|s| (0, s)));
+                }
+                ConnectionUpdate::Message(DaemonMessage::Event(event)) => self.on_sync_event(event),
+            }
+        }
+    }
+
+    fn on_sync_event(&mut self, event: SyncEvent) {
+        if let SyncEvent::ManagedTorrentUpdate(Some((id, stats))) = &event {
+            self.ui_state.lifetime.observe(*id, stats);
+        }
+        self.ui_state.history.observe(&event, self.ui_state.lifetime.totals());
+        self.restore.observe(&event);
+        match event {
+            SyncEvent::ManagedTorrentUpdate(update) => self.on_managed_torrent_update(update),
+            SyncEvent::StatusUpdate(status) => {
+                if status == SyncStatus::CheckingRemote {
+                    self.ui_state.last_remote_check = Some(chrono::Utc::now());
+                }
+                if status == SyncStatus::CheckingLocal {
+                    // A new verification starts; forget the previous findings
+                    self.ui_state.missing_files = None;
+                    self.ui_state.extra_files = None;
+                    self.ui_state.revision += 1;
+                    self.ui_state.files_revision += 1;
+                }
+                self.status = status;
+            }
+            SyncEvent::Error(err) => {
+                if let SyncError::DiskFull(message) = &err
+                    && self.disk_full_dismissed.as_ref() != Some(message)
+                {
+                    self.disk_full = Some(message.clone());
+                }
+                self.last_error = Some(err.message().to_string());
+                self.sync_error = Some(err);
+            }
+            // Gaming mode keeps notices from popping up mid-game
+            SyncEvent::Notice(msg) if self.gaming_mode => println!("UI: Notice held back in gaming mode: {}", msg),
+            SyncEvent::Notice(msg) => self.notice = Some(msg),
+            SyncEvent::GamingMode(enabled) => self.gaming_mode = enabled,
+            SyncEvent::PowerRestriction(power) => self.power = power,
+            SyncEvent::RemoteUpdateFound(torrent, summary) => {
+                self.pending_update = Some(torrent);
+                self.pending_summary = Some(summary);
+            }
+            SyncEvent::TorrentAdded(_) => {
+                self.pending_update = None;
+                self.pending_summary = None;
+                self.ui_state.swarm = None;
+                self.ui_state.stalled = None;
+            }
+            SyncEvent::Swarm(swarm) => self.ui_state.swarm = swarm,
+            SyncEvent::Stalled(stalled) => self.ui_state.stalled = stalled,
+            SyncEvent::Peers(peers) => self.ui_state.peers = peers,
+            SyncEvent::Trackers(trackers) => self.ui_state.trackers = trackers,
+            SyncEvent::TorrentFiles(files) => {
+                self.ui_state.files = files;
+                // Findings about the previous torrent no longer apply
+                self.ui_state.missing_files = None;
+                self.ui_state.extra_files = None;
+                self.ui_state.revision += 1;
+                self.ui_state.files_revision += 1;
+            }
+            SyncEvent::MissingFilesFound(missing) => {
+                self.ui_state.missing_files = Some(missing);
+                self.ui_state.revision += 1;
+            }
+            SyncEvent::ExtraFilesFound(extra) => {
+                // The daemon only reports missing files when there are some,
+                // and always reports extra files after checking for them
+                self.ui_state.missing_files.get_or_insert_default();
+                // Reported as full paths so they can be deleted; every view
+                // works with paths relative to the download folder
+                let folder = PathBuf::from(self.ui_state.folder.trim());
+                let extra = extra
+                    .into_iter()
+                    .map(|path| path.strip_prefix(&folder).map(PathBuf::from).unwrap_or(path))
+                    .collect();
+                self.ui_state.extra_files = Some(extra);
+                self.ui_state.revision += 1;
+                self.ui_state.files_revision += 1;
+            }
+            SyncEvent::RemoteSource(url) => self.ui_state.remote_source = Some(url),
+            SyncEvent::RemoteAttempt(attempt) => self.ui_state.health.record(attempt),
+            SyncEvent::GitSourceStatus(status) => self.ui_state.git_source = Some(status),
+            SyncEvent::DiskUsage(usage) => self.ui_state.disk_usage = Some(usage),
+            SyncEvent::ConsistencyCheck(problems) => self.ui_state.inconsistencies = problems,
+            SyncEvent::NextCheckAt(at) => self.ui_state.next_check_at = at.as_deref().and_then(parse_timestamp),
+            // Already folded into the history above
+            SyncEvent::RunStarted(_) | SyncEvent::RunFinished => {}
+            // The status that follows says where the restored session is
+            SyncEvent::SessionRestored => {}
+        }
+    }
+
+    /// Save the inputs as settings and hand the new config to the daemon.
+    fn load_settings_into_daemon(&mut self) {
+        let mut settings = AppSettings {
+            torrent_url: self.ui_state.url.trim().to_string(),
+            download_path: PathBuf::from(self.ui_state.folder.trim()),
+            max_upload_speed: self.upload_str.trim().parse().ok(),
+            max_download_speed: self.download_str.trim().parse().ok(),
+            should_seed: self.should_seed,
+            ..AppSettings::load().unwrap_or_default()
+        };
+        settings.remember_download_path();
+        if let Err(e) = settings.save() {
+            self.last_error = Some(tr_args("settings-save-failed", &[("error", format!("{:#}", e).into())]));
+            return;
+        }
+        match settings.to_sync_config() {
+            Ok(config) => {
+                self.send(SyncCommand::UpdateConfig(Box::new(config)));
+                self.send(SyncCommand::DownloadAndCompare(settings.torrent_url));
+            }
+            Err(e) => self.last_error = Some(format!("{:#}", e)),
+        }
+    }
+
+    /// Dropdown switching between the bandwidth presets, hidden while none
+    /// are defined.
+    fn preset_picker(&mut self, ui: &mut egui::Ui) {
+        if self.bandwidth_presets.is_empty() {
+            return;
+        }
+        let (upload, download) = (self.upload_str.trim().parse().ok(), self.download_str.trim().parse().ok());
+        let active = self.bandwidth_presets.iter().find(|p| p.is_active(upload, download));
+        let selected = active.map_or_else(|| tr("app-preset-custom"), |p| p.name.clone());
+        let mut chosen = None;
+        egui::ComboBox::from_id_salt("bandwidth_preset")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for preset in &self.bandwidth_presets {
+                    if ui.selectable_label(active == Some(preset), &preset.name).clicked() {
+                        chosen = Some(preset.clone());
+                    }
+                }
+            })
+            .response
+            .on_hover_text(tr("app-preset-hover"));
+        if let Some(preset) = chosen {
+            self.apply_preset(&preset);
+        }
+    }
+
+    /// Use the limits of `preset` right away and keep them in the settings.
+    fn apply_preset(&mut self, preset: &BandwidthPreset) {
+        let text = |limit: Option<u32>| limit.map(|v| v.to_string()).unwrap_or_default();
+        self.upload_str = text(preset.max_upload_speed);
+        self.download_str = text(preset.max_download_speed);
+        if self.settings_panel.loaded() {
+            self.settings_panel.upload_str = self.upload_str.clone();
+            self.settings_panel.download_str = self.download_str.clone();
+        }
+        let settings = AppSettings {
+            max_upload_speed: preset.max_upload_speed,
+            max_download_speed: preset.max_download_speed,
+            ..AppSettings::load().unwrap_or_default()
+        };
+        if let Err(e) = settings.save() {
+            self.last_error = Some(tr_args("settings-save-failed", &[("error", format!("{:#}", e).into())]));
+        }
+        println!("UI: Switched to bandwidth preset {}", preset.name);
+        self.send(SyncCommand::SetSpeedLimits {
+            max_upload_speed: preset.max_upload_speed,
+            max_download_speed: preset.max_download_speed,
+        });
+    }
+
+    /// Open the tab and panels of `layout`, as saved by `save_layout`.
+    pub fn restore_layout(&mut self, layout: WindowLayout) {
+        self.tab = layout.tab;
+        self.settings_panel.open = layout.settings_open;
+        self.stats_detached = layout.stats_detached;
+        self.log_detached = layout.log_detached;
+        #[cfg(feature = "grpc")]
+        {
+            self.fleet.open = layout.fleet_open;
+        }
+        self.layout = layout;
+    }
+
+    fn save_layout(&mut self) {
+        self.layout.tab = self.tab;
+        self.layout.settings_open = self.settings_panel.open;
+        self.layout.stats_detached = self.stats_detached;
+        self.layout.log_detached = self.log_detached;
+        #[cfg(feature = "grpc")]
+        {
+            self.layout.fleet_open = self.fleet.open;
+        }
+        if let Err(e) = self.layout.save() {
+            eprintln!("UI: Failed to save the window layout: {:#}", e);
+        }
+    }
+
+    /// Switch the UI language and remember it in the settings.
+    fn change_language(&mut self, code: &str) {
+        if let Err(e) = i18n::set_language(code) {
+            self.last_error = Some(format!("{:#}", e));
+            return;
+        }
+        let mut settings = AppSettings::load().unwrap_or_default();
+        settings.language = code.to_string();
+        if let Err(e) = settings.save() {
+            self.last_error = Some(tr_args("settings-save-failed", &[("error", format!("{:#}", e).into())]));
+        }
+    }
+
+    fn send(&mut self, cmd: SyncCommand) {
+        match &self.daemon {
+            Some(link) => {
+                if link.cmd_tx.send(cmd).is_err() {
+                    self.last_error = Some(tr("daemon-stopped"));
+                }
+            }
+            None => self.last_error = Some(tr("daemon-none")),
+        }
+    }
+}
+
+/// Hover text naming the keyboard shortcut bound to `action`.
+fn shortcut_hint(ctx: &egui::Context, action: Action) -> String {
+    SHORTCUTS
+        .iter()
+        .find(|(a, _)| *a == action)
+        .map(|(_, shortcut)| ctx.format_shortcut(shortcut))
+        .unwrap_or_default()
+}
@@ -0,0 +1,26 @@
+use eframe::egui;
+use egui::{FontId, TextStyle};
+
+use crate::settings::DisplaySettings;
+use crate::ui::format;
+
+/// Apply the zoom factor, text sizes and units from `display`. Values
+/// outside the supported ranges (e.g. from a hand-edited settings file) are
+/// clamped.
+pub fn apply(ctx: &egui::Context, display: &DisplaySettings) {
+    let scale = display.ui_scale.clamp(*DisplaySettings::UI_SCALE_RANGE.start(), *DisplaySettings::UI_SCALE_RANGE.end());
+    let body = display.font_size.clamp(*DisplaySettings::FONT_SIZE_RANGE.start(), *DisplaySettings::FONT_SIZE_RANGE.end());
+    ctx.set_zoom_factor(scale);
+
+    let mut style = (*ctx.style()).clone();
+    for (text_style, font) in style.text_styles.iter_mut() {
+        *font = match text_style {
+            TextStyle::Heading => FontId::proportional(body * 2.0),
+            TextStyle::Small => FontId::proportional(body * 0.75),
+            TextStyle::Monospace => FontId::monospace(body * 0.9),
+            _ => FontId::new(body, font.family.clone()),
+        };
+    }
+    ctx.set_style(style);
+    format::set_units(display.units);
+}
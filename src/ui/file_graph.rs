@@ -0,0 +1,613 @@
+use eframe::egui;
+use egui::{Color32, Pos2, Rect, RichText, Stroke};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::f32::consts::TAU;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::{tr, tr_args};
+use crate::sync::TorrentFile;
+use crate::ui::actions::{path_menu, UiAction};
+use crate::ui::theme::Theme;
+use crate::ui::format;
+
+/// Distance between the rings of successive folder depths.
+const RING_SPACING: f32 = 90.0;
+/// Node radius range; nodes are scaled by size within it.
+const MIN_RADIUS: f32 = 3.0;
+const MAX_RADIUS: f32 = 24.0;
+const ROOT_RADIUS: f32 = 10.0;
+/// Nodes shown before deeper folders start out collapsed.
+const NODE_BUDGET: usize = 1500;
+/// Folder labels are skipped once they'd be smaller than this on screen.
+const MIN_LABEL_PIXELS: f32 = 7.0;
+const LABEL_SIZE: f32 = 12.0;
+
+/// Sync state of a file, or the most urgent state below a folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NodeState {
+    #[default]
+    Complete,
+    Extra,
+    Partial,
+    Missing,
+}
+
+impl NodeState {
+    /// In legend order.
+    pub const ALL: [NodeState; 4] = [NodeState::Complete, NodeState::Partial, NodeState::Missing, NodeState::Extra];
+
+    pub fn color(self, theme: &Theme) -> Color32 {
+        match self {
+            NodeState::Complete => theme.progress_done,
+            NodeState::Partial => theme.progress_partial,
+            NodeState::Missing => theme.error,
+            NodeState::Extra => theme.text_muted,
+        }
+    }
+
+    pub fn label_id(self) -> &'static str {
+        match self {
+            NodeState::Complete => "graph-complete",
+            NodeState::Partial => "graph-partial",
+            NodeState::Missing => "graph-missing",
+            NodeState::Extra => "graph-extra",
+        }
+    }
+
+    /// State of a torrent file. The cleaner's missing list wins over
+    /// progress, which may still count pieces of a file that was deleted.
+    fn of_file(file: &TorrentFile, done: u64, missing: Option<&HashSet<PathBuf>>) -> Self {
+        if missing.is_some_and(|m| m.contains(&file.path)) {
+            NodeState::Missing
+        } else if file.included && done < file.length {
+            NodeState::Partial
+        } else {
+            NodeState::Complete
+        }
+    }
+}
+
+/// What the graph is built from: the torrent's files with their progress and
+/// the cleaner's latest findings.
+pub struct GraphInput<'a> {
+    pub files: &'a [TorrentFile],
+    pub file_progress: &'a [u64],
+    /// `None` until the folder has been verified.
+    pub missing_files: Option<&'a HashSet<PathBuf>>,
+    pub extra_files: &'a [PathBuf],
+}
+
+/// A node currently on screen: a file, an expanded folder, or a collapsed
+/// folder standing in for everything below it.
+struct GraphNode {
+    label: String,
+    path: PathBuf,
+    parent: Option<usize>,
+    pos: Pos2,
+    radius: f32,
+    state: NodeState,
+    folder: bool,
+    collapsed: bool,
+    length: u64,
+    file_count: usize,
+}
+
+struct FileEntry {
+    name: String,
+    path: PathBuf,
+    length: u64,
+    /// Index into the torrent's files, `None` for extra files.
+    index: Option<usize>,
+    state: NodeState,
+}
+
+/// Folder tree with per-folder totals. Built once per file list; states and
+/// totals are refreshed in place when progress changes.
+#[derive(Default)]
+struct Folder {
+    folders: BTreeMap<String, Folder>,
+    files: Vec<FileEntry>,
+    length: u64,
+    file_count: usize,
+    state: NodeState,
+    /// Files below this folder per state, in `NodeState::ALL` order.
+    counts: [usize; 4],
+}
+
+impl Folder {
+    fn build(input: &GraphInput) -> Self {
+        let mut root = Folder::default();
+        for (i, file) in input.files.iter().enumerate() {
+            root.insert(&file.path, file.length, Some(i));
+        }
+        for path in input.extra_files {
+            root.insert(path, 0, None);
+        }
+        root
+    }
+
+    fn insert(&mut self, path: &Path, length: u64, index: Option<usize>) {
+        let mut folder = self;
+        if let Some(parent) = path.parent() {
+            for component in parent.components() {
+                folder = folder.folders.entry(component.as_os_str().to_string_lossy().into_owned()).or_default();
+            }
+        }
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        folder.files.push(FileEntry { name, path: path.to_path_buf(), length, index, state: NodeState::Extra });
+    }
+
+    /// Recompute file states and folder totals from `input`.
+    fn refresh(&mut self, input: &GraphInput) {
+        self.length = 0;
+        self.file_count = 0;
+        self.state = NodeState::Complete;
+        self.counts = [0; 4];
+        for child in self.folders.values_mut() {
+            child.refresh(input);
+            self.length += child.length;
+            self.file_count += child.file_count;
+            self.state = self.state.max(child.state);
+            for (count, child_count) in self.counts.iter_mut().zip(child.counts) {
+                *count += child_count;
+            }
+        }
+        for entry in &mut self.files {
+            entry.state = match entry.index.and_then(|i| input.files.get(i)) {
+                Some(file) => {
+                    let done = entry.index.and_then(|i| input.file_progress.get(i)).copied().unwrap_or(0);
+                    NodeState::of_file(file, done, input.missing_files)
+                }
+                None => NodeState::Extra,
+            };
+            self.length += entry.length;
+            self.file_count += 1;
+            self.state = self.state.max(entry.state);
+            if let Some(slot) = NodeState::ALL.iter().position(|s| *s == entry.state) {
+                self.counts[slot] += 1;
+            }
+        }
+    }
+
+    /// Nodes per depth, the root being depth 0.
+    fn count_by_depth(&self, depth: usize, counts: &mut Vec<usize>) {
+        if counts.len() <= depth + 1 {
+            counts.resize(depth + 2, 0);
+        }
+        counts[depth + 1] += self.folders.len() + self.files.len();
+        for child in self.folders.values() {
+            child.count_by_depth(depth + 1, counts);
+        }
+    }
+}
+
+/// Leaf slots around the circle, handed out in layout order.
+struct Leaves {
+    total: f32,
+    next: usize,
+}
+
+impl Leaves {
+    fn angle(&self, slot: f32) -> f32 {
+        slot / self.total * TAU
+    }
+}
+
+/// Radial graph of the download folder: the root in the middle, folders on
+/// rings by depth and files as leaves, colored by sync state and sized by
+/// bytes. Folders can be collapsed into a single aggregate node; large trees
+/// start out collapsed below the depth that fits `NODE_BUDGET` nodes.
+pub struct FileGraph {
+    tree: Folder,
+    nodes: Vec<GraphNode>,
+    /// `UiState` revisions the tree and the nodes were built from.
+    built_files_revision: Option<u64>,
+    built_revision: Option<u64>,
+    /// Folders at this depth and below start out collapsed.
+    auto_collapse_depth: usize,
+    /// Folders the user expanded (`false`) or collapsed (`true`) by hand.
+    overrides: HashMap<PathBuf, bool>,
+    layout_dirty: bool,
+    scene_rect: Rect,
+    hovered: Option<usize>,
+    /// Node the open context menu belongs to.
+    menu_node: Option<usize>,
+    /// Path picked with "Reveal in graph", outlined until the next reveal.
+    highlight: Option<PathBuf>,
+    /// Center the view on `highlight` once the layout is up to date.
+    focus_pending: bool,
+}
+
+impl Default for FileGraph {
+    fn default() -> Self {
+        Self {
+            tree: Folder::default(),
+            nodes: Vec::new(),
+            built_files_revision: None,
+            built_revision: None,
+            auto_collapse_depth: usize::MAX,
+            overrides: HashMap::new(),
+            layout_dirty: true,
+            scene_rect: Rect::ZERO,
+            hovered: None,
+            menu_node: None,
+            highlight: None,
+            focus_pending: false,
+        }
+    }
+}
+
+impl FileGraph {
+    /// Bring the tree and the nodes up to date. The tree is only rebuilt when
+    /// the file list changed; progress updates just refresh its totals.
+    fn update(&mut self, files_revision: u64, revision: u64, input: &GraphInput) {
+        if self.built_files_revision != Some(files_revision) {
+            self.tree = Folder::build(input);
+            self.auto_collapse_depth = auto_collapse_depth(&self.tree);
+            self.overrides.clear();
+            self.built_files_revision = Some(files_revision);
+            self.built_revision = None;
+        }
+        if self.built_revision != Some(revision) {
+            self.tree.refresh(input);
+            self.built_revision = Some(revision);
+            self.layout_dirty = true;
+        }
+        if self.layout_dirty {
+            self.layout();
+            self.layout_dirty = false;
+        }
+    }
+
+    /// Expand every folder above `path` and center the view on it the next
+    /// time the graph is shown.
+    pub fn reveal(&mut self, path: &Path) {
+        for ancestor in path.ancestors().skip(1) {
+            if !ancestor.as_os_str().is_empty() {
+                self.overrides.insert(ancestor.to_path_buf(), false);
+            }
+        }
+        self.highlight = Some(path.to_path_buf());
+        self.focus_pending = true;
+        self.layout_dirty = true;
+    }
+
+    fn is_collapsed(&self, path: &Path, depth: usize) -> bool {
+        depth > 0 && self.overrides.get(path).copied().unwrap_or(depth >= self.auto_collapse_depth)
+    }
+
+    /// Leaf slots `folder` takes up with the current collapse state.
+    fn leaves(&self, folder: &Folder, path: &Path, depth: usize) -> usize {
+        if self.is_collapsed(path, depth) {
+            return 1;
+        }
+        let below: usize = folder
+            .folders
+            .iter()
+            .map(|(name, child)| self.leaves(child, &path.join(name), depth + 1))
+            .sum();
+        (below + folder.files.len()).max(1)
+    }
+
+    fn layout(&mut self) {
+        let tree = std::mem::take(&mut self.tree);
+        self.nodes.clear();
+        self.hovered = None;
+        self.menu_node = None;
+        let mut leaves = Leaves { total: self.leaves(&tree, Path::new(""), 0) as f32, next: 0 };
+        self.place(&tree, "/".to_string(), PathBuf::new(), 0, None, &mut leaves);
+        self.tree = tree;
+
+        // Size nodes relative to the largest one below the root
+        let max_length = self.nodes.iter().skip(1).map(|n| n.length).max().unwrap_or(0).max(1) as f32;
+        for (i, node) in self.nodes.iter_mut().enumerate() {
+            node.radius = if i == 0 {
+                ROOT_RADIUS
+            } else {
+                MIN_RADIUS + (MAX_RADIUS - MIN_RADIUS) * (node.length as f32 / max_length).sqrt()
+            };
+        }
+    }
+
+    /// Add `folder` and everything visible below it, returning the folder's
+    /// node index. Leaves are spread evenly around the circle and each folder
+    /// sits at the middle of the angle its leaves span.
+    fn place(
+        &mut self,
+        folder: &Folder,
+        label: String,
+        path: PathBuf,
+        depth: usize,
+        parent: Option<usize>,
+        leaves: &mut Leaves,
+    ) -> usize {
+        let index = self.nodes.len();
+        let collapsed = self.is_collapsed(&path, depth);
+        self.nodes.push(GraphNode {
+            label,
+            path: path.clone(),
+            parent,
+            pos: Pos2::ZERO,
+            radius: 0.0,
+            state: folder.state,
+            folder: true,
+            collapsed,
+            length: folder.length,
+            file_count: folder.file_count,
+        });
+
+        let first_leaf = leaves.next;
+        if !collapsed {
+            for (name, child) in &folder.folders {
+                self.place(child, name.clone(), path.join(name), depth + 1, Some(index), leaves);
+            }
+            for entry in &folder.files {
+                let angle = leaves.angle(leaves.next as f32);
+                leaves.next += 1;
+                self.nodes.push(GraphNode {
+                    label: entry.name.clone(),
+                    path: entry.path.clone(),
+                    parent: Some(index),
+                    pos: polar(angle, (depth + 1) as f32 * RING_SPACING),
+                    radius: 0.0,
+                    state: entry.state,
+                    folder: false,
+                    collapsed: false,
+                    length: entry.length,
+                    file_count: 1,
+                });
+            }
+        }
+        if leaves.next == first_leaf {
+            // Empty and collapsed folders take a slot of their own
+            leaves.next += 1;
+        }
+
+        let mid_leaf = (first_leaf + leaves.next - 1) as f32 / 2.0;
+        self.nodes[index].pos = polar(leaves.angle(mid_leaf), depth as f32 * RING_SPACING);
+        index
+    }
+
+    /// Show the tab, returning what the user picked from the context menu.
+    pub fn ui(&mut self, ui: &mut egui::Ui, files_revision: u64, revision: u64, input: &GraphInput) -> Vec<UiAction> {
+        let mut actions = Vec::new();
+        self.update(files_revision, revision, input);
+        let theme = Theme::current(ui.ctx());
+        if self.focus_pending {
+            self.focus_pending = false;
+            if let Some(node) = self.nodes.iter().find(|n| Some(&n.path) == self.highlight.as_ref()) {
+                let size = if self.scene_rect.is_positive() { self.scene_rect.size() } else { egui::vec2(800.0, 600.0) };
+                self.scene_rect = Rect::from_center_size(node.pos, size);
+            }
+        }
+
+        ui.horizontal(|ui| {
+            for (state, count) in NodeState::ALL.into_iter().zip(self.tree.counts) {
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                ui.painter().circle_filled(rect.center(), 6.0, state.color(&theme));
+                ui.label(tr_args(state.label_id(), &[("count", count.into())]));
+                ui.add_space(8.0);
+            }
+            if input.missing_files.is_none() {
+                ui.label(RichText::new(tr("graph-not-verified")).weak());
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button(tr("graph-reset-view")).on_hover_text(tr("graph-reset-view-hover")).clicked() {
+                    // An empty rect makes the scene fit its contents again
+                    self.scene_rect = Rect::ZERO;
+                }
+                if ui.button(tr("graph-collapse-all")).clicked() {
+                    self.overrides.clear();
+                    self.auto_collapse_depth = 1;
+                    self.layout_dirty = true;
+                }
+                if ui.button(tr("graph-expand-all")).on_hover_text(tr("graph-expand-all-hover")).clicked() {
+                    self.overrides.clear();
+                    self.auto_collapse_depth = usize::MAX;
+                    self.layout_dirty = true;
+                }
+            });
+        });
+        ui.label(RichText::new(tr("graph-help")).weak());
+        ui.separator();
+
+        if input.files.is_empty() && input.extra_files.is_empty() {
+            ui.label(tr("files-empty"));
+            return actions;
+        }
+
+        let hovered = self.hovered;
+        let highlight = self.highlight.as_ref();
+        let nodes = &self.nodes;
+        let zoom = if self.scene_rect.width() > 0.0 { ui.available_width() / self.scene_rect.width() } else { 1.0 };
+        let response = egui::Scene::new()
+            .zoom_range(0.02..=4.0)
+            .show(ui, &mut self.scene_rect, |ui| {
+                // Level of detail: skip anything outside the visible area and
+                // labels too small to read
+                let visible = ui.clip_rect().expand(MAX_RADIUS);
+                let painter = ui.painter();
+                let edge = Stroke::new(1.0, theme.text_muted.gamma_multiply(0.5));
+                for node in nodes {
+                    if let Some(parent) = node.parent {
+                        let from = nodes[parent].pos;
+                        if visible.intersects(Rect::from_two_pos(from, node.pos)) {
+                            painter.line_segment([from, node.pos], edge);
+                        }
+                    }
+                }
+                let show_labels = LABEL_SIZE * zoom >= MIN_LABEL_PIXELS;
+                for (i, node) in nodes.iter().enumerate() {
+                    if !visible.contains(node.pos) {
+                        continue;
+                    }
+                    painter.circle_filled(node.pos, node.radius, node.state.color(&theme));
+                    if node.collapsed {
+                        painter.circle_stroke(node.pos, node.radius, Stroke::new(2.0, theme.text));
+                    }
+                    if hovered == Some(i) {
+                        painter.circle_stroke(node.pos, node.radius + 2.0, Stroke::new(2.0, theme.accent));
+                    }
+                    if highlight == Some(&node.path) {
+                        painter.circle_stroke(node.pos, node.radius + 6.0, Stroke::new(3.0, theme.accent));
+                    }
+                    if node.folder && show_labels {
+                        let label = if node.collapsed {
+                            tr_args("graph-collapsed-label", &[("name", node.label.as_str().into()), ("count", node.file_count.into())])
+                        } else {
+                            node.label.clone()
+                        };
+                        painter.text(
+                            node.pos + egui::vec2(0.0, -node.radius - 2.0),
+                            egui::Align2::CENTER_BOTTOM,
+                            label,
+                            egui::FontId::proportional(LABEL_SIZE),
+                            theme.text,
+                        );
+                    }
+                }
+            })
+            .response;
+
+        self.hovered = response.hover_pos().and_then(|pos| self.node_at(pos));
+        if response.secondary_clicked() {
+            self.menu_node = self.hovered.filter(|&i| i > 0);
+        }
+        if let Some(node) = self.menu_node.and_then(|i| self.nodes.get(i)) {
+            let path = node.path.clone();
+            response.context_menu(|ui| path_menu(ui, &path, false, &mut actions));
+        }
+        if response.clicked()
+            && let Some(i) = self.hovered
+        {
+            self.toggle(i);
+        } else if let Some(node) = self.hovered.map(|i| &self.nodes[i]) {
+            response.on_hover_ui_at_pointer(|ui| {
+                let path = if node.path.as_os_str().is_empty() { "/".to_string() } else { node.path.display().to_string() };
+                ui.label(RichText::new(path).strong());
+                ui.label(format::size(node.length));
+                if node.folder {
+                    ui.label(tr_args("graph-folder-files", &[("count", node.file_count.into())]));
+                }
+                ui.label(RichText::new(tr_args(node.state.label_id(), &[("count", node.file_count.into())])).color(node.state.color(&theme)));
+            });
+        }
+        actions
+    }
+
+    /// Collapse or expand the folder at node `i`.
+    fn toggle(&mut self, i: usize) {
+        let node = &self.nodes[i];
+        if !node.folder || node.parent.is_none() {
+            return;
+        }
+        self.overrides.insert(node.path.clone(), !node.collapsed);
+        self.layout_dirty = true;
+    }
+
+    /// The node under `pos` in scene coordinates, preferring the closest.
+    fn node_at(&self, pos: Pos2) -> Option<usize> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (i, node.pos.distance(pos), node.radius))
+            .filter(|(_, distance, radius)| *distance <= radius + 2.0)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _, _)| i)
+    }
+}
+
+/// Deepest folder depth that can be expanded while keeping the graph within
+/// `NODE_BUDGET` nodes; `usize::MAX` when the whole tree fits.
+fn auto_collapse_depth(tree: &Folder) -> usize {
+    let mut counts = Vec::new();
+    tree.count_by_depth(0, &mut counts);
+    let mut shown = 1;
+    for (depth, count) in counts.iter().enumerate().skip(1) {
+        shown += count;
+        if shown > NODE_BUDGET {
+            // Folders at the previous depth become aggregates
+            return (depth - 1).max(1);
+        }
+    }
+    usize::MAX
+}
+
+fn polar(angle: f32, radius: f32) -> Pos2 {
+    Pos2::new(angle.cos() * radius, angle.sin() * radius)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, length: u64) -> TorrentFile {
+        TorrentFile { path: PathBuf::from(path), length, included: true }
+    }
+
+    #[test]
+    fn test_folder_state_is_most_urgent_child() {
+        let files = vec![file("mods/a.pbo", 10), file("mods/b.pbo", 10), file("keys/k.bikey", 1)];
+        let progress = vec![10, 4, 1];
+        let missing: HashSet<PathBuf> = [PathBuf::from("keys/k.bikey")].into();
+        let extra = vec![PathBuf::from("mods/old.pbo")];
+        let input = GraphInput {
+            files: &files,
+            file_progress: &progress,
+            missing_files: Some(&missing),
+            extra_files: &extra,
+        };
+        let mut graph = FileGraph::default();
+        graph.update(0, 0, &input);
+
+        let state_of = |path: &str| graph.nodes.iter().find(|n| n.path == Path::new(path)).unwrap().state;
+        assert_eq!(state_of("mods/a.pbo"), NodeState::Complete);
+        assert_eq!(state_of("mods/b.pbo"), NodeState::Partial);
+        assert_eq!(state_of("mods/old.pbo"), NodeState::Extra);
+        assert_eq!(state_of("mods"), NodeState::Partial);
+        assert_eq!(state_of("keys"), NodeState::Missing);
+        assert_eq!(state_of(""), NodeState::Missing);
+        assert_eq!(graph.nodes.len(), 7);
+        assert_eq!(graph.tree.counts, [1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_collapsed_folder_aggregates_its_subtree() {
+        let files = vec![file("mods/a/x.pbo", 30), file("mods/b.pbo", 10), file("z.txt", 40)];
+        let progress = vec![0, 10, 40];
+        let input = GraphInput { files: &files, file_progress: &progress, missing_files: None, extra_files: &[] };
+        let mut graph = FileGraph::default();
+        graph.update(0, 0, &input);
+        assert_eq!(graph.nodes.len(), 6);
+
+        let mods = graph.nodes.iter().position(|n| n.path == Path::new("mods")).unwrap();
+        graph.toggle(mods);
+        graph.update(0, 0, &input);
+        assert_eq!(graph.nodes.len(), 3);
+        let mods = graph.nodes.iter().find(|n| n.path == Path::new("mods")).unwrap();
+        assert!(mods.collapsed);
+        assert_eq!((mods.length, mods.file_count, mods.state), (40, 2, NodeState::Partial));
+        // Sized by bytes: the 40 byte folder and file match, the root is fixed
+        let z = graph.nodes.iter().find(|n| n.path == Path::new("z.txt")).unwrap();
+        assert_eq!(mods.radius, z.radius);
+        assert_eq!(z.radius, MAX_RADIUS);
+    }
+
+    #[test]
+    fn test_large_trees_start_collapsed() {
+        let files: Vec<_> = (0..NODE_BUDGET * 2).map(|i| file(&format!("mod{}/addons/{}.pbo", i % 10, i), 1)).collect();
+        let progress = vec![1; files.len()];
+        let input = GraphInput { files: &files, file_progress: &progress, missing_files: None, extra_files: &[] };
+        let mut graph = FileGraph::default();
+        graph.update(0, 0, &input);
+
+        // Root, ten mod folders and their addons folders as aggregates
+        assert_eq!(graph.auto_collapse_depth, 2);
+        assert_eq!(graph.nodes.len(), 21);
+        assert_eq!(graph.tree.counts[0], NODE_BUDGET * 2);
+
+        graph.reveal(Path::new("mod3/addons/13.pbo"));
+        graph.update(0, 0, &input);
+        assert!(graph.nodes.iter().any(|n| n.path == Path::new("mod3/addons/13.pbo")));
+    }
+}
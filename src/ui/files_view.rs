@@ -0,0 +1,326 @@
+use eframe::egui;
+use egui::RichText;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::i18n::{tr, tr_args};
+use crate::sync::TorrentFile;
+use crate::ui::actions::{path_menu, UiAction};
+use crate::ui::theme::Theme;
+use crate::ui::format;
+
+const ROW_HEIGHT: f32 = 20.0;
+const SIZE_COLUMN_WIDTH: f32 = 90.0;
+const PROGRESS_COLUMN_WIDTH: f32 = 110.0;
+const TREE_BAR_WIDTH: f32 = 80.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilesMode {
+    Tree,
+    List,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Name,
+    Size,
+    Progress,
+}
+
+/// Files tab: the torrent's files as a folder tree or as a flat list with
+/// sortable columns. Both modes show the same data and honor the filter.
+pub struct FilesView {
+    mode: FilesMode,
+    filter: String,
+    sort: SortColumn,
+    ascending: bool,
+}
+
+impl Default for FilesView {
+    fn default() -> Self {
+        Self {
+            mode: FilesMode::Tree,
+            filter: String::new(),
+            sort: SortColumn::Name,
+            ascending: true,
+        }
+    }
+}
+
+/// Fraction of `file` downloaded, given its entry in `file_progress`.
+fn progress_fraction(file: &TorrentFile, done: u64) -> f64 {
+    fraction(done, file.length)
+}
+
+/// Whether `file` still has bytes to download. Files left out of the
+/// download are never incomplete.
+fn is_incomplete(file: &TorrentFile, done: u64) -> bool {
+    file.included && done < file.length
+}
+
+/// Small progress bar for one file or folder, green when complete and orange
+/// while bytes are missing.
+fn progress_bar(ui: &mut egui::Ui, fraction: f64, width: f32) -> egui::Response {
+    let theme = Theme::current(ui.ctx());
+    let fill = if fraction >= 1.0 { theme.progress_done } else { theme.progress_partial };
+    ui.add(
+        egui::ProgressBar::new(fraction as f32)
+            .desired_width(width)
+            .desired_height(ROW_HEIGHT - 6.0)
+            .fill(fill)
+            .text(format!("{:.0}%", fraction * 100.0)),
+    )
+}
+
+/// Indices into `files` whose path contains `filter` (case-insensitive),
+/// ordered by `sort`.
+pub fn visible_files(
+    files: &[TorrentFile],
+    file_progress: &[u64],
+    filter: &str,
+    sort: SortColumn,
+    ascending: bool,
+) -> Vec<usize> {
+    let filter = filter.trim().to_lowercase();
+    let done = |i: usize| file_progress.get(i).copied().unwrap_or(0);
+    let mut rows: Vec<usize> = (0..files.len())
+        .filter(|&i| filter.is_empty() || files[i].path.to_string_lossy().to_lowercase().contains(&filter))
+        .collect();
+    rows.sort_by(|&a, &b| {
+        let ordering = match sort {
+            SortColumn::Name => files[a].path.cmp(&files[b].path),
+            SortColumn::Size => files[a].length.cmp(&files[b].length),
+            SortColumn::Progress => progress_fraction(&files[a], done(a))
+                .partial_cmp(&progress_fraction(&files[b], done(b)))
+                .unwrap_or(Ordering::Equal),
+        };
+        // Ties keep name order so rows don't jump around between frames
+        let ordering = ordering.then_with(|| files[a].path.cmp(&files[b].path));
+        if ascending { ordering } else { ordering.reverse() }
+    });
+    rows
+}
+
+/// Folder in the tree view: subfolders by name and the indices of the files
+/// directly inside it.
+#[derive(Default)]
+struct FolderNode {
+    folders: BTreeMap<String, FolderNode>,
+    files: Vec<usize>,
+}
+
+impl FolderNode {
+    /// Total size and downloaded bytes of the files in this folder and below.
+    fn totals(&self, files: &[TorrentFile], file_progress: &[u64]) -> (u64, u64) {
+        let own = self.files.iter().fold((0, 0), |(length, done), &i| {
+            let file_done = file_progress.get(i).copied().unwrap_or(0).min(files[i].length);
+            (length + files[i].length, done + file_done)
+        });
+        self.folders.values().fold(own, |(length, done), child| {
+            let (child_length, child_done) = child.totals(files, file_progress);
+            (length + child_length, done + child_done)
+        })
+    }
+
+    fn build(files: &[TorrentFile], rows: &[usize]) -> Self {
+        let mut root = Self::default();
+        for &i in rows {
+            let mut node = &mut root;
+            if let Some(parent) = files[i].path.parent() {
+                for component in parent.components() {
+                    let name = component.as_os_str().to_string_lossy().into_owned();
+                    node = node.folders.entry(name).or_default();
+                }
+            }
+            node.files.push(i);
+        }
+        root
+    }
+}
+
+impl FilesView {
+    /// Show the tab, returning what the user picked from context menus.
+    pub fn ui(&mut self, ui: &mut egui::Ui, files: &[TorrentFile], file_progress: &[u64]) -> Vec<UiAction> {
+        let mut actions = Vec::new();
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.mode, FilesMode::Tree, tr("files-tree"));
+            ui.selectable_value(&mut self.mode, FilesMode::List, tr("files-list"));
+            ui.add_space(12.0);
+            let label = ui.label(tr("files-filter"));
+            ui.add(egui::widgets::TextEdit::singleline(&mut self.filter).hint_text(tr("files-filter-hint")).desired_width(220.0))
+                .labelled_by(label.id);
+            if !self.filter.is_empty() && ui.small_button("x").on_hover_text(tr("files-filter-clear")).clicked() {
+                self.filter.clear();
+            }
+        });
+        ui.add_space(4.0);
+
+        if files.is_empty() {
+            ui.label(tr("files-empty"));
+            return actions;
+        }
+
+        let rows = visible_files(files, file_progress, &self.filter, self.sort, self.ascending);
+        ui.label(tr_args("files-count", &[("shown", rows.len().into()), ("total", files.len().into())]));
+        match self.mode {
+            FilesMode::Tree => self.tree_ui(ui, files, file_progress, &rows, &mut actions),
+            FilesMode::List => self.list_ui(ui, files, file_progress, &rows, &mut actions),
+        }
+        actions
+    }
+
+    fn tree_ui(&self, ui: &mut egui::Ui, files: &[TorrentFile], file_progress: &[u64], rows: &[usize], actions: &mut Vec<UiAction>) {
+        // The tree is always alphabetic; sorting only applies to the list
+        let mut by_name = rows.to_vec();
+        by_name.sort_by(|&a, &b| files[a].path.cmp(&files[b].path));
+        let root = FolderNode::build(files, &by_name);
+        let expand = !self.filter.trim().is_empty();
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            folder_ui(ui, &root, Path::new(""), files, file_progress, expand, actions);
+        });
+    }
+
+    fn list_ui(&mut self, ui: &mut egui::Ui, files: &[TorrentFile], file_progress: &[u64], rows: &[usize], actions: &mut Vec<UiAction>) {
+        let name_width = (ui.available_width() - SIZE_COLUMN_WIDTH - PROGRESS_COLUMN_WIDTH - 2.0 * ui.spacing().item_spacing.x).max(120.0);
+        ui.horizontal(|ui| {
+            self.header_button(ui, SortColumn::Name, "files-col-name", name_width);
+            self.header_button(ui, SortColumn::Size, "files-col-size", SIZE_COLUMN_WIDTH);
+            self.header_button(ui, SortColumn::Progress, "files-col-progress", PROGRESS_COLUMN_WIDTH);
+        });
+        ui.separator();
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show_rows(ui, ROW_HEIGHT, rows.len(), |ui, range| {
+            for &i in &rows[range] {
+                let file = &files[i];
+                let done = file_progress.get(i).copied().unwrap_or(0);
+                ui.horizontal(|ui| {
+                    ui.add_sized([name_width, ROW_HEIGHT], egui::Label::new(file_name_text(ui, file, done, file.path.display().to_string())).truncate())
+                        .on_hover_text(file.path.display().to_string());
+                    ui.add_sized([SIZE_COLUMN_WIDTH, ROW_HEIGHT], egui::Label::new(format::size(file.length)));
+                    progress_bar(ui, progress_fraction(file, done), PROGRESS_COLUMN_WIDTH)
+                        .on_hover_text(transferred_text(file, done));
+                })
+                .response
+                .interact(egui::Sense::click())
+                .context_menu(|ui| path_menu(ui, &file.path, true, actions));
+            }
+        });
+    }
+
+    /// Column header that sorts by `column`, toggling direction when it is
+    /// already the sort column.
+    fn header_button(&mut self, ui: &mut egui::Ui, column: SortColumn, label_id: &str, width: f32) {
+        let mut text = tr(label_id);
+        if self.sort == column {
+            text.push_str(if self.ascending { " ^" } else { " v" });
+        }
+        let response = ui
+            .add_sized([width, ROW_HEIGHT], egui::widgets::Button::new(RichText::new(text).strong()).frame(false))
+            .on_hover_text(tr("files-sort-hover"));
+        if response.clicked() {
+            if self.sort == column {
+                self.ascending = !self.ascending;
+            } else {
+                self.sort = column;
+                self.ascending = true;
+            }
+        }
+    }
+}
+
+fn folder_ui(
+    ui: &mut egui::Ui,
+    node: &FolderNode,
+    path: &Path,
+    files: &[TorrentFile],
+    file_progress: &[u64],
+    expand: bool,
+    actions: &mut Vec<UiAction>,
+) {
+    let theme = Theme::current(ui.ctx());
+    for (name, child) in &node.folders {
+        let child_path = path.join(name);
+        let (length, done) = child.totals(files, file_progress);
+        let mut title = RichText::new(format!("{}/  {} ({:.0}%)", name, format::size(length), fraction(done, length) * 100.0));
+        if done < length {
+            title = title.color(theme.progress_partial);
+        }
+        egui::CollapsingHeader::new(title)
+            .id_salt(("files_tree", &child_path))
+            .open(expand.then_some(true))
+            .show(ui, |ui| folder_ui(ui, child, &child_path, files, file_progress, expand, actions))
+            .header_response
+            .context_menu(|ui| path_menu(ui, &child_path, true, actions));
+    }
+    for &i in &node.files {
+        let file = &files[i];
+        let done = file_progress.get(i).copied().unwrap_or(0);
+        let name = file.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        ui.horizontal(|ui| {
+            progress_bar(ui, progress_fraction(file, done), TREE_BAR_WIDTH).on_hover_text(transferred_text(file, done));
+            ui.label(file_name_text(ui, file, done, name));
+            ui.label(RichText::new(format::size(file.length)).weak());
+        })
+        .response
+        .interact(egui::Sense::click())
+        .context_menu(|ui| path_menu(ui, &file.path, true, actions));
+    }
+}
+
+fn fraction(done: u64, length: u64) -> f64 {
+    if length == 0 { 1.0 } else { (done as f64 / length as f64).min(1.0) }
+}
+
+/// `name` highlighted when the file is incomplete and dimmed when it is not
+/// part of the download.
+fn file_name_text(ui: &egui::Ui, file: &TorrentFile, done: u64, name: String) -> RichText {
+    let text = RichText::new(name);
+    if !file.included {
+        text.weak()
+    } else if is_incomplete(file, done) {
+        text.color(Theme::current(ui.ctx()).progress_partial)
+    } else {
+        text
+    }
+}
+
+fn transferred_text(file: &TorrentFile, done: u64) -> String {
+    tr_args(
+        "files-transferred",
+        &[("done", format::size(done.min(file.length)).into()), ("total", format::size(file.length).into())],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn file(path: &str, length: u64) -> TorrentFile {
+        TorrentFile { path: PathBuf::from(path), length, included: true }
+    }
+
+    #[test]
+    fn test_visible_files_filters_and_sorts() {
+        let files = vec![file("mods/b.pbo", 300), file("mods/a.pbo", 100), file("keys/a.bikey", 10)];
+        let progress = vec![300, 0, 5];
+
+        assert_eq!(visible_files(&files, &progress, "", SortColumn::Name, true), vec![2, 1, 0]);
+        assert_eq!(visible_files(&files, &progress, "", SortColumn::Size, false), vec![0, 1, 2]);
+        assert_eq!(visible_files(&files, &progress, "", SortColumn::Progress, true), vec![1, 2, 0]);
+        assert_eq!(visible_files(&files, &progress, "MODS", SortColumn::Name, true), vec![1, 0]);
+        assert!(visible_files(&files, &progress, "missing", SortColumn::Name, true).is_empty());
+    }
+
+    #[test]
+    fn test_folder_totals_include_subfolders() {
+        let files = vec![file("mods/a/x.pbo", 100), file("mods/y.pbo", 50), file("z.txt", 10)];
+        let progress = vec![40, 50, 200];
+        let root = FolderNode::build(&files, &[0, 1, 2]);
+
+        assert_eq!(root.folders["mods"].totals(&files, &progress), (150, 90));
+        assert_eq!(root.totals(&files, &progress), (160, 100));
+        assert!(is_incomplete(&files[0], progress[0]));
+        assert!(!is_incomplete(&files[2], progress[2]));
+    }
+}
@@ -0,0 +1,248 @@
+use eframe::egui;
+use egui::RichText;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::fleet::{check_fleet, query_fleet, NodeStatus};
+use crate::i18n::{tr, tr_args};
+use crate::settings::AppSettings;
+use crate::ui::theme::Theme;
+use crate::ui::format;
+
+/// How often node statuses are refreshed while the window is open.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+enum NodeRow {
+    Pending,
+    Online(NodeStatus),
+    Offline(String),
+}
+
+enum FleetResult {
+    Statuses(Vec<(String, Result<NodeStatus, String>)>),
+    Checked(Vec<(String, Result<(), String>)>),
+}
+
+/// Window listing remote daemons with their status and bulk actions.
+pub struct FleetPanel {
+    pub open: bool,
+    loaded: bool,
+    nodes: Vec<String>,
+    new_node: String,
+    rows: HashMap<String, NodeRow>,
+    results_tx: mpsc::UnboundedSender<FleetResult>,
+    results_rx: mpsc::UnboundedReceiver<FleetResult>,
+    last_refresh: Option<Instant>,
+    message: Option<String>,
+}
+
+impl Default for FleetPanel {
+    fn default() -> Self {
+        let (results_tx, results_rx) = mpsc::unbounded_channel();
+        Self {
+            open: false,
+            loaded: false,
+            nodes: Vec::new(),
+            new_node: String::new(),
+            rows: HashMap::new(),
+            results_tx,
+            results_rx,
+            last_refresh: None,
+            message: None,
+        }
+    }
+}
+
+impl FleetPanel {
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+        if !self.loaded {
+            self.loaded = true;
+            match AppSettings::load() {
+                Ok(settings) => self.nodes = settings.fleet_nodes,
+                Err(e) => self.message = Some(tr_args("settings-load-failed", &[("error", format!("{:#}", e).into())])),
+            }
+        }
+        self.drain_results();
+        if self.last_refresh.is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL) {
+            self.refresh();
+        }
+
+        let mut open = self.open;
+        egui::Window::new(tr("fleet-title"))
+            .id(egui::Id::new("fleet_window"))
+            .open(&mut open)
+            .default_width(640.0)
+            .show(ctx, |ui| self.ui(ui));
+        self.open = open;
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        let theme = Theme::current(ui.ctx());
+        ui.horizontal(|ui| {
+            if ui.button(tr("fleet-refresh")).clicked() {
+                self.refresh();
+            }
+            if ui
+                .add(egui::widgets::Button::new(RichText::new(tr("fleet-check-all")).strong()).fill(theme.accent))
+                .clicked()
+            {
+                self.check_all();
+            }
+        });
+        ui.add_space(6.0);
+
+        let mut remove = None;
+        egui::Grid::new("fleet_table").striped(true).num_columns(7).show(ui, |ui| {
+            for header in [
+                "fleet-col-node",
+                "fleet-col-status",
+                "fleet-col-version",
+                "fleet-col-progress",
+                "fleet-col-seed-ratio",
+                "fleet-col-update",
+            ] {
+                ui.label(RichText::new(tr(header)).strong());
+            }
+            ui.label("");
+            ui.end_row();
+
+            for (i, node) in self.nodes.iter().enumerate() {
+                ui.label(node);
+                match self.rows.get(node).unwrap_or(&NodeRow::Pending) {
+                    NodeRow::Pending => {
+                        ui.label("...");
+                        for _ in 0..4 {
+                            ui.label("");
+                        }
+                    }
+                    NodeRow::Online(status) => {
+                        if status.error.is_empty() {
+                            ui.label(&status.status);
+                        } else {
+                            ui.label(RichText::new(&status.status).color(theme.error))
+                                .on_hover_text(&status.error);
+                        }
+                        ui.label(&status.version);
+                        ui.label(format!(
+                            "{} / {}",
+                            format::size(status.progress_bytes),
+                            format::size(status.total_bytes)
+                        ));
+                        ui.label(format::number(status.seed_ratio(), 2));
+                        ui.label(if status.update_available { tr("fleet-update-available") } else { "-".to_string() });
+                    }
+                    NodeRow::Offline(err) => {
+                        ui.label(RichText::new(tr("fleet-offline")).color(theme.text_muted))
+                            .on_hover_text(err);
+                        for _ in 0..4 {
+                            ui.label("");
+                        }
+                    }
+                }
+                if ui.small_button(tr("fleet-remove")).on_hover_text(tr_args("fleet-remove-hover", &[("node", node.as_str().into())])).clicked() {
+                    remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(i) = remove {
+            let node = self.nodes.remove(i);
+            self.rows.remove(&node);
+            self.save_nodes();
+        }
+
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            ui.add(egui::widgets::TextEdit::singleline(&mut self.new_node).hint_text("http://host:50051").desired_width(260.0))
+                .on_hover_text(tr("fleet-node-hover"));
+            let new_node = self.new_node.trim().to_string();
+            if ui.button(tr("fleet-add-node")).clicked() && !new_node.is_empty() && !self.nodes.contains(&new_node) {
+                self.nodes.push(new_node);
+                self.new_node.clear();
+                self.save_nodes();
+                self.refresh();
+            }
+        });
+
+        if let Some(msg) = &self.message {
+            ui.colored_label(theme.notice, msg);
+        }
+    }
+
+    fn drain_results(&mut self) {
+        while let Ok(result) = self.results_rx.try_recv() {
+            match result {
+                FleetResult::Statuses(statuses) => {
+                    for (node, status) in statuses {
+                        let row = match status {
+                            Ok(status) => NodeRow::Online(status),
+                            Err(e) => NodeRow::Offline(e),
+                        };
+                        self.rows.insert(node, row);
+                    }
+                }
+                FleetResult::Checked(results) => {
+                    let failed: Vec<_> = results.iter().filter(|(_, r)| r.is_err()).map(|(n, _)| n.as_str()).collect();
+                    self.message = Some(if failed.is_empty() {
+                        tr_args("fleet-check-started", &[("count", results.len().into())])
+                    } else {
+                        tr_args("fleet-check-failed", &[("nodes", failed.join(", ").into())])
+                    });
+                    self.refresh();
+                }
+            }
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.last_refresh = Some(Instant::now());
+        let nodes = self.nodes.clone();
+        let tx = self.results_tx.clone();
+        self.spawn(async move {
+            let statuses = query_fleet(&nodes)
+                .await
+                .into_iter()
+                .map(|(node, r)| (node, r.map_err(|e| format!("{:#}", e))))
+                .collect();
+            let _ = tx.send(FleetResult::Statuses(statuses));
+        });
+    }
+
+    fn check_all(&mut self) {
+        let nodes = self.nodes.clone();
+        let tx = self.results_tx.clone();
+        self.spawn(async move {
+            let results = check_fleet(&nodes)
+                .await
+                .into_iter()
+                .map(|(node, r)| (node, r.map_err(|e| format!("{:#}", e))))
+                .collect();
+            let _ = tx.send(FleetResult::Checked(results));
+        });
+    }
+
+    fn spawn<F>(&mut self, task: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(task);
+            }
+            Err(_) => self.message = Some(tr("fleet-no-runtime")),
+        }
+    }
+
+    fn save_nodes(&mut self) {
+        let mut settings = AppSettings::load().unwrap_or_default();
+        settings.fleet_nodes = self.nodes.clone();
+        if let Err(e) = settings.save() {
+            self.message = Some(tr_args("settings-save-failed", &[("error", format!("{:#}", e).into())]));
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Numbers, sizes and speeds as shown in the UI.
+//!
+//! Decimal and thousands separators follow the UI language and sizes use
+//! the units picked in the display settings, so every view shows the same
+//! figure the same way. Like the language, the units are process-wide and
+//! set with `set_units`.
+
+use std::sync::RwLock;
+
+use crate::i18n;
+use crate::settings::UnitSystem;
+
+static UNITS: RwLock<UnitSystem> = RwLock::new(UnitSystem::Binary);
+
+pub fn units() -> UnitSystem {
+    *UNITS.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn set_units(units: UnitSystem) {
+    *UNITS.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = units;
+}
+
+/// Decimal and thousands separators of `language`.
+fn separators(language: &str) -> (char, char) {
+    match language {
+        "de" => (',', '.'),
+        _ => ('.', ','),
+    }
+}
+
+fn number_in(value: f64, decimals: usize, language: &str) -> String {
+    let (decimal, group) = separators(language);
+    let text = format!("{:.*}", decimals, value.abs());
+    let (whole, fraction) = text.split_once('.').map_or((text.as_str(), None), |(whole, fraction)| (whole, Some(fraction)));
+    let mut out = String::new();
+    if value < 0.0 && text.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+        out.push('-');
+    }
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            out.push(group);
+        }
+        out.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        out.push(decimal);
+        out.push_str(fraction);
+    }
+    out
+}
+
+fn size_in(bytes: u64, units: UnitSystem, language: &str) -> String {
+    let (step, names) = match units {
+        UnitSystem::Binary => (1024.0, ["KiB", "MiB", "GiB", "TiB"]),
+        UnitSystem::Si => (1000.0, ["kB", "MB", "GB", "TB"]),
+    };
+    let mut value = bytes as f64;
+    if value < step {
+        return format!("{} B", number_in(value, 0, language));
+    }
+    let mut name = names[0];
+    for next in names {
+        if value < step {
+            break;
+        }
+        value /= step;
+        name = next;
+    }
+    format!("{} {}", number_in(value, 2, language), name)
+}
+
+/// `value` with `decimals` decimal places and grouped thousands.
+pub fn number(value: f64, decimals: usize) -> String {
+    number_in(value, decimals, i18n::current_language())
+}
+
+/// `bytes` in the configured units with two decimal places.
+pub fn size(bytes: u64) -> String {
+    size_in(bytes, units(), i18n::current_language())
+}
+
+pub fn speed(bytes_per_sec: f64) -> String {
+    format!("{}/s", size(bytes_per_sec.max(0.0) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes_and_numbers() {
+        assert_eq!(size_in(512, UnitSystem::Binary, "en"), "512 B");
+        assert_eq!(size_in(1536, UnitSystem::Binary, "en"), "1.50 KiB");
+        assert_eq!(size_in(1536, UnitSystem::Si, "de"), "1,54 kB");
+        assert_eq!(size_in(38 * 1024 * 1024 * 1024, UnitSystem::Binary, "en"), "38.00 GiB");
+        assert_eq!(size_in(5_000_000_000_000_000, UnitSystem::Si, "en"), "5,000.00 TB");
+        assert_eq!(number_in(1234567.891, 2, "de"), "1.234.567,89");
+        assert_eq!(number_in(-0.001, 2, "en"), "0.00");
+        assert_eq!(number_in(-1000.0, 0, "en"), "-1,000");
+    }
+}
@@ -1,5 +1,8 @@
 use eframe::egui;
-use egui::{RichText, Color32};
+use egui::RichText;
+
+use crate::i18n::tr;
+use crate::ui::theme::Theme;
 
 #[derive(Default)]
 pub struct Header {}
@@ -8,9 +11,10 @@ impl Header {
     pub fn ui(&self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
             ui.add_space(6.0);
-            let banner = RichText::new("ModSync").size(34.0).strong().color(Color32::from_rgb(180, 255, 200));
+            let theme = Theme::current(ui.ctx());
+            let banner = RichText::new("ModSync").size(34.0).strong().color(theme.banner);
             ui.heading(banner);
-            ui.label(RichText::new("Background sync manager").color(Color32::from_rgb(160, 160, 170)));
+            ui.label(RichText::new(tr("header-tagline")).color(theme.text_muted));
         });
     }
 }
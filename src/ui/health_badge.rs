@@ -0,0 +1,41 @@
+use eframe::egui;
+
+use crate::i18n::{tr, tr_args};
+use crate::sync::{EndpointHealth, Health};
+use crate::ui::theme::Theme;
+
+const BADGE_SIZE: f32 = 12.0;
+
+fn label_id(health: Health) -> &'static str {
+    match health {
+        Health::Unknown => "health-unknown",
+        Health::Good => "health-good",
+        Health::Degraded => "health-degraded",
+        Health::Down => "health-down",
+    }
+}
+
+/// Colored dot for `url`'s health, with the latest latency and error on hover.
+pub fn badge(ui: &mut egui::Ui, health: &EndpointHealth, url: &str) -> egui::Response {
+    let theme = Theme::current(ui.ctx());
+    let state = health.health(url);
+    let color = match state {
+        Health::Unknown => theme.text_muted,
+        Health::Good => theme.success,
+        Health::Degraded => theme.warning,
+        Health::Down => theme.error,
+    };
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(BADGE_SIZE, BADGE_SIZE), egui::Sense::hover());
+    ui.painter().circle_filled(rect.center(), BADGE_SIZE / 2.0, color);
+
+    let mut hover = tr(label_id(state));
+    if let Some(last) = health.last(url) {
+        hover.push('\n');
+        hover.push_str(&tr_args("health-latency", &[("ms", last.latency_ms.into())]));
+    }
+    if let Some(err) = health.last_error(url) {
+        hover.push('\n');
+        hover.push_str(&tr_args("health-last-error", &[("error", err.into())]));
+    }
+    response.on_hover_text(hover)
+}
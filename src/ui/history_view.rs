@@ -0,0 +1,124 @@
+use eframe::egui;
+use egui::RichText;
+use rfd::FileDialog;
+
+use crate::i18n::{tr, tr_args};
+use crate::sync::{RunRecord, RunTrigger, SyncHistory};
+use crate::ui::status_bar::human_readable_duration;
+use crate::ui::theme::Theme;
+use crate::ui::format;
+
+/// History tab: the most recent sync runs, newest first, with CSV export.
+#[derive(Default)]
+pub struct HistoryView {
+    message: Option<String>,
+}
+
+fn trigger_label_id(trigger: RunTrigger) -> &'static str {
+    match trigger {
+        RunTrigger::Check => "history-trigger-check",
+        RunTrigger::Scheduled => "history-trigger-scheduled",
+        RunTrigger::Update => "history-trigger-update",
+        RunTrigger::Verify => "history-trigger-verify",
+        RunTrigger::Repair => "history-trigger-repair",
+    }
+}
+
+fn parse_time(rfc3339: &str) -> Option<chrono::DateTime<chrono::Local>> {
+    chrono::DateTime::parse_from_rfc3339(rfc3339)
+        .ok()
+        .map(|t| t.with_timezone(&chrono::Local))
+}
+
+impl HistoryView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, history: &SyncHistory) {
+        let theme = Theme::current(ui.ctx());
+        ui.horizontal(|ui| {
+            let has_runs = history.runs().next().is_some();
+            if ui.add_enabled(has_runs, egui::widgets::Button::new(tr("history-export"))).clicked() {
+                self.export(history);
+            }
+            if let Some(msg) = &self.message {
+                ui.colored_label(theme.notice, msg);
+            }
+        });
+        ui.add_space(6.0);
+
+        if history.current().is_none() && history.runs().next().is_none() {
+            ui.label(RichText::new(tr("history-empty")).color(theme.text_muted));
+            return;
+        }
+
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            egui::Grid::new("history_table").striped(true).num_columns(6).show(ui, |ui| {
+                for header in [
+                    "history-col-started",
+                    "history-col-trigger",
+                    "history-col-duration",
+                    "history-col-transferred",
+                    "history-col-files",
+                    "history-col-result",
+                ] {
+                    ui.label(RichText::new(tr(header)).strong());
+                }
+                ui.end_row();
+
+                for run in history.current().into_iter().chain(history.runs().rev()) {
+                    run_row(ui, &theme, run);
+                }
+            });
+        });
+    }
+
+    fn export(&mut self, history: &SyncHistory) {
+        let Some(path) = FileDialog::new()
+            .set_file_name("modsync-history.csv")
+            .add_filter("CSV", &["csv"])
+            .save_file()
+        else {
+            return;
+        };
+        self.message = Some(match std::fs::write(&path, history.to_csv()) {
+            Ok(()) => tr_args("history-exported", &[("path", path.display().to_string().into())]),
+            Err(e) => {
+                eprintln!("UI: Failed to export history to {}: {}", path.display(), e);
+                tr_args("history-export-failed", &[("error", e.to_string().into())])
+            }
+        });
+    }
+}
+
+fn run_row(ui: &mut egui::Ui, theme: &Theme, run: &RunRecord) {
+    let started = parse_time(&run.started_at);
+    ui.label(started.map_or_else(|| run.started_at.clone(), |t| t.format("%Y-%m-%d %H:%M:%S").to_string()));
+    ui.label(tr(trigger_label_id(run.trigger)));
+    let finished = run.finished_at.as_deref().and_then(parse_time);
+    match (started, finished) {
+        (Some(started), Some(finished)) => {
+            let elapsed = (finished - started).to_std().unwrap_or_default();
+            ui.label(human_readable_duration(elapsed));
+        }
+        _ => {
+            ui.label("-");
+        }
+    }
+    ui.label(format!(
+        "{} / {}",
+        format::size(run.downloaded_bytes),
+        format::size(run.uploaded_bytes)
+    ));
+    ui.label(run.files_changed.to_string());
+    match (&run.finished_at, &run.error) {
+        (_, Some(err)) => {
+            ui.label(RichText::new(tr("history-result-failed")).color(theme.error))
+                .on_hover_text(err);
+        }
+        (None, None) => {
+            ui.label(RichText::new(tr("history-result-running")).color(theme.warning));
+        }
+        (Some(_), None) => {
+            ui.label(RichText::new(tr("history-result-ok")).color(theme.success));
+        }
+    }
+    ui.end_row();
+}
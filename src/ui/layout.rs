@@ -0,0 +1,120 @@
+//! Window size and position, the open and popped-out panels and the
+//! selected tab, kept in a file next to the settings so the window comes
+//! back the way it was left. It is written when the window closes and is
+//! not a setting: a missing or unreadable file just means the default
+//! layout.
+
+use anyhow::{Context, Result};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::settings::AppSettings;
+use crate::ui::app::Tab;
+
+/// Smallest window restored, so a bad file can't leave it unusable.
+const MIN_SIZE: [f32; 2] = [320.0, 240.0];
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowLayout {
+    /// Outer position of the window in logical pixels, while not maximized.
+    pub position: Option<[f32; 2]>,
+    /// Inner size of the window in logical pixels, while not maximized.
+    pub size: Option<[f32; 2]>,
+    pub maximized: bool,
+    pub settings_open: bool,
+    pub fleet_open: bool,
+    /// Statistics and log shown in windows of their own.
+    pub stats_detached: bool,
+    pub log_detached: bool,
+    pub tab: Tab,
+}
+
+impl WindowLayout {
+    fn file_path() -> Result<PathBuf> {
+        Ok(AppSettings::data_dir()?.join("modsync-layout.toml"))
+    }
+
+    /// The saved layout, or the default one if there is none.
+    pub fn load() -> Self {
+        let load = || -> Result<Self> {
+            let path = Self::file_path()?;
+            if !path.exists() {
+                return Ok(Self::default());
+            }
+            let s = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            toml::from_str(&s).context("Failed to parse the window layout")
+        };
+        load().unwrap_or_else(|e| {
+            eprintln!("UI: {:#}, using the default window layout", e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path()?;
+        let toml = toml::to_string_pretty(self).context("Failed to serialize the window layout")?;
+        fs::write(&path, toml).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// `builder` opening the window where it was.
+    pub fn viewport(&self, mut builder: egui::ViewportBuilder) -> egui::ViewportBuilder {
+        if let Some([width, height]) = self.size {
+            builder = builder.with_inner_size([width.max(MIN_SIZE[0]), height.max(MIN_SIZE[1])]);
+        }
+        if let Some(position) = self.position {
+            builder = builder.with_position(position);
+        }
+        builder.with_maximized(self.maximized)
+    }
+
+    /// Take the window's current geometry from `ctx`. Position and size are
+    /// kept from before the window was maximized or minimized.
+    pub fn capture(&mut self, ctx: &egui::Context) {
+        // Viewport rects are in UI points, which include the zoom factor
+        let zoom = ctx.zoom_factor();
+        ctx.input(|i| {
+            let viewport = i.viewport();
+            self.maximized = viewport.maximized.unwrap_or(false);
+            if self.maximized || viewport.minimized == Some(true) {
+                return;
+            }
+            if let Some(outer) = viewport.outer_rect {
+                self.position = Some([outer.min.x * zoom, outer.min.y * zoom]);
+            }
+            if let Some(inner) = viewport.inner_rect {
+                self.size = Some([inner.width() * zoom, inner.height() * zoom]);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_round_trip_and_viewport() -> Result<()> {
+        let layout = WindowLayout {
+            position: Some([40.0, 60.0]),
+            size: Some([100.0, 900.0]),
+            maximized: false,
+            settings_open: true,
+            fleet_open: false,
+            stats_detached: true,
+            log_detached: false,
+            tab: Tab::Log,
+        };
+        let parsed: WindowLayout = toml::from_str(&toml::to_string_pretty(&layout)?)?;
+        assert_eq!(parsed, layout);
+        // A file from an older version without some fields still loads
+        assert_eq!(toml::from_str::<WindowLayout>("maximized = true")?, WindowLayout { maximized: true, ..Default::default() });
+
+        let viewport = layout.viewport(egui::ViewportBuilder::default());
+        assert_eq!(viewport.inner_size, Some(egui::vec2(MIN_SIZE[0], 900.0)));
+        assert_eq!(viewport.position, Some(egui::pos2(40.0, 60.0)));
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use eframe::egui;
+use egui::RichText;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::diagnostics;
+use crate::i18n::tr;
+
+/// Journal entries shown.
+const LOG_VIEW_LINES: usize = 500;
+
+/// How often the journal is re-read while the log is shown.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The latest entries of the event journal, re-read in the background while
+/// shown.
+pub struct LogView {
+    lines: Vec<String>,
+    last_refresh: Option<Instant>,
+    tx: mpsc::UnboundedSender<Vec<String>>,
+    rx: mpsc::UnboundedReceiver<Vec<String>>,
+}
+
+impl Default for LogView {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            lines: Vec::new(),
+            last_refresh: None,
+            tx,
+            rx,
+        }
+    }
+}
+
+impl LogView {
+    fn refresh(&mut self) {
+        while let Ok(lines) = self.rx.try_recv() {
+            self.lines = lines;
+        }
+        if self.last_refresh.is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL)
+            && let Ok(handle) = tokio::runtime::Handle::try_current()
+        {
+            self.last_refresh = Some(Instant::now());
+            let tx = self.tx.clone();
+            handle.spawn_blocking(move || {
+                let _ = tx.send(diagnostics::recent_log(LOG_VIEW_LINES));
+            });
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        self.refresh();
+        ui.ctx().request_repaint_after(REFRESH_INTERVAL);
+        if self.lines.is_empty() {
+            ui.label(RichText::new(tr("log-empty")).weak());
+            return;
+        }
+        egui::ScrollArea::both().auto_shrink([false, false]).stick_to_bottom(true).show(ui, |ui| {
+            for line in &self.lines {
+                ui.label(RichText::new(line).monospace());
+            }
+        });
+    }
+}
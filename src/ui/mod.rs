@@ -1,16 +1,69 @@
+#[cfg(feature = "grpc")]
+pub mod fleet_panel;
+pub mod actions;
+pub mod display;
+pub mod file_graph;
+pub mod format;
+pub mod files_view;
 pub mod header;
+pub mod health_badge;
+pub mod history_view;
+pub mod layout;
+pub mod log_view;
+pub mod palette;
+pub mod peers_view;
+pub mod popout;
+pub mod repaint;
+pub mod restore_panel;
 pub mod settings_panel;
+pub mod shortcuts;
+pub mod sound;
+pub mod state;
+pub mod statistics;
+pub mod storage_view;
+pub mod status_bar;
+pub mod theme;
+pub mod toast;
+pub mod stall_banner;
+pub mod taskbar;
+pub mod trackers_view;
 pub mod torrent_progress;
+pub mod validation;
+pub mod version_diff;
 pub mod app;
 pub use app::ModApp;
  
-/// Run the native UI by building the app
+/// Run the native UI. The sync engine runs in a separate daemon process that
+/// keeps seeding after the window closes; the UI attaches to it over local
+/// IPC, starting it first if needed.
 pub fn run_ui() {
-    let native_options = eframe::NativeOptions::default();
+    crate::crash::install_hook("ui");
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to build tokio runtime");
+    let _guard = runtime.enter();
+    let layout = layout::WindowLayout::load();
+    let mut app = match crate::ipc::endpoint() {
+        Ok(endpoint) => {
+            let (cmd_tx, updates) = crate::ipc::connect(endpoint);
+            ModApp::with_daemon(cmd_tx, updates)
+        }
+        Err(e) => {
+            eprintln!("UI: Cannot locate daemon endpoint: {:#}", e);
+            ModApp::default()
+        }
+    };
+
+    let native_options = eframe::NativeOptions {
+        viewport: layout.viewport(eframe::egui::ViewportBuilder::default()),
+        ..Default::default()
+    };
+    app.restore_layout(layout);
     eframe::run_native(
         "ModSync",
         native_options,
-        Box::new(|_cc| Ok(Box::new(ModApp::default()) as Box<dyn eframe::App>)),
+        Box::new(move |cc| {
+            app.wake_on_updates(&cc.egui_ctx);
+            Ok(Box::new(app) as Box<dyn eframe::App>)
+        }),
     )
     .expect("Failed to start UI");
 }
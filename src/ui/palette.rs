@@ -0,0 +1,139 @@
+//! Command palette: a search box over everything the app can do, opened
+//! with Ctrl+P. Typing narrows the list with a fuzzy match, arrows move
+//! the selection and Enter runs it.
+
+use eframe::egui;
+use egui::{Key, Modifiers, RichText};
+
+use crate::i18n::tr;
+
+/// Score of `text` for `query` when every character of the query appears in
+/// it in order, ignoring case. Runs of consecutive characters and matches at
+/// the start of words score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut previous: Option<usize> = None;
+    for c in query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()) {
+        let found = pos + text[pos..].iter().position(|t| *t == c)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+#[derive(Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    query: String,
+    selected: usize,
+    /// Whether the search box still needs the keyboard focus.
+    focus: bool,
+}
+
+impl CommandPalette {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.selected = 0;
+        self.focus = self.open;
+    }
+
+    /// Show the palette over `entries` of command and label, returning the
+    /// command picked this frame.
+    pub fn ui<T: Clone>(&mut self, ctx: &egui::Context, entries: &[(T, String)]) -> Option<T> {
+        if !self.open {
+            return None;
+        }
+        let mut matches: Vec<(u32, usize)> = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, label))| Some((fuzzy_score(&self.query, label)?, i)))
+            .collect();
+        // Best match first, ties in the given order
+        matches.sort_by_key(|&(score, i)| (std::cmp::Reverse(score), i));
+
+        let (up, down, enter, escape) = ctx.input_mut(|input| {
+            (
+                input.consume_key(Modifiers::NONE, Key::ArrowUp),
+                input.consume_key(Modifiers::NONE, Key::ArrowDown),
+                input.consume_key(Modifiers::NONE, Key::Enter),
+                input.consume_key(Modifiers::NONE, Key::Escape),
+            )
+        });
+        if escape {
+            self.open = false;
+            return None;
+        }
+        if down {
+            self.selected += 1;
+        }
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let mut picked = enter.then(|| matches.get(self.selected).map(|&(_, i)| i)).flatten();
+        egui::Window::new(tr("palette-title"))
+            .id(egui::Id::new("command_palette"))
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .fixed_size([420.0, 0.0])
+            .show(ctx, |ui| {
+                let response = ui.add(egui::TextEdit::singleline(&mut self.query).desired_width(f32::INFINITY).hint_text(tr("palette-hint")));
+                if self.focus {
+                    response.request_focus();
+                    self.focus = false;
+                }
+                if response.changed() {
+                    self.selected = 0;
+                }
+                ui.separator();
+                if matches.is_empty() {
+                    ui.label(RichText::new(tr("palette-no-match")).weak());
+                }
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for (row, &(_, i)) in matches.iter().enumerate() {
+                        let selected = row == self.selected;
+                        let label = ui.add(egui::Button::selectable(selected, &entries[i].1).min_size(egui::vec2(ui.available_width(), 0.0)));
+                        if selected && (up || down) {
+                            label.scroll_to_me(None);
+                        }
+                        if label.clicked() {
+                            picked = Some(i);
+                        }
+                    }
+                });
+            });
+        let picked = picked.map(|i| entries[i].0.clone());
+        if picked.is_some() {
+            self.open = false;
+        }
+        picked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("chk upd", "Check for updates").is_some());
+        assert_eq!(fuzzy_score("xyz", "Check for updates"), None);
+        assert_eq!(fuzzy_score("", "Anything"), Some(0));
+        // Word starts and runs beat scattered letters
+        assert!(fuzzy_score("pau", "Pause or resume") > fuzzy_score("pau", "Open download folder"));
+        assert!(fuzzy_score("of", "Open folder") > fuzzy_score("of", "Show this list of shortcuts"));
+    }
+}
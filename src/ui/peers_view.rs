@@ -0,0 +1,51 @@
+use eframe::egui;
+use egui::RichText;
+use std::net::IpAddr;
+
+use crate::i18n::tr;
+use crate::sync::peers::PeerInfo;
+use crate::ui::actions::UiAction;
+use crate::ui::theme::Theme;
+use crate::ui::format;
+
+/// Peers tab: the live peers of the torrent, with a button banning each.
+pub fn ui(ui: &mut egui::Ui, peers: &[PeerInfo], banned: &[IpAddr]) -> Vec<UiAction> {
+    let theme = Theme::current(ui.ctx());
+    let mut actions = Vec::new();
+    ui.label(RichText::new(tr("peers-hint")).weak());
+    ui.add_space(6.0);
+    if peers.is_empty() {
+        ui.label(RichText::new(tr("peers-empty")).color(theme.text_muted));
+        return actions;
+    }
+    egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+        egui::Grid::new("peers_table").striped(true).num_columns(5).show(ui, |ui| {
+            for header in ["peers-col-address", "peers-col-downloaded", "peers-col-connections", "peers-col-errors", ""] {
+                ui.label(RichText::new(if header.is_empty() { String::new() } else { tr(header) }).strong());
+            }
+            ui.end_row();
+
+            for peer in peers {
+                ui.label(&peer.addr);
+                ui.label(format::size(peer.downloaded));
+                ui.label(peer.connections.to_string());
+                ui.label(peer.errors.to_string());
+                match peer.ip() {
+                    Some(ip) if banned.contains(&ip) => {
+                        ui.label(RichText::new(tr("peers-banned")).color(theme.warning));
+                    }
+                    Some(ip) => {
+                        if ui.small_button(tr("peers-ban")).on_hover_text(tr("peers-ban-hover")).clicked() {
+                            actions.push(UiAction::BanPeer(ip));
+                        }
+                    }
+                    None => {
+                        ui.label("");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    });
+    actions
+}
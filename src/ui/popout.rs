@@ -0,0 +1,34 @@
+//! Panels that can be popped out of the main window into their own, to keep
+//! them on another monitor. Where eframe can't open more native windows the
+//! panel floats inside the main window instead.
+
+use eframe::egui;
+
+use crate::i18n::tr;
+
+/// Button popping a panel out; returns whether it was clicked.
+pub fn button(ui: &mut egui::Ui) -> bool {
+    ui.small_button("⧉").on_hover_text(tr("popout-hover")).clicked()
+}
+
+/// Show `add_contents` in a window of its own while `detached`, which is
+/// cleared when that window is closed.
+pub fn show(ctx: &egui::Context, id: &str, title: &str, detached: &mut bool, mut add_contents: impl FnMut(&mut egui::Ui)) {
+    if !*detached {
+        return;
+    }
+    let viewport_id = egui::ViewportId::from_hash_of(id);
+    let builder = egui::ViewportBuilder::default().with_title(title).with_inner_size([420.0, 320.0]);
+    ctx.show_viewport_immediate(viewport_id, builder, |ctx, class| {
+        if class == egui::ViewportClass::Embedded {
+            let mut open = true;
+            egui::Window::new(title).id(egui::Id::new(id)).open(&mut open).show(ctx, |ui| add_contents(ui));
+            *detached &= open;
+            return;
+        }
+        egui::CentralPanel::default().show(ctx, |ui| add_contents(ui));
+        if ctx.input(|i| i.viewport().close_requested()) {
+            *detached = false;
+        }
+    });
+}
@@ -0,0 +1,56 @@
+//! When the window is drawn again.
+//!
+//! egui repaints by itself on input and while an animation or spinner runs.
+//! What else the window shows comes from the daemon, so each incoming
+//! update wakes it, and a slow heartbeat keeps relative times current and
+//! picks up results of the panels' background work. An idle window draws
+//! about once a second rather than every frame.
+
+use eframe::egui;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Longest the window goes without a repaint.
+pub const HEARTBEAT: Duration = Duration::from_secs(1);
+
+/// Receiver of everything `updates` receives, waking `ctx` for each.
+/// Spawns the forwarding task on the current tokio runtime.
+pub fn wake_on_receive<T: Send + 'static>(ctx: &egui::Context, mut updates: mpsc::UnboundedReceiver<T>) -> mpsc::UnboundedReceiver<T> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        while let Some(update) = updates.recv().await {
+            if tx.send(update).is_err() {
+                break;
+            }
+            ctx.request_repaint();
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_updates_wake_the_window() {
+        let ctx = egui::Context::default();
+        let wakes = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&wakes);
+        ctx.set_request_repaint_callback(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut rx = wake_on_receive(&ctx, rx);
+        tx.send(7).unwrap();
+        assert_eq!(rx.recv().await, Some(7));
+        tokio::task::yield_now().await;
+        assert!(wakes.load(Ordering::SeqCst) >= 1);
+        drop(tx);
+        assert_eq!(rx.recv().await, None);
+    }
+}
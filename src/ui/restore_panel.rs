@@ -0,0 +1,120 @@
+use eframe::egui;
+use egui::RichText;
+
+use crate::i18n::{tr, tr_args};
+use crate::sync::checking::CheckProgress;
+use crate::sync::status::SyncStatus;
+use crate::sync::{SyncEvent, TorrentSnapshot};
+use crate::ui::status_bar::human_readable_duration;
+use crate::ui::theme::Theme;
+use crate::ui::format;
+
+/// Where the daemon is in restoring the previous session: adding the cached
+/// torrent, then checking the files already on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RestoreState {
+    #[default]
+    Off,
+    Adding,
+    Checking,
+}
+
+impl RestoreState {
+    pub fn observe(&mut self, event: &SyncEvent) {
+        *self = match (*self, event) {
+            (_, SyncEvent::StatusUpdate(SyncStatus::RestoringSession)) => RestoreState::Adding,
+            (RestoreState::Adding, SyncEvent::TorrentAdded(_)) => RestoreState::Checking,
+            // Nothing was added
+            (RestoreState::Adding, SyncEvent::SessionRestored) => RestoreState::Off,
+            (RestoreState::Checking, SyncEvent::ManagedTorrentUpdate(Some((_, stats)))) if stats.state != "initializing" => {
+                RestoreState::Off
+            }
+            (_, SyncEvent::ManagedTorrentUpdate(None)) => RestoreState::Off,
+            (state, _) => state,
+        };
+    }
+
+    pub fn is_active(self) -> bool {
+        self != RestoreState::Off
+    }
+}
+
+/// Checked bytes of the running check, with its speed and time left once
+/// they are known.
+pub fn check_text(check: &CheckProgress) -> String {
+    let text = tr_args(
+        "check-progress",
+        &[("checked", format::size(check.checked_bytes).into()), ("total", format::size(check.total_bytes).into())],
+    );
+    match (check.bytes_per_sec, check.remaining) {
+        (Some(rate), Some(remaining)) => {
+            let speed = tr_args(
+                "check-speed",
+                &[("speed", format::size(rate as u64).into()), ("remaining", human_readable_duration(remaining).into())],
+            );
+            format!("{} — {}", text, speed)
+        }
+        _ => text,
+    }
+}
+
+/// Panel explaining the restore, with a progress bar that follows the
+/// initial check.
+pub fn ui(ui: &mut egui::Ui, stats: Option<&TorrentSnapshot>, check: Option<&CheckProgress>) {
+    let theme = Theme::current(ui.ctx());
+    let total = stats.map_or(0, |stats| stats.total_bytes);
+    egui::Frame::group(ui.style()).show(ui, |ui| {
+        let title = if total > 0 {
+            tr_args("restore-title-checking", &[("size", format::size(total).into())])
+        } else {
+            tr("restore-title")
+        };
+        ui.label(RichText::new(title).strong());
+        ui.label(RichText::new(tr("restore-explanation")).color(theme.text_muted));
+        match check {
+            Some(check) if check.total_bytes > 0 => {
+                let fraction = check.checked_bytes as f32 / check.total_bytes as f32;
+                ui.add(egui::ProgressBar::new(fraction).show_percentage());
+                ui.label(check_text(check));
+                if check.slow {
+                    ui.label(RichText::new(tr("check-slow")).color(theme.warning));
+                }
+            }
+            _ => {
+                ui.add(egui::ProgressBar::new(0.0).animate(true));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(state: &str) -> SyncEvent {
+        let stats = TorrentSnapshot {
+            state: state.to_string(),
+            ..TorrentSnapshot::default()
+        };
+        SyncEvent::ManagedTorrentUpdate(Some((0, stats)))
+    }
+
+    #[test]
+    fn test_restore_follows_the_check() {
+        let mut restore = RestoreState::default();
+        for (event, expected) in [
+            (SyncEvent::StatusUpdate(SyncStatus::RestoringSession), RestoreState::Adding),
+            (SyncEvent::TorrentAdded(0), RestoreState::Checking),
+            (SyncEvent::SessionRestored, RestoreState::Checking),
+            (update("initializing"), RestoreState::Checking),
+            (update("live"), RestoreState::Off),
+        ] {
+            restore.observe(&event);
+            assert_eq!(restore, expected);
+        }
+
+        restore.observe(&SyncEvent::StatusUpdate(SyncStatus::RestoringSession));
+        restore.observe(&SyncEvent::SessionRestored);
+        assert!(!restore.is_active());
+    }
+}
@@ -1,106 +1,1312 @@
-use crate::settings::AppSettings;
+use crate::i18n::{tr, tr_args};
+use crate::settings::{AppSettings, BandwidthPreset, DisplaySettings, NotificationSettings, ThemePreset, ThemeSettings, UnitSystem};
+use crate::sync::cache::{CacheEntry, TorrentCache};
+use crate::sync::credentials::{Credential, CredentialStore};
+use crate::sync::git::GitSourceConfig;
+use crate::sync::extract::ExtractRule;
+use crate::sync::filters::DownloadFilter;
+use crate::sync::mapping::FolderMapping;
+use crate::sync::power::{PowerAction, PowerConfig, LOW_POWER_DOWNLOAD_SPEED, LOW_POWER_UPLOAD_SPEED};
+use crate::sync::discord::DiscordConfig;
+use crate::sync::telemetry::TelemetryConfig;
+use crate::sync::seed_window::SeedWindow;
+use crate::sync::stall;
+use crate::sync::schedule;
+use crate::sync::quarantine::{Quarantine, QuarantineBatch, QUARANTINE_DIR_NAME};
+use crate::sync::hooks::HooksConfig;
+use crate::sync::http::RedirectConfig;
+use crate::sync::ipfs::IpfsConfig;
+use crate::sync::rsync::{RsyncConfig, TransferEngine};
+use crate::sync::s3::S3Config;
+use crate::sync::utils::calculate_torrent_hash;
+use crate::sync::{EndpointHealth, TorrentSnapshot};
+use crate::ui::health_badge;
+use crate::ui::shortcuts;
+use crate::ui::theme::Theme;
+use crate::ui::format;
+use crate::ui::validation::{self, PathProblem, UrlProblem};
 use eframe::egui;
-use egui::{RichText, Color32};
+use egui::RichText;
 
 #[derive(Default)]
 pub struct SettingsPanel {
     pub open: bool,
     pub url_str: String,
+    /// Mirror URLs, one per line.
+    pub mirrors_str: String,
+    pub version_url_str: String,
+    /// Extra announce URLs, one per line.
+    pub trackers_str: String,
+    pub replace_trackers: bool,
+    pub stall_minutes_str: String,
+    pub check_jitter_str: String,
+    pub check_splay_str: String,
     pub upload_str: String,
     pub download_str: String,
     pub path_str: String,
+    /// Shared download store folder as typed.
+    pub shared_store_str: String,
+    /// Download folders used before, offered next to the path.
+    recent_paths: Vec<std::path::PathBuf>,
     pub save_message: Option<String>,
     pub should_seed: bool,
+    /// Seeding hours as typed, like `22:00-08:00`.
+    pub seed_window_str: String,
+    pub read_only: bool,
+    pub strict_mirror: bool,
+    pub pinned: bool,
+    /// Asking whether to turn on strict mirror mode.
+    confirm_strict_mirror: bool,
+    /// Previewed live by the app; persisted on Save.
+    pub theme: ThemeSettings,
+    /// Previewed live by the app; persisted on Save.
+    pub display: DisplaySettings,
+    pub play_sounds: bool,
+    pub discord: DiscordConfig,
+    pub telemetry: TelemetryConfig,
+    /// Quiet hours as typed, like `22:00-08:00`.
+    pub quiet_hours_str: String,
+    /// Protected paths; the app adds to it from the file context menus.
+    pub ignored_paths: Vec<std::path::PathBuf>,
+    pub banned_peers: Vec<std::net::IpAddr>,
+    pub redirects: RedirectConfig,
+    pub s3: S3Config,
+    /// SFTP key file path as typed.
+    pub sftp_key_str: String,
+    pub credentials: CredentialStore,
+    pub git_source: GitSourceConfig,
+    pub git_folder_str: String,
+    pub engine: TransferEngine,
+    pub rsync: RsyncConfig,
+    pub ipfs: IpfsConfig,
+    pub hooks: HooksConfig,
+    pub script_str: String,
+    pub extract: Vec<ExtractRule>,
+    pub keys_dir_str: String,
+    pub folder_mappings: Vec<FolderMapping>,
+    pub download_filters: Vec<DownloadFilter>,
+    pub bandwidth_presets: Vec<BandwidthPreset>,
+    /// Gaming mode upload cap in KB/s as typed.
+    pub gaming_upload_str: String,
+    pub gaming_shortcut_str: String,
+    pub power: PowerConfig,
+    /// Low-power caps in KB/s as typed.
+    pub power_upload_str: String,
+    pub power_download_str: String,
+    /// Hash check read cap in MB/s as typed.
+    pub check_max_str: String,
+    pub check_low_io_priority: bool,
+    pub fastresume: bool,
+    /// Memory tunables in MiB and checks at once, as typed.
+    pub write_buffer_str: String,
+    pub piece_cache_str: String,
+    pub concurrent_checks_str: String,
+    pub hashing_threads_str: String,
+    /// Torrent cache size cap in MiB as typed.
+    pub cache_max_str: String,
+    /// Cached torrent versions kept, as typed.
+    pub cache_versions_str: String,
+    /// Cached torrents, read when the panel loads and after clearing.
+    cache_entries: Vec<CacheEntry>,
+    /// Hash of the cached torrent the daemon syncs, which can't be rolled
+    /// back to.
+    current_version: Option<String>,
+    /// Cached torrent the user chose to roll back to, awaiting confirmation.
+    confirm_roll_back: Option<std::path::PathBuf>,
+    /// Confirmed rollback, taken by the app to send to the daemon.
+    pub roll_back: Option<std::path::PathBuf>,
+    /// Asked to compare cached versions, taken by the app.
+    pub compare_versions: bool,
+    pub quarantine: bool,
+    /// Days quarantined files are kept, as typed.
+    pub quarantine_days_str: String,
+    /// Quarantined batches, read when the panel loads and after changes.
+    quarantine_batches: Vec<QuarantineBatch>,
+    /// Checks of the URL and path as last typed, redone when they change.
+    url_check: Option<(String, Result<(), UrlProblem>)>,
+    path_check: Option<(String, Result<(), PathProblem>, Option<u64>)>,
+    url_problem: Option<UrlProblem>,
+    path_problem: Option<PathProblem>,
+    /// Download path as last loaded or saved, where the torrent's finished
+    /// bytes already are.
+    saved_path: String,
+    loaded: bool,
+}
+
+fn power_action_label(action: PowerAction) -> &'static str {
+    match action {
+        PowerAction::Ignore => "settings-power-ignore",
+        PowerAction::LowPower => "settings-power-low-power",
+        PowerAction::Pause => "settings-power-pause",
+    }
 }
 
 impl SettingsPanel {
-    pub fn ui(&mut self, ui: &mut egui::Ui) {
-        // lazy load if needed
-        if self.save_message.is_none() && self.path_str.is_empty() {
-            if let Ok(s) = AppSettings::load() {
-                self.url_str = s.torrent_url.clone();
-                self.upload_str = s.max_upload_speed.map(|v| v.to_string()).unwrap_or_default();
-                self.download_str = s.max_download_speed.map(|v| v.to_string()).unwrap_or_default();
-                self.path_str = s.download_path.to_string_lossy().to_string();
-                self.should_seed = s.should_seed;
+    /// Whether the fields have been filled from the saved settings yet.
+    pub fn loaded(&self) -> bool {
+        self.loaded
+    }
+
+    /// Notification settings as entered; quiet hours that don't parse are
+    /// off.
+    pub fn notifications(&self) -> NotificationSettings {
+        NotificationSettings { sound: self.play_sounds, quiet_hours: SeedWindow::parse(&self.quiet_hours_str) }
+    }
+
+    /// Write the panel's fields to the settings file.
+    /// Whether the URL and download path passed their last checks.
+    pub fn can_save(&self) -> bool {
+        self.url_problem.is_none() && self.path_problem.is_none()
+    }
+
+    /// Recheck the URL and path, redoing the slow checks only when they
+    /// changed since the last frame.
+    fn validate(&mut self, stats: Option<&TorrentSnapshot>) {
+        if self.url_check.as_ref().is_none_or(|(url, _)| *url != self.url_str) {
+            self.url_check = Some((self.url_str.clone(), validation::validate_url(&self.url_str)));
+        }
+        if self.path_check.as_ref().is_none_or(|(path, _, _)| *path != self.path_str) {
+            let check = validation::validate_download_path(&self.path_str);
+            let free = crate::sync::usage::available_space(std::path::Path::new(self.path_str.trim()));
+            self.path_check = Some((self.path_str.clone(), check, free));
+        }
+        self.url_problem = self.url_check.as_ref().and_then(|(_, check)| check.clone().err());
+        self.path_problem = self.path_check.as_ref().and_then(|(_, check, free)| {
+            // A new folder needs the whole torrent, the current one only what's missing
+            let needed = stats.map(|s| {
+                if self.path_str.trim() == self.saved_path.trim() { s.total_bytes.saturating_sub(s.progress_bytes) } else { s.total_bytes }
+            });
+            check.clone().and_then(|()| needed.map_or(Ok(()), |needed| validation::check_space(needed, *free))).err()
+        });
+    }
+
+    pub fn save(&mut self) {
+        if !self.can_save() {
+            return;
+        }
+        let mut settings = AppSettings::load().unwrap_or_default();
+        settings.max_upload_speed = if self.upload_str.trim().is_empty() { None } else { self.upload_str.trim().parse::<u32>().ok() };
+        settings.max_download_speed = if self.download_str.trim().is_empty() { None } else { self.download_str.trim().parse::<u32>().ok() };
+        settings.download_path = std::path::PathBuf::from(self.path_str.clone());
+        settings.remember_download_path();
+        let shared_store = self.shared_store_str.trim();
+        settings.shared_store = (!shared_store.is_empty()).then(|| std::path::PathBuf::from(shared_store));
+        settings.torrent_url = self.url_str.clone();
+        settings.torrent_mirrors = self
+            .mirrors_str
+            .lines()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(String::from)
+            .collect();
+        settings.extra_trackers = self.trackers_str.lines().map(str::trim).filter(|url| !url.is_empty()).map(String::from).collect();
+        settings.replace_trackers = self.replace_trackers;
+        settings.stall_minutes = self.stall_minutes_str.trim().parse::<u64>().ok();
+        settings.check_jitter_secs = self.check_jitter_str.trim().parse::<u64>().ok();
+        settings.check_splay_secs = self.check_splay_str.trim().parse::<u64>().ok();
+        settings.version_url = self.version_url_str.trim().to_string();
+        settings.should_seed = self.should_seed;
+        settings.seed_window = SeedWindow::parse(&self.seed_window_str);
+        settings.read_only = self.read_only;
+        settings.strict_mirror = self.strict_mirror;
+        settings.pinned = self.pinned;
+        settings.quarantine = self.quarantine;
+        settings.quarantine_days = self.quarantine_days_str.trim().parse().ok();
+        settings.theme = self.theme.clone();
+        settings.display = self.display.clone();
+        settings.notifications = self.notifications();
+        settings.discord = DiscordConfig { enabled: self.discord.enabled, client_id: self.discord.client_id.trim().to_string() };
+        settings.telemetry = TelemetryConfig { enabled: self.telemetry.enabled, endpoint: self.telemetry.endpoint.trim().to_string() };
+        settings.ignored_paths = self.ignored_paths.clone();
+        settings.banned_peers = self.banned_peers.clone();
+        settings.redirects = self.redirects.clone();
+        settings.s3 = self.s3.clone();
+        settings.credentials = self.credentials.clone();
+        settings.engine = self.engine;
+        settings.rsync = self.rsync.clone();
+        settings.ipfs = self.ipfs.clone();
+        settings.hooks = self.hooks.clone();
+        settings.extract = self.extract.clone();
+        settings.folder_mappings = self.folder_mappings.clone();
+        settings.download_filters = self.download_filters.iter().filter(|f| !f.pattern.trim().is_empty()).cloned().collect();
+        settings.cache_max_mb = self.cache_max_str.trim().parse::<u64>().ok();
+        settings.cache_versions = self.cache_versions_str.trim().parse::<usize>().ok().filter(|&n| n > 0);
+        settings.bandwidth_presets = self.bandwidth_presets.iter().filter(|p| !p.name.trim().is_empty()).cloned().collect();
+        settings.gaming_upload_speed = self.gaming_upload_str.trim().parse::<u32>().ok();
+        settings.gaming_mode_shortcut = self.gaming_shortcut_str.trim().to_string();
+        settings.power = PowerConfig {
+            upload_speed: self.power_upload_str.trim().parse::<u32>().ok(),
+            download_speed: self.power_download_str.trim().parse::<u32>().ok(),
+            ..self.power.clone()
+        };
+        settings.check_max_mb_per_sec = self.check_max_str.trim().parse::<u32>().ok().filter(|&mb| mb > 0);
+        settings.check_low_io_priority = self.check_low_io_priority;
+        settings.fastresume = self.fastresume;
+        settings.write_buffer_mb = self.write_buffer_str.trim().parse::<usize>().ok().filter(|&mb| mb > 0);
+        settings.piece_cache_mb = self.piece_cache_str.trim().parse::<u64>().ok().filter(|&mb| mb > 0);
+        settings.concurrent_checks = self.concurrent_checks_str.trim().parse::<usize>().ok().filter(|&n| n > 0);
+        settings.hashing_threads = self.hashing_threads_str.trim().parse::<usize>().ok().filter(|&n| n > 0);
+        let keys_dir = self.keys_dir_str.trim();
+        settings.keys_dir = (!keys_dir.is_empty()).then(|| std::path::PathBuf::from(keys_dir));
+        let script = self.script_str.trim();
+        settings.script_path = (!script.is_empty()).then(|| std::path::PathBuf::from(script));
+        settings.git_source = GitSourceConfig {
+            folder: std::path::PathBuf::from(self.git_folder_str.trim()),
+            ..self.git_source.clone()
+        };
+        let sftp_key = self.sftp_key_str.trim();
+        settings.sftp.identity_file = (!sftp_key.is_empty()).then(|| std::path::PathBuf::from(sftp_key));
+        match settings.save() {
+            Ok(()) => {
+                self.saved_path = self.path_str.clone();
+                self.recent_paths = settings.recent_paths;
+                self.save_message = Some(tr("settings-saved"));
             }
+            Err(e) => self.save_message = Some(tr_args("settings-save-failed", &[("error", e.to_string().into())])),
         }
+    }
+
+    fn refresh_cache_entries(&mut self) {
+        self.cache_entries = match AppSettings::cache_dir().and_then(|dir| TorrentCache::new(dir).entries()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("UI: Failed to list the torrent cache: {:#}", e);
+                Vec::new()
+            }
+        };
+        self.current_version = AppSettings::cached_torrent_path(&self.url_str)
+            .ok()
+            .and_then(|path| std::fs::read(path).ok())
+            .map(|torrent| calculate_torrent_hash(&torrent));
+    }
+
+    /// Remove every cached torrent version.
+    fn clear_cache(&mut self) {
+        match AppSettings::cache_dir().and_then(|dir| TorrentCache::new(dir).clear()) {
+            Ok(count) => self.save_message = Some(tr_args("settings-cache-cleared", &[("count", count.into())])),
+            Err(e) => self.save_message = Some(tr_args("settings-cache-clear-failed", &[("error", format!("{:#}", e).into())])),
+        }
+        self.refresh_cache_entries();
+    }
+
+    fn quarantine_store() -> anyhow::Result<Quarantine> {
+        Ok(Quarantine::new(AppSettings::cache_dir()?.join(QUARANTINE_DIR_NAME)))
+    }
+
+    fn refresh_quarantine(&mut self) {
+        self.quarantine_batches = match Self::quarantine_store().and_then(|q| q.batches()) {
+            Ok(batches) => batches,
+            Err(e) => {
+                eprintln!("UI: Failed to list the quarantine: {:#}", e);
+                Vec::new()
+            }
+        };
+    }
+
+    /// Move the files of `batch` back into the download folder.
+    fn restore_batch(&mut self, batch: &QuarantineBatch) {
+        let download_path = std::path::PathBuf::from(self.saved_path.trim());
+        match Self::quarantine_store().and_then(|q| q.restore(batch, &download_path)) {
+            Ok(count) => self.save_message = Some(tr_args("settings-quarantine-restored", &[("count", count.into())])),
+            Err(e) => self.save_message = Some(tr_args("settings-quarantine-failed", &[("error", format!("{:#}", e).into())])),
+        }
+        self.refresh_quarantine();
+    }
+
+    fn discard_batch(&mut self, batch: &QuarantineBatch) {
+        if let Err(e) = Self::quarantine_store().and_then(|q| q.discard(batch)) {
+            self.save_message = Some(tr_args("settings-quarantine-failed", &[("error", format!("{:#}", e).into())]));
+        }
+        self.refresh_quarantine();
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, health: &EndpointHealth, stats: Option<&TorrentSnapshot>) {
+        // lazy load if needed
+        if !self.loaded
+            && let Ok(s) = AppSettings::load()
+        {
+            self.loaded = true;
+            self.url_str = s.torrent_url.clone();
+            self.mirrors_str = s.torrent_mirrors.join("\n");
+            self.trackers_str = s.extra_trackers.join("\n");
+            self.replace_trackers = s.replace_trackers;
+            self.stall_minutes_str = s.stall_minutes.map(|v| v.to_string()).unwrap_or_default();
+            self.check_jitter_str = s.check_jitter_secs.map(|v| v.to_string()).unwrap_or_default();
+            self.check_splay_str = s.check_splay_secs.map(|v| v.to_string()).unwrap_or_default();
+            self.version_url_str = s.version_url.clone();
+            self.upload_str = s.max_upload_speed.map(|v| v.to_string()).unwrap_or_default();
+            self.download_str = s.max_download_speed.map(|v| v.to_string()).unwrap_or_default();
+            self.path_str = s.download_path.to_string_lossy().to_string();
+            self.saved_path = self.path_str.clone();
+            self.recent_paths = s.recent_paths;
+            self.shared_store_str = s.shared_store.map(|p| p.display().to_string()).unwrap_or_default();
+            self.should_seed = s.should_seed;
+            self.seed_window_str = s.seed_window.map(|w| w.to_string()).unwrap_or_default();
+            self.read_only = s.read_only;
+            self.strict_mirror = s.strict_mirror;
+            self.pinned = s.pinned;
+            self.quarantine = s.quarantine;
+            self.quarantine_days_str = s.quarantine_days.map(|v| v.to_string()).unwrap_or_default();
+            self.refresh_quarantine();
+            self.theme = s.theme;
+            self.display = s.display;
+            self.play_sounds = s.notifications.sound;
+            self.discord = s.discord.clone();
+            self.telemetry = s.telemetry.clone();
+            self.quiet_hours_str = s.notifications.quiet_hours.map(|w| w.to_string()).unwrap_or_default();
+            self.ignored_paths = s.ignored_paths;
+            self.banned_peers = s.banned_peers;
+            self.redirects = s.redirects;
+            self.s3 = s.s3;
+            self.credentials = s.credentials;
+            self.engine = s.engine;
+            self.rsync = s.rsync;
+            self.ipfs = s.ipfs;
+            self.hooks = s.hooks;
+            self.extract = s.extract;
+            self.folder_mappings = s.folder_mappings;
+            self.download_filters = s.download_filters;
+            self.bandwidth_presets = s.bandwidth_presets;
+            self.gaming_upload_str = s.gaming_upload_speed.map(|v| v.to_string()).unwrap_or_default();
+            self.gaming_shortcut_str = s.gaming_mode_shortcut;
+            self.power_upload_str = s.power.upload_speed.map(|v| v.to_string()).unwrap_or_default();
+            self.power_download_str = s.power.download_speed.map(|v| v.to_string()).unwrap_or_default();
+            self.power = s.power;
+            self.check_max_str = s.check_max_mb_per_sec.map(|v| v.to_string()).unwrap_or_default();
+            self.check_low_io_priority = s.check_low_io_priority;
+            self.fastresume = s.fastresume;
+            self.write_buffer_str = s.write_buffer_mb.map(|v| v.to_string()).unwrap_or_default();
+            self.piece_cache_str = s.piece_cache_mb.map(|v| v.to_string()).unwrap_or_default();
+            self.concurrent_checks_str = s.concurrent_checks.map(|v| v.to_string()).unwrap_or_default();
+            self.hashing_threads_str = s.hashing_threads.map(|v| v.to_string()).unwrap_or_default();
+            self.cache_max_str = s.cache_max_mb.map(|v| v.to_string()).unwrap_or_default();
+            self.cache_versions_str = s.cache_versions.map(|v| v.to_string()).unwrap_or_default();
+            self.refresh_cache_entries();
+            self.keys_dir_str = s.keys_dir.map(|p| p.display().to_string()).unwrap_or_default();
+            self.script_str = s.script_path.map(|p| p.display().to_string()).unwrap_or_default();
+            self.git_folder_str = s.git_source.folder.display().to_string();
+            self.git_source = s.git_source;
+            self.sftp_key_str = s.sftp.identity_file.map(|p| p.display().to_string()).unwrap_or_default();
+        }
+        self.validate(stats);
+        let theme = Theme::current(ui.ctx());
 
         // Side panel friendly layout
         egui::ScrollArea::vertical().show(ui, |ui| {
             egui::Frame::group(ui.style()).show(ui, |ui| {
                 ui.vertical(|ui| {
-                    ui.label(RichText::new("Application Settings").heading());
+                    ui.label(RichText::new(tr("settings-title")).heading());
                     ui.add_space(6.0);
 
                     ui.horizontal(|ui| {
-                        ui.label("Torrent URL:");
+                        let label = ui.label(tr("settings-torrent-url"));
+                        health_badge::badge(ui, health, self.url_str.trim());
                         let url_widget = egui::widgets::TextEdit::singleline(&mut self.url_str).desired_width(260.0);
-                        ui.add(url_widget);
+                        ui.add(url_widget).labelled_by(label.id);
+                    });
+                    if let Some(problem) = &self.url_problem {
+                        ui.colored_label(theme.error, problem.message());
+                    }
+
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-mirrors"));
+                        let mirrors_widget = egui::widgets::TextEdit::multiline(&mut self.mirrors_str)
+                            .desired_rows(2)
+                            .desired_width(260.0)
+                            .hint_text(tr("settings-mirrors-hint"));
+                        ui.add(mirrors_widget).labelled_by(label.id).on_hover_text(tr("settings-mirrors-hover"));
+                    });
+                    for mirror in self.mirrors_str.lines().map(str::trim).filter(|url| !url.is_empty()) {
+                        ui.horizontal(|ui| {
+                            health_badge::badge(ui, health, mirror);
+                            ui.label(mirror);
+                        });
+                    }
+
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-trackers"));
+                        let trackers_widget = egui::widgets::TextEdit::multiline(&mut self.trackers_str)
+                            .desired_rows(2)
+                            .desired_width(260.0)
+                            .hint_text(tr("settings-mirrors-hint"));
+                        ui.add(trackers_widget).labelled_by(label.id).on_hover_text(tr("settings-trackers-hover"));
+                    });
+                    ui.checkbox(&mut self.replace_trackers, tr("settings-replace-trackers"))
+                        .on_hover_text(tr("settings-replace-trackers-hover"));
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-stall-minutes"));
+                        ui.add(
+                            egui::widgets::TextEdit::singleline(&mut self.stall_minutes_str)
+                                .desired_width(80.0)
+                                .hint_text(stall::DEFAULT_STALL_MINUTES.to_string()),
+                        )
+                        .labelled_by(label.id)
+                        .on_hover_text(tr("settings-stall-minutes-hover"));
+                    });
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-check-jitter"));
+                        ui.add(
+                            egui::widgets::TextEdit::singleline(&mut self.check_jitter_str)
+                                .desired_width(80.0)
+                                .hint_text(schedule::DEFAULT_JITTER_SECS.to_string()),
+                        )
+                        .labelled_by(label.id)
+                        .on_hover_text(tr("settings-check-jitter-hover"));
+                        let label = ui.label(tr("settings-check-splay"));
+                        ui.add(egui::widgets::TextEdit::singleline(&mut self.check_splay_str).desired_width(80.0).hint_text("0"))
+                            .labelled_by(label.id)
+                            .on_hover_text(tr("settings-check-splay-hover"));
+                    });
+
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-version-url"));
+                        let version_widget = egui::widgets::TextEdit::singleline(&mut self.version_url_str)
+                            .desired_width(260.0)
+                            .hint_text(tr("settings-version-url-hint"));
+                        ui.add(version_widget).labelled_by(label.id).on_hover_text(tr("settings-version-url-hover"));
+                    });
+                    ui.checkbox(&mut self.pinned, tr("settings-pinned")).on_hover_text(tr("settings-pinned-hover"));
+
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-max-redirects"));
+                        ui.add(egui::DragValue::new(&mut self.redirects.max_redirects).range(0..=50))
+                            .labelled_by(label.id)
+                            .on_hover_text(tr("settings-max-redirects-hover"));
+                        ui.checkbox(&mut self.redirects.allow_cross_host, tr("settings-cross-host-redirects"))
+                            .on_hover_text(tr("settings-cross-host-redirects-hover"));
+                    });
+
+                    egui::CollapsingHeader::new(tr("settings-s3"))
+                        .id_salt("settings_s3")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-s3-hint")).weak());
+                            egui::Grid::new("settings_s3_grid").num_columns(2).show(ui, |ui| {
+                                let mut signing_endpoint = self.s3.signing_endpoint.clone().unwrap_or_default();
+                                let fields = [
+                                    ("settings-s3-endpoint", &mut self.s3.endpoint, false),
+                                    ("settings-s3-region", &mut self.s3.region, false),
+                                    ("settings-s3-access-key", &mut self.s3.access_key_id, false),
+                                    ("settings-s3-secret-key", &mut self.s3.secret_access_key, true),
+                                    ("settings-s3-signing-endpoint", &mut signing_endpoint, false),
+                                ];
+                                for (label_id, value, password) in fields {
+                                    let label = ui.label(tr(label_id));
+                                    ui.add(egui::widgets::TextEdit::singleline(value).password(password).desired_width(220.0))
+                                        .labelled_by(label.id);
+                                    ui.end_row();
+                                }
+                                self.s3.signing_endpoint = (!signing_endpoint.trim().is_empty()).then_some(signing_endpoint);
+                            });
+                            ui.checkbox(&mut self.s3.path_style, tr("settings-s3-path-style"))
+                                .on_hover_text(tr("settings-s3-path-style-hover"));
+                        });
+
+                    egui::CollapsingHeader::new(tr("settings-credentials"))
+                        .id_salt("settings_credentials")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-credentials-hint")).weak());
+                            let mut remove = None;
+                            egui::Grid::new("settings_credentials_grid").num_columns(4).show(ui, |ui| {
+                                for header in ["settings-credentials-host", "settings-credentials-user", "settings-credentials-password"] {
+                                    ui.label(RichText::new(tr(header)).strong());
+                                }
+                                ui.end_row();
+                                for (i, login) in self.credentials.entries.iter_mut().enumerate() {
+                                    ui.add(egui::widgets::TextEdit::singleline(&mut login.host).desired_width(140.0));
+                                    ui.add(egui::widgets::TextEdit::singleline(&mut login.username).desired_width(100.0));
+                                    ui.add(egui::widgets::TextEdit::singleline(&mut login.password).password(true).desired_width(100.0));
+                                    if ui.small_button(tr("settings-ignored-remove")).clicked() {
+                                        remove = Some(i);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                            if let Some(i) = remove {
+                                self.credentials.entries.remove(i);
+                            }
+                            if ui.button(tr("settings-credentials-add")).clicked() {
+                                self.credentials.entries.push(Credential::default());
+                            }
+                        });
+
+                    egui::CollapsingHeader::new(tr("settings-git"))
+                        .id_salt("settings_git")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-git-hint")).weak());
+                            egui::Grid::new("settings_git_grid").num_columns(2).show(ui, |ui| {
+                                let fields = [
+                                    ("settings-git-url", &mut self.git_source.url),
+                                    ("settings-git-branch", &mut self.git_source.branch),
+                                    ("settings-git-folder", &mut self.git_folder_str),
+                                ];
+                                for (label_id, value) in fields {
+                                    let label = ui.label(tr(label_id));
+                                    ui.add(egui::widgets::TextEdit::singleline(value).desired_width(220.0))
+                                        .labelled_by(label.id);
+                                    ui.end_row();
+                                }
+                            });
+                        });
+
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-ipfs-gateway"));
+                        ui.add(egui::widgets::TextEdit::singleline(&mut self.ipfs.gateway).desired_width(220.0))
+                            .labelled_by(label.id)
+                            .on_hover_text(tr("settings-ipfs-gateway-hover"));
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(tr("settings-engine"));
+                        ui.radio_value(&mut self.engine, TransferEngine::Torrent, tr("settings-engine-torrent"));
+                        ui.radio_value(&mut self.engine, TransferEngine::Rsync, tr("settings-engine-rsync"))
+                            .on_hover_text(tr("settings-engine-rsync-hover"));
+                    });
+                    if self.engine == TransferEngine::Rsync {
+                        ui.horizontal(|ui| {
+                            let label = ui.label(tr("settings-rsync-source"));
+                            ui.add(
+                                egui::widgets::TextEdit::singleline(&mut self.rsync.source)
+                                    .desired_width(220.0)
+                                    .hint_text("user@host:/path"),
+                            )
+                            .labelled_by(label.id);
+                        });
+                    }
+
+                    egui::CollapsingHeader::new(tr("settings-mappings"))
+                        .id_salt("settings_mappings")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-mappings-hint")).weak());
+                            let mut remove = None;
+                            egui::Grid::new("settings_mappings_grid").num_columns(3).show(ui, |ui| {
+                                for header in ["settings-mappings-folder", "settings-mappings-target"] {
+                                    ui.label(RichText::new(tr(header)).strong());
+                                }
+                                ui.end_row();
+                                for (i, mapping) in self.folder_mappings.iter_mut().enumerate() {
+                                    ui.add(
+                                        egui::widgets::TextEdit::singleline(&mut mapping.folder)
+                                            .desired_width(140.0)
+                                            .hint_text(tr("settings-mappings-whole-torrent")),
+                                    );
+                                    let mut target = mapping.target.display().to_string();
+                                    if ui.add(egui::widgets::TextEdit::singleline(&mut target).desired_width(140.0)).changed() {
+                                        mapping.target = std::path::PathBuf::from(target);
+                                    }
+                                    if ui.small_button(tr("settings-ignored-remove")).clicked() {
+                                        remove = Some(i);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                            if let Some(i) = remove {
+                                self.folder_mappings.remove(i);
+                            }
+                            if ui.button(tr("settings-mappings-add")).clicked() {
+                                self.folder_mappings.push(FolderMapping::default());
+                            }
+                        });
+
+                    egui::CollapsingHeader::new(tr("settings-filters"))
+                        .id_salt("settings_filters")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-filters-hint")).weak());
+                            let mut remove = None;
+                            egui::Grid::new("settings_filters_grid").num_columns(3).show(ui, |ui| {
+                                for header in ["settings-filters-pattern", "settings-filters-min-size"] {
+                                    ui.label(RichText::new(tr(header)).strong());
+                                }
+                                ui.end_row();
+                                for (i, filter) in self.download_filters.iter_mut().enumerate() {
+                                    ui.add(
+                                        egui::widgets::TextEdit::singleline(&mut filter.pattern)
+                                            .desired_width(180.0)
+                                            .hint_text("/optional/**"),
+                                    );
+                                    let mut min_size = filter.min_size_mb.map(|v| v.to_string()).unwrap_or_default();
+                                    let response = ui.add(
+                                        egui::widgets::TextEdit::singleline(&mut min_size)
+                                            .desired_width(80.0)
+                                            .hint_text(tr("settings-filters-any-size")),
+                                    );
+                                    if response.changed() {
+                                        filter.min_size_mb = min_size.trim().parse::<u64>().ok();
+                                    }
+                                    if ui.small_button(tr("settings-ignored-remove")).clicked() {
+                                        remove = Some(i);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                            if let Some(i) = remove {
+                                self.download_filters.remove(i);
+                            }
+                            if ui.button(tr("settings-filters-add")).clicked() {
+                                self.download_filters.push(DownloadFilter::default());
+                            }
+                        });
+
+                    egui::CollapsingHeader::new(tr("settings-extract"))
+                        .id_salt("settings_extract")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-extract-hint")).weak());
+                            let mut remove = None;
+                            egui::Grid::new("settings_extract_grid").num_columns(3).show(ui, |ui| {
+                                for header in ["settings-extract-archive", "settings-extract-target"] {
+                                    ui.label(RichText::new(tr(header)).strong());
+                                }
+                                ui.end_row();
+                                for (i, rule) in self.extract.iter_mut().enumerate() {
+                                    for path in [&mut rule.archive, &mut rule.target] {
+                                        let mut text = path.display().to_string();
+                                        if ui.add(egui::widgets::TextEdit::singleline(&mut text).desired_width(140.0)).changed() {
+                                            *path = std::path::PathBuf::from(text);
+                                        }
+                                    }
+                                    if ui.small_button(tr("settings-ignored-remove")).clicked() {
+                                        remove = Some(i);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                            if let Some(i) = remove {
+                                self.extract.remove(i);
+                            }
+                            if ui.button(tr("settings-extract-add")).clicked() {
+                                self.extract.push(ExtractRule::default());
+                            }
+                        });
+
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-keys-dir"));
+                        ui.add(egui::widgets::TextEdit::singleline(&mut self.keys_dir_str).desired_width(200.0))
+                            .labelled_by(label.id)
+                            .on_hover_text(tr("settings-keys-dir-hover"));
+                        if ui.button(tr("app-browse")).clicked()
+                            && let Some(folder) = rfd::FileDialog::new().pick_folder()
+                        {
+                            self.keys_dir_str = folder.display().to_string();
+                        }
+                    });
+
+                    egui::CollapsingHeader::new(tr("settings-hooks"))
+                        .id_salt("settings_hooks")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-hooks-hint")).weak());
+                            egui::Grid::new("settings_hooks_grid").num_columns(2).show(ui, |ui| {
+                                let fields = [
+                                    ("settings-hooks-pre-update", "settings-hooks-pre-update-hover", &mut self.hooks.pre_update),
+                                    ("settings-hooks-post-update", "settings-hooks-post-update-hover", &mut self.hooks.post_update),
+                                    ("settings-hooks-post-verify", "settings-hooks-post-verify-hover", &mut self.hooks.post_verify),
+                                ];
+                                for (label_id, hover_id, value) in fields {
+                                    let label = ui.label(tr(label_id));
+                                    ui.add(egui::widgets::TextEdit::singleline(value).code_editor().desired_width(220.0))
+                                        .labelled_by(label.id)
+                                        .on_hover_text(tr(hover_id));
+                                    ui.end_row();
+                                }
+                            });
+                            #[cfg(feature = "scripting")]
+                            ui.horizontal(|ui| {
+                                let label = ui.label(tr("settings-script"));
+                                ui.add(egui::widgets::TextEdit::singleline(&mut self.script_str).desired_width(200.0))
+                                    .labelled_by(label.id)
+                                    .on_hover_text(tr("settings-script-hover"));
+                                if ui.button(tr("app-browse")).clicked()
+                                    && let Some(file) = rfd::FileDialog::new().add_filter("Rhai", &["rhai"]).pick_file()
+                                {
+                                    self.script_str = file.display().to_string();
+                                }
+                            });
+                        });
+
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-sftp-key"));
+                        ui.add(egui::widgets::TextEdit::singleline(&mut self.sftp_key_str).desired_width(200.0))
+                            .labelled_by(label.id)
+                            .on_hover_text(tr("settings-sftp-key-hover"));
+                        if ui.button(tr("app-browse")).clicked()
+                            && let Some(file) = rfd::FileDialog::new().pick_file()
+                        {
+                            self.sftp_key_str = file.display().to_string();
+                        }
                     });
 
                     ui.horizontal(|ui| {
-                        ui.label("Download path:");
+                        let label = ui.label(tr("settings-download-path"));
                         let path_widget = egui::widgets::TextEdit::singleline(&mut self.path_str).desired_width(220.0);
-                        ui.add(path_widget);
+                        ui.add(path_widget).labelled_by(label.id);
+                        if ui.button(tr("app-browse")).on_hover_text(tr("app-browse-hover")).clicked()
+                            && let Some(folder) = rfd::FileDialog::new().pick_folder()
+                        {
+                            self.path_str = folder.display().to_string();
+                        }
+                        ui.add_enabled_ui(!self.recent_paths.is_empty(), |ui| {
+                            ui.menu_button(tr("settings-recent-paths"), |ui| {
+                                for path in &self.recent_paths {
+                                    if ui.button(path.display().to_string()).clicked() {
+                                        self.path_str = path.display().to_string();
+                                        ui.close();
+                                    }
+                                }
+                            })
+                            .response
+                            .on_hover_text(tr("settings-recent-paths-hover"));
+                        });
+                    });
+                    if let Some(problem) = &self.path_problem {
+                        ui.colored_label(theme.error, problem.message());
+                    }
+
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-shared-store"));
+                        ui.add(
+                            egui::widgets::TextEdit::singleline(&mut self.shared_store_str)
+                                .desired_width(220.0)
+                                .hint_text(tr("settings-shared-store-off")),
+                        )
+                        .labelled_by(label.id)
+                        .on_hover_text(tr("settings-shared-store-hover"));
+                        if ui.button(tr("app-browse")).clicked()
+                            && let Some(folder) = rfd::FileDialog::new().pick_folder()
+                        {
+                            self.shared_store_str = folder.display().to_string();
+                        }
                     });
 
                     ui.separator();
 
                     ui.horizontal(|ui| {
-                        ui.label("Seeding:");
-                        ui.checkbox(&mut self.should_seed, "Enable seeding");
+                        ui.label(tr("settings-seeding"));
+                        ui.checkbox(&mut self.should_seed, tr("app-enable-seeding"));
                     });
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-seed-window"));
+                        let response = ui
+                            .add_enabled(
+                                self.should_seed,
+                                egui::widgets::TextEdit::singleline(&mut self.seed_window_str).desired_width(100.0).hint_text("22:00-08:00"),
+                            )
+                            .labelled_by(label.id)
+                            .on_hover_text(tr("settings-seed-window-hover"));
+                        let window = self.seed_window_str.trim();
+                        if !window.is_empty() && SeedWindow::parse(window).is_none() {
+                            response.on_hover_text(tr("settings-seed-window-invalid"));
+                            ui.label(RichText::new("⚠").color(theme.warning));
+                        }
+                    });
+                    ui.checkbox(&mut self.read_only, tr("settings-read-only")).on_hover_text(tr("settings-read-only-hover"));
+                    let mut strict_mirror = self.strict_mirror;
+                    let strict = ui
+                        .add_enabled(!self.read_only, egui::Checkbox::new(&mut strict_mirror, tr("settings-strict-mirror")))
+                        .on_hover_text(tr("settings-strict-mirror-hover"))
+                        .on_disabled_hover_text(tr("settings-strict-mirror-read-only"));
+                    if strict.changed() {
+                        // Turning it on deletes files without asking, so that is confirmed first
+                        self.confirm_strict_mirror = strict_mirror;
+                        if !strict_mirror {
+                            self.strict_mirror = false;
+                        }
+                    }
+                    if self.confirm_strict_mirror {
+                        egui::Frame::group(ui.style()).show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-strict-mirror-warning")).color(theme.warning));
+                            ui.horizontal(|ui| {
+                                if ui.button(RichText::new(tr("settings-strict-mirror-confirm")).color(theme.error)).clicked() {
+                                    self.strict_mirror = true;
+                                    self.confirm_strict_mirror = false;
+                                    self.quarantine = false;
+                                    self.quarantine_days_str.clear();
+                                }
+                                if ui.button(tr("app-cancel")).clicked() {
+                                    self.confirm_strict_mirror = false;
+                                }
+                            });
+                        });
+                    }
 
                     ui.horizontal(|ui| {
                         ui.vertical(|ui| {
-                            ui.label("Max upload (KB/s):");
-                            ui.add(egui::widgets::TextEdit::singleline(&mut self.upload_str).desired_width(140.0));
+                            let label = ui.label(tr("app-max-upload"));
+                            ui.add(egui::widgets::TextEdit::singleline(&mut self.upload_str).desired_width(140.0)).labelled_by(label.id);
                         });
                         ui.add_space(8.0);
                         ui.vertical(|ui| {
-                            ui.label("Max download (KB/s):");
-                            ui.add(egui::widgets::TextEdit::singleline(&mut self.download_str).desired_width(140.0));
+                            let label = ui.label(tr("app-max-download"));
+                            ui.add(egui::widgets::TextEdit::singleline(&mut self.download_str).desired_width(140.0)).labelled_by(label.id);
                         });
                     });
 
-                    ui.add_space(6.0);
+                    egui::CollapsingHeader::new(tr("settings-presets"))
+                        .id_salt("settings_presets")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-presets-hint")).weak());
+                            let mut remove = None;
+                            egui::Grid::new("settings_presets_grid").num_columns(4).show(ui, |ui| {
+                                for header in ["settings-presets-name", "app-max-upload", "app-max-download"] {
+                                    ui.label(RichText::new(tr(header)).strong());
+                                }
+                                ui.end_row();
+                                for (i, preset) in self.bandwidth_presets.iter_mut().enumerate() {
+                                    ui.add(
+                                        egui::widgets::TextEdit::singleline(&mut preset.name)
+                                            .desired_width(120.0)
+                                            .hint_text(tr("settings-presets-name-hint")),
+                                    );
+                                    for limit in [&mut preset.max_upload_speed, &mut preset.max_download_speed] {
+                                        let mut text = limit.map(|v| v.to_string()).unwrap_or_default();
+                                        let response = ui.add(
+                                            egui::widgets::TextEdit::singleline(&mut text)
+                                                .desired_width(80.0)
+                                                .hint_text(tr("settings-presets-unlimited")),
+                                        );
+                                        if response.changed() {
+                                            *limit = text.trim().parse::<u32>().ok();
+                                        }
+                                    }
+                                    if ui.small_button(tr("settings-ignored-remove")).clicked() {
+                                        remove = Some(i);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                            if let Some(i) = remove {
+                                self.bandwidth_presets.remove(i);
+                            }
+                            if ui.button(tr("settings-presets-add")).clicked() {
+                                self.bandwidth_presets.push(BandwidthPreset::default());
+                            }
+                        });
+
+                    egui::CollapsingHeader::new(tr("settings-gaming"))
+                        .id_salt("settings_gaming")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-gaming-hint")).weak());
+                            egui::Grid::new("settings_gaming_grid").num_columns(2).show(ui, |ui| {
+                                let label = ui.label(tr("settings-gaming-upload"));
+                                ui.add(
+                                    egui::widgets::TextEdit::singleline(&mut self.gaming_upload_str)
+                                        .desired_width(80.0)
+                                        .hint_text(crate::sync::types::GAMING_UPLOAD_SPEED.to_string()),
+                                )
+                                .labelled_by(label.id);
+                                ui.end_row();
+                                let label = ui.label(tr("settings-gaming-shortcut"));
+                                let response = ui
+                                    .add(
+                                        egui::widgets::TextEdit::singleline(&mut self.gaming_shortcut_str)
+                                            .desired_width(120.0)
+                                            .hint_text("Ctrl+Shift+G"),
+                                    )
+                                    .labelled_by(label.id);
+                                let shortcut = self.gaming_shortcut_str.trim();
+                                if !shortcut.is_empty() && shortcuts::parse_shortcut(shortcut).is_none() {
+                                    response.on_hover_text(tr("settings-gaming-shortcut-invalid"));
+                                    ui.label(RichText::new("⚠").color(theme.warning));
+                                }
+                                ui.end_row();
+                            });
+                        });
+
+                    egui::CollapsingHeader::new(tr("settings-power"))
+                        .id_salt("settings_power")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-power-hint")).weak());
+                            egui::Grid::new("settings_power_grid").num_columns(2).show(ui, |ui| {
+                                for (label, id, action) in [
+                                    ("settings-power-metered", "settings_power_metered", &mut self.power.on_metered),
+                                    ("settings-power-battery", "settings_power_battery", &mut self.power.on_battery),
+                                ] {
+                                    ui.label(tr(label));
+                                    egui::ComboBox::from_id_salt(id).selected_text(tr(power_action_label(*action))).show_ui(ui, |ui| {
+                                        for option in [PowerAction::Ignore, PowerAction::LowPower, PowerAction::Pause] {
+                                            ui.selectable_value(action, option, tr(power_action_label(option)));
+                                        }
+                                    });
+                                    ui.end_row();
+                                }
+                                for (label, text, default) in [
+                                    ("settings-power-upload", &mut self.power_upload_str, LOW_POWER_UPLOAD_SPEED),
+                                    ("settings-power-download", &mut self.power_download_str, LOW_POWER_DOWNLOAD_SPEED),
+                                ] {
+                                    let label = ui.label(tr(label));
+                                    ui.add(egui::widgets::TextEdit::singleline(text).desired_width(80.0).hint_text(default.to_string()))
+                                        .labelled_by(label.id);
+                                    ui.end_row();
+                                }
+                            });
+                        });
+
+                    egui::CollapsingHeader::new(tr("settings-check"))
+                        .id_salt("settings_check")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-check-hint")).weak());
+                            ui.horizontal(|ui| {
+                                let label = ui.label(tr("settings-check-max"));
+                                ui.add(
+                                    egui::widgets::TextEdit::singleline(&mut self.check_max_str)
+                                        .desired_width(80.0)
+                                        .hint_text(tr("settings-presets-unlimited")),
+                                )
+                                .labelled_by(label.id);
+                            });
+                            ui.checkbox(&mut self.check_low_io_priority, tr("settings-check-low-priority"))
+                                .on_hover_text(tr("settings-check-low-priority-hover"));
+                            ui.checkbox(&mut self.fastresume, tr("settings-check-fastresume"))
+                                .on_hover_text(tr("settings-check-fastresume-hover"));
+                        });
+
+                    egui::CollapsingHeader::new(tr("settings-memory"))
+                        .id_salt("settings_memory")
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(tr("settings-memory-hint")).weak());
+                            egui::Grid::new("memory_grid").num_columns(2).spacing([10.0, 4.0]).show(ui, |ui| {
+                                let fields = [
+                                    ("settings-memory-write-buffer", "settings-memory-write-buffer-hover", &mut self.write_buffer_str),
+                                    ("settings-memory-piece-cache", "settings-memory-piece-cache-hover", &mut self.piece_cache_str),
+                                    ("settings-memory-concurrent-checks", "settings-memory-concurrent-checks-hover", &mut self.concurrent_checks_str),
+                                    ("settings-memory-hashing-threads", "settings-memory-hashing-threads-hover", &mut self.hashing_threads_str),
+                                ];
+                                for (label, hover, value) in fields {
+                                    let label = ui.label(tr(label));
+                                    ui.add(egui::widgets::TextEdit::singleline(value).desired_width(80.0))
+                                        .labelled_by(label.id)
+                                        .on_hover_text(tr(hover));
+                                    ui.end_row();
+                                }
+                            });
+                        });
+
+                    egui::CollapsingHeader::new(tr("settings-cache"))
+                        .id_salt("settings_cache")
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                let label = ui.label(tr("settings-cache-max"));
+                                ui.add(egui::widgets::TextEdit::singleline(&mut self.cache_max_str).desired_width(80.0))
+                                    .labelled_by(label.id)
+                                    .on_hover_text(tr("settings-cache-max-hover"));
+                            });
+                            ui.horizontal(|ui| {
+                                let label = ui.label(tr("settings-cache-versions"));
+                                ui.add(egui::widgets::TextEdit::singleline(&mut self.cache_versions_str).desired_width(80.0))
+                                    .labelled_by(label.id)
+                                    .on_hover_text(tr("settings-cache-versions-hover"));
+                            });
+                            let total: u64 = self.cache_entries.iter().map(|e| e.size).sum();
+                            ui.label(tr_args(
+                                "settings-cache-total",
+                                &[("count", self.cache_entries.len().into()), ("size", format::size(total).into())],
+                            ));
+                            let mut roll_back = None;
+                            egui::Grid::new("settings_cache_grid").num_columns(4).show(ui, |ui| {
+                                for entry in &self.cache_entries {
+                                    let name = entry.path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+                                    ui.label(RichText::new(name.chars().take(12).collect::<String>()).monospace())
+                                        .on_hover_text(entry.path.display().to_string());
+                                    ui.label(format::size(entry.size));
+                                    let last_used: chrono::DateTime<chrono::Local> = entry.last_used.into();
+                                    ui.label(last_used.format("%Y-%m-%d %H:%M").to_string());
+                                    if self.current_version.as_deref() == Some(&*name) {
+                                        ui.label(RichText::new(tr("settings-cache-current")).weak());
+                                    } else if ui.button(tr("settings-cache-roll-back")).on_hover_text(tr("settings-cache-roll-back-hover")).clicked() {
+                                        roll_back = Some(entry.path.clone());
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                            if roll_back.is_some() {
+                                self.confirm_roll_back = roll_back;
+                            }
+                            if let Some(path) = self.confirm_roll_back.clone() {
+                                egui::Frame::group(ui.style()).show(ui, |ui| {
+                                    let warning = if self.read_only { "settings-cache-roll-back-read-only" } else { "settings-cache-roll-back-warning" };
+                                    ui.label(RichText::new(tr(warning)).color(theme.warning));
+                                    ui.horizontal(|ui| {
+                                        if ui.button(RichText::new(tr("settings-cache-roll-back-confirm")).color(theme.error)).clicked() {
+                                            self.roll_back = Some(path);
+                                            self.confirm_roll_back = None;
+                                        }
+                                        if ui.button(tr("app-cancel")).clicked() {
+                                            self.confirm_roll_back = None;
+                                        }
+                                    });
+                                });
+                            }
+                            ui.horizontal(|ui| {
+                                if ui.button(tr("settings-cache-refresh")).clicked() {
+                                    self.refresh_cache_entries();
+                                }
+                                if ui
+                                    .add_enabled(self.cache_entries.len() >= 2, egui::Button::new(tr("settings-cache-compare")))
+                                    .on_hover_text(tr("settings-cache-compare-hover"))
+                                    .clicked()
+                                {
+                                    self.compare_versions = true;
+                                }
+                                if ui.add_enabled(!self.cache_entries.is_empty(), egui::Button::new(tr("settings-cache-clear"))).clicked() {
+                                    self.clear_cache();
+                                }
+                            });
+                        });
+
+                    egui::CollapsingHeader::new(tr("settings-quarantine"))
+                        .id_salt("settings_quarantine")
+                        .show(ui, |ui| {
+                            ui.checkbox(&mut self.quarantine, tr("settings-quarantine-enable"))
+                                .on_hover_text(tr("settings-quarantine-enable-hover"));
+                            ui.horizontal(|ui| {
+                                let label = ui.label(tr("settings-quarantine-days"));
+                                ui.add(
+                                    egui::widgets::TextEdit::singleline(&mut self.quarantine_days_str)
+                                        .desired_width(80.0)
+                                        .hint_text(crate::sync::quarantine::DEFAULT_QUARANTINE_DAYS.to_string()),
+                                )
+                                .labelled_by(label.id);
+                            });
+                            if self.quarantine_batches.is_empty() {
+                                ui.label(RichText::new(tr("settings-quarantine-empty")).weak());
+                            }
+                            let mut restore = None;
+                            let mut discard = None;
+                            egui::Grid::new("settings_quarantine_grid").num_columns(4).show(ui, |ui| {
+                                for (i, batch) in self.quarantine_batches.iter().enumerate() {
+                                    let at: chrono::DateTime<chrono::Local> = batch.quarantined_at.into();
+                                    ui.label(at.format("%Y-%m-%d %H:%M").to_string());
+                                    let files = batch.files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join("\n");
+                                    ui.label(tr_args(
+                                        "settings-quarantine-batch",
+                                        &[("count", batch.files.len().into()), ("size", format::size(batch.size).into())],
+                                    ))
+                                    .on_hover_text(files);
+                                    if ui.small_button(tr("settings-quarantine-restore")).clicked() {
+                                        restore = Some(i);
+                                    }
+                                    if ui.small_button(tr("settings-quarantine-discard")).clicked() {
+                                        discard = Some(i);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                            if let Some(i) = restore {
+                                let batch = self.quarantine_batches[i].clone();
+                                self.restore_batch(&batch);
+                            }
+                            if let Some(i) = discard {
+                                let batch = self.quarantine_batches[i].clone();
+                                self.discard_batch(&batch);
+                            }
+                            if ui.button(tr("settings-cache-refresh")).clicked() {
+                                self.refresh_quarantine();
+                            }
+                        });
+
+                    ui.separator();
+
+                    ui.label(RichText::new(tr("settings-appearance")).strong());
+                    ui.horizontal(|ui| {
+                        ui.label(tr("settings-theme"));
+                        ui.radio_value(&mut self.theme.preset, ThemePreset::System, tr("settings-theme-system"))
+                            .on_hover_text(tr("settings-theme-system-hover"));
+                        ui.radio_value(&mut self.theme.preset, ThemePreset::Dark, tr("settings-theme-dark"));
+                        ui.radio_value(&mut self.theme.preset, ThemePreset::Light, tr("settings-theme-light"));
+                    });
+                    ui.horizontal(|ui| {
+                        let mut custom = self.theme.accent.is_some();
+                        if ui.checkbox(&mut custom, tr("settings-custom-accent")).changed() {
+                            self.theme.accent = custom.then(|| {
+                                let preset = Theme::from_settings(&ThemeSettings { accent: None, ..self.theme.clone() }, ui.ctx().system_theme());
+                                [preset.accent.r(), preset.accent.g(), preset.accent.b()]
+                            });
+                        }
+                        if let Some(accent) = &mut self.theme.accent {
+                            ui.color_edit_button_srgb(accent).on_hover_text(tr("settings-accent-hover"));
+                        }
+                    });
+
+                    ui.separator();
+
+                    ui.label(RichText::new(tr("settings-display")).strong());
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-ui-scale"));
+                        // Apply on release so the slider doesn't move under the pointer while dragging
+                        let mut scale = self.display.ui_scale;
+                        let response = ui.add(egui::Slider::new(&mut scale, DisplaySettings::UI_SCALE_RANGE).step_by(0.05).suffix("x")).labelled_by(label.id);
+                        if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                            self.display.ui_scale = scale;
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-font-size"));
+                        ui.add(egui::Slider::new(&mut self.display.font_size, DisplaySettings::FONT_SIZE_RANGE).step_by(1.0).suffix(" pt")).labelled_by(label.id);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr("settings-units"));
+                        ui.radio_value(&mut self.display.units, UnitSystem::Binary, tr("settings-units-binary"));
+                        ui.radio_value(&mut self.display.units, UnitSystem::Si, tr("settings-units-si"));
+                    });
+                    if ui.button(tr("settings-display-reset")).clicked() {
+                        self.display = DisplaySettings::default();
+                    }
+
+                    ui.separator();
 
+                    ui.label(RichText::new(tr("settings-notifications")).strong());
+                    ui.checkbox(&mut self.play_sounds, tr("settings-play-sounds")).on_hover_text(tr("settings-play-sounds-hover"));
                     ui.horizontal(|ui| {
-                        if ui.add(egui::widgets::Button::new("Save").fill(Color32::from_rgb(80, 160, 120))).clicked() {
-                            let mut settings = AppSettings::load().unwrap_or_default();
-                            settings.max_upload_speed = if self.upload_str.trim().is_empty() { None } else { self.upload_str.trim().parse::<u32>().ok() };
-                            settings.max_download_speed = if self.download_str.trim().is_empty() { None } else { self.download_str.trim().parse::<u32>().ok() };
-                            settings.download_path = std::path::PathBuf::from(self.path_str.clone());
-                            settings.torrent_url = self.url_str.clone();
-                            settings.should_seed = self.should_seed;
-                            match settings.save() {
-                                Ok(()) => self.save_message = Some("Settings saved".to_string()),
-                                Err(e) => self.save_message = Some(format!("Failed to save settings: {}", e)),
+                        let label = ui.label(tr("settings-quiet-hours"));
+                        let response = ui
+                            .add(egui::widgets::TextEdit::singleline(&mut self.quiet_hours_str).desired_width(100.0).hint_text("22:00-08:00"))
+                            .labelled_by(label.id)
+                            .on_hover_text(tr("settings-quiet-hours-hover"));
+                        let hours = self.quiet_hours_str.trim();
+                        if !hours.is_empty() && SeedWindow::parse(hours).is_none() {
+                            response.on_hover_text(tr("settings-seed-window-invalid"));
+                            ui.label(RichText::new("⚠").color(theme.warning));
+                        }
+                    });
+                    ui.checkbox(&mut self.discord.enabled, tr("settings-discord")).on_hover_text(tr("settings-discord-hover"));
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-discord-client-id"));
+                        ui.add_enabled(
+                            self.discord.enabled,
+                            egui::widgets::TextEdit::singleline(&mut self.discord.client_id).desired_width(180.0),
+                        )
+                        .labelled_by(label.id)
+                        .on_hover_text(tr("settings-discord-client-id-hover"));
+                    });
+                    ui.checkbox(&mut self.telemetry.enabled, tr("settings-telemetry")).on_hover_text(tr("settings-telemetry-hover"));
+                    ui.horizontal(|ui| {
+                        let label = ui.label(tr("settings-telemetry-endpoint"));
+                        ui.add_enabled(
+                            self.telemetry.enabled,
+                            egui::widgets::TextEdit::singleline(&mut self.telemetry.endpoint).desired_width(260.0).hint_text("https://"),
+                        )
+                        .labelled_by(label.id)
+                        .on_hover_text(tr("settings-telemetry-endpoint-hover"));
+                    });
+
+                    ui.separator();
+
+                    ui.label(RichText::new(tr("settings-ignored")).strong()).on_hover_text(tr("menu-ignore-hover"));
+                    if self.ignored_paths.is_empty() {
+                        ui.label(RichText::new(tr("settings-ignored-empty")).weak());
+                    }
+                    let mut remove = None;
+                    for (i, path) in self.ignored_paths.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(path.display().to_string());
+                            if ui.small_button(tr("settings-ignored-remove")).clicked() {
+                                remove = Some(i);
                             }
+                        });
+                    }
+                    if let Some(i) = remove {
+                        self.ignored_paths.remove(i);
+                    }
+
+                    ui.separator();
+
+                    ui.label(RichText::new(tr("settings-banned-peers")).strong()).on_hover_text(tr("settings-banned-peers-hover"));
+                    if self.banned_peers.is_empty() {
+                        ui.label(RichText::new(tr("settings-banned-peers-empty")).weak());
+                    }
+                    let mut remove = None;
+                    for (i, ip) in self.banned_peers.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(ip.to_string());
+                            if ui.small_button(tr("settings-banned-peers-unban")).clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = remove {
+                        self.banned_peers.remove(i);
+                    }
+
+                    ui.add_space(6.0);
+
+                    ui.horizontal(|ui| {
+                        let save = ui
+                            .add_enabled(self.can_save(), egui::widgets::Button::new(tr("settings-save")).fill(theme.secondary))
+                            .on_disabled_hover_text(tr("settings-save-invalid"));
+                        if save.clicked() {
+                            self.save();
                         }
 
-                        if ui.add(egui::widgets::Button::new("Reset").fill(Color32::from_rgb(160, 80, 80))).clicked() {
+                        if ui.add(egui::widgets::Button::new(tr("settings-reset")).fill(theme.error)).clicked() {
                             match AppSettings::reset() {
                                 Ok(()) => {
                                     self.url_str.clear();
+                                    self.mirrors_str.clear();
+                                    self.trackers_str.clear();
+                                    self.replace_trackers = false;
+                                    self.stall_minutes_str.clear();
+                                    self.check_jitter_str.clear();
+                                    self.check_splay_str.clear();
+                                    self.version_url_str.clear();
                                     self.upload_str.clear();
                                     self.download_str.clear();
                                     self.path_str.clear();
+                                    self.recent_paths.clear();
+                                    self.shared_store_str.clear();
                                     self.should_seed = AppSettings::default().should_seed;
-                                    self.save_message = Some("Settings reset to defaults".to_string());
+                                    self.seed_window_str.clear();
+                                    self.read_only = false;
+                                    self.strict_mirror = false;
+                                    self.confirm_strict_mirror = false;
+                                    self.pinned = false;
+                                    self.theme = ThemeSettings::default();
+                                    self.display = DisplaySettings::default();
+                                    self.play_sounds = false;
+                                    self.discord = DiscordConfig::default();
+                                    self.telemetry = TelemetryConfig::default();
+                                    self.quiet_hours_str.clear();
+                                    self.ignored_paths.clear();
+                                    self.banned_peers.clear();
+                                    self.redirects = RedirectConfig::default();
+                                    self.s3 = S3Config::default();
+                                    self.sftp_key_str.clear();
+                                    self.credentials = CredentialStore::default();
+                                    self.engine = TransferEngine::default();
+                                    self.rsync = RsyncConfig::default();
+                                    self.ipfs = IpfsConfig::default();
+                                    self.hooks = HooksConfig::default();
+                                    self.script_str.clear();
+                                    self.extract.clear();
+                                    self.keys_dir_str.clear();
+                                    self.folder_mappings.clear();
+                                    self.download_filters.clear();
+                                    self.bandwidth_presets.clear();
+                                    self.gaming_upload_str.clear();
+                                    self.gaming_shortcut_str = AppSettings::default().gaming_mode_shortcut;
+                                    self.power = PowerConfig::default();
+                                    self.power_upload_str.clear();
+                                    self.power_download_str.clear();
+                                    self.check_max_str.clear();
+                                    self.check_low_io_priority = false;
+                                    self.fastresume = false;
+                                    self.write_buffer_str.clear();
+                                    self.piece_cache_str.clear();
+                                    self.concurrent_checks_str.clear();
+                                    self.hashing_threads_str.clear();
+                                    self.cache_max_str.clear();
+                                    self.cache_versions_str.clear();
+                                    self.git_source = GitSourceConfig::default();
+                                    self.git_folder_str = self.git_source.folder.display().to_string();
+                                    self.save_message = Some(tr("settings-reset-done"));
                                 }
-                                Err(e) => self.save_message = Some(format!("Failed to reset settings: {}", e)),
+                                Err(e) => self.save_message = Some(tr_args("settings-reset-failed", &[("error", e.to_string().into())])),
                             }
                         }
 
-                        if ui.add(egui::widgets::Button::new("Close")).clicked() {
+                        if ui.add(egui::widgets::Button::new(tr("settings-close"))).clicked() {
                             self.open = false;
                         }
                     });
 
                     if let Some(msg) = &self.save_message {
-                        ui.colored_label(Color32::from_rgb(210, 180, 140), msg);
+                        ui.colored_label(theme.notice, msg);
                     }
                 });
             });
@@ -0,0 +1,111 @@
+use eframe::egui;
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+/// Main-window actions reachable from the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    CheckForUpdates,
+    Verify,
+    TogglePause,
+    OpenFolder,
+    OpenSettings,
+    ShowShortcuts,
+    CommandPalette,
+    /// Bound to the configurable `AppSettings::gaming_mode_shortcut` rather
+    /// than listed in `SHORTCUTS`.
+    ToggleGamingMode,
+}
+
+/// Key bindings, in the order they are listed in the cheat sheet.
+pub const SHORTCUTS: &[(Action, KeyboardShortcut)] = &[
+    (Action::CheckForUpdates, KeyboardShortcut::new(Modifiers::COMMAND, Key::U)),
+    (Action::Verify, KeyboardShortcut::new(Modifiers::COMMAND, Key::R)),
+    // Before the palette, whose Ctrl+P would also match Ctrl+Shift+P
+    (Action::TogglePause, KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::P)),
+    (Action::OpenFolder, KeyboardShortcut::new(Modifiers::COMMAND, Key::O)),
+    (Action::OpenSettings, KeyboardShortcut::new(Modifiers::COMMAND, Key::Comma)),
+    (Action::CommandPalette, KeyboardShortcut::new(Modifiers::COMMAND, Key::P)),
+    (Action::ShowShortcuts, KeyboardShortcut::new(Modifiers::NONE, Key::F1)),
+];
+
+impl Action {
+    /// Translation id describing the action.
+    pub fn label_id(self) -> &'static str {
+        match self {
+            Action::CheckForUpdates => "shortcut-check-updates",
+            Action::Verify => "shortcut-verify",
+            Action::TogglePause => "shortcut-pause",
+            Action::OpenFolder => "shortcut-open-folder",
+            Action::OpenSettings => "shortcut-open-settings",
+            Action::ShowShortcuts => "shortcut-show-shortcuts",
+            Action::CommandPalette => "shortcut-command-palette",
+            Action::ToggleGamingMode => "shortcut-gaming-mode",
+        }
+    }
+}
+
+/// Actions whose shortcut was pressed this frame. The key presses are
+/// consumed so widgets don't also react to them.
+pub fn pressed(ctx: &egui::Context) -> Vec<Action> {
+    ctx.input_mut(|input| {
+        SHORTCUTS
+            .iter()
+            .filter(|(_, shortcut)| input.consume_shortcut(shortcut))
+            .map(|(action, _)| *action)
+            .collect()
+    })
+}
+
+/// Parse a shortcut written like `Ctrl+Shift+G`. `Ctrl` and `Cmd` both mean
+/// the platform's command key; the key is any name egui knows, such as `G`,
+/// `F9` or `Space`.
+pub fn parse_shortcut(text: &str) -> Option<KeyboardShortcut> {
+    let mut modifiers = Modifiers::NONE;
+    let mut key = None;
+    for part in text.split('+').map(str::trim) {
+        match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" | "cmd" | "command" => modifiers |= Modifiers::COMMAND,
+            "shift" => modifiers |= Modifiers::SHIFT,
+            "alt" | "option" => modifiers |= Modifiers::ALT,
+            _ if key.is_none() => key = Some(Key::from_name(part).or_else(|| Key::from_name(&part.to_ascii_uppercase()))?),
+            _ => return None,
+        }
+    }
+    Some(KeyboardShortcut::new(modifiers, key?))
+}
+
+/// Whether `shortcut` was pressed this frame, consuming it.
+pub fn shortcut_pressed(ctx: &egui::Context, shortcut: &KeyboardShortcut) -> bool {
+    ctx.input_mut(|input| input.consume_shortcut(shortcut))
+}
+
+/// Whether Escape was pressed this frame, consuming it.
+pub fn escape_pressed(ctx: &egui::Context) -> bool {
+    ctx.input_mut(|input| input.consume_key(Modifiers::NONE, Key::Escape))
+}
+
+/// Whether Enter was pressed this frame while no text field has focus,
+/// consuming it.
+pub fn enter_pressed(ctx: &egui::Context) -> bool {
+    if ctx.wants_keyboard_input() {
+        return false;
+    }
+    ctx.input_mut(|input| input.consume_key(Modifiers::NONE, Key::Enter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shortcut() {
+        assert_eq!(
+            parse_shortcut("Ctrl+Shift+G"),
+            Some(KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::G))
+        );
+        assert_eq!(parse_shortcut(" alt + f9 "), Some(KeyboardShortcut::new(Modifiers::ALT, Key::F9)));
+        assert_eq!(parse_shortcut("Ctrl+"), None);
+        assert_eq!(parse_shortcut("G+H"), None);
+        assert_eq!(parse_shortcut("Ctrl+Nope"), None);
+    }
+}
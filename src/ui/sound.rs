@@ -0,0 +1,72 @@
+//! Short alert sounds, played with what the platform already has: the
+//! system sounds through `MessageBeep` on Windows, `afplay` on macOS and the
+//! freedesktop sound theme through `canberra-gtk-play` or `paplay`
+//! elsewhere. A missing player is silently skipped.
+
+/// What a sound announces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    /// A sync finished downloading.
+    Completed,
+    UpdateFound,
+}
+
+/// Play `sound` without waiting for it to finish.
+pub fn play(sound: Sound) {
+    std::thread::spawn(move || platform::play(sound));
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::Sound;
+
+    const MB_OK: u32 = 0x0000_0000;
+    const MB_ICONASTERISK: u32 = 0x0000_0040;
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn MessageBeep(kind: u32) -> i32;
+    }
+
+    pub fn play(sound: Sound) {
+        let kind = match sound {
+            Sound::Completed => MB_OK,
+            Sound::UpdateFound => MB_ICONASTERISK,
+        };
+        // Safety: plays a system sound asynchronously and touches no memory
+        unsafe { MessageBeep(kind) };
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Sound;
+
+    pub fn play(sound: Sound) {
+        let file = match sound {
+            Sound::Completed => "/System/Library/Sounds/Glass.aiff",
+            Sound::UpdateFound => "/System/Library/Sounds/Ping.aiff",
+        };
+        let _ = std::process::Command::new("afplay").arg(file).status();
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::Sound;
+    use std::process::{Command, Stdio};
+
+    pub fn play(sound: Sound) {
+        let name = match sound {
+            Sound::Completed => "complete",
+            Sound::UpdateFound => "message-new-instant",
+        };
+        let run = |program: &str, args: &[&str]| {
+            Command::new(program).args(args).stdout(Stdio::null()).stderr(Stdio::null()).status().is_ok_and(|s| s.success())
+        };
+        let file = format!("/usr/share/sounds/freedesktop/stereo/{}.oga", name);
+        if !run("canberra-gtk-play", &["-i", name]) {
+            run("paplay", &[&file]);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use eframe::egui;
+use egui::RichText;
+
+use crate::i18n::{tr, tr_args};
+use crate::sync::stall::{StallDiagnosis, StallSuggestion};
+use crate::ui::theme::Theme;
+
+/// Banner for a stalled download: what was checked and what to try.
+/// Returns whether it was dismissed.
+pub fn ui(ui: &mut egui::Ui, stalled: &StallDiagnosis) -> bool {
+    let theme = Theme::current(ui.ctx());
+    let mut dismissed = false;
+    egui::Frame::group(ui.style()).show(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(tr_args("stall-title", &[("minutes", stalled.minutes.into())])).color(theme.error).strong());
+            dismissed = ui.small_button(tr("stall-dismiss")).clicked();
+        });
+        ui.label(tr_args("stall-peers", &[("count", stalled.peers.into())]));
+        let trackers = match stalled.swarm {
+            _ if !stalled.has_trackers => tr("stall-trackers-none"),
+            Some(swarm) => tr_args("stall-trackers-answered", &[("seeders", swarm.seeders.into()), ("leechers", swarm.leechers.into())]),
+            None => tr("stall-trackers-silent"),
+        };
+        ui.label(trackers);
+        ui.label(match stalled.listen_port {
+            Some(port) => tr_args("stall-port-open", &[("port", port.into())]),
+            None => tr("stall-port-closed"),
+        });
+        ui.add_space(4.0);
+        for suggestion in &stalled.suggestions {
+            let id = match suggestion {
+                StallSuggestion::WaitForSeeders => "stall-suggest-wait",
+                StallSuggestion::CheckTrackers => "stall-suggest-trackers",
+                StallSuggestion::CheckFirewall => "stall-suggest-firewall",
+                StallSuggestion::UseRsync => "stall-suggest-rsync",
+            };
+            ui.label(RichText::new(format!("• {}", tr(id))).color(theme.warning));
+        }
+    });
+    dismissed
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::sync::peers::PeerInfo;
+use crate::sync::stall::StallDiagnosis;
+use crate::sync::swarm::SwarmHealth;
+use crate::sync::trackers::TrackerInfo;
+use crate::sync::{DiskUsage, EndpointHealth, Inconsistency, GitSourceStatus, LifetimeCounter, SyncHistory, TorrentFile, TorrentSnapshot};
+
+/// UI-local state shared by the main window's views, mostly the latest
+/// values reported by the daemon.
+pub struct UiState {
+    pub url: String,
+    pub folder: String,
+    /// Files of the managed torrent and bytes downloaded per file, in torrent
+    /// order. Shared by every view of the torrent's files.
+    pub files: Vec<TorrentFile>,
+    pub file_progress: Vec<u64>,
+    /// Latest cleaner findings relative to the download folder, `None`
+    /// until the folder has been verified.
+    pub missing_files: Option<HashSet<PathBuf>>,
+    pub extra_files: Option<Vec<PathBuf>>,
+    /// Bumped whenever any of the file data above changes, so views can
+    /// cache what they derive from it. `files_revision` only changes with
+    /// the set of paths (torrent files and extra files).
+    pub revision: u64,
+    pub files_revision: u64,
+    /// Transfer figures from the latest torrent snapshot, in MiB/s.
+    pub download_speed: f64,
+    pub upload_speed: f64,
+    pub live_peers: usize,
+    /// The loaded torrent is private.
+    pub private: bool,
+    /// When the daemon last started checking the remote torrent.
+    pub last_remote_check: Option<DateTime<Utc>>,
+    /// URL that served the torrent in the latest check since connecting,
+    /// either the configured one or a mirror.
+    pub remote_source: Option<String>,
+    /// When the daemon will next check the remote torrent on its own.
+    pub next_check_at: Option<DateTime<Utc>>,
+    /// Lifetime transfer totals, seeded from the daemon on connect.
+    pub lifetime: LifetimeCounter,
+    /// Recent sync runs, seeded from the daemon on connect.
+    pub history: SyncHistory,
+    /// Live peers from the latest status refresh.
+    pub peers: Vec<PeerInfo>,
+    /// Trackers of the loaded torrent.
+    pub trackers: Vec<TrackerInfo>,
+    /// Seeders and leechers from the latest tracker scrape.
+    pub swarm: Option<SwarmHealth>,
+    /// Why the download stalled, until it moves again or is dismissed.
+    pub stalled: Option<StallDiagnosis>,
+    /// Recent download outcomes per remote URL, seeded from the daemon.
+    pub health: EndpointHealth,
+    /// Latest pull of the git source, `None` until one was reported.
+    pub git_source: Option<GitSourceStatus>,
+    /// Latest size breakdown of the download folder.
+    pub disk_usage: Option<DiskUsage>,
+    /// Problems from the latest consistency check of the loaded torrent.
+    pub inconsistencies: Vec<Inconsistency>,
+}
+
+impl UiState {
+    pub fn new(url: String, folder: String) -> Self {
+        Self {
+            url,
+            folder,
+            files: Vec::new(),
+            file_progress: Vec::new(),
+            missing_files: None,
+            extra_files: None,
+            revision: 0,
+            files_revision: 0,
+            download_speed: 0.0,
+            upload_speed: 0.0,
+            live_peers: 0,
+            private: false,
+            last_remote_check: None,
+            next_check_at: None,
+            remote_source: None,
+            lifetime: LifetimeCounter::default(),
+            history: SyncHistory::default(),
+            peers: Vec::new(),
+            trackers: Vec::new(),
+            swarm: None,
+            stalled: None,
+            health: EndpointHealth::default(),
+            git_source: None,
+            disk_usage: None,
+            inconsistencies: Vec::new(),
+        }
+    }
+
+    /// Take the per-file progress and transfer figures from `stats`, or
+    /// clear them when no torrent is loaded.
+    pub fn update_stats(&mut self, stats: Option<&TorrentSnapshot>) {
+        let file_progress = stats.map(|s| s.file_progress.clone()).unwrap_or_default();
+        if self.file_progress != file_progress {
+            self.file_progress = file_progress;
+            self.revision += 1;
+        }
+        self.download_speed = stats.map_or(0.0, |s| s.download_speed);
+        self.upload_speed = stats.map_or(0.0, |s| s.upload_speed);
+        self.live_peers = stats.map_or(0, |s| s.live_peers);
+        self.private = stats.is_some_and(|s| s.private);
+    }
+}
+
+/// Parse an RFC 3339 timestamp as sent by the daemon.
+pub fn parse_timestamp(rfc3339: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(rfc3339).ok().map(|t| t.with_timezone(&Utc))
+}
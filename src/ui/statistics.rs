@@ -0,0 +1,30 @@
+use eframe::egui;
+use egui::RichText;
+
+use crate::i18n::tr;
+use crate::sync::LifetimeStats;
+use crate::ui::theme::Theme;
+use crate::ui::format;
+
+/// Lifetime transfer totals and the resulting share ratio.
+pub fn ui(ui: &mut egui::Ui, stats: &LifetimeStats) {
+    let theme = Theme::current(ui.ctx());
+    egui::CollapsingHeader::new(RichText::new(tr("stats-title")).strong())
+        .id_salt("lifetime_stats")
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::Grid::new("lifetime_stats_table").num_columns(2).show(ui, |ui| {
+                ui.label(tr("stats-downloaded"));
+                ui.label(format::size(stats.downloaded_bytes));
+                ui.end_row();
+                ui.label(tr("stats-uploaded"));
+                ui.label(format::size(stats.uploaded_bytes));
+                ui.end_row();
+                ui.label(tr("stats-ratio")).on_hover_text(tr("stats-ratio-hover"));
+                let ratio = stats.ratio();
+                let color = if ratio >= 1.0 { theme.success } else { theme.text };
+                ui.label(RichText::new(format::number(ratio, 2)).color(color));
+                ui.end_row();
+            });
+        });
+}
@@ -0,0 +1,169 @@
+use eframe::egui;
+use egui::RichText;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::i18n::{tr, tr_args};
+use crate::sync::status::SyncStatus;
+use crate::sync::usage::available_space;
+use crate::ui::state::UiState;
+use crate::ui::theme::Theme;
+use crate::ui::format;
+
+/// How often free disk space is re-read.
+const DISK_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Free space on the volume holding the download folder, read off the UI
+/// thread so a slow network drive can't stall a frame.
+struct DiskSpacePoller {
+    path: PathBuf,
+    last_poll: Option<Instant>,
+    free: Option<u64>,
+    tx: mpsc::UnboundedSender<(PathBuf, Option<u64>)>,
+    rx: mpsc::UnboundedReceiver<(PathBuf, Option<u64>)>,
+}
+
+impl Default for DiskSpacePoller {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            path: PathBuf::new(),
+            last_poll: None,
+            free: None,
+            tx,
+            rx,
+        }
+    }
+}
+
+impl DiskSpacePoller {
+    /// Free bytes on the volume of `folder`, starting a new reading when the
+    /// last one is stale or was for another folder.
+    fn free_space(&mut self, folder: &Path) -> Option<u64> {
+        while let Ok((path, free)) = self.rx.try_recv() {
+            if path == self.path {
+                self.free = free;
+            }
+        }
+        if self.path != folder {
+            self.path = folder.to_path_buf();
+            self.free = None;
+            self.last_poll = None;
+        }
+        if self.last_poll.is_none_or(|t| t.elapsed() >= DISK_POLL_INTERVAL)
+            && let Ok(handle) = tokio::runtime::Handle::try_current()
+        {
+            self.last_poll = Some(Instant::now());
+            let path = self.path.clone();
+            let tx = self.tx.clone();
+            handle.spawn_blocking(move || {
+                let free = available_space(&path);
+                let _ = tx.send((path, free));
+            });
+        }
+        self.free
+    }
+}
+
+/// Compact duration such as "45s", "7m 12s" or "3h 5m".
+pub fn human_readable_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else if secs < 86400 {
+        format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{}d {}h", secs / 86400, secs % 86400 / 3600)
+    }
+}
+
+fn speed_text(mib_per_sec: f64) -> String {
+    format::speed(mib_per_sec * 1024.0 * 1024.0)
+}
+
+/// Single line of session facts along the bottom of the window.
+#[derive(Default)]
+pub struct StatusBar {
+    disk: DiskSpacePoller,
+}
+
+impl StatusBar {
+    /// Returns whether "Check now" was clicked.
+    pub fn ui(&mut self, ui: &mut egui::Ui, status: &SyncStatus, state: &UiState) -> bool {
+        let theme = Theme::current(ui.ctx());
+        let free = self.disk.free_space(Path::new(state.folder.trim()));
+        ui.horizontal(|ui| {
+            let status_color = match status {
+                SyncStatus::Error(_) => theme.error,
+                SyncStatus::RemoteChanged => theme.warning,
+                _ => theme.text,
+            };
+            ui.label(RichText::new(status.display_text()).color(status_color));
+            ui.separator();
+            ui.label(tr_args("statusbar-download", &[("speed", speed_text(state.download_speed).into())]));
+            ui.label(tr_args("statusbar-upload", &[("speed", speed_text(state.upload_speed).into())]));
+            ui.separator();
+            ui.label(tr_args("statusbar-peers", &[("count", state.live_peers.into())]));
+            if state.private {
+                ui.label(RichText::new(tr("statusbar-private")).color(theme.notice)).on_hover_text(tr("statusbar-private-hover"));
+            }
+            if let Some(swarm) = state.swarm {
+                let text = tr_args("statusbar-swarm", &[("seeders", swarm.seeders.into()), ("leechers", swarm.leechers.into())]);
+                let color = if swarm.is_empty() { theme.error } else { theme.text };
+                ui.label(RichText::new(text).color(color))
+                    .on_hover_text(tr_args("statusbar-swarm-hover", &[("downloaded", swarm.downloaded.into())]));
+            }
+            ui.separator();
+            let free_text = free.map_or_else(|| "-".to_string(), format::size);
+            ui.label(tr_args("statusbar-free-space", &[("free", free_text.into())]))
+                .on_hover_text(tr("statusbar-free-space-hover"));
+            ui.separator();
+            let last_check = match state.last_remote_check {
+                Some(at) => {
+                    let elapsed = (chrono::Utc::now() - at).to_std().unwrap_or_default();
+                    tr_args("statusbar-last-check", &[("elapsed", human_readable_duration(elapsed).into())])
+                }
+                None => tr("statusbar-never-checked"),
+            };
+            let response = ui.label(last_check);
+            if let Some(url) = &state.remote_source {
+                response.on_hover_text(tr_args("statusbar-remote-source", &[("url", url.as_str().into())]));
+            }
+            if let Some(at) = state.next_check_at {
+                let remaining = (at - chrono::Utc::now()).to_std().unwrap_or_default();
+                ui.separator();
+                ui.label(tr_args("statusbar-next-check", &[("remaining", human_readable_duration(remaining).into())]));
+            }
+            if let Some(git) = &state.git_source {
+                ui.separator();
+                let commit = git.commit.as_deref().unwrap_or("-");
+                match &git.error {
+                    Some(err) => ui
+                        .label(RichText::new(tr_args("statusbar-git-failed", &[("commit", commit.into())])).color(theme.error))
+                        .on_hover_text(err.as_str()),
+                    None => ui.label(tr_args("statusbar-git", &[("commit", commit.into())])),
+                };
+            }
+            ui.small_button(tr("statusbar-check-now"))
+                .on_hover_text(tr("statusbar-check-now-hover"))
+                .clicked()
+        })
+        .inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_readable_duration() {
+        assert_eq!(human_readable_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(human_readable_duration(Duration::from_secs(432)), "7m 12s");
+        assert_eq!(human_readable_duration(Duration::from_secs(3 * 3600 + 5 * 60 + 9)), "3h 5m");
+        assert_eq!(human_readable_duration(Duration::from_secs(2 * 86400 + 3600)), "2d 1h");
+    }
+}
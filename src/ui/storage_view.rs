@@ -0,0 +1,39 @@
+use eframe::egui;
+use egui::RichText;
+
+use crate::i18n::{tr, tr_args};
+use crate::sync::DiskUsage;
+use crate::ui::status_bar::human_readable_duration;
+use crate::ui::theme::Theme;
+use crate::ui::format;
+
+/// Breakdown of the download folder's size from the latest scan.
+pub fn ui(ui: &mut egui::Ui, usage: &DiskUsage) {
+    let theme = Theme::current(ui.ctx());
+    egui::CollapsingHeader::new(RichText::new(tr("storage-title")).strong())
+        .id_salt("storage_usage")
+        .show(ui, |ui| {
+            egui::Grid::new("storage_usage_table").num_columns(2).show(ui, |ui| {
+                ui.label(tr("storage-repo"));
+                ui.label(format::size(usage.repo_bytes));
+                ui.end_row();
+                ui.label(tr("storage-extra")).on_hover_text(tr("storage-extra-hover"));
+                let color = if usage.extra_bytes > 0 { theme.warning } else { theme.text };
+                ui.label(RichText::new(format::size(usage.extra_bytes)).color(color));
+                ui.end_row();
+                ui.label(tr("storage-protected")).on_hover_text(tr("storage-protected-hover"));
+                ui.label(format::size(usage.protected_bytes));
+                ui.end_row();
+                ui.label(tr("storage-free"));
+                ui.label(usage.free_bytes.map_or_else(|| "-".to_string(), format::size));
+                ui.end_row();
+            });
+            if let Ok(at) = chrono::DateTime::parse_from_rfc3339(&usage.scanned_at) {
+                let elapsed = (chrono::Utc::now() - at.to_utc()).to_std().unwrap_or_default();
+                ui.label(
+                    RichText::new(tr_args("storage-scanned", &[("elapsed", human_readable_duration(elapsed).into())]))
+                        .color(theme.text_muted),
+                );
+            }
+        });
+}
@@ -0,0 +1,170 @@
+//! Download progress outside the window: the progress bar of the taskbar
+//! button on Windows, through `ITaskbarList3`, and a percentage badge on the
+//! dock icon on macOS. Other platforms have no common API for it and show
+//! nothing.
+
+use eframe::Frame;
+
+use crate::sync::TorrentSnapshot;
+
+/// What the taskbar shows. Progress is in thousandths, so redrawing is only
+/// needed when it visibly moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskbarProgress {
+    /// Nothing to download, or the download finished.
+    Hidden,
+    Downloading(u32),
+    Paused(u32),
+    Error,
+}
+
+impl TaskbarProgress {
+    pub fn of(stats: Option<&TorrentSnapshot>) -> Self {
+        let Some(stats) = stats.filter(|stats| !stats.finished && stats.total_bytes > 0) else {
+            return TaskbarProgress::Hidden;
+        };
+        let permille = (stats.progress_bytes.min(stats.total_bytes) as u128 * 1000 / stats.total_bytes as u128) as u32;
+        match stats.state.as_str() {
+            "error" => TaskbarProgress::Error,
+            "paused" => TaskbarProgress::Paused(permille),
+            _ => TaskbarProgress::Downloading(permille),
+        }
+    }
+}
+
+/// Keeps the taskbar showing the latest progress.
+#[derive(Default)]
+pub struct Taskbar {
+    shown: Option<TaskbarProgress>,
+    platform: platform::Taskbar,
+}
+
+impl Taskbar {
+    /// Show `progress` for the window of `frame`, if it changed.
+    pub fn show(&mut self, frame: &Frame, progress: TaskbarProgress) {
+        if self.shown == Some(progress) {
+            return;
+        }
+        self.shown = Some(progress);
+        if let Err(e) = self.platform.show(frame, progress) {
+            eprintln!("UI: Failed to show progress on the taskbar: {:#}", e);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use anyhow::{anyhow, Result};
+    use eframe::Frame;
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+    use windows::Win32::UI::Shell::{ITaskbarList3, TaskbarList, TBPF_ERROR, TBPF_NOPROGRESS, TBPF_NORMAL, TBPF_PAUSED};
+
+    use super::TaskbarProgress;
+
+    #[derive(Default)]
+    pub struct Taskbar {
+        list: Option<ITaskbarList3>,
+    }
+
+    impl Taskbar {
+        pub fn show(&mut self, frame: &Frame, progress: TaskbarProgress) -> Result<()> {
+            let RawWindowHandle::Win32(handle) = frame.window_handle()?.as_raw() else {
+                return Err(anyhow!("Not a Win32 window"));
+            };
+            let hwnd = HWND(handle.hwnd.get() as *mut _);
+            if self.list.is_none() {
+                // Safety: COM is initialized on the UI thread by winit
+                let list: ITaskbarList3 = unsafe { CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER)? };
+                unsafe { list.HrInit()? };
+                self.list = Some(list);
+            }
+            let list = self.list.as_ref().unwrap();
+            // Safety: `hwnd` is the live window of this frame
+            unsafe {
+                match progress {
+                    TaskbarProgress::Hidden => list.SetProgressState(hwnd, TBPF_NOPROGRESS)?,
+                    TaskbarProgress::Downloading(permille) => {
+                        list.SetProgressState(hwnd, TBPF_NORMAL)?;
+                        list.SetProgressValue(hwnd, permille.into(), 1000)?;
+                    }
+                    TaskbarProgress::Paused(permille) => {
+                        list.SetProgressState(hwnd, TBPF_PAUSED)?;
+                        list.SetProgressValue(hwnd, permille.into(), 1000)?;
+                    }
+                    TaskbarProgress::Error => {
+                        list.SetProgressState(hwnd, TBPF_ERROR)?;
+                        list.SetProgressValue(hwnd, 1000, 1000)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use anyhow::{anyhow, Result};
+    use eframe::Frame;
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::NSApplication;
+    use objc2_foundation::NSString;
+
+    use super::TaskbarProgress;
+
+    #[derive(Default)]
+    pub struct Taskbar;
+
+    impl Taskbar {
+        pub fn show(&mut self, _frame: &Frame, progress: TaskbarProgress) -> Result<()> {
+            let mtm = MainThreadMarker::new().ok_or_else(|| anyhow!("Not on the main thread"))?;
+            let label = match progress {
+                TaskbarProgress::Hidden => None,
+                TaskbarProgress::Downloading(permille) => Some(format!("{}%", permille / 10)),
+                TaskbarProgress::Paused(permille) => Some(format!("⏸ {}%", permille / 10)),
+                TaskbarProgress::Error => Some("!".to_string()),
+            };
+            let label = label.map(|label| NSString::from_str(&label));
+            // Safety: called on the main thread, which owns the dock tile
+            unsafe { NSApplication::sharedApplication(mtm).dockTile().setBadgeLabel(label.as_deref()) };
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    use anyhow::Result;
+    use eframe::Frame;
+
+    use super::TaskbarProgress;
+
+    #[derive(Default)]
+    pub struct Taskbar;
+
+    impl Taskbar {
+        pub fn show(&mut self, _frame: &Frame, _progress: TaskbarProgress) -> Result<()> {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_taskbar_progress() {
+        let mut stats = TorrentSnapshot { state: "live".to_string(), progress_bytes: 250, total_bytes: 1000, ..Default::default() };
+        assert_eq!(TaskbarProgress::of(Some(&stats)), TaskbarProgress::Downloading(250));
+        stats.state = "paused".to_string();
+        assert_eq!(TaskbarProgress::of(Some(&stats)), TaskbarProgress::Paused(250));
+        stats.state = "error".to_string();
+        assert_eq!(TaskbarProgress::of(Some(&stats)), TaskbarProgress::Error);
+        stats.finished = true;
+        assert_eq!(TaskbarProgress::of(Some(&stats)), TaskbarProgress::Hidden);
+        assert_eq!(TaskbarProgress::of(None), TaskbarProgress::Hidden);
+    }
+}
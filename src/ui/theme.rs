@@ -0,0 +1,142 @@
+use eframe::egui;
+use egui::Color32;
+
+use crate::settings::{ThemePreset, ThemeSettings};
+
+/// Every color the UI paints with. Widgets read the active theme with
+/// `Theme::current` instead of hard-coding RGB values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub dark: bool,
+    pub window_fill: Color32,
+    pub widget_fill: Color32,
+    pub text: Color32,
+    /// Secondary text such as the tagline or offline nodes.
+    pub text_muted: Color32,
+    /// Primary buttons and selection.
+    pub accent: Color32,
+    pub banner: Color32,
+    /// Browse / save style buttons.
+    pub secondary: Color32,
+    /// Verify button.
+    pub caution: Color32,
+    pub success: Color32,
+    pub warning: Color32,
+    pub error: Color32,
+    /// Informational messages such as "Settings saved".
+    pub notice: Color32,
+    /// Progress bar background and segment colors.
+    pub track: Color32,
+    pub progress_done: Color32,
+    pub progress_partial: Color32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            dark: true,
+            window_fill: Color32::from_rgb(10, 10, 12),
+            widget_fill: Color32::from_rgb(24, 24, 26),
+            text: Color32::from_rgb(235, 235, 235),
+            text_muted: Color32::from_rgb(160, 160, 170),
+            accent: Color32::from_rgb(75, 135, 185),
+            banner: Color32::from_rgb(180, 255, 200),
+            secondary: Color32::from_rgb(100, 160, 100),
+            caution: Color32::from_rgb(190, 120, 90),
+            success: Color32::from_rgb(120, 200, 140),
+            warning: Color32::from_rgb(200, 160, 80),
+            error: Color32::from_rgb(220, 90, 90),
+            notice: Color32::from_rgb(210, 180, 140),
+            track: Color32::from_rgb(30, 30, 30),
+            progress_done: Color32::from_rgb(100, 200, 120),
+            progress_partial: Color32::from_rgb(240, 150, 60),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            dark: false,
+            window_fill: Color32::from_rgb(245, 245, 247),
+            widget_fill: Color32::from_rgb(225, 225, 230),
+            text: Color32::from_rgb(25, 25, 28),
+            text_muted: Color32::from_rgb(95, 95, 105),
+            accent: Color32::from_rgb(50, 110, 170),
+            banner: Color32::from_rgb(30, 130, 80),
+            secondary: Color32::from_rgb(80, 145, 80),
+            caution: Color32::from_rgb(185, 105, 70),
+            success: Color32::from_rgb(40, 140, 70),
+            warning: Color32::from_rgb(170, 120, 20),
+            error: Color32::from_rgb(190, 40, 40),
+            notice: Color32::from_rgb(130, 95, 40),
+            track: Color32::from_rgb(215, 215, 220),
+            progress_done: Color32::from_rgb(60, 170, 90),
+            progress_partial: Color32::from_rgb(230, 130, 40),
+        }
+    }
+
+    /// Preset from `settings`, with the custom accent applied if set.
+    /// `system` is the operating system's theme, dark when unknown.
+    pub fn from_settings(settings: &ThemeSettings, system: Option<egui::Theme>) -> Self {
+        let mut theme = match settings.preset {
+            ThemePreset::System if system == Some(egui::Theme::Light) => Self::light(),
+            ThemePreset::System | ThemePreset::Dark => Self::dark(),
+            ThemePreset::Light => Self::light(),
+        };
+        if let Some([r, g, b]) = settings.accent {
+            theme.accent = Color32::from_rgb(r, g, b);
+        }
+        theme
+    }
+
+    /// The theme last applied to `ctx`.
+    pub fn current(ctx: &egui::Context) -> Self {
+        ctx.data(|d| d.get_temp::<Self>(egui::Id::NULL)).unwrap_or_default()
+    }
+
+    /// Install this theme's visuals and spacing on `ctx`. Text sizes are
+    /// left to `ui::display::apply`.
+    pub fn apply(&self, ctx: &egui::Context) {
+        // egui keeps a style per theme and would otherwise switch to its
+        // untouched one when the system theme changes
+        ctx.set_theme(if self.dark { egui::Theme::Dark } else { egui::Theme::Light });
+        let mut style = (*ctx.style()).clone();
+        style.visuals = if self.dark { egui::Visuals::dark() } else { egui::Visuals::light() };
+        style.visuals.widgets.inactive.bg_fill = self.widget_fill;
+        style.visuals.widgets.inactive.weak_bg_fill = self.widget_fill;
+        style.visuals.window_fill = self.window_fill;
+        style.visuals.panel_fill = self.window_fill;
+        style.visuals.override_text_color = Some(self.text);
+        style.visuals.selection.bg_fill = self.accent;
+        style.visuals.hyperlink_color = self.accent;
+        style.spacing.item_spacing = egui::vec2(10.0, 6.0);
+        style.spacing.button_padding = egui::vec2(12.0, 8.0);
+        ctx.set_style(style);
+        ctx.data_mut(|d| d.insert_temp(egui::Id::NULL, *self));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_accent_overrides_preset() {
+        let settings = ThemeSettings {
+            preset: ThemePreset::Light,
+            accent: Some([1, 2, 3]),
+        };
+        let theme = Theme::from_settings(&settings, Some(egui::Theme::Dark));
+        assert_eq!(theme.accent, Color32::from_rgb(1, 2, 3));
+        assert_eq!(Theme { accent: Theme::light().accent, ..theme }, Theme::light());
+
+        let system = ThemeSettings { preset: ThemePreset::System, accent: None };
+        assert_eq!(Theme::from_settings(&system, Some(egui::Theme::Light)), Theme::light());
+        assert_eq!(Theme::from_settings(&system, None), Theme::dark());
+    }
+}
@@ -0,0 +1,149 @@
+//! Windows toast notifications for found updates.
+//!
+//! The toast's "Apply update" and "Later" buttons come back through its
+//! `Activated` event, which fires in this process while the window is open,
+//! so an update can be applied without bringing the window forward. An
+//! unpackaged app can only show toasts under an app id registered by a
+//! Start menu shortcut, so they are shown under PowerShell's. Other
+//! platforms show nothing.
+
+use eframe::egui;
+use std::sync::mpsc;
+
+/// Button clicked on a toast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastAction {
+    ApplyUpdate,
+    Later,
+}
+
+impl ToastAction {
+    /// Activation argument of the action's button.
+    fn argument(self) -> &'static str {
+        match self {
+            ToastAction::ApplyUpdate => "apply-update",
+            ToastAction::Later => "later",
+        }
+    }
+
+    fn from_argument(argument: &str) -> Option<Self> {
+        [ToastAction::ApplyUpdate, ToastAction::Later].into_iter().find(|action| action.argument() == argument)
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Toast XML with `title`, `body` and a button for each of `buttons`.
+pub fn toast_xml(title: &str, body: &str, buttons: &[(ToastAction, String)]) -> String {
+    let mut xml = format!(
+        "<toast><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text></binding></visual>",
+        escape_xml(title),
+        escape_xml(body)
+    );
+    if !buttons.is_empty() {
+        xml.push_str("<actions>");
+        for (action, label) in buttons {
+            xml.push_str(&format!(
+                "<action content=\"{}\" arguments=\"{}\" activationType=\"foreground\"/>",
+                escape_xml(label),
+                action.argument()
+            ));
+        }
+        xml.push_str("</actions>");
+    }
+    xml.push_str("</toast>");
+    xml
+}
+
+/// Shows toasts and collects the buttons clicked on them.
+pub struct Toasts {
+    tx: mpsc::Sender<ToastAction>,
+    rx: mpsc::Receiver<ToastAction>,
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self { tx, rx }
+    }
+}
+
+impl Toasts {
+    /// Whether toasts can be shown on this platform.
+    pub fn supported() -> bool {
+        cfg!(windows)
+    }
+
+    /// Show a toast, waking `ctx` when one of its buttons is clicked.
+    pub fn show(&self, ctx: &egui::Context, title: &str, body: &str, buttons: &[(ToastAction, String)]) {
+        let xml = toast_xml(title, body, buttons);
+        let tx = self.tx.clone();
+        let ctx = ctx.clone();
+        let on_action = move |argument: &str| {
+            if let Some(action) = ToastAction::from_argument(argument) {
+                let _ = tx.send(action);
+                ctx.request_repaint();
+            }
+        };
+        if let Err(e) = platform::show(&xml, on_action) {
+            eprintln!("UI: Failed to show a notification: {:#}", e);
+        }
+    }
+
+    /// Buttons clicked since the last call.
+    pub fn actions(&self) -> Vec<ToastAction> {
+        self.rx.try_iter().collect()
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use anyhow::Result;
+    use windows::core::{IInspectable, Interface, Ref, HSTRING};
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::Foundation::TypedEventHandler;
+    use windows::UI::Notifications::{ToastActivatedEventArgs, ToastNotification, ToastNotificationManager};
+
+    /// PowerShell's app id, registered on every Windows install.
+    const APP_ID: &str = r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+    pub fn show(xml: &str, on_action: impl Fn(&str) + Send + 'static) -> Result<()> {
+        let document = XmlDocument::new()?;
+        document.LoadXml(&HSTRING::from(xml))?;
+        let toast = ToastNotification::CreateToastNotification(&document)?;
+        toast.Activated(&TypedEventHandler::<ToastNotification, IInspectable>::new(move |_, args: Ref<'_, IInspectable>| {
+            if let Some(args) = args.as_ref() {
+                let args: ToastActivatedEventArgs = args.cast()?;
+                on_action(&args.Arguments()?.to_string_lossy());
+            }
+            Ok(())
+        }))?;
+        ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))?.Show(&toast)?;
+        Ok(())
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use anyhow::Result;
+
+    pub fn show(_xml: &str, _on_action: impl Fn(&str) + Send + 'static) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toast_xml() {
+        let xml = toast_xml("Update <1>", "A & B", &[(ToastAction::ApplyUpdate, "Apply \"now\"".to_string())]);
+        assert!(xml.contains("<text>Update &lt;1&gt;</text><text>A &amp; B</text>"));
+        assert!(xml.contains("<action content=\"Apply &quot;now&quot;\" arguments=\"apply-update\" activationType=\"foreground\"/>"));
+        assert_eq!(ToastAction::from_argument("later"), Some(ToastAction::Later));
+        assert_eq!(ToastAction::from_argument(""), None);
+    }
+}
@@ -1,7 +1,10 @@
 use eframe::egui;
-use egui::{Color32, Vec2, Pos2, Rect, CornerRadius};
+use egui::{Vec2, Pos2, Rect, CornerRadius};
 use std::time::Instant;
-use librqbit::TorrentStats;
+use crate::i18n::tr_args;
+use crate::sync::TorrentSnapshot;
+use crate::ui::format;
+use crate::ui::theme::Theme;
 
 /// UI component that renders aggregate + per-file torrent progress.
 pub struct TorrentProgress {
@@ -11,6 +14,12 @@ pub struct TorrentProgress {
     last_update: std::time::Instant,
 }
 
+impl Default for TorrentProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TorrentProgress {
     /// Create an empty progress widget.
     pub fn new() -> Self {
@@ -23,7 +32,7 @@ impl TorrentProgress {
     }
 
     /// Update the widget from canonical stats.
-    pub fn update_from_stats(&mut self, stats: &TorrentStats) {
+    pub fn update_from_stats(&mut self, stats: &TorrentSnapshot) {
         self.file_progress = stats.file_progress.clone();
         self.progress_bytes = stats.progress_bytes;
         self.total_bytes = stats.total_bytes;
@@ -31,7 +40,7 @@ impl TorrentProgress {
     }
 
     /// Temporary helper used by the UI demo: directly set internal fields from
-    /// simulated values without requiring a full `TorrentStats` instance.
+    /// simulated values without requiring a full `TorrentSnapshot` instance.
     pub fn update_from_simulated(&mut self, file_progress: Vec<u64>, progress_bytes: u64, total_bytes: u64) {
         self.file_progress = file_progress;
         self.progress_bytes = progress_bytes;
@@ -56,10 +65,10 @@ impl TorrentProgress {
             0.0
         };
         let header_text = format!(
-            "{:.2}% — {} / {}",
-            percent,
-            human_readable_bytes(self.progress_bytes),
-            human_readable_bytes(self.total_bytes)
+            "{}% — {} / {}",
+            format::number(percent, 2),
+            format::size(self.progress_bytes),
+            format::size(self.total_bytes)
         );
 
         // Reserve the header area first so the caller can provide the full
@@ -75,12 +84,16 @@ impl TorrentProgress {
     let bar_size = Vec2::new(desired_size.x, bar_height);
 
     // Allocate exact size for the bar and get the painter
-    let (rect, _alloc_resp) = ui.allocate_exact_size(bar_size, egui::Sense::hover());
+    let (rect, bar_resp) = ui.allocate_exact_size(bar_size, egui::Sense::hover());
+    // Expose the bar to screen readers as a single progress indicator
+    let overall = tr_args("progress-overall", &[("percent", format::number(percent, 2).into())]);
+    bar_resp.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::ProgressIndicator, true, &overall));
     let painter = ui.painter();
 
     // Background bar (rounded corners)
     let bar_radius: u8 = 8;
-    let bar_fill = Color32::from_rgb(30, 30, 30);
+    let theme = Theme::current(ui.ctx());
+    let bar_fill = theme.track;
     painter.rect_filled(rect, CornerRadius::same(bar_radius), bar_fill);
 
         // Draw nothing else if there are no files (header still shown)
@@ -113,23 +126,23 @@ impl TorrentProgress {
         for (i, &w) in seg_widths.iter().enumerate() {
             let seg_rect = Rect::from_min_size(Pos2::new(x, rect.top()), Vec2::new(w, bar_height));
 
-            // Color: greenish if effectively complete, otherwise orange
+            // Color: done if effectively complete, otherwise partial
             let color = if self.total_bytes > 0 {
                 let ratio = (files[i] as f64) / (self.total_bytes as f64);
                 if ratio >= 0.999 {
-                    Color32::from_rgb(100, 200, 120) // greenish
+                    theme.progress_done
                 } else {
-                    Color32::from_rgb(240, 150, 60) // orange
+                    theme.progress_partial
                 }
             } else {
-                Color32::from_rgb(100, 200, 120)
+                theme.progress_done
             };
 
             // Draw segment filled
             painter.rect_filled(seg_rect, CornerRadius::same(bar_radius), color);
 
             // Interaction: create a hover response for the exact segment rectangle
-            let id = ui.id().with((self.last_update.elapsed().as_nanos(), i));
+            let id = ui.id().with(("progress_segment", i));
             let response = ui.interact(seg_rect, id, Sense::hover());
             if response.hovered() {
                 let file_bytes = files[i];
@@ -138,12 +151,14 @@ impl TorrentProgress {
                 } else {
                     0.0
                 };
-                let tooltip = format!(
-                    "File {}: {} / {} ({:.2}%)",
-                    i,
-                    human_readable_bytes(file_bytes),
-                    human_readable_bytes(self.total_bytes),
-                    pct
+                let tooltip = tr_args(
+                    "progress-file-tooltip",
+                    &[
+                        ("index", i.into()),
+                        ("done", format::size(file_bytes).into()),
+                        ("total", format::size(self.total_bytes).into()),
+                        ("percent", format::number(pct, 2).into()),
+                    ],
                 );
                 response.on_hover_text(tooltip);
             }
@@ -152,20 +167,3 @@ impl TorrentProgress {
         }
     }
 }
-/// Simple helper to format bytes in KiB/MiB/GiB with two decimal places.
-fn human_readable_bytes(b: u64) -> String {
-    let b_f = b as f64;
-    const KIB: f64 = 1024.0;
-    const MIB: f64 = KIB * 1024.0;
-    const GIB: f64 = MIB * 1024.0;
-
-    if b_f >= GIB {
-        format!("{:.2} GiB", b_f / GIB)
-    } else if b_f >= MIB {
-        format!("{:.2} MiB", b_f / MIB)
-    } else if b_f >= KIB {
-        format!("{:.2} KiB", b_f / KIB)
-    } else {
-        format!("{} B", b)
-    }
-}
\ No newline at end of file
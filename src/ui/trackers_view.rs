@@ -0,0 +1,38 @@
+use eframe::egui;
+use egui::RichText;
+
+use crate::i18n::tr;
+use crate::sync::trackers::{TrackerInfo, TrackerSource};
+use crate::ui::theme::Theme;
+
+/// Trackers tab: the announce URLs of the loaded torrent and where each
+/// comes from.
+pub fn ui(ui: &mut egui::Ui, trackers: &[TrackerInfo]) {
+    let theme = Theme::current(ui.ctx());
+    if trackers.is_empty() {
+        ui.label(RichText::new(tr("trackers-empty")).color(theme.text_muted));
+        return;
+    }
+    egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+        egui::Grid::new("trackers_table").striped(true).num_columns(2).show(ui, |ui| {
+            ui.label(RichText::new(tr("trackers-col-url")).strong());
+            ui.label(RichText::new(tr("trackers-col-source")).strong());
+            ui.end_row();
+
+            for tracker in trackers {
+                let url = RichText::new(&tracker.url);
+                ui.label(if tracker.active { url } else { url.strikethrough().color(theme.text_muted) });
+                let source = tr(match tracker.source {
+                    TrackerSource::Torrent => "trackers-source-torrent",
+                    TrackerSource::Config => "trackers-source-config",
+                });
+                if tracker.active {
+                    ui.label(source);
+                } else {
+                    ui.label(RichText::new(format!("{} ({})", source, tr("trackers-replaced"))).color(theme.text_muted));
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
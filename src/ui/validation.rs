@@ -0,0 +1,147 @@
+// src/ui/validation.rs
+
+//! Checks of the settings inputs as they are typed.
+//!
+//! The download path has to be a folder that exists or can be created, that
+//! we can write to, that isn't a system folder and whose volume has room for
+//! what is left to download. The torrent URL has to be one a registered
+//! source handles; whether it answers is left to the health badge.
+
+use std::path::PathBuf;
+
+use crate::i18n::{tr, tr_args};
+use crate::sync::backend::Backends;
+use crate::sync::preflight::{is_system_folder, probe_write};
+use crate::ui::format;
+
+/// Why the download path can't be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathProblem {
+    Empty,
+    NotAFolder,
+    SystemFolder,
+    NotWritable,
+    NotEnoughSpace { needed: u64, free: u64 },
+}
+
+impl PathProblem {
+    pub fn message(&self) -> String {
+        match self {
+            PathProblem::Empty => tr("validate-path-empty"),
+            PathProblem::NotAFolder => tr("validate-path-not-folder"),
+            PathProblem::SystemFolder => tr("validate-path-system"),
+            PathProblem::NotWritable => tr("validate-path-not-writable"),
+            PathProblem::NotEnoughSpace { needed, free } => tr_args(
+                "validate-path-space",
+                &[("needed", format::size(*needed).into()), ("free", format::size(*free).into())],
+            ),
+        }
+    }
+}
+
+/// Why the torrent URL can't be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlProblem {
+    Invalid,
+    UnsupportedScheme(String),
+}
+
+impl UrlProblem {
+    pub fn message(&self) -> String {
+        match self {
+            UrlProblem::Invalid => tr("validate-url-invalid"),
+            UrlProblem::UnsupportedScheme(scheme) => tr_args("validate-url-scheme", &[("scheme", scheme.clone().into())]),
+        }
+    }
+}
+
+/// Check `input` as a download path. Writing is tested by creating and
+/// removing a file in the folder, or in its closest existing parent if it
+/// doesn't exist yet, so callers check again only when the input changes.
+pub fn validate_download_path(input: &str) -> Result<(), PathProblem> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(PathProblem::Empty);
+    }
+    let path = std::path::absolute(input).unwrap_or_else(|_| PathBuf::from(input));
+    if is_system_folder(&path) {
+        return Err(PathProblem::SystemFolder);
+    }
+    let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+        return Err(PathProblem::NotWritable);
+    };
+    if !existing.is_dir() {
+        return Err(PathProblem::NotAFolder);
+    }
+    if probe_write(existing).is_err() {
+        return Err(PathProblem::NotWritable);
+    }
+    Ok(())
+}
+
+/// Check that `free` bytes, when known, leave room for `needed`.
+pub fn check_space(needed: u64, free: Option<u64>) -> Result<(), PathProblem> {
+    match free {
+        Some(free) if free < needed => Err(PathProblem::NotEnoughSpace { needed, free }),
+        _ => Ok(()),
+    }
+}
+
+/// Check `input` as a torrent URL. An empty URL is fine: nothing is synced
+/// until one is entered.
+pub fn validate_url(input: &str) -> Result<(), UrlProblem> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(());
+    }
+    let url = reqwest::Url::parse(input).map_err(|_| UrlProblem::Invalid)?;
+    if !url.has_host() {
+        return Err(UrlProblem::Invalid);
+    }
+    Backends::default()
+        .source_for(input)
+        .map(|_| ())
+        .map_err(|_| UrlProblem::UnsupportedScheme(url.scheme().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_download_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().display().to_string();
+        assert_eq!(validate_download_path(&path), Ok(()));
+        assert_eq!(validate_download_path(&dir.path().join("new/mods").display().to_string()), Ok(()));
+        assert_eq!(validate_download_path(" "), Err(PathProblem::Empty));
+
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"").unwrap();
+        assert_eq!(validate_download_path(&file.join("mods").display().to_string()), Err(PathProblem::NotAFolder));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_system_folders() {
+        assert_eq!(validate_download_path("/"), Err(PathProblem::SystemFolder));
+        assert_eq!(validate_download_path("/usr/share/mods"), Err(PathProblem::SystemFolder));
+    }
+
+    #[test]
+    fn test_check_space() {
+        assert_eq!(check_space(10, Some(10)), Ok(()));
+        assert_eq!(check_space(10, None), Ok(()));
+        assert_eq!(check_space(11, Some(10)), Err(PathProblem::NotEnoughSpace { needed: 11, free: 10 }));
+    }
+
+    #[test]
+    fn test_validate_url() {
+        assert_eq!(validate_url(""), Ok(()));
+        assert_eq!(validate_url("https://example.com/mods.torrent"), Ok(()));
+        assert_eq!(validate_url("s3://mods/repo.torrent"), Ok(()));
+        assert_eq!(validate_url("example.com/mods.torrent"), Err(UrlProblem::Invalid));
+        assert_eq!(validate_url("ftp://example.com/mods.torrent"), Err(UrlProblem::UnsupportedScheme("ftp".into())));
+    }
+}
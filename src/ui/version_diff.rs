@@ -0,0 +1,165 @@
+use eframe::egui;
+use egui::RichText;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::{tr, tr_args};
+use crate::settings::AppSettings;
+use crate::sync::cache::{CacheEntry, TorrentCache};
+use crate::sync::diff::{diff_torrents, TorrentDiff};
+use crate::ui::theme::Theme;
+use crate::ui::format;
+
+/// Window comparing two cached torrent versions, for checking what a
+/// release changed before announcing it.
+#[derive(Default)]
+pub struct VersionDiffView {
+    pub open: bool,
+    entries: Vec<CacheEntry>,
+    old: Option<PathBuf>,
+    new: Option<PathBuf>,
+    /// Diff of the chosen pair, redone when the choice changes.
+    diff: Option<(PathBuf, PathBuf, Result<TorrentDiff, String>)>,
+}
+
+fn entry_label(entry: &CacheEntry) -> String {
+    let hash = entry.path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    let last_used: chrono::DateTime<chrono::Local> = entry.last_used.into();
+    format!("{} {}", last_used.format("%Y-%m-%d %H:%M"), hash.chars().take(12).collect::<String>())
+}
+
+fn signed_bytes(bytes: i64) -> String {
+    let sign = if bytes < 0 { "-" } else { "+" };
+    format!("{}{}", sign, format::size(bytes.unsigned_abs()))
+}
+
+fn compare(old: &Path, new: &Path) -> Result<TorrentDiff, String> {
+    let read = |path: &Path| std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e));
+    diff_torrents(&read(old)?, &read(new)?).map_err(|e| format!("{:#}", e))
+}
+
+impl VersionDiffView {
+    /// Open the window on the cached versions, comparing the two most
+    /// recently seen ones.
+    pub fn show_versions(&mut self) {
+        self.entries = match AppSettings::cache_dir().and_then(|dir| TorrentCache::new(dir).entries()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("UI: Failed to list the torrent cache: {:#}", e);
+                Vec::new()
+            }
+        };
+        self.new = self.entries.first().map(|e| e.path.clone());
+        self.old = self.entries.get(1).map(|e| e.path.clone());
+        self.diff = None;
+        self.open = true;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+        let mut open = self.open;
+        egui::Window::new(tr("version-diff-title"))
+            .id(egui::Id::new("version_diff_window"))
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| self.ui(ui));
+        self.open = open;
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        let theme = Theme::current(ui.ctx());
+        if self.entries.len() < 2 {
+            ui.label(RichText::new(tr("version-diff-no-versions")).color(theme.text_muted));
+            return;
+        }
+        egui::Grid::new("version_diff_pick").num_columns(2).show(ui, |ui| {
+            for (label, id, choice) in [
+                ("version-diff-old", "version_diff_old", &mut self.old),
+                ("version-diff-new", "version_diff_new", &mut self.new),
+            ] {
+                ui.label(tr(label));
+                let selected = self.entries.iter().find(|e| Some(&e.path) == choice.as_ref()).map(entry_label).unwrap_or_default();
+                egui::ComboBox::from_id_salt(id).selected_text(selected).width(260.0).show_ui(ui, |ui| {
+                    for entry in &self.entries {
+                        ui.selectable_value(choice, Some(entry.path.clone()), entry_label(entry));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        let (Some(old), Some(new)) = (self.old.clone(), self.new.clone()) else {
+            return;
+        };
+        if self.diff.as_ref().is_none_or(|(o, n, _)| *o != old || *n != new) {
+            let diff = compare(&old, &new);
+            self.diff = Some((old, new, diff));
+        }
+        ui.separator();
+        let diff = match self.diff.as_ref().map(|(_, _, diff)| diff) {
+            Some(Ok(diff)) => diff,
+            Some(Err(e)) => {
+                ui.colored_label(theme.error, tr_args("version-diff-failed", &[("error", e.as_str().into())]));
+                return;
+            }
+            None => return,
+        };
+        if diff.is_empty() {
+            ui.label(tr_args("version-diff-none", &[("unchanged", diff.unchanged.into())]));
+            return;
+        }
+        ui.label(tr_args(
+            "version-diff-summary",
+            &[
+                ("added", diff.added.len().into()),
+                ("removed", diff.removed.len().into()),
+                ("resized", diff.resized.len().into()),
+                ("unchanged", diff.unchanged.into()),
+                ("size", signed_bytes(diff.size_change()).into()),
+            ],
+        ));
+        egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+            for (title, files, color) in [
+                ("version-diff-added", &diff.added, theme.success),
+                ("version-diff-removed", &diff.removed, theme.error),
+            ] {
+                if files.is_empty() {
+                    continue;
+                }
+                egui::CollapsingHeader::new(RichText::new(tr_args(title, &[("count", files.len().into())])).color(color))
+                    .id_salt(title)
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::Grid::new(title).striped(true).num_columns(2).show(ui, |ui| {
+                            for (path, length) in files {
+                                ui.label(path.display().to_string());
+                                ui.label(format::size(*length));
+                                ui.end_row();
+                            }
+                        });
+                    });
+            }
+            if !diff.resized.is_empty() {
+                egui::CollapsingHeader::new(
+                    RichText::new(tr_args("version-diff-resized", &[("count", diff.resized.len().into())])).color(theme.warning),
+                )
+                .id_salt("version-diff-resized")
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::Grid::new("version-diff-resized").striped(true).num_columns(3).show(ui, |ui| {
+                        for file in &diff.resized {
+                            ui.label(file.path.display().to_string());
+                            ui.label(format!(
+                                "{} → {}",
+                                format::size(file.old_length),
+                                format::size(file.new_length)
+                            ));
+                            ui.label(signed_bytes(file.new_length as i64 - file.old_length as i64));
+                            ui.end_row();
+                        }
+                    });
+                });
+            }
+        });
+    }
+}
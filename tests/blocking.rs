@@ -0,0 +1,40 @@
+use anyhow::Result;
+use tempfile::tempdir;
+
+use modsync::blocking::sync_once;
+use modsync::sync::recovery::SessionLock;
+use modsync::sync::SyncConfig;
+
+#[test]
+fn sync_once_without_url_or_cache_is_a_no_op() -> Result<()> {
+    let dir = tempdir()?;
+    let config = SyncConfig {
+        download_path: dir.path().join("downloads"),
+        ..Default::default()
+    };
+
+    let report = sync_once(config)?;
+    assert!(!report.update_found);
+    assert!(!report.update_applied);
+    assert!(report.errors.is_empty());
+    assert!(report.is_success());
+    Ok(())
+}
+
+#[test]
+fn failed_sync_once_leaves_a_clean_lock() -> Result<()> {
+    let dir = tempdir()?;
+    let cache_dir = dir.path().join("cache");
+    let config = SyncConfig {
+        torrent_url: "http://127.0.0.1:1/repo.torrent".to_string(),
+        download_path: dir.path().join("downloads"),
+        cache_dir: Some(cache_dir.clone()),
+        ..Default::default()
+    };
+
+    let report = sync_once(config)?;
+    assert!(!report.is_success());
+    let lock = SessionLock::acquire(&cache_dir)?.expect("lock is free again");
+    assert_eq!(lock.previous, None);
+    Ok(())
+}
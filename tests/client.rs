@@ -0,0 +1,107 @@
+use anyhow::Result;
+use std::time::Duration;
+use tempfile::tempdir;
+
+use modsync::sync::{SyncConfig, SyncError, SyncEvent};
+use modsync::ModSyncClient;
+
+#[tokio::test]
+async fn client_verify_without_torrent_reports_error() -> Result<()> {
+    let dir = tempdir()?;
+    let config = SyncConfig {
+        download_path: dir.path().join("downloads"),
+        ..Default::default()
+    };
+
+    let client = ModSyncClient::new(config).await?;
+    let mut events = client.subscribe_events();
+    client.verify().await?;
+
+    // The manager has no torrent loaded, so verification must fail with an error event
+    let error = tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            match events.recv().await {
+                Ok(SyncEvent::Error(msg)) => return Some(msg),
+                Ok(_) => continue,
+                Err(_) => return None,
+            }
+        }
+    })
+    .await?;
+
+    assert_eq!(error, Some(SyncError::Other("No active torrent to verify against".to_string())));
+    assert!(client.stats().is_none());
+    client.shutdown().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_event_stream_and_callback_receive_events() -> Result<()> {
+    use futures::StreamExt;
+    use std::sync::{Arc, Mutex};
+
+    let dir = tempdir()?;
+    let config = SyncConfig {
+        download_path: dir.path().join("downloads"),
+        ..Default::default()
+    };
+
+    let client = ModSyncClient::new(config).await?;
+    let stream = client.event_stream();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_cb = Arc::clone(&seen);
+    let handle = client.on_event(move |event| seen_cb.lock().unwrap().push(event));
+
+    client.verify().await?;
+
+    let errors = stream.filter(|e| futures::future::ready(matches!(e, SyncEvent::Error(_))));
+    futures::pin_mut!(errors);
+    let first = tokio::time::timeout(Duration::from_secs(10), errors.next()).await?;
+    assert!(matches!(first, Some(SyncEvent::Error(_))));
+
+    // The callback sees the same events as the stream
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(seen.lock().unwrap().iter().any(|e| matches!(e, SyncEvent::Error(_))));
+
+    handle.abort();
+    client.shutdown().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_restores_cached_torrent_after_starting() -> Result<()> {
+    let dir = tempdir()?;
+    let cached = dir.path().join("cached.torrent");
+    std::fs::write(&cached, b"not a torrent")?;
+    let config = SyncConfig {
+        download_path: dir.path().join("downloads"),
+        cached_torrent_path: Some(cached),
+        ..Default::default()
+    };
+
+    // A broken cached torrent no longer keeps the client from starting
+    let client = ModSyncClient::new(config).await?;
+    tokio::time::timeout(Duration::from_secs(10), client.wait_restored()).await?;
+    assert!(client.stats().is_none());
+    client.shutdown().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn dropped_client_leaves_a_clean_lock() -> Result<()> {
+    use modsync::sync::recovery::SessionLock;
+
+    let dir = tempdir()?;
+    let cache_dir = dir.path().join("cache");
+    let config = SyncConfig {
+        download_path: dir.path().join("downloads"),
+        cache_dir: Some(cache_dir.clone()),
+        ..Default::default()
+    };
+
+    let client = ModSyncClient::new(config).await?;
+    drop(client);
+    let lock = SessionLock::acquire(&cache_dir)?.expect("lock is free again");
+    assert_eq!(lock.previous, None);
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+use modsync::settings::AppSettings;
+
+// The data directory can only be chosen once per process, so everything
+// depending on it is checked in this one test.
+#[test]
+fn config_dir_arg_moves_every_path() -> Result<()> {
+    let tmp = tempdir()?;
+    let dir = tmp.path().join("profile");
+    let mut args: Vec<String> = ["--config-dir", dir.to_str().unwrap(), "daemon"].map(String::from).to_vec();
+    AppSettings::apply_config_dir_arg(&mut args)?;
+    assert_eq!(args, vec!["daemon".to_string()]);
+    assert!(AppSettings::set_data_dir(PathBuf::from("elsewhere")).is_err());
+
+    assert_eq!(AppSettings::data_dir()?, dir);
+    assert!(dir.is_dir());
+    assert_eq!(AppSettings::settings_file_path()?, dir.join("modsync-settings.toml"));
+    assert_eq!(AppSettings::cache_dir()?, dir.join("cache"));
+    assert!(AppSettings::cached_torrent_path("https://example.com/mods.torrent")?.starts_with(&dir));
+
+    let settings = AppSettings {
+        torrent_url: "https://example.com/mods.torrent".into(),
+        download_path: tmp.path().join("downloads"),
+        ..Default::default()
+    };
+    settings.save()?;
+    assert_eq!(AppSettings::load()?, settings);
+
+    let config = settings.to_sync_config()?;
+    assert!(config.lifetime_stats_path.is_some_and(|path| path.starts_with(&dir)));
+    Ok(())
+}
@@ -0,0 +1,195 @@
+//! Offline swarm for integration tests: a seeder session on localhost with
+//! DHT off and no trackers, and a local HTTP server for the torrent file.
+//! Clients reach the seeder through `SyncConfig::peers`, so downloads never
+//! wait on peers from the internet.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use librqbit::{AddTorrent, AddTorrentOptions, SessionOptions};
+use modsync::sync::create::{create_torrent, CreateOptions};
+use modsync::sync::{SyncConfig, SyncEvent, SyncStats};
+use modsync::ModSyncClient;
+
+/// How long a download from the local seeder may take.
+pub const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Files of the test repository: a few pieces' worth of PBOs with
+/// distinct contents, so a piece from the wrong offset fails its hash.
+pub fn repo_files() -> Vec<(&'static str, Vec<u8>)> {
+    let pbo = |seed: u8, len: usize| (0..len).map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed)).collect();
+    vec![
+        ("addons/a.pbo", pbo(1, 300_000)),
+        ("addons/b.pbo", pbo(2, 70_000)),
+        ("mod.cpp", b"name = \"test mod\";".to_vec()),
+    ]
+}
+
+pub struct Seeder {
+    session: Arc<librqbit::Session>,
+    /// Where the seeder accepts peer connections.
+    pub addr: SocketAddr,
+    pub torrent: Vec<u8>,
+    /// Holds the seeded folder.
+    dir: TempDir,
+}
+
+impl Seeder {
+    /// Write `files` to a folder, make a torrent of it and seed it until
+    /// the seeder is dropped.
+    pub async fn start(files: &[(&str, Vec<u8>)]) -> Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let folder = dir.path().join("@mod");
+        write_files(&folder, files)?;
+        let torrent = create_torrent(&folder, CreateOptions::default()).await?;
+
+        let port = free_port()?;
+        let session = librqbit::Session::new_with_opts(
+            folder.clone(),
+            SessionOptions {
+                disable_dht: true,
+                listen_port_range: Some(port..port + 1),
+                ..Default::default()
+            },
+        )
+        .await
+        .context("Failed to start the seeder session")?;
+        let port = session.tcp_listen_port().context("Seeder isn't listening for peers")?;
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let options = AddTorrentOptions {
+            output_folder: Some(folder.to_string_lossy().into_owned()),
+            overwrite: true,
+            ..Default::default()
+        };
+        let handle = session
+            .add_torrent(AddTorrent::from_bytes(torrent.clone()), Some(options))
+            .await
+            .context("Failed to add the torrent to the seeder")?
+            .into_handle()
+            .context("Seeder added the torrent in list-only mode")?;
+        tokio::time::timeout(DOWNLOAD_TIMEOUT, handle.wait_until_completed())
+            .await
+            .context("Seeder didn't finish checking its files")??;
+        Ok(Self { session, addr, torrent, dir })
+    }
+
+    /// Config downloading from this seeder into `download_path`, with the
+    /// torrent cached at `cached_torrent_path` so the client adds it on
+    /// start.
+    pub fn config(&self, download_path: &Path, cached_torrent_path: PathBuf) -> Result<SyncConfig> {
+        std::fs::write(&cached_torrent_path, &self.torrent)?;
+        Ok(SyncConfig {
+            download_path: download_path.to_path_buf(),
+            cached_torrent_path: Some(cached_torrent_path),
+            should_seed: true,
+            peers: vec![self.addr],
+            ..Default::default()
+        })
+    }
+}
+
+impl Drop for Seeder {
+    fn drop(&mut self) {
+        let session = Arc::clone(&self.session);
+        tokio::spawn(async move { session.stop().await });
+    }
+}
+
+fn write_files(folder: &Path, files: &[(&str, Vec<u8>)]) -> Result<()> {
+    for (path, contents) in files {
+        let path = folder.join(path);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+/// Torrent of `files`, for another version of the seeded repository.
+pub async fn torrent_of(files: &[(&str, Vec<u8>)]) -> Result<Vec<u8>> {
+    let dir = tempfile::tempdir()?;
+    let folder = dir.path().join("@mod");
+    write_files(&folder, files)?;
+    create_torrent(&folder, CreateOptions::default()).await
+}
+
+/// Wait until the client finishes the run a command started.
+pub async fn wait_run_finished(events: &mut broadcast::Receiver<SyncEvent>) -> Result<()> {
+    let finished = async {
+        loop {
+            match events.recv().await {
+                Ok(SyncEvent::RunFinished) => return Ok(()),
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => bail!("Client stopped"),
+            }
+        }
+    };
+    tokio::time::timeout(DOWNLOAD_TIMEOUT, finished).await.context("Run didn't finish")?
+}
+
+/// A localhost port nothing listens on right now.
+fn free_port() -> Result<u16> {
+    Ok(std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?.local_addr()?.port())
+}
+
+/// Wait until the client's torrent has every piece.
+pub async fn wait_finished(client: &ModSyncClient) -> Result<SyncStats> {
+    let finished = async {
+        loop {
+            if let Some(stats) = client.stats().filter(|stats| stats.finished) {
+                return stats;
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    };
+    match tokio::time::timeout(DOWNLOAD_TIMEOUT, finished).await {
+        Ok(stats) => Ok(stats),
+        Err(_) => bail!("Download didn't finish: {:?}", client.stats()),
+    }
+}
+
+/// Check that `folder` holds `files` with the same contents.
+pub fn assert_files(folder: &Path, files: &[(&str, Vec<u8>)]) {
+    for (path, contents) in files {
+        let on_disk = std::fs::read(folder.join(path)).unwrap_or_else(|e| panic!("{} not downloaded: {}", path, e));
+        assert!(on_disk == *contents, "{} differs from the seeded file", path);
+    }
+}
+
+/// Serve `body` to every HTTP request on a localhost port and return a URL
+/// to it.
+pub async fn serve(body: Vec<u8>) -> Result<String> {
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+    let url = format!("http://{}/repo.torrent", listener.local_addr()?);
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let body = body.clone();
+            tokio::spawn(async move {
+                // Read up to the end of the request head; requests here have no body
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/x-bittorrent\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(head.as_bytes()).await;
+                let _ = stream.write_all(&body).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+    Ok(url)
+}
@@ -0,0 +1,163 @@
+mod harness;
+
+use anyhow::Result;
+use tempfile::tempdir;
+
+use harness::{assert_files, repo_files, serve, torrent_of, wait_finished, wait_run_finished, Seeder};
+use modsync::ModSyncClient;
+
+#[tokio::test]
+async fn offline_download_matches_seeder() -> Result<()> {
+    let files = repo_files();
+    let seeder = Seeder::start(&files).await?;
+    let dir = tempdir()?;
+    let downloads = dir.path().join("downloads");
+
+    let client = ModSyncClient::new(seeder.config(&downloads, dir.path().join("cached.torrent"))?).await?;
+    let stats = wait_finished(&client).await?;
+    assert_eq!(stats.total_bytes, files.iter().map(|(_, contents)| contents.len() as u64).sum::<u64>());
+    assert_files(&downloads, &files);
+    client.shutdown().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn offline_restart_resumes_from_downloaded_files() -> Result<()> {
+    let files = repo_files();
+    let seeder = Seeder::start(&files).await?;
+    let dir = tempdir()?;
+    let downloads = dir.path().join("downloads");
+    let config = seeder.config(&downloads, dir.path().join("cached.torrent"))?;
+
+    let client = ModSyncClient::new(config.clone()).await?;
+    wait_finished(&client).await?;
+    client.shutdown().await;
+
+    // The files on disk pass the check, so nothing is fetched again
+    let client = ModSyncClient::new(config).await?;
+    let stats = wait_finished(&client).await?;
+    assert_eq!(stats.fetched_bytes, 0);
+    assert_files(&downloads, &files);
+    client.shutdown().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn offline_corrupted_file_is_downloaded_again() -> Result<()> {
+    let files = repo_files();
+    let seeder = Seeder::start(&files).await?;
+    let dir = tempdir()?;
+    let downloads = dir.path().join("downloads");
+    let config = seeder.config(&downloads, dir.path().join("cached.torrent"))?;
+
+    let client = ModSyncClient::new(config.clone()).await?;
+    wait_finished(&client).await?;
+    client.shutdown().await;
+
+    // Same size, different bytes: only the piece hashes can tell
+    let corrupted = downloads.join("addons/a.pbo");
+    let mut contents = std::fs::read(&corrupted)?;
+    contents[100_000..100_100].fill(0xff);
+    std::fs::write(&corrupted, contents)?;
+
+    let client = ModSyncClient::new(config).await?;
+    let stats = wait_finished(&client).await?;
+    assert!(stats.fetched_bytes > 0);
+    assert_files(&downloads, &files);
+    client.shutdown().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn offline_roll_back_keeps_user_files() -> Result<()> {
+    let files = repo_files();
+    let seeder = Seeder::start(&files).await?;
+    let dir = tempdir()?;
+    let downloads = dir.path().join("downloads");
+    let cached = dir.path().join("cached.torrent");
+
+    let client = ModSyncClient::new(seeder.config(&downloads, cached.clone())?).await?;
+    wait_finished(&client).await?;
+    let user_file = downloads.join("userconfig/profile.txt");
+    std::fs::create_dir_all(user_file.parent().unwrap())?;
+    std::fs::write(&user_file, "mine")?;
+
+    // The older version doesn't have a.pbo yet
+    let older_files: Vec<_> = files.iter().filter(|(path, _)| *path != "addons/a.pbo").cloned().collect();
+    let older = torrent_of(&older_files).await?;
+    let mut events = client.subscribe_events();
+    client.roll_back(older.clone()).await?;
+    wait_run_finished(&mut events).await?;
+    assert!(!downloads.join("addons/a.pbo").exists());
+    assert_eq!(std::fs::read_to_string(&user_file)?, "mine");
+    assert_files(&downloads, &older_files);
+    assert_eq!(std::fs::read(&cached)?, older);
+
+    // A version that can't be added doesn't become the cached one
+    client.roll_back(b"not a torrent".to_vec()).await?;
+    wait_run_finished(&mut events).await?;
+    assert_eq!(std::fs::read(&cached)?, older);
+    assert_eq!(std::fs::read_to_string(&user_file)?, "mine");
+    client.shutdown().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn offline_refused_update_keeps_the_torrent() -> Result<()> {
+    let files = repo_files();
+    let seeder = Seeder::start(&files).await?;
+    let dir = tempdir()?;
+    let downloads = dir.path().join("downloads");
+    let cached = dir.path().join("cached.torrent");
+
+    let client = ModSyncClient::new(seeder.config(&downloads, cached.clone())?).await?;
+    wait_finished(&client).await?;
+    let mut events = client.subscribe_events();
+    client.apply_update(b"<html>Not Found</html>".to_vec()).await?;
+    wait_run_finished(&mut events).await?;
+
+    // The working torrent is still managed and its files untouched
+    assert_eq!(client.session().with_torrents(|torrents| torrents.count()), 1);
+    assert!(wait_finished(&client).await?.finished);
+    assert_eq!(std::fs::read(&cached)?, seeder.torrent);
+    assert_files(&downloads, &files);
+    client.shutdown().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn offline_found_update_waits_to_be_applied() -> Result<()> {
+    let files = repo_files();
+    let seeder = Seeder::start(&files).await?;
+    let dir = tempdir()?;
+    let downloads = dir.path().join("downloads");
+    let cached = dir.path().join("cached.torrent");
+    let mut config = seeder.config(&downloads, cached.clone())?;
+    config.torrent_url = serve(seeder.torrent.clone()).await?;
+
+    let client = ModSyncClient::new(config).await?;
+    wait_finished(&client).await?;
+    let older_files: Vec<_> = files.iter().filter(|(path, _)| *path != "addons/a.pbo").cloned().collect();
+    let older = torrent_of(&older_files).await?;
+    let mut events = client.subscribe_events();
+    client.roll_back(older.clone()).await?;
+    wait_run_finished(&mut events).await?;
+
+    // The newer version is found, but a recheck still goes by the older one
+    client.check_remote().await?;
+    wait_run_finished(&mut events).await?;
+    assert_eq!(client.pending_update(), Some(seeder.torrent.clone()));
+    assert_eq!(std::fs::read(&cached)?, older);
+    client.force_recheck().await?;
+    wait_run_finished(&mut events).await?;
+    assert_eq!(std::fs::read(&cached)?, older);
+    assert!(!downloads.join("addons/a.pbo").exists());
+
+    client.apply_update(seeder.torrent.clone()).await?;
+    wait_run_finished(&mut events).await?;
+    assert_eq!(std::fs::read(&cached)?, seeder.torrent);
+    wait_finished(&client).await?;
+    assert_files(&downloads, &files);
+    client.shutdown().await;
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use eframe::egui::{self, accesskit, Event, Key, Modifiers, PointerButton, Pos2, RawInput, Rect};
+use eframe::App;
+use std::sync::OnceLock;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+use modsync::ipc::{ConnectionUpdate, DaemonMessage};
+use modsync::settings::AppSettings;
+use modsync::sync::{SyncCommand, SyncError, SyncEvent, TorrentSummary};
+use modsync::ui::ModApp;
+
+/// The app against mocked daemon channels, run frame by frame. Widgets are
+/// found by their text in the AccessKit tree egui builds each frame.
+struct Harness {
+    ctx: egui::Context,
+    frame: eframe::Frame,
+    app: ModApp,
+    commands: mpsc::UnboundedReceiver<SyncCommand>,
+    updates: mpsc::UnboundedSender<ConnectionUpdate>,
+    nodes: Vec<accesskit::Node>,
+    events: Vec<Event>,
+    time: f64,
+}
+
+impl Harness {
+    fn new() -> Self {
+        // The data directory can only be chosen once per process; every test
+        // starts from the same empty settings
+        static DATA_DIR: OnceLock<TempDir> = OnceLock::new();
+        DATA_DIR.get_or_init(|| {
+            let dir = tempfile::tempdir().unwrap();
+            AppSettings::set_data_dir(dir.path().to_path_buf()).unwrap();
+            dir
+        });
+        let (cmd_tx, commands) = mpsc::unbounded_channel();
+        let (updates, update_rx) = mpsc::unbounded_channel();
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let mut harness = Harness {
+            ctx,
+            frame: eframe::Frame::_new_kittest(),
+            app: ModApp::with_daemon(cmd_tx, update_rx),
+            commands,
+            updates,
+            nodes: Vec::new(),
+            events: Vec::new(),
+            time: 0.0,
+        };
+        harness.updates.send(ConnectionUpdate::Connected).unwrap();
+        harness.run();
+        harness
+    }
+
+    /// Run one frame with the queued input.
+    fn run(&mut self) {
+        self.time += 0.1;
+        let input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, egui::vec2(1280.0, 960.0))),
+            events: std::mem::take(&mut self.events),
+            time: Some(self.time),
+            ..Default::default()
+        };
+        let (app, frame) = (&mut self.app, &mut self.frame);
+        let output = self.ctx.run(input, |ctx| app.update(ctx, frame));
+        self.nodes = output.platform_output.accesskit_update.map(|update| update.nodes.into_iter().map(|(_, node)| node).collect()).unwrap_or_default();
+    }
+
+    /// Run a few frames, for windows that size themselves first.
+    fn settle(&mut self) {
+        for _ in 0..3 {
+            self.run();
+        }
+    }
+
+    fn find(&self, text: &str) -> Option<&accesskit::Node> {
+        self.nodes.iter().find(|node| node.label() == Some(text) || node.value() == Some(text))
+    }
+
+    fn shows(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    fn click(&mut self, text: &str) {
+        let bounds = self.find(text).and_then(|node| node.bounds()).unwrap_or_else(|| panic!("No widget labelled {:?}", text));
+        let pos = Pos2::new(((bounds.x0 + bounds.x1) / 2.0) as f32, ((bounds.y0 + bounds.y1) / 2.0) as f32);
+        let button = |pressed| Event::PointerButton { pos, button: PointerButton::Primary, pressed, modifiers: Modifiers::NONE };
+        self.events.extend([Event::PointerMoved(pos), button(true)]);
+        self.run();
+        self.events.push(button(false));
+        self.settle();
+    }
+
+    fn press(&mut self, modifiers: Modifiers, key: Key) {
+        self.events.push(Event::Key { key, physical_key: None, pressed: true, repeat: false, modifiers });
+        self.settle();
+    }
+
+    fn type_text(&mut self, text: &str) {
+        self.events.push(Event::Text(text.to_string()));
+        self.settle();
+    }
+
+    fn receive(&mut self, event: SyncEvent) {
+        self.updates.send(ConnectionUpdate::Message(DaemonMessage::Event(event))).unwrap();
+        self.settle();
+    }
+
+    fn sent(&mut self) -> Vec<SyncCommand> {
+        std::iter::from_fn(|| self.commands.try_recv().ok()).collect()
+    }
+}
+
+#[test]
+fn settings_window_opens_and_closes() {
+    let mut ui = Harness::new();
+    assert!(!ui.shows("Torrent URL:"));
+    ui.click("Settings");
+    assert!(ui.shows("Torrent URL:"));
+    ui.press(Modifiers::NONE, Key::Escape);
+    assert!(!ui.shows("Torrent URL:"));
+}
+
+#[test]
+fn check_button_sends_command() {
+    let mut ui = Harness::new();
+    ui.click("Check for updates");
+    assert!(matches!(ui.sent().as_slice(), [SyncCommand::DownloadAndCompare(_)]));
+}
+
+#[test]
+fn found_update_can_be_applied() {
+    let mut ui = Harness::new();
+    assert!(!ui.shows("Apply update"));
+    ui.receive(SyncEvent::RemoteUpdateFound(b"new torrent".to_vec(), TorrentSummary::default()));
+    ui.click("Apply update");
+    assert!(matches!(ui.sent().as_slice(), [SyncCommand::ApplyUpdate(torrent)] if torrent == b"new torrent"));
+    assert!(!ui.shows("Apply update"));
+}
+
+#[test]
+fn disk_full_dialog_stays_dismissed() {
+    let mut ui = Harness::new();
+    let error = SyncError::DiskFull("No space left on device".to_string());
+    ui.receive(SyncEvent::Error(error.clone()));
+    assert!(ui.shows("Disk full"));
+    ui.press(Modifiers::NONE, Key::Escape);
+    assert!(!ui.shows("Disk full"));
+    // The same error repeated by the status refresh doesn't reopen it
+    ui.receive(SyncEvent::Error(error));
+    assert!(!ui.shows("Disk full"));
+}
+
+#[test]
+fn command_palette_runs_the_match() {
+    let mut ui = Harness::new();
+    ui.press(Modifiers::COMMAND, Key::P);
+    assert!(ui.shows("Commands"));
+    ui.type_text("check local");
+    ui.press(Modifiers::NONE, Key::Enter);
+    assert!(matches!(ui.sent().as_slice(), [SyncCommand::VerifyFolder]));
+    assert!(!ui.shows("Commands"));
+}